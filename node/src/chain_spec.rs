@@ -1195,7 +1195,8 @@ fn spreehafen_testnet_genesis(
         },
         session: SessionConfig {
             keys: invulnerables
-                .into_iter()
+                .iter()
+                .cloned()
                 .map(|(acc, aura)| {
                     (
                         acc.clone(),                    // account id
@@ -1241,6 +1242,8 @@ fn spreehafen_testnet_genesis(
             stakers: [].into(),
             max_candidate_stake: datahighway_parachain_runtime::constants::staking::MAX_CANDIDATE_STAKE,
             reward_per_block: datahighway_parachain_runtime::constants::staking::REWARD_PER_BLOCK,
+            invulnerables: invulnerables.into_iter().map(|(acc, _)| acc).collect(),
+            max_commission: datahighway_parachain_runtime::constants::staking::MAX_COMMISSION,
         },
         vesting: Default::default(),
     }
@@ -1279,7 +1282,8 @@ fn testnet_genesis(
         },
         session: SessionConfig {
             keys: invulnerables
-                .into_iter()
+                .iter()
+                .cloned()
                 .map(|(acc, aura)| {
                     (
                         acc.clone(),                    // account id
@@ -1325,6 +1329,8 @@ fn testnet_genesis(
             stakers: [].into(),
             max_candidate_stake: datahighway_parachain_runtime::constants::staking::MAX_CANDIDATE_STAKE,
             reward_per_block: datahighway_parachain_runtime::constants::staking::REWARD_PER_BLOCK,
+            invulnerables: invulnerables.into_iter().map(|(acc, _)| acc).collect(),
+            max_commission: datahighway_parachain_runtime::constants::staking::MAX_COMMISSION,
         },
         vesting: Default::default(),
     }
@@ -1363,7 +1369,8 @@ fn dev_genesis(
         },
         session: SessionConfig {
             keys: invulnerables
-                .into_iter()
+                .iter()
+                .cloned()
                 .map(|(acc, aura)| {
                     (
                         acc.clone(),                    // account id
@@ -1409,6 +1416,8 @@ fn dev_genesis(
             stakers: [].into(),
             max_candidate_stake: datahighway_parachain_runtime::constants::staking::MAX_CANDIDATE_STAKE,
             reward_per_block: datahighway_parachain_runtime::constants::staking::REWARD_PER_BLOCK,
+            invulnerables: invulnerables.into_iter().map(|(acc, _)| acc).collect(),
+            max_commission: datahighway_parachain_runtime::constants::staking::MAX_COMMISSION,
         },
         vesting: Default::default(),
     }
@@ -1458,7 +1467,8 @@ fn baikal_testnet_genesis(
         },
         session: SessionConfig {
             keys: invulnerables
-                .into_iter()
+                .iter()
+                .cloned()
                 .map(|(acc, aura)| {
                     (
                         acc.clone(),                    // account id
@@ -1504,6 +1514,8 @@ fn baikal_testnet_genesis(
             stakers: [].into(),
             max_candidate_stake: datahighway_parachain_runtime::constants::staking::MAX_CANDIDATE_STAKE,
             reward_per_block: datahighway_parachain_runtime::constants::staking::REWARD_PER_BLOCK,
+            invulnerables: invulnerables.into_iter().map(|(acc, _)| acc).collect(),
+            max_commission: datahighway_parachain_runtime::constants::staking::MAX_COMMISSION,
         },
         vesting: Default::default(),
     }
@@ -1542,7 +1554,8 @@ fn tanganika_testnet_genesis(
         },
         session: SessionConfig {
             keys: invulnerables
-                .into_iter()
+                .iter()
+                .cloned()
                 .map(|(acc, aura)| {
                     (
                         acc.clone(),                    // account id
@@ -1588,6 +1601,8 @@ fn tanganika_testnet_genesis(
             stakers: [].into(),
             max_candidate_stake: datahighway_parachain_runtime::constants::staking::MAX_CANDIDATE_STAKE,
             reward_per_block: datahighway_parachain_runtime::constants::staking::REWARD_PER_BLOCK,
+            invulnerables: invulnerables.into_iter().map(|(acc, _)| acc).collect(),
+            max_commission: datahighway_parachain_runtime::constants::staking::MAX_COMMISSION,
         },
         vesting: Default::default(),
     }
@@ -0,0 +1,211 @@
+// KILT Blockchain – https://botlabs.org
+// Copyright (C) 2019-2022 BOTLabs GmbH
+
+// The KILT Blockchain is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The KILT Blockchain is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// If you feel like getting in touch with us, you can do so at info@botlabs.org
+
+//! RPC interface of the parachain-staking pallet.
+//!
+//! All methods are available under the `staking` namespace and can be
+//! queried at a specific block by passing its hash. Otherwise, the best block
+//! is used.
+//!
+//! Balances are returned as decimal strings since JSON numbers cannot
+//! represent all values of `u128`. Balance parameters are accepted as numbers
+//! or hex strings.
+
+use std::{convert::TryFrom, marker::PhantomData, sync::Arc};
+
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+};
+use parity_scale_codec::Codec;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_rpc::number::NumberOrHex;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use parachain_staking_runtime_api::{
+	CandidateInfo, ParachainStakingApi as ParachainStakingRuntimeApi, RewardEstimate, RoundInfo, RoundStats,
+	StakerInfo,
+};
+
+#[rpc(client, server)]
+pub trait ParachainStakingApi<BlockHash, AccountId, Balance, BlockNumber> {
+	/// Return the collator candidates which are selected for the next session
+	/// together with their stake.
+	#[method(name = "staking_selectedCandidates")]
+	fn selected_candidates(&self, at: Option<BlockHash>) -> RpcResult<Vec<CandidateInfo<AccountId, Balance>>>;
+
+	/// Return the stake, delegations, unstaking requests and pending rewards
+	/// of the given account.
+	#[method(name = "staking_stakerInfo")]
+	fn staker_info(
+		&self,
+		account: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<StakerInfo<AccountId, Balance, BlockNumber>>;
+
+	/// Estimate the rewards per block and per year of a new stake of the given
+	/// amount for the collator candidate.
+	#[method(name = "staking_estimateRewards")]
+	fn estimate_rewards(
+		&self,
+		collator: AccountId,
+		stake: NumberOrHex,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<RewardEstimate<Balance>>>;
+
+	/// Return the current staking round.
+	#[method(name = "staking_roundInfo")]
+	fn round_info(&self, at: Option<BlockHash>) -> RpcResult<RoundInfo<BlockNumber>>;
+
+	/// Return the total stake, the authored blocks and the rewards of the
+	/// collators in the given round if it has not been pruned yet.
+	///
+	/// Requires version 2 of the runtime API.
+	#[method(name = "staking_roundStats")]
+	fn round_stats(&self, round: u32, at: Option<BlockHash>) -> RpcResult<Option<RoundStats<AccountId, Balance>>>;
+}
+
+/// Provides the RPC methods to query the state of the parachain-staking
+/// pallet.
+pub struct ParachainStaking<C, Block> {
+	client: Arc<C>,
+	_marker: PhantomData<Block>,
+}
+
+impl<C, Block> ParachainStaking<C, Block> {
+	/// Create a new instance of the staking RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self {
+			client,
+			_marker: Default::default(),
+		}
+	}
+}
+
+/// Error codes of the staking RPC methods.
+pub enum Error {
+	/// The call to the runtime failed.
+	RuntimeError,
+	/// A balance parameter does not fit into the runtime's balance type.
+	InvalidBalance,
+	/// The runtime at the queried block does not provide the method.
+	UnsupportedApiVersion,
+}
+
+impl From<Error> for i32 {
+	fn from(e: Error) -> i32 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::InvalidBalance => 2,
+			Error::UnsupportedApiVersion => 3,
+		}
+	}
+}
+
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(
+		Error::RuntimeError.into(),
+		"Unable to query the staking state.",
+		Some(format!("{:?}", err)),
+	))
+	.into()
+}
+
+impl<C, Block, AccountId, Balance, BlockNumber>
+	ParachainStakingApiServer<<Block as BlockT>::Hash, AccountId, Balance, BlockNumber> for ParachainStaking<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: ParachainStakingRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+	AccountId: Codec,
+	Balance: Codec + TryFrom<NumberOrHex>,
+	BlockNumber: Codec,
+{
+	fn selected_candidates(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<CandidateInfo<AccountId, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.selected_candidates(&at).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn staker_info(
+		&self,
+		account: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<StakerInfo<AccountId, Balance, BlockNumber>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.staker_info(&at, account).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn estimate_rewards(
+		&self,
+		collator: AccountId,
+		stake: NumberOrHex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<RewardEstimate<Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		let stake = Balance::try_from(stake).map_err(|_| {
+			CallError::Custom(ErrorObject::owned(
+				Error::InvalidBalance.into(),
+				"The stake does not fit into a balance.",
+				None::<()>,
+			))
+		})?;
+
+		api.estimate_rewards(&at, collator, stake).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn round_info(&self, at: Option<<Block as BlockT>::Hash>) -> RpcResult<RoundInfo<BlockNumber>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.round_info(&at).map_err(runtime_error_into_rpc_err)
+	}
+
+	fn round_stats(
+		&self,
+		round: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<RoundStats<AccountId, Balance>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		// the round statistics have been added in version 2 of the runtime API
+		let version = api
+			.api_version::<dyn ParachainStakingRuntimeApi<Block, AccountId, Balance, BlockNumber>>(&at)
+			.map_err(runtime_error_into_rpc_err)?;
+		if version.unwrap_or_default() < 2 {
+			return Err(CallError::Custom(ErrorObject::owned(
+				Error::UnsupportedApiVersion.into(),
+				"The runtime does not provide the round statistics.",
+				None::<()>,
+			))
+			.into());
+		}
+
+		api.round_stats(&at, round).map_err(runtime_error_into_rpc_err)
+	}
+}
@@ -0,0 +1,277 @@
+// KILT Blockchain – https://botlabs.org
+// Copyright (C) 2019-2022 BOTLabs GmbH
+
+// The KILT Blockchain is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The KILT Blockchain is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// If you feel like getting in touch with us, you can do so at info@botlabs.org
+
+//! Runtime API definition of the parachain-staking pallet.
+//!
+//! The API exposes the staking state in a decoded form such that wallets and
+//! explorers do not need to read the storage of the pallet directly.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments, clippy::unnecessary_mut_passed)]
+
+use parity_scale_codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::{Perbill, RuntimeDebug};
+use sp_std::vec::Vec;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// Serialize balances as strings since JSON numbers cannot represent all
+/// values of `u128`.
+#[cfg(feature = "std")]
+mod serde_balance {
+	use serde::{Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer, T: std::fmt::Display>(t: &T, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&t.to_string())
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>, T: std::str::FromStr>(deserializer: D) -> Result<T, D::Error> {
+		let s = String::deserialize(deserializer)?;
+		s.parse::<T>().map_err(|_| serde::de::Error::custom("Parse from string failed"))
+	}
+}
+
+/// Serialize optional balances as strings, see [serde_balance].
+#[cfg(feature = "std")]
+mod serde_option_balance {
+	use serde::{Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer, T: std::fmt::Display>(t: &Option<T>, serializer: S) -> Result<S::Ok, S::Error> {
+		match t {
+			Some(t) => serializer.serialize_some(&t.to_string()),
+			None => serializer.serialize_none(),
+		}
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>, T: std::str::FromStr>(
+		deserializer: D,
+	) -> Result<Option<T>, D::Error> {
+		Option::<String>::deserialize(deserializer)?
+			.map(|s| s.parse::<T>().map_err(|_| serde::de::Error::custom("Parse from string failed")))
+			.transpose()
+	}
+}
+
+/// The stake of a selected collator candidate.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+#[cfg_attr(
+	feature = "std",
+	serde(bound(
+		serialize = "AccountId: Serialize, Balance: std::fmt::Display",
+		deserialize = "AccountId: Deserialize<'de>, Balance: std::str::FromStr"
+	))
+)]
+pub struct CandidateInfo<AccountId, Balance> {
+	/// The account of the collator candidate.
+	pub id: AccountId,
+	/// The self-stake of the collator candidate.
+	#[cfg_attr(feature = "std", serde(with = "serde_balance"))]
+	pub stake: Balance,
+	/// The sum of the self-stake and the stake of all delegators.
+	#[cfg_attr(feature = "std", serde(with = "serde_balance"))]
+	pub total: Balance,
+	/// The share of the delegators' rewards which is credited to the
+	/// collator candidate.
+	pub commission: Perbill,
+}
+
+/// A single delegation of a delegator.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+#[cfg_attr(
+	feature = "std",
+	serde(bound(
+		serialize = "AccountId: Serialize, Balance: std::fmt::Display",
+		deserialize = "AccountId: Deserialize<'de>, Balance: std::str::FromStr"
+	))
+)]
+pub struct DelegationInfo<AccountId, Balance> {
+	/// The account of the collator candidate.
+	pub collator: AccountId,
+	/// The amount delegated to the collator candidate.
+	#[cfg_attr(feature = "std", serde(with = "serde_balance"))]
+	pub amount: Balance,
+}
+
+/// An unstaking request which can be unlocked at the given block.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+#[cfg_attr(
+	feature = "std",
+	serde(bound(
+		serialize = "Balance: std::fmt::Display, BlockNumber: Serialize",
+		deserialize = "Balance: std::str::FromStr, BlockNumber: Deserialize<'de>"
+	))
+)]
+pub struct UnstakingInfo<Balance, BlockNumber> {
+	/// The block from which on the funds can be unlocked.
+	pub unlock_block: BlockNumber,
+	/// The amount which is unstaking.
+	#[cfg_attr(feature = "std", serde(with = "serde_balance"))]
+	pub amount: Balance,
+}
+
+/// The staking positions of an account.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+#[cfg_attr(
+	feature = "std",
+	serde(bound(
+		serialize = "AccountId: Serialize, Balance: std::fmt::Display, BlockNumber: Serialize",
+		deserialize = "AccountId: Deserialize<'de>, Balance: std::str::FromStr, BlockNumber: Deserialize<'de>"
+	))
+)]
+pub struct StakerInfo<AccountId, Balance, BlockNumber> {
+	/// The self-stake of the account if it is a collator candidate.
+	#[cfg_attr(feature = "std", serde(with = "serde_option_balance"))]
+	pub self_stake: Option<Balance>,
+	/// The delegations of the account if it is a delegator.
+	pub delegations: Vec<DelegationInfo<AccountId, Balance>>,
+	/// The unstaking requests of the account.
+	pub unstaking: Vec<UnstakingInfo<Balance, BlockNumber>>,
+	/// The rewards which can currently be claimed.
+	#[cfg_attr(feature = "std", serde(with = "serde_balance"))]
+	pub pending_rewards: Balance,
+}
+
+/// The estimated rewards of a stake.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+#[cfg_attr(
+	feature = "std",
+	serde(bound(
+		serialize = "Balance: std::fmt::Display",
+		deserialize = "Balance: std::str::FromStr"
+	))
+)]
+pub struct RewardEstimate<Balance> {
+	/// The average rewards per block.
+	#[cfg_attr(feature = "std", serde(with = "serde_balance"))]
+	pub per_block: Balance,
+	/// The rewards per year, assuming the current reward per block.
+	#[cfg_attr(feature = "std", serde(with = "serde_balance"))]
+	pub per_year: Balance,
+}
+
+/// The current staking round.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct RoundInfo<BlockNumber> {
+	/// The current round index.
+	pub current: u32,
+	/// The first block of the current round.
+	pub first: BlockNumber,
+	/// The length of the current round in blocks.
+	pub length: BlockNumber,
+}
+
+/// The block production and rewards of a collator in a staking round.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+#[cfg_attr(
+	feature = "std",
+	serde(bound(
+		serialize = "AccountId: Serialize, Balance: std::fmt::Display",
+		deserialize = "AccountId: Deserialize<'de>, Balance: std::str::FromStr"
+	))
+)]
+pub struct CollatorRoundStats<AccountId, Balance> {
+	/// The account of the collator.
+	pub collator: AccountId,
+	/// The number of blocks the collator authored in the round.
+	pub blocks_authored: u32,
+	/// The block rewards credited to the collator including the commission.
+	#[cfg_attr(feature = "std", serde(with = "serde_balance"))]
+	pub collator_rewards: Balance,
+	/// The block rewards credited to the delegators of the collator.
+	#[cfg_attr(feature = "std", serde(with = "serde_balance"))]
+	pub delegator_rewards: Balance,
+}
+
+/// The statistics of a past or the current staking round.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+#[cfg_attr(
+	feature = "std",
+	serde(bound(
+		serialize = "AccountId: Serialize, Balance: std::fmt::Display",
+		deserialize = "AccountId: Deserialize<'de>, Balance: std::str::FromStr"
+	))
+)]
+pub struct RoundStats<AccountId, Balance> {
+	/// The round index.
+	pub round: u32,
+	/// The stake of the selected collators at the start of the round.
+	#[cfg_attr(feature = "std", serde(with = "serde_balance"))]
+	pub collator_stake: Balance,
+	/// The stake of the delegators of the selected collators at the start of
+	/// the round.
+	#[cfg_attr(feature = "std", serde(with = "serde_balance"))]
+	pub delegator_stake: Balance,
+	/// The collators which authored blocks in the round.
+	pub collators: Vec<CollatorRoundStats<AccountId, Balance>>,
+}
+
+sp_api::decl_runtime_apis! {
+	/// The API to query the state of the parachain-staking pallet.
+	///
+	/// Version 2 adds `round_stats`.
+	#[api_version(2)]
+	pub trait ParachainStakingApi<AccountId, Balance, BlockNumber>
+	where
+		AccountId: Codec,
+		Balance: Codec,
+		BlockNumber: Codec,
+	{
+		/// Return the collator candidates which are selected for the next
+		/// session together with their stake.
+		fn selected_candidates() -> Vec<CandidateInfo<AccountId, Balance>>;
+
+		/// Return the stake, delegations, unstaking requests and pending
+		/// rewards of the given account.
+		fn staker_info(account: AccountId) -> StakerInfo<AccountId, Balance, BlockNumber>;
+
+		/// Estimate the rewards of a new stake of the given amount for the
+		/// collator candidate.
+		///
+		/// Returns `None` if the account is not a collator candidate.
+		fn estimate_rewards(collator: AccountId, stake: Balance) -> Option<RewardEstimate<Balance>>;
+
+		/// Return the current staking round.
+		fn round_info() -> RoundInfo<BlockNumber>;
+
+		/// Return the statistics of the given round.
+		///
+		/// Returns `None` if the round has not started yet or its statistics
+		/// have been pruned.
+		///
+		/// Available from version 2 on.
+		fn round_stats(round: u32) -> Option<RoundStats<AccountId, Balance>>;
+	}
+}
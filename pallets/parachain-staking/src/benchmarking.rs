@@ -18,23 +18,37 @@
 #![cfg(feature = "runtime-benchmarks")]
 
 //! Benchmarking
-use crate::{types::RoundInfo, *};
+use crate::{
+	inflation::InflationInfo,
+	types::{LoyaltyInfo, PausedOperations, PoolId, RoundInfo},
+	*,
+};
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, Zero};
 use frame_support::{
 	assert_ok,
 	traits::{Currency, Get, OnInitialize},
+	BoundedVec,
 };
 use frame_system::{Pallet as System, RawOrigin};
 use pallet_session::Pallet as Session;
+use parity_scale_codec::{Decode, Encode};
 use sp_runtime::{
-	traits::{One, SaturatedConversion, StaticLookup},
-	Perquintill,
+	traits::{One, SaturatedConversion, StaticLookup, TrailingZeroInput},
+	Perbill, Percent, Perquintill,
 };
 use sp_std::{convert::TryInto, vec::Vec};
 
 const COLLATOR_ACCOUNT_SEED: u32 = 0;
 const DELEGATOR_ACCOUNT_SEED: u32 = 1;
 
+/// Registers session keys for the account which are required to join the set
+/// of collator candidates.
+fn set_session_keys<T: Config>(who: &T::AccountId) {
+	let keys = <T as pallet_session::Config>::Keys::decode(&mut TrailingZeroInput::new(who.encode().as_ref()))
+		.expect("Keys can be decoded from any input q.e.d.");
+	assert_ok!(Session::<T>::set_keys(RawOrigin::Signed(who.clone()).into(), keys, Vec::new()));
+}
+
 /// Fills the candidate pool up to `num_candidates`.
 fn setup_collator_candidates<T: Config>(
 	num_candidates: u32,
@@ -48,6 +62,7 @@ fn setup_collator_candidates<T: Config>(
 
 	for acc in collators.iter() {
 		T::Currency::make_free_balance_be(acc, amount);
+		set_session_keys::<T>(acc);
 		assert_ok!(<Pallet<T>>::join_candidates(
 			T::Origin::from(Some(acc.clone()).into()),
 			amount,
@@ -115,6 +130,19 @@ where
 	assert!(<Unstaking<T>>::get(who).len() <= T::MaxUnstakeRequests::get().try_into().unwrap());
 }
 
+/// Creates a delegation pool which delegates `amount` to the collator.
+fn setup_pool<T: Config>(collator: &T::AccountId, amount: T::CurrencyBalance) -> (PoolId, T::AccountId) {
+	let operator: T::AccountId = account("operator", 0, COLLATOR_ACCOUNT_SEED);
+	T::Currency::make_free_balance_be(&operator, amount + amount);
+	let pool_id = <NextPoolId<T>>::get();
+	assert_ok!(<Pallet<T>>::create_pool(
+		RawOrigin::Signed(operator.clone()).into(),
+		T::Lookup::unlookup(collator.clone()),
+		amount,
+	));
+	(pool_id, operator)
+}
+
 benchmarks! {
 	where_clause { where u64: Into<<T as frame_system::Config>::BlockNumber> }
 
@@ -134,6 +162,22 @@ benchmarks! {
 		assert_eq!(<Round<T>>::get().current, 1u32);
 	}
 
+	on_initialize_inflation_update {
+		setup_collator_candidates::<T>(T::MinCollators::get(), None);
+		<InflationConfig<T>>::put(InflationInfo::new(
+			Perquintill::from_percent(50),
+			Perquintill::from_percent(2),
+			Perquintill::from_percent(10),
+			Perquintill::from_percent(20),
+		));
+		let round = <Round<T>>::get();
+		assert_eq!(round.current, 0u32);
+	}: { Pallet::<T>::on_initialize(round.length) }
+	verify {
+		assert_eq!(<Round<T>>::get().current, 1u32);
+		assert!(!<CurrentInflation<T>>::get().collator_staking_rate.is_zero());
+	}
+
 	on_initialize_network_rewards {
 		let issuance = T::Currency::total_issuance();
 		// if we only add by one, we also initialize a new year
@@ -215,6 +259,7 @@ benchmarks! {
 
 		let new_candidate = account("new_collator", u32::MAX , COLLATOR_ACCOUNT_SEED);
 		T::Currency::make_free_balance_be(&new_candidate, min_candidate_stake);
+		set_session_keys::<T>(&new_candidate);
 
 	}: _(RawOrigin::Signed(new_candidate.clone()), min_candidate_stake)
 	verify {
@@ -523,6 +568,7 @@ benchmarks! {
 		let free_balance = T::CurrencyBalance::from(u128::MAX);
 		let stake = T::MinCollatorCandidateStake::get();
 		T::Currency::make_free_balance_be(&candidate, free_balance);
+		set_session_keys::<T>(&candidate);
 		assert_ok!(<Pallet<T>>::join_candidates(
 			T::Origin::from(Some(candidate.clone()).into()),
 			stake,
@@ -555,6 +601,498 @@ benchmarks! {
 		assert_eq!(<MaxCollatorCandidateStake<T>>::get(), new);
 	}
 
+	claim_rewards {
+		let n in 1 .. T::MaxCollatorsPerDelegator::get();
+		// leave room for the claiming delegator
+		let m in 0 .. T::MaxDelegatorsPerCollator::get() - 1;
+
+		let candidates = setup_collator_candidates::<T>(n.max(T::MinCollators::get()), None);
+		for (i, c) in candidates.iter().take(n as usize).enumerate() {
+			fill_delegators::<T>(m, c.clone(), i.saturated_into::<u32>());
+		}
+		let delegator: T::AccountId = account("delegator", 0u32, DELEGATOR_ACCOUNT_SEED);
+		let amount = T::MinDelegatorStake::get();
+		T::Currency::make_free_balance_be(&delegator, amount * T::CurrencyBalance::from(n as u64));
+		for (i, collator) in candidates.iter().take(n as usize).enumerate() {
+			if i == 0 {
+				assert_ok!(<Pallet<T>>::join_delegators(
+					RawOrigin::Signed(delegator.clone()).into(),
+					T::Lookup::unlookup(collator.clone()),
+					amount,
+				));
+			} else {
+				assert_ok!(<Pallet<T>>::delegate_another_candidate(
+					RawOrigin::Signed(delegator.clone()).into(),
+					T::Lookup::unlookup(collator.clone()),
+					amount,
+				));
+			}
+			// restake all rewards to increase the stake of each collator
+			assert_ok!(<Pallet<T>>::set_auto_compound(
+				RawOrigin::Signed(delegator.clone()).into(),
+				T::Lookup::unlookup(collator.clone()),
+				Percent::one(),
+			));
+			// pretend the collator authored a block
+			<CollatorBlockRewards<T>>::insert(collator, <RewardPerBlock<T>>::get());
+		}
+		assert!(!<Pallet<T>>::pending_rewards(&delegator).is_zero());
+	}: _(RawOrigin::Signed(delegator.clone()))
+	verify {
+		assert!(<Rewards<T>>::get(&delegator).is_zero());
+		assert!(<Pallet<T>>::pending_rewards(&delegator).is_zero());
+		assert!(<DelegatorState<T>>::get(&delegator).unwrap().total > amount * T::CurrencyBalance::from(n as u64));
+	}
+
+	set_max_commission {
+		let new = Perbill::from_percent(10);
+	}: _(RawOrigin::Root, new)
+	verify {
+		assert_eq!(<MaxCommission<T>>::get(), new);
+	}
+
+	set_commission {
+		let m in 0 .. T::MaxDelegatorsPerCollator::get();
+
+		let candidates = setup_collator_candidates::<T>(T::MinCollators::get(), None);
+		let collator = candidates[0].clone();
+		fill_delegators::<T>(m, collator.clone(), COLLATOR_ACCOUNT_SEED);
+		<MaxCommission<T>>::put(Perbill::from_percent(10));
+		<Commission<T>>::insert(&collator, Perbill::from_percent(10));
+		// pretend the collator authored a block
+		<CollatorBlockRewards<T>>::insert(&collator, <RewardPerBlock<T>>::get());
+		let new = Perbill::from_percent(5);
+	}: _(RawOrigin::Signed(collator.clone()), new)
+	verify {
+		assert_eq!(<Commission<T>>::get(&collator), new);
+	}
+
+	execute_commission_change {
+		let m in 0 .. T::MaxDelegatorsPerCollator::get();
+
+		let candidates = setup_collator_candidates::<T>(T::MinCollators::get(), None);
+		let collator = candidates[0].clone();
+		fill_delegators::<T>(m, collator.clone(), COLLATOR_ACCOUNT_SEED);
+		let new = Perbill::from_percent(10);
+		<MaxCommission<T>>::put(new);
+		assert_ok!(<Pallet<T>>::set_commission(RawOrigin::Signed(collator.clone()).into(), new));
+		// pretend the collator authored a block
+		<CollatorBlockRewards<T>>::insert(&collator, <RewardPerBlock<T>>::get());
+		<Round<T>>::mutate(|round| round.current = round.current.saturating_add(T::CommissionChangeDelay::get()));
+		let caller: T::AccountId = account("caller", 0, COLLATOR_ACCOUNT_SEED);
+	}: _(RawOrigin::Signed(caller), T::Lookup::unlookup(collator.clone()))
+	verify {
+		assert_eq!(<Commission<T>>::get(&collator), new);
+		assert!(<PendingCommission<T>>::get(&collator).is_none());
+	}
+
+	redelegate {
+		// we need at least 2 collators
+		let n in 2 .. T::MaxTopCandidates::get();
+		let m in 1 .. T::MaxDelegatorsPerCollator::get();
+
+		let candidates = setup_collator_candidates::<T>(n, None);
+		for (i, c) in candidates.iter().enumerate() {
+			fill_delegators::<T>(m, c.clone(), i.saturated_into::<u32>());
+		}
+		let from = candidates[0].clone();
+		let to = candidates.last().unwrap().clone();
+		let delegator = <CandidatePool<T>>::get(&from).unwrap().delegators.into_bounded_vec()[0].owner.clone();
+
+		// stake more than the other delegators to kick one of them if the new collator is full
+		let amount = T::MinDelegatorStake::get();
+		T::Currency::make_free_balance_be(&delegator, amount + amount);
+		assert_ok!(<Pallet<T>>::delegator_stake_more(
+			RawOrigin::Signed(delegator.clone()).into(),
+			T::Lookup::unlookup(from.clone()),
+			amount,
+		));
+		// the delegator has already delegated in this round
+		<Round<T>>::mutate(|round| round.current = round.current.saturating_add(1));
+	}: _(RawOrigin::Signed(delegator.clone()), T::Lookup::unlookup(from.clone()), T::Lookup::unlookup(to.clone()), amount + amount)
+	verify {
+		let state = <CandidatePool<T>>::get(&to).unwrap();
+		assert!(state.delegators.into_iter().any(|x| x.owner == delegator));
+		let state = <CandidatePool<T>>::get(&from).unwrap();
+		assert!(!state.delegators.into_iter().any(|x| x.owner == delegator));
+	}
+
+	rebond {
+		// we need at least 1 collators
+		let n in 1 .. T::MaxTopCandidates::get();
+		// we need at least 1 delegator
+		let m in 1 .. T::MaxDelegatorsPerCollator::get() - 1;
+		let u in 1 .. (T::MaxUnstakeRequests::get().saturated_into::<u32>() - 1);
+
+		let candidates = setup_collator_candidates::<T>(n, None);
+		for (i, c) in candidates.iter().enumerate() {
+			fill_delegators::<T>(m, c.clone(), i.saturated_into::<u32>());
+		}
+		let collator = candidates[0].clone();
+		let amount = T::MinDelegatorStake::get();
+
+		// make sure delegator collated to collator
+		let state = <CandidatePool<T>>::get(&collator).unwrap();
+		let delegator = state.delegators.into_bounded_vec()[0].owner.clone();
+
+		// increase stake so we can unstake, because current stake is minimum
+		T::Currency::make_free_balance_be(&delegator, T::CurrencyBalance::from(u128::MAX));
+		assert_ok!(<Pallet<T>>::delegator_stake_more(RawOrigin::Signed(delegator.clone()).into(), T::Lookup::unlookup(collator.clone()), T::CurrencyBalance::from(u as u64)));
+
+		// fill unstake BTreeMap by unstaked many entries of 1
+		fill_unstaking::<T>(&collator, Some(&delegator), u as u64);
+		assert_eq!(<DelegatorState<T>>::get(&delegator).unwrap().total, amount);
+		let unlookup_collator = T::Lookup::unlookup(collator.clone());
+	}: _(RawOrigin::Signed(delegator.clone()), unlookup_collator, T::CurrencyBalance::from(u as u64))
+	verify {
+		assert_eq!(<DelegatorState<T>>::get(&delegator).unwrap().total, amount + T::CurrencyBalance::from(u as u64));
+		assert!(<Unstaking<T>>::get(&delegator).is_empty());
+	}
+
+	set_auto_compound {
+		let m in 0 .. T::MaxDelegatorsPerCollator::get();
+
+		let candidates = setup_collator_candidates::<T>(T::MinCollators::get(), None);
+		let collator = candidates[0].clone();
+		fill_delegators::<T>(m, collator.clone(), COLLATOR_ACCOUNT_SEED);
+		<MaxCommission<T>>::put(Perbill::from_percent(10));
+		<Commission<T>>::insert(&collator, Perbill::from_percent(10));
+		// pretend the collator authored a block
+		<CollatorBlockRewards<T>>::insert(&collator, <RewardPerBlock<T>>::get());
+		let percent = Percent::from_percent(50);
+	}: _(RawOrigin::Signed(collator.clone()), T::Lookup::unlookup(collator.clone()), percent)
+	verify {
+		assert_eq!(<AutoCompound<T>>::get(&collator, &collator), percent);
+	}
+
+	set_payee {
+		let candidates = setup_collator_candidates::<T>(T::MinCollators::get(), None);
+		let collator = candidates[0].clone();
+		let payee: T::AccountId = account("payee", 0, COLLATOR_ACCOUNT_SEED);
+	}: _(RawOrigin::Signed(collator.clone()), T::Lookup::unlookup(payee.clone()))
+	verify {
+		assert_eq!(<Payee<T>>::get(&collator), Some(payee));
+	}
+
+	create_pool {
+		let n in (T::MinCollators::get()) .. T::MaxTopCandidates::get();
+		// leave room for the pool
+		let m in 0 .. T::MaxDelegatorsPerCollator::get() - 1;
+
+		let candidates = setup_collator_candidates::<T>(n, None);
+		for (i, c) in candidates.iter().enumerate() {
+			fill_delegators::<T>(m, c.clone(), i.saturated_into::<u32>());
+		}
+		let collator = candidates[0].clone();
+		let operator: T::AccountId = account("operator", 0, COLLATOR_ACCOUNT_SEED);
+		let amount = T::MinDelegatorStake::get();
+		T::Currency::make_free_balance_be(&operator, amount + amount);
+	}: _(RawOrigin::Signed(operator.clone()), T::Lookup::unlookup(collator.clone()), amount)
+	verify {
+		assert_eq!(<Pools<T>>::get(0).unwrap().stake, amount);
+		let pool = Pallet::<T>::pool_account(0);
+		assert!(<CandidatePool<T>>::get(&collator).unwrap().delegators.into_iter().any(|x| x.owner == pool));
+	}
+
+	join_pool {
+		let n in (T::MinCollators::get()) .. T::MaxTopCandidates::get();
+		let m in 0 .. T::MaxDelegatorsPerCollator::get() - 1;
+
+		let candidates = setup_collator_candidates::<T>(n, None);
+		for (i, c) in candidates.iter().enumerate() {
+			fill_delegators::<T>(m, c.clone(), i.saturated_into::<u32>());
+		}
+		let collator = candidates[0].clone();
+		let amount = T::MinDelegatorStake::get();
+		let (pool_id, _) = setup_pool::<T>(&collator, amount);
+		// pretend the collator authored a block
+		<CollatorBlockRewards<T>>::insert(&collator, <RewardPerBlock<T>>::get());
+
+		let member: T::AccountId = account("member", 0, DELEGATOR_ACCOUNT_SEED);
+		T::Currency::make_free_balance_be(&member, amount + amount);
+	}: _(RawOrigin::Signed(member.clone()), pool_id, amount)
+	verify {
+		assert_eq!(<PoolMembers<T>>::get(pool_id, &member).unwrap().points, amount);
+		assert_eq!(<Pools<T>>::get(pool_id).unwrap().stake, amount + amount);
+	}
+
+	unbond_from_pool {
+		let n in (T::MinCollators::get()) .. T::MaxTopCandidates::get();
+		let m in 0 .. T::MaxDelegatorsPerCollator::get() - 1;
+		// leave a slot for the unbonding and one for a forced removal
+		let u in 0 .. (T::MaxUnstakeRequests::get().saturated_into::<u32>() - 2);
+
+		let candidates = setup_collator_candidates::<T>(n, None);
+		for (i, c) in candidates.iter().enumerate() {
+			fill_delegators::<T>(m, c.clone(), i.saturated_into::<u32>());
+		}
+		let collator = candidates[0].clone();
+		let amount = T::MinDelegatorStake::get();
+		let (pool_id, _) = setup_pool::<T>(&collator, amount);
+		<CollatorBlockRewards<T>>::insert(&collator, <RewardPerBlock<T>>::get());
+
+		let member: T::AccountId = account("member", 0, DELEGATOR_ACCOUNT_SEED);
+		let stake = amount + T::CurrencyBalance::from(u as u64) + T::CurrencyBalance::one();
+		T::Currency::make_free_balance_be(&member, stake + amount);
+		assert_ok!(<Pallet<T>>::join_pool(RawOrigin::Signed(member.clone()).into(), pool_id, stake));
+		// fill the unbonding requests of the member, which are rounded up to multiples of the stake duration
+		for _ in 0 .. u {
+			assert_ok!(<Pallet<T>>::unbond_from_pool(
+				RawOrigin::Signed(member.clone()).into(),
+				pool_id,
+				T::CurrencyBalance::one(),
+			));
+			System::<T>::set_block_number(System::<T>::block_number() + T::StakeDuration::get());
+		}
+	}: _(RawOrigin::Signed(member.clone()), pool_id, amount)
+	verify {
+		assert_eq!(<PoolMembers<T>>::get(pool_id, &member).unwrap().unbonding.len(), u as usize + 1);
+	}
+
+	withdraw_from_pool {
+		let u in 1 .. (T::MaxUnstakeRequests::get().saturated_into::<u32>() - 1);
+
+		let candidates = setup_collator_candidates::<T>(T::MinCollators::get(), None);
+		let collator = candidates[0].clone();
+		let amount = T::MinDelegatorStake::get();
+		let (pool_id, _) = setup_pool::<T>(&collator, amount);
+
+		let member: T::AccountId = account("member", 0, DELEGATOR_ACCOUNT_SEED);
+		let stake = T::CurrencyBalance::from(u as u64);
+		T::Currency::make_free_balance_be(&member, stake + amount);
+		assert_ok!(<Pallet<T>>::join_pool(RawOrigin::Signed(member.clone()).into(), pool_id, stake));
+		for _ in 0 .. u {
+			assert_ok!(<Pallet<T>>::unbond_from_pool(
+				RawOrigin::Signed(member.clone()).into(),
+				pool_id,
+				T::CurrencyBalance::one(),
+			));
+			System::<T>::set_block_number(System::<T>::block_number() + T::StakeDuration::get());
+		}
+		System::<T>::set_block_number(System::<T>::block_number() + T::StakeDuration::get());
+	}: _(RawOrigin::Signed(member.clone()), pool_id)
+	verify {
+		assert!(<PoolMembers<T>>::get(pool_id, &member).is_none());
+	}
+
+	claim_pool_rewards {
+		let n in 1 .. T::MaxCollatorsPerDelegator::get();
+		// leave room for the pool
+		let m in 0 .. T::MaxDelegatorsPerCollator::get() - 1;
+
+		let candidates = setup_collator_candidates::<T>(n.max(T::MinCollators::get()), None);
+		for (i, c) in candidates.iter().take(n as usize).enumerate() {
+			fill_delegators::<T>(m, c.clone(), i.saturated_into::<u32>());
+		}
+		let amount = T::MinDelegatorStake::get();
+		let (pool_id, operator) = setup_pool::<T>(&candidates[0], amount * T::CurrencyBalance::from(n as u64));
+		// spread the pool's stake across n collators
+		for collator in candidates.iter().take(n as usize).skip(1) {
+			<Round<T>>::mutate(|round| round.current = round.current.saturating_add(1));
+			assert_ok!(<Pallet<T>>::redelegate_pool(
+				RawOrigin::Signed(operator.clone()).into(),
+				pool_id,
+				T::Lookup::unlookup(candidates[0].clone()),
+				T::Lookup::unlookup(collator.clone()),
+				amount,
+			));
+		}
+		// pretend the collators authored a block
+		for collator in candidates.iter().take(n as usize) {
+			<CollatorBlockRewards<T>>::insert(collator, <RewardPerBlock<T>>::get());
+		}
+	}: _(RawOrigin::Signed(operator.clone()), pool_id)
+	verify {
+		assert!(<Rewards<T>>::get(&Pallet::<T>::pool_account(pool_id)).is_zero());
+	}
+
+	redelegate_pool {
+		// we need at least 2 collators
+		let n in 2 .. T::MaxTopCandidates::get();
+		let m in 1 .. T::MaxDelegatorsPerCollator::get();
+
+		let candidates = setup_collator_candidates::<T>(n, None);
+		for (i, c) in candidates.iter().enumerate() {
+			fill_delegators::<T>(m, c.clone(), i.saturated_into::<u32>());
+		}
+		let from = candidates[0].clone();
+		let to = candidates.last().unwrap().clone();
+		// stake more than the other delegators to kick one of them if the new collator is full
+		let amount = T::MinDelegatorStake::get() + T::MinDelegatorStake::get();
+		let (pool_id, operator) = setup_pool::<T>(&from, amount);
+		// the pool has already delegated in this round
+		<Round<T>>::mutate(|round| round.current = round.current.saturating_add(1));
+	}: _(RawOrigin::Signed(operator), pool_id, T::Lookup::unlookup(from.clone()), T::Lookup::unlookup(to.clone()), amount)
+	verify {
+		let pool = Pallet::<T>::pool_account(pool_id);
+		assert!(<CandidatePool<T>>::get(&to).unwrap().delegators.into_iter().any(|x| x.owner == pool));
+	}
+
+	set_inflation {
+		let n in 1 .. T::MaxTopCandidates::get();
+		let m in 0 .. T::MaxDelegatorsPerCollator::get();
+
+		let candidates = setup_collator_candidates::<T>(n, None);
+		for (i, c) in candidates.iter().enumerate() {
+			fill_delegators::<T>(m, c.clone(), i.saturated_into::<u32>());
+			// pretend the collator authored a block
+			<CollatorBlockRewards<T>>::insert(c, <RewardPerBlock<T>>::get());
+		}
+		let inflation = InflationInfo::new(
+			Perquintill::from_percent(50),
+			Perquintill::from_percent(2),
+			Perquintill::from_percent(10),
+			Perquintill::from_percent(20),
+		);
+	}: _(RawOrigin::Root, Some(inflation))
+	verify {
+		assert_eq!(<InflationConfig<T>>::get(), Some(inflation));
+		assert!(!<CurrentInflation<T>>::get().collator_staking_rate.is_zero());
+		assert_eq!(<SettledBlockRewards<T>>::get(&candidates[0], &candidates[0]), <RewardPerBlock<T>>::get());
+	}
+
+	leave_waitlist {
+		let n in 1 .. T::MaxWaitlistSize::get();
+
+		let candidates = setup_collator_candidates::<T>(T::MinCollators::get(), None);
+		let collator = candidates[0].clone();
+		// all delegators beyond the maximum are placed on the waitlist
+		let delegators = fill_delegators::<T>(T::MaxDelegatorsPerCollator::get() + n, collator.clone(), 0);
+		let delegator = delegators.last().unwrap().clone();
+		assert!(<Waitlisted<T>>::get(&delegator, &collator).is_some());
+	}: _(RawOrigin::Signed(delegator.clone()), T::Lookup::unlookup(collator.clone()))
+	verify {
+		assert!(<Waitlisted<T>>::get(&delegator, &collator).is_none());
+		assert_eq!(<Waitlist<T>>::get(&collator).len(), (n - 1) as usize);
+	}
+
+	set_loyalty_bonus {
+		let n in 1 .. T::MaxTopCandidates::get();
+		let m in 0 .. T::MaxDelegatorsPerCollator::get();
+
+		let candidates = setup_collator_candidates::<T>(n, None);
+		for (i, c) in candidates.iter().enumerate() {
+			fill_delegators::<T>(m, c.clone(), i.saturated_into::<u32>());
+			// pretend the collator authored a block
+			<CollatorBlockRewards<T>>::insert(c, <RewardPerBlock<T>>::get());
+		}
+		let loyalty = LoyaltyInfo::new(Perbill::from_percent(10), 100);
+	}: _(RawOrigin::Root, Some(loyalty))
+	verify {
+		assert_eq!(<LoyaltyConfig<T>>::get(), Some(loyalty));
+		assert_eq!(<SettledBlockRewards<T>>::get(&candidates[0], &candidates[0]), <RewardPerBlock<T>>::get());
+	}
+
+	add_invulnerable {
+		let n in 0 .. (T::MinCollators::get() - 1);
+
+		let invulnerables: Vec<T::AccountId> =
+			(0..n).map(|i| account("invulnerable", i, COLLATOR_ACCOUNT_SEED)).collect();
+		<Invulnerables<T>>::put(BoundedVec::try_from(invulnerables).expect("n is below MaxTopCandidates q.e.d."));
+		let invulnerable: T::AccountId = account("invulnerable", n, COLLATOR_ACCOUNT_SEED);
+		let invulnerable_lookup = T::Lookup::unlookup(invulnerable.clone());
+	}: _(RawOrigin::Root, invulnerable_lookup)
+	verify {
+		assert!(<Invulnerables<T>>::get().contains(&invulnerable));
+	}
+
+	remove_invulnerable {
+		let n in 1 .. T::MinCollators::get();
+
+		let invulnerables: Vec<T::AccountId> =
+			(0..n).map(|i| account("invulnerable", i, COLLATOR_ACCOUNT_SEED)).collect();
+		<Invulnerables<T>>::put(BoundedVec::try_from(invulnerables).expect("n is below MaxTopCandidates q.e.d."));
+		let invulnerable: T::AccountId = account("invulnerable", n - 1, COLLATOR_ACCOUNT_SEED);
+		let invulnerable_lookup = T::Lookup::unlookup(invulnerable.clone());
+	}: _(RawOrigin::Root, invulnerable_lookup)
+	verify {
+		assert!(!<Invulnerables<T>>::get().contains(&invulnerable));
+	}
+
+	set_paused_operations {
+		let paused = PausedOperations::all();
+	}: _(RawOrigin::Root, paused)
+	verify {
+		assert!(<Paused<T>>::get().is_emergency());
+	}
+
+	update_top_candidates {
+		let n in 1 .. T::MaxTopCandidates::get();
+
+		let candidates = setup_collator_candidates::<T>(n, None);
+		// the lowest ranked candidate ascends to the top which is the worst case
+		let candidate = candidates.last().unwrap().clone();
+		let state = <CandidatePool<T>>::get(&candidate).unwrap();
+		let old_delegators = state.total - state.stake;
+		let new_stake = TopCandidates::<T>::get()[0].amount + T::MinCollatorCandidateStake::get();
+	}: {
+		Pallet::<T>::update_top_candidates(candidate.clone(), state.stake, old_delegators, new_stake, old_delegators);
+	}
+	verify {
+		assert_eq!(TopCandidates::<T>::get()[0].owner, candidate);
+	}
+
+	bond_candidate_for {
+		let n in 1 .. T::MaxTopCandidates::get() - 1;
+		let m in 0 .. T::MaxDelegatorsPerCollator::get();
+
+		let min_candidate_stake = T::MinCollatorCandidateStake::get();
+		let candidates = setup_collator_candidates::<T>(n, None);
+		for (i, c) in candidates.iter().enumerate() {
+			fill_delegators::<T>(m, c.clone(), i.saturated_into::<u32>());
+		}
+
+		let funder: T::AccountId = account("funder", 0, COLLATOR_ACCOUNT_SEED);
+		T::Currency::make_free_balance_be(&funder, min_candidate_stake + min_candidate_stake);
+		let new_candidate = account("new_collator", u32::MAX , COLLATOR_ACCOUNT_SEED);
+		T::Currency::make_free_balance_be(&new_candidate, T::Currency::minimum_balance());
+		set_session_keys::<T>(&new_candidate);
+		AcceptedFunders::<T>::insert(&new_candidate, &funder);
+		let unlookup_candidate = T::Lookup::unlookup(new_candidate.clone());
+
+	}: _(RawOrigin::Signed(funder.clone()), unlookup_candidate, min_candidate_stake)
+	verify {
+		let candidates = TopCandidates::<T>::get();
+		assert!(candidates.into_iter().any(|other| other.owner == new_candidate));
+		assert_eq!(Funders::<T>::get(&new_candidate), Some((funder.clone(), min_candidate_stake)));
+		assert_eq!(FundedStake::<T>::get(&funder), min_candidate_stake);
+	}
+
+	bond_delegator_for {
+		let n in 1 .. T::MaxTopCandidates::get();
+		let m in 1 .. T::MaxDelegatorsPerCollator::get() - 1;
+
+		let candidates = setup_collator_candidates::<T>(n, None);
+		for (i, c) in candidates.iter().enumerate() {
+			fill_delegators::<T>(m, c.clone(), i.saturated_into::<u32>());
+		}
+		let collator = candidates[0].clone();
+		let amount = T::MinDelegatorStake::get();
+		let funder: T::AccountId = account("funder", 0, DELEGATOR_ACCOUNT_SEED);
+		T::Currency::make_free_balance_be(&funder, amount + amount + amount + amount);
+		let delegator: T::AccountId = account("new-delegator", 0, DELEGATOR_ACCOUNT_SEED);
+		T::Currency::make_free_balance_be(&delegator, T::Currency::minimum_balance());
+		AcceptedFunders::<T>::insert(&delegator, &funder);
+		let unlookup_delegator = T::Lookup::unlookup(delegator.clone());
+		let unlookup_collator = T::Lookup::unlookup(collator.clone());
+
+	}: _(RawOrigin::Signed(funder.clone()), unlookup_delegator, unlookup_collator, amount)
+	verify {
+		let state = <CandidatePool<T>>::get(&collator).unwrap();
+		assert!(state.delegators.into_iter().any(|x| x.owner == delegator));
+		assert_eq!(Funders::<T>::get(&delegator), Some((funder.clone(), amount)));
+		assert_eq!(FundedStake::<T>::get(&funder), amount);
+	}
+
+	accept_funder {
+		let staker: T::AccountId = account("staker", 0, DELEGATOR_ACCOUNT_SEED);
+		let funder: T::AccountId = account("funder", 0, DELEGATOR_ACCOUNT_SEED);
+		let unlookup_funder = T::Lookup::unlookup(funder.clone());
+	}: _(RawOrigin::Signed(staker.clone()), Some(unlookup_funder))
+	verify {
+		assert_eq!(AcceptedFunders::<T>::get(&staker), Some(funder));
+	}
+
 	// [Post-launch TODO]: Activate after increasing MaxCollatorsPerDelegator to at least 2. Expected to throw otherwise.
 	// delegate_another_candidate {
 	// 	// we need at least 2 collators
@@ -599,6 +1137,7 @@ impl_benchmark_test_suite!(
 	crate::mock::ExtBuilder::default()
 		.with_balances(vec![(u64::MAX, 1000 * crate::mock::MILLI_KILT)])
 		.with_collators(vec![(u64::MAX, 1000 * crate::mock::MILLI_KILT)])
+		.with_waitlist_size(4)
 		.build(),
 	crate::mock::Test,
 );
@@ -51,7 +51,7 @@ pub trait WeightInfo {
 	fn on_initialize_new_year() -> Weight;
 	fn on_initialize_network_rewards() -> Weight;
 	fn force_new_round() -> Weight;
-	fn set_inflation() -> Weight;
+	fn set_inflation(n: u32, m: u32, ) -> Weight;
 	fn set_max_selected_candidates(n: u32, m: u32, ) -> Weight;
 	fn set_blocks_per_round() -> Weight;
 	fn force_remove_candidate(n: u32, m: u32, ) -> Weight;
@@ -68,6 +68,30 @@ pub trait WeightInfo {
 	fn leave_delegators(n: u32, m: u32, ) -> Weight;
 	fn unlock_unstaked(u: u32, ) -> Weight;
 	fn set_max_candidate_stake() -> Weight;
+	fn claim_rewards(n: u32, m: u32, ) -> Weight;
+	fn set_max_commission() -> Weight;
+	fn set_commission(n: u32, ) -> Weight;
+	fn execute_commission_change(n: u32, ) -> Weight;
+	fn redelegate(n: u32, m: u32, ) -> Weight;
+	fn rebond(n: u32, m: u32, u: u32, ) -> Weight;
+	fn set_auto_compound(n: u32, ) -> Weight;
+	fn set_payee() -> Weight;
+	fn on_initialize_inflation_update() -> Weight;
+	fn create_pool(n: u32, m: u32, ) -> Weight;
+	fn join_pool(n: u32, m: u32, ) -> Weight;
+	fn unbond_from_pool(n: u32, m: u32, u: u32, ) -> Weight;
+	fn withdraw_from_pool(u: u32, ) -> Weight;
+	fn claim_pool_rewards(n: u32, m: u32, ) -> Weight;
+	fn redelegate_pool(n: u32, m: u32, ) -> Weight;
+	fn leave_waitlist(n: u32, ) -> Weight;
+	fn set_loyalty_bonus(n: u32, m: u32, ) -> Weight;
+	fn add_invulnerable(n: u32, ) -> Weight;
+	fn remove_invulnerable(n: u32, ) -> Weight;
+	fn set_paused_operations() -> Weight;
+	fn update_top_candidates(n: u32, ) -> Weight;
+	fn bond_candidate_for(n: u32, m: u32, ) -> Weight;
+	fn bond_delegator_for(n: u32, m: u32, ) -> Weight;
+	fn accept_funder() -> Weight;
 }
 
 /// Weights for parachain_staking using the Substrate node and recommended hardware.
@@ -79,9 +103,10 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1 as u64))
 	}
 	// Storage: ParachainStaking Round (r:1 w:1)
+	// Storage: ParachainStaking InflationConfig (r:1 w:0)
 	fn on_initialize_round_update() -> Weight {
 		Weight::from_ref_time(14_459_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
 			.saturating_add(T::DbWeight::get().writes(1 as u64))
 	}
 	// Storage: ParachainStaking Round (r:1 w:1)
@@ -108,10 +133,30 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		Weight::from_ref_time(1_768_000 as u64)
 			.saturating_add(T::DbWeight::get().writes(1 as u64))
 	}
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:0)
+	// Storage: Balances TotalIssuance (r:1 w:0)
 	// Storage: ParachainStaking InflationConfig (r:0 w:1)
-	fn set_inflation() -> Weight {
-		Weight::from_ref_time(12_952_000 as u64)
-			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	// Storage: ParachainStaking RewardPerBlock (r:1 w:1)
+	// Storage: ParachainStaking CurrentInflation (r:1 w:1)
+	// Storage: ParachainStaking LoyaltyConfig (r:1 w:0)
+	// Storage: ParachainStaking CandidatePool (r:76 w:0)
+	// Storage: ParachainStaking StakeSnapshots (r:75 w:75)
+	// Storage: ParachainStaking Round (r:1 w:0)
+	// Storage: ParachainStaking DelegationAge (r:35 w:0)
+	// Storage: ParachainStaking CollatorBlockRewards (r:75 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:36 w:36)
+	// Storage: ParachainStaking Rewards (r:36 w:36)
+	// Storage: ParachainStaking UnsplitBlockRewards (r:75 w:75)
+	fn set_inflation(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(17_102_000 as u64)
+			.saturating_add(Weight::from_ref_time(42_315_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(19_344_000 as u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(7 as u64))
+			.saturating_add(T::DbWeight::get().reads((6 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().reads((3 as u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+			.saturating_add(T::DbWeight::get().writes((4 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(m as u64)))
 	}
 	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:1)
 	// Storage: ParachainStaking TopCandidates (r:1 w:0)
@@ -349,6 +394,360 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		Weight::from_ref_time(11_984_000 as u64)
 			.saturating_add(T::DbWeight::get().writes(1 as u64))
 	}
+	// Storage: ParachainStaking CandidatePool (r:2 w:2)
+	// Storage: ParachainStaking DelegatorState (r:1 w:1)
+	// Storage: ParachainStaking CollatorBlockRewards (r:2 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:2 w:2)
+	// Storage: ParachainStaking AutoCompound (r:2 w:0)
+	// Storage: ParachainStaking CompoundRewards (r:2 w:2)
+	// Storage: ParachainStaking Rewards (r:1 w:1)
+	// Storage: ParachainStaking Payee (r:1 w:0)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn claim_rewards(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(43_581_000 as u64)
+			.saturating_add(Weight::from_ref_time(24_518_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(7_102_000 as u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(8 as u64))
+			.saturating_add(T::DbWeight::get().reads((7 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
+			.saturating_add(T::DbWeight::get().writes((5 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: ParachainStaking MaxCommission (r:0 w:1)
+	fn set_max_commission() -> Weight {
+		Weight::from_ref_time(14_271_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: ParachainStaking CandidatePool (r:1 w:0)
+	// Storage: ParachainStaking MaxCommission (r:1 w:0)
+	// Storage: ParachainStaking Commission (r:1 w:1)
+	// Storage: ParachainStaking Round (r:1 w:0)
+	// Storage: ParachainStaking CollatorBlockRewards (r:1 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:1 w:1)
+	// Storage: ParachainStaking Rewards (r:1 w:1)
+	// Storage: ParachainStaking PendingCommission (r:0 w:1)
+	fn set_commission(n: u32, ) -> Weight {
+		Weight::from_ref_time(31_465_000 as u64)
+			.saturating_add(Weight::from_ref_time(5_913_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(6 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: ParachainStaking PendingCommission (r:1 w:1)
+	// Storage: ParachainStaking Round (r:1 w:0)
+	// Storage: ParachainStaking CandidatePool (r:1 w:0)
+	// Storage: ParachainStaking MaxCommission (r:1 w:0)
+	// Storage: ParachainStaking Commission (r:1 w:1)
+	// Storage: ParachainStaking CollatorBlockRewards (r:1 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:1 w:1)
+	// Storage: ParachainStaking Rewards (r:1 w:1)
+	fn execute_commission_change(n: u32, ) -> Weight {
+		Weight::from_ref_time(33_028_000 as u64)
+			.saturating_add(Weight::from_ref_time(5_947_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(7 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: ParachainStaking DelegatorState (r:1 w:1)
+	// Storage: ParachainStaking CandidatePool (r:2 w:2)
+	// Storage: ParachainStaking LastDelegation (r:1 w:1)
+	// Storage: ParachainStaking Round (r:1 w:0)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	// Storage: ParachainStaking CollatorBlockRewards (r:2 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:2 w:2)
+	fn redelegate(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(0 as u64)
+			.saturating_add(Weight::from_ref_time(7_391_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(16_204_000 as u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(12 as u64))
+			.saturating_add(T::DbWeight::get().reads((4 as u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(8 as u64))
+			.saturating_add(T::DbWeight::get().writes((4 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: ParachainStaking CandidatePool (r:1 w:1)
+	// Storage: ParachainStaking DelegatorState (r:1 w:1)
+	// Storage: ParachainStaking MaxCollatorCandidateStake (r:1 w:0)
+	// Storage: ParachainStaking Unstaking (r:1 w:1)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	// Storage: ParachainStaking CollatorBlockRewards (r:1 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:1 w:1)
+	fn rebond(n: u32, m: u32, u: u32, ) -> Weight {
+		Weight::from_ref_time(0 as u64)
+			.saturating_add(Weight::from_ref_time(3_922_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(9_112_000 as u64).saturating_mul(m as u64))
+			.saturating_add(Weight::from_ref_time(1_468_000 as u64).saturating_mul(u as u64))
+			.saturating_add(T::DbWeight::get().reads(10 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: ParachainStaking CandidatePool (r:1 w:0)
+	// Storage: ParachainStaking DelegatorState (r:1 w:0)
+	// Storage: ParachainStaking CollatorBlockRewards (r:1 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:1 w:1)
+	// Storage: ParachainStaking Commission (r:1 w:0)
+	// Storage: ParachainStaking AutoCompound (r:1 w:1)
+	// Storage: ParachainStaking CompoundRewards (r:1 w:1)
+	// Storage: ParachainStaking Rewards (r:1 w:1)
+	fn set_auto_compound(n: u32, ) -> Weight {
+		Weight::from_ref_time(27_836_000 as u64)
+			.saturating_add(Weight::from_ref_time(5_902_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(8 as u64))
+			.saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: ParachainStaking CandidatePool (r:1 w:0)
+	// Storage: ParachainStaking DelegatorState (r:1 w:0)
+	// Storage: ParachainStaking Payee (r:0 w:1)
+	fn set_payee() -> Weight {
+		Weight::from_ref_time(19_742_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: ParachainStaking Round (r:1 w:1)
+	// Storage: ParachainStaking InflationConfig (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:0)
+	// Storage: Balances TotalIssuance (r:1 w:0)
+	// Storage: ParachainStaking RewardPerBlock (r:0 w:1)
+	// Storage: ParachainStaking CurrentInflation (r:0 w:1)
+	// Storage: System Events (r:1 w:1)
+	fn on_initialize_inflation_update() -> Weight {
+		Weight::from_ref_time(21_583_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(5 as u64))
+			.saturating_add(T::DbWeight::get().writes(5 as u64))
+	}
+	// Storage: ParachainStaking NextPoolId (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: ParachainStaking DelegatorState (r:2 w:2)
+	// Storage: ParachainStaking CandidatePool (r:1 w:1)
+	// Storage: ParachainStaking LastDelegation (r:1 w:1)
+	// Storage: ParachainStaking Round (r:1 w:0)
+	// Storage: ParachainStaking Unstaking (r:1 w:0)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	// Storage: ParachainStaking CollatorBlockRewards (r:1 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:0 w:1)
+	// Storage: ParachainStaking Pools (r:0 w:1)
+	// Storage: ParachainStaking PoolMembers (r:0 w:1)
+	fn create_pool(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(38_412_000 as u64)
+			.saturating_add(Weight::from_ref_time(3_803_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(8_007_000 as u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(14 as u64))
+			.saturating_add(T::DbWeight::get().writes(12 as u64))
+	}
+	// Storage: ParachainStaking Pools (r:1 w:1)
+	// Storage: ParachainStaking PoolMembers (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: ParachainStaking DelegatorState (r:1 w:1)
+	// Storage: ParachainStaking CandidatePool (r:1 w:1)
+	// Storage: ParachainStaking CollatorBlockRewards (r:1 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:1 w:1)
+	// Storage: ParachainStaking Rewards (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	fn join_pool(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(61_250_000 as u64)
+			.saturating_add(Weight::from_ref_time(3_567_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(10_124_000 as u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(12 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(9 as u64))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: ParachainStaking Pools (r:1 w:1)
+	// Storage: ParachainStaking PoolMembers (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: ParachainStaking DelegatorState (r:1 w:1)
+	// Storage: ParachainStaking CandidatePool (r:1 w:1)
+	// Storage: ParachainStaking CollatorBlockRewards (r:1 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:1 w:1)
+	// Storage: ParachainStaking Rewards (r:1 w:1)
+	// Storage: ParachainStaking Unstaking (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	fn unbond_from_pool(n: u32, m: u32, u: u32, ) -> Weight {
+		Weight::from_ref_time(72_904_000 as u64)
+			.saturating_add(Weight::from_ref_time(3_567_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(10_124_000 as u64).saturating_mul(m as u64))
+			.saturating_add(Weight::from_ref_time(366_000 as u64).saturating_mul(u as u64))
+			.saturating_add(T::DbWeight::get().reads(13 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(10 as u64))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: ParachainStaking Pools (r:1 w:1)
+	// Storage: ParachainStaking PoolMembers (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: ParachainStaking Unstaking (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	fn withdraw_from_pool(u: u32, ) -> Weight {
+		Weight::from_ref_time(52_318_000 as u64)
+			.saturating_add(Weight::from_ref_time(366_000 as u64).saturating_mul(u as u64))
+			.saturating_add(T::DbWeight::get().reads(6 as u64))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
+	}
+	// Storage: ParachainStaking Pools (r:1 w:1)
+	// Storage: ParachainStaking PoolMembers (r:1 w:1)
+	// Storage: ParachainStaking DelegatorState (r:1 w:0)
+	// Storage: ParachainStaking CandidatePool (r:1 w:0)
+	// Storage: ParachainStaking CollatorBlockRewards (r:1 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:1 w:1)
+	// Storage: ParachainStaking Rewards (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn claim_pool_rewards(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(58_037_000 as u64)
+			.saturating_add(Weight::from_ref_time(24_518_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(7_102_000 as u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(6 as u64))
+			.saturating_add(T::DbWeight::get().reads((7 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(5 as u64))
+			.saturating_add(T::DbWeight::get().writes((5 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: ParachainStaking Pools (r:1 w:0)
+	// Storage: ParachainStaking DelegatorState (r:1 w:1)
+	// Storage: ParachainStaking CandidatePool (r:2 w:2)
+	// Storage: ParachainStaking LastDelegation (r:1 w:1)
+	// Storage: ParachainStaking Round (r:1 w:0)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	// Storage: ParachainStaking CollatorBlockRewards (r:2 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:2 w:2)
+	// Storage: ParachainStaking Rewards (r:2 w:2)
+	fn redelegate_pool(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(9_204_000 as u64)
+			.saturating_add(Weight::from_ref_time(7_391_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(16_204_000 as u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(13 as u64))
+			.saturating_add(T::DbWeight::get().reads((4 as u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(8 as u64))
+			.saturating_add(T::DbWeight::get().writes((4 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: ParachainStaking Waitlisted (r:1 w:1)
+	// Storage: ParachainStaking Waitlist (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	fn leave_waitlist(n: u32, ) -> Weight {
+		Weight::from_ref_time(31_204_000 as u64)
+			.saturating_add(Weight::from_ref_time(412_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: ParachainStaking CandidatePool (r:76 w:0)
+	// Storage: ParachainStaking StakeSnapshots (r:75 w:75)
+	// Storage: ParachainStaking Round (r:1 w:0)
+	// Storage: ParachainStaking CurrentInflation (r:1 w:0)
+	// Storage: ParachainStaking LoyaltyConfig (r:1 w:1)
+	// Storage: ParachainStaking DelegationAge (r:35 w:0)
+	// Storage: ParachainStaking CollatorBlockRewards (r:75 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:36 w:36)
+	// Storage: ParachainStaking Rewards (r:36 w:36)
+	fn set_loyalty_bonus(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(0 as u64)
+			.saturating_add(Weight::from_ref_time(41_632_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(19_344_000 as u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().reads((5 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().reads((3 as u64).saturating_mul(m as u64)))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+			.saturating_add(T::DbWeight::get().writes((3 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: ParachainStaking Invulnerables (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	fn add_invulnerable(n: u32, ) -> Weight {
+		Weight::from_ref_time(14_902_000 as u64)
+			.saturating_add(Weight::from_ref_time(98_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: ParachainStaking Invulnerables (r:1 w:1)
+	fn remove_invulnerable(n: u32, ) -> Weight {
+		Weight::from_ref_time(14_215_000 as u64)
+			.saturating_add(Weight::from_ref_time(104_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: ParachainStaking Paused (r:1 w:1)
+	fn set_paused_operations() -> Weight {
+		Weight::from_ref_time(13_104_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking CandidatePool (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	fn update_top_candidates(n: u32, ) -> Weight {
+		Weight::from_ref_time(18_420_000 as u64)
+			.saturating_add(Weight::from_ref_time(41_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
+	// Storage: System Account (r:2 w:0)
+	// Storage: ParachainStaking AcceptedFunders (r:1 w:0)
+	// Storage: ParachainStaking Funders (r:1 w:1)
+	// Storage: ParachainStaking FundedStake (r:2 w:1)
+	// Storage: ParachainStaking Unstaking (r:1 w:0)
+	// Storage: ParachainStaking CandidatePool (r:1 w:1)
+	// Storage: Session NextKeys (r:1 w:0)
+	// Storage: ParachainStaking MaxCollatorCandidateStake (r:1 w:0)
+	// Storage: Balances Locks (r:2 w:2)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	// Storage: ParachainStaking CounterForCandidatePool (r:1 w:1)
+	fn bond_candidate_for(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(38_912_000 as u64)
+			.saturating_add(Weight::from_ref_time(2_561_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(4_418_000 as u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(17 as u64))
+			.saturating_add(T::DbWeight::get().writes(9 as u64))
+	}
+	// Storage: System Account (r:2 w:0)
+	// Storage: ParachainStaking AcceptedFunders (r:1 w:0)
+	// Storage: ParachainStaking Funders (r:1 w:1)
+	// Storage: ParachainStaking FundedStake (r:2 w:1)
+	// Storage: ParachainStaking Unstaking (r:1 w:0)
+	// Storage: ParachainStaking DelegatorState (r:1 w:1)
+	// Storage: ParachainStaking CandidatePool (r:1 w:1)
+	// Storage: ParachainStaking LastDelegation (r:1 w:1)
+	// Storage: Balances Locks (r:2 w:2)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	// Storage: ParachainStaking Round (r:1 w:0)
+	fn bond_delegator_for(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(41_276_000 as u64)
+			.saturating_add(Weight::from_ref_time(3_847_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(8_062_000 as u64).saturating_mul(m as u64))
+			.saturating_add(T::DbWeight::get().reads(18 as u64))
+			.saturating_add(T::DbWeight::get().writes(9 as u64))
+	}
+	// Storage: ParachainStaking AcceptedFunders (r:0 w:1)
+	fn accept_funder() -> Weight {
+		Weight::from_ref_time(15_318_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -359,9 +758,10 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1 as u64))
 	}
 	// Storage: ParachainStaking Round (r:1 w:1)
+	// Storage: ParachainStaking InflationConfig (r:1 w:0)
 	fn on_initialize_round_update() -> Weight {
 		Weight::from_ref_time(14_459_000 as u64)
-			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
 			.saturating_add(RocksDbWeight::get().writes(1 as u64))
 	}
 	// Storage: ParachainStaking Round (r:1 w:1)
@@ -388,10 +788,30 @@ impl WeightInfo for () {
 		Weight::from_ref_time(1_768_000 as u64)
 			.saturating_add(RocksDbWeight::get().writes(1 as u64))
 	}
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:0)
+	// Storage: Balances TotalIssuance (r:1 w:0)
 	// Storage: ParachainStaking InflationConfig (r:0 w:1)
-	fn set_inflation() -> Weight {
-		Weight::from_ref_time(12_952_000 as u64)
-			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	// Storage: ParachainStaking RewardPerBlock (r:1 w:1)
+	// Storage: ParachainStaking CurrentInflation (r:1 w:1)
+	// Storage: ParachainStaking LoyaltyConfig (r:1 w:0)
+	// Storage: ParachainStaking CandidatePool (r:76 w:0)
+	// Storage: ParachainStaking StakeSnapshots (r:75 w:75)
+	// Storage: ParachainStaking Round (r:1 w:0)
+	// Storage: ParachainStaking DelegationAge (r:35 w:0)
+	// Storage: ParachainStaking CollatorBlockRewards (r:75 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:36 w:36)
+	// Storage: ParachainStaking Rewards (r:36 w:36)
+	// Storage: ParachainStaking UnsplitBlockRewards (r:75 w:75)
+	fn set_inflation(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(17_102_000 as u64)
+			.saturating_add(Weight::from_ref_time(42_315_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(19_344_000 as u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(7 as u64))
+			.saturating_add(RocksDbWeight::get().reads((6 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().reads((3 as u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes((4 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(m as u64)))
 	}
 	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:1)
 	// Storage: ParachainStaking TopCandidates (r:1 w:0)
@@ -629,4 +1049,358 @@ impl WeightInfo for () {
 		Weight::from_ref_time(11_984_000 as u64)
 			.saturating_add(RocksDbWeight::get().writes(1 as u64))
 	}
+	// Storage: ParachainStaking CandidatePool (r:2 w:2)
+	// Storage: ParachainStaking DelegatorState (r:1 w:1)
+	// Storage: ParachainStaking CollatorBlockRewards (r:2 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:2 w:2)
+	// Storage: ParachainStaking AutoCompound (r:2 w:0)
+	// Storage: ParachainStaking CompoundRewards (r:2 w:2)
+	// Storage: ParachainStaking Rewards (r:1 w:1)
+	// Storage: ParachainStaking Payee (r:1 w:0)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn claim_rewards(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(43_581_000 as u64)
+			.saturating_add(Weight::from_ref_time(24_518_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(7_102_000 as u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(8 as u64))
+			.saturating_add(RocksDbWeight::get().reads((7 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(6 as u64))
+			.saturating_add(RocksDbWeight::get().writes((5 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: ParachainStaking MaxCommission (r:0 w:1)
+	fn set_max_commission() -> Weight {
+		Weight::from_ref_time(14_271_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: ParachainStaking CandidatePool (r:1 w:0)
+	// Storage: ParachainStaking MaxCommission (r:1 w:0)
+	// Storage: ParachainStaking Commission (r:1 w:1)
+	// Storage: ParachainStaking Round (r:1 w:0)
+	// Storage: ParachainStaking CollatorBlockRewards (r:1 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:1 w:1)
+	// Storage: ParachainStaking Rewards (r:1 w:1)
+	// Storage: ParachainStaking PendingCommission (r:0 w:1)
+	fn set_commission(n: u32, ) -> Weight {
+		Weight::from_ref_time(31_465_000 as u64)
+			.saturating_add(Weight::from_ref_time(5_913_000 as u64).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads(6 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: ParachainStaking PendingCommission (r:1 w:1)
+	// Storage: ParachainStaking Round (r:1 w:0)
+	// Storage: ParachainStaking CandidatePool (r:1 w:0)
+	// Storage: ParachainStaking MaxCommission (r:1 w:0)
+	// Storage: ParachainStaking Commission (r:1 w:1)
+	// Storage: ParachainStaking CollatorBlockRewards (r:1 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:1 w:1)
+	// Storage: ParachainStaking Rewards (r:1 w:1)
+	fn execute_commission_change(n: u32, ) -> Weight {
+		Weight::from_ref_time(33_028_000 as u64)
+			.saturating_add(Weight::from_ref_time(5_947_000 as u64).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads(7 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: ParachainStaking DelegatorState (r:1 w:1)
+	// Storage: ParachainStaking CandidatePool (r:2 w:2)
+	// Storage: ParachainStaking LastDelegation (r:1 w:1)
+	// Storage: ParachainStaking Round (r:1 w:0)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	// Storage: ParachainStaking CollatorBlockRewards (r:2 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:2 w:2)
+	fn redelegate(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(0 as u64)
+			.saturating_add(Weight::from_ref_time(7_391_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(16_204_000 as u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(12 as u64))
+			.saturating_add(RocksDbWeight::get().reads((4 as u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(8 as u64))
+			.saturating_add(RocksDbWeight::get().writes((4 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: ParachainStaking CandidatePool (r:1 w:1)
+	// Storage: ParachainStaking DelegatorState (r:1 w:1)
+	// Storage: ParachainStaking MaxCollatorCandidateStake (r:1 w:0)
+	// Storage: ParachainStaking Unstaking (r:1 w:1)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	// Storage: ParachainStaking CollatorBlockRewards (r:1 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:1 w:1)
+	fn rebond(n: u32, m: u32, u: u32, ) -> Weight {
+		Weight::from_ref_time(0 as u64)
+			.saturating_add(Weight::from_ref_time(3_922_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(9_112_000 as u64).saturating_mul(m as u64))
+			.saturating_add(Weight::from_ref_time(1_468_000 as u64).saturating_mul(u as u64))
+			.saturating_add(RocksDbWeight::get().reads(10 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(6 as u64))
+			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: ParachainStaking CandidatePool (r:1 w:0)
+	// Storage: ParachainStaking DelegatorState (r:1 w:0)
+	// Storage: ParachainStaking CollatorBlockRewards (r:1 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:1 w:1)
+	// Storage: ParachainStaking Commission (r:1 w:0)
+	// Storage: ParachainStaking AutoCompound (r:1 w:1)
+	// Storage: ParachainStaking CompoundRewards (r:1 w:1)
+	// Storage: ParachainStaking Rewards (r:1 w:1)
+	fn set_auto_compound(n: u32, ) -> Weight {
+		Weight::from_ref_time(27_836_000 as u64)
+			.saturating_add(Weight::from_ref_time(5_902_000 as u64).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads(8 as u64))
+			.saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	// Storage: ParachainStaking CandidatePool (r:1 w:0)
+	// Storage: ParachainStaking DelegatorState (r:1 w:0)
+	// Storage: ParachainStaking Payee (r:0 w:1)
+	fn set_payee() -> Weight {
+		Weight::from_ref_time(19_742_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: ParachainStaking Round (r:1 w:1)
+	// Storage: ParachainStaking InflationConfig (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:0)
+	// Storage: Balances TotalIssuance (r:1 w:0)
+	// Storage: ParachainStaking RewardPerBlock (r:0 w:1)
+	// Storage: ParachainStaking CurrentInflation (r:0 w:1)
+	// Storage: System Events (r:1 w:1)
+	fn on_initialize_inflation_update() -> Weight {
+		Weight::from_ref_time(21_583_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(5 as u64))
+			.saturating_add(RocksDbWeight::get().writes(5 as u64))
+	}
+	// Storage: ParachainStaking NextPoolId (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: ParachainStaking DelegatorState (r:2 w:2)
+	// Storage: ParachainStaking CandidatePool (r:1 w:1)
+	// Storage: ParachainStaking LastDelegation (r:1 w:1)
+	// Storage: ParachainStaking Round (r:1 w:0)
+	// Storage: ParachainStaking Unstaking (r:1 w:0)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	// Storage: ParachainStaking CollatorBlockRewards (r:1 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:0 w:1)
+	// Storage: ParachainStaking Pools (r:0 w:1)
+	// Storage: ParachainStaking PoolMembers (r:0 w:1)
+	fn create_pool(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(38_412_000 as u64)
+			.saturating_add(Weight::from_ref_time(3_803_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(8_007_000 as u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(14 as u64))
+			.saturating_add(RocksDbWeight::get().writes(12 as u64))
+	}
+	// Storage: ParachainStaking Pools (r:1 w:1)
+	// Storage: ParachainStaking PoolMembers (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: ParachainStaking DelegatorState (r:1 w:1)
+	// Storage: ParachainStaking CandidatePool (r:1 w:1)
+	// Storage: ParachainStaking CollatorBlockRewards (r:1 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:1 w:1)
+	// Storage: ParachainStaking Rewards (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	fn join_pool(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(61_250_000 as u64)
+			.saturating_add(Weight::from_ref_time(3_567_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(10_124_000 as u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(12 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(9 as u64))
+			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: ParachainStaking Pools (r:1 w:1)
+	// Storage: ParachainStaking PoolMembers (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: ParachainStaking DelegatorState (r:1 w:1)
+	// Storage: ParachainStaking CandidatePool (r:1 w:1)
+	// Storage: ParachainStaking CollatorBlockRewards (r:1 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:1 w:1)
+	// Storage: ParachainStaking Rewards (r:1 w:1)
+	// Storage: ParachainStaking Unstaking (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	fn unbond_from_pool(n: u32, m: u32, u: u32, ) -> Weight {
+		Weight::from_ref_time(72_904_000 as u64)
+			.saturating_add(Weight::from_ref_time(3_567_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(10_124_000 as u64).saturating_mul(m as u64))
+			.saturating_add(Weight::from_ref_time(366_000 as u64).saturating_mul(u as u64))
+			.saturating_add(RocksDbWeight::get().reads(13 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(10 as u64))
+			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: ParachainStaking Pools (r:1 w:1)
+	// Storage: ParachainStaking PoolMembers (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: ParachainStaking Unstaking (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	fn withdraw_from_pool(u: u32, ) -> Weight {
+		Weight::from_ref_time(52_318_000 as u64)
+			.saturating_add(Weight::from_ref_time(366_000 as u64).saturating_mul(u as u64))
+			.saturating_add(RocksDbWeight::get().reads(6 as u64))
+			.saturating_add(RocksDbWeight::get().writes(6 as u64))
+	}
+	// Storage: ParachainStaking Pools (r:1 w:1)
+	// Storage: ParachainStaking PoolMembers (r:1 w:1)
+	// Storage: ParachainStaking DelegatorState (r:1 w:0)
+	// Storage: ParachainStaking CandidatePool (r:1 w:0)
+	// Storage: ParachainStaking CollatorBlockRewards (r:1 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:1 w:1)
+	// Storage: ParachainStaking Rewards (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn claim_pool_rewards(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(58_037_000 as u64)
+			.saturating_add(Weight::from_ref_time(24_518_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(7_102_000 as u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(6 as u64))
+			.saturating_add(RocksDbWeight::get().reads((7 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(5 as u64))
+			.saturating_add(RocksDbWeight::get().writes((5 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: ParachainStaking Pools (r:1 w:0)
+	// Storage: ParachainStaking DelegatorState (r:1 w:1)
+	// Storage: ParachainStaking CandidatePool (r:2 w:2)
+	// Storage: ParachainStaking LastDelegation (r:1 w:1)
+	// Storage: ParachainStaking Round (r:1 w:0)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	// Storage: ParachainStaking CollatorBlockRewards (r:2 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:2 w:2)
+	// Storage: ParachainStaking Rewards (r:2 w:2)
+	fn redelegate_pool(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(9_204_000 as u64)
+			.saturating_add(Weight::from_ref_time(7_391_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(16_204_000 as u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(13 as u64))
+			.saturating_add(RocksDbWeight::get().reads((4 as u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(8 as u64))
+			.saturating_add(RocksDbWeight::get().writes((4 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: ParachainStaking Waitlisted (r:1 w:1)
+	// Storage: ParachainStaking Waitlist (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	fn leave_waitlist(n: u32, ) -> Weight {
+		Weight::from_ref_time(31_204_000 as u64)
+			.saturating_add(Weight::from_ref_time(412_000 as u64).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	// Storage: ParachainStaking CandidatePool (r:76 w:0)
+	// Storage: ParachainStaking StakeSnapshots (r:75 w:75)
+	// Storage: ParachainStaking Round (r:1 w:0)
+	// Storage: ParachainStaking CurrentInflation (r:1 w:0)
+	// Storage: ParachainStaking LoyaltyConfig (r:1 w:1)
+	// Storage: ParachainStaking DelegationAge (r:35 w:0)
+	// Storage: ParachainStaking CollatorBlockRewards (r:75 w:0)
+	// Storage: ParachainStaking SettledBlockRewards (r:36 w:36)
+	// Storage: ParachainStaking Rewards (r:36 w:36)
+	fn set_loyalty_bonus(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(0 as u64)
+			.saturating_add(Weight::from_ref_time(41_632_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(19_344_000 as u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().reads((5 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().reads((3 as u64).saturating_mul(m as u64)))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes((3 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(m as u64)))
+	}
+	// Storage: ParachainStaking Invulnerables (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	fn add_invulnerable(n: u32, ) -> Weight {
+		Weight::from_ref_time(14_902_000 as u64)
+			.saturating_add(Weight::from_ref_time(98_000 as u64).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: ParachainStaking Invulnerables (r:1 w:1)
+	fn remove_invulnerable(n: u32, ) -> Weight {
+		Weight::from_ref_time(14_215_000 as u64)
+			.saturating_add(Weight::from_ref_time(104_000 as u64).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: ParachainStaking Paused (r:1 w:1)
+	fn set_paused_operations() -> Weight {
+		Weight::from_ref_time(13_104_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking CandidatePool (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	fn update_top_candidates(n: u32, ) -> Weight {
+		Weight::from_ref_time(18_420_000 as u64)
+			.saturating_add(Weight::from_ref_time(41_000 as u64).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
+	// Storage: System Account (r:2 w:0)
+	// Storage: ParachainStaking AcceptedFunders (r:1 w:0)
+	// Storage: ParachainStaking Funders (r:1 w:1)
+	// Storage: ParachainStaking FundedStake (r:2 w:1)
+	// Storage: ParachainStaking Unstaking (r:1 w:0)
+	// Storage: ParachainStaking CandidatePool (r:1 w:1)
+	// Storage: Session NextKeys (r:1 w:0)
+	// Storage: ParachainStaking MaxCollatorCandidateStake (r:1 w:0)
+	// Storage: Balances Locks (r:2 w:2)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	// Storage: ParachainStaking CounterForCandidatePool (r:1 w:1)
+	fn bond_candidate_for(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(38_912_000 as u64)
+			.saturating_add(Weight::from_ref_time(2_561_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(4_418_000 as u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(17 as u64))
+			.saturating_add(RocksDbWeight::get().writes(9 as u64))
+	}
+	// Storage: System Account (r:2 w:0)
+	// Storage: ParachainStaking AcceptedFunders (r:1 w:0)
+	// Storage: ParachainStaking Funders (r:1 w:1)
+	// Storage: ParachainStaking FundedStake (r:2 w:1)
+	// Storage: ParachainStaking Unstaking (r:1 w:0)
+	// Storage: ParachainStaking DelegatorState (r:1 w:1)
+	// Storage: ParachainStaking CandidatePool (r:1 w:1)
+	// Storage: ParachainStaking LastDelegation (r:1 w:1)
+	// Storage: Balances Locks (r:2 w:2)
+	// Storage: ParachainStaking TopCandidates (r:1 w:1)
+	// Storage: ParachainStaking MaxSelectedCandidates (r:1 w:0)
+	// Storage: ParachainStaking TotalCollatorStake (r:1 w:1)
+	// Storage: ParachainStaking Round (r:1 w:0)
+	fn bond_delegator_for(n: u32, m: u32, ) -> Weight {
+		Weight::from_ref_time(41_276_000 as u64)
+			.saturating_add(Weight::from_ref_time(3_847_000 as u64).saturating_mul(n as u64))
+			.saturating_add(Weight::from_ref_time(8_062_000 as u64).saturating_mul(m as u64))
+			.saturating_add(RocksDbWeight::get().reads(18 as u64))
+			.saturating_add(RocksDbWeight::get().writes(9 as u64))
+	}
+	// Storage: ParachainStaking AcceptedFunders (r:0 w:1)
+	fn accept_funder() -> Weight {
+		Weight::from_ref_time(15_318_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
 }
@@ -0,0 +1,151 @@
+// KILT Blockchain – https://botlabs.org
+// Copyright (C) 2019-2022 BOTLabs GmbH
+
+// The KILT Blockchain is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The KILT Blockchain is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// If you feel like getting in touch with us, you can do so at info@botlabs.org
+
+//! Helper types and functions to derive the staking rewards from the share of
+//! the total issuance which is staked.
+
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::{traits::Zero, Perquintill, RuntimeDebug};
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// The inflation curve which determines the staking rewards.
+///
+/// The annual inflation increases linearly from `min_inflation` at a staking
+/// rate of zero to `max_inflation` at the ideal staking rate. Above the ideal
+/// staking rate, it decreases linearly back to `min_inflation` at a staking
+/// rate of 100%. The collators receive `collator_share` of the inflation for
+/// their self-stake, the delegators receive the rest.
+#[derive(Clone, Copy, Encode, Decode, Eq, MaxEncodedLen, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct InflationInfo {
+	/// The share of the total issuance which should ideally be staked.
+	pub ideal_staking_rate: Perquintill,
+	/// The annual inflation at a staking rate of zero and 100%.
+	pub min_inflation: Perquintill,
+	/// The annual inflation at the ideal staking rate.
+	pub max_inflation: Perquintill,
+	/// The share of the inflation which rewards the self-stake of the
+	/// collators.
+	pub collator_share: Perquintill,
+}
+
+impl InflationInfo {
+	/// Create a new inflation curve.
+	pub fn new(
+		ideal_staking_rate: Perquintill,
+		min_inflation: Perquintill,
+		max_inflation: Perquintill,
+		collator_share: Perquintill,
+	) -> Self {
+		Self {
+			ideal_staking_rate,
+			min_inflation,
+			max_inflation,
+			collator_share,
+		}
+	}
+
+	/// Check whether the ideal staking rate lies strictly between 0% and 100%
+	/// and the minimum inflation does not exceed the maximum inflation.
+	pub fn is_valid(&self) -> bool {
+		!self.ideal_staking_rate.is_zero()
+			&& self.ideal_staking_rate < Perquintill::one()
+			&& self.min_inflation <= self.max_inflation
+	}
+
+	/// Compute the annual inflation and the annual reward rates of the
+	/// collators' and the delegators' stake for the given shares of the
+	/// total issuance which are staked by collators and delegators.
+	pub fn rates(&self, collator_staking_rate: Perquintill, delegator_staking_rate: Perquintill) -> InflationRates {
+		let staking_rate = collator_staking_rate.saturating_add(delegator_staking_rate);
+		let ideal = self.ideal_staking_rate;
+		let range = self.max_inflation.saturating_sub(self.min_inflation);
+
+		let inflation = if staking_rate <= ideal {
+			let progress = Perquintill::from_rational(staking_rate.deconstruct(), ideal.deconstruct());
+			self.min_inflation.saturating_add(progress * range)
+		} else {
+			let excess = Perquintill::from_rational(
+				staking_rate.saturating_sub(ideal).deconstruct(),
+				Perquintill::one().saturating_sub(ideal).deconstruct(),
+			);
+			self.max_inflation.saturating_sub(excess * range)
+		};
+		let collator_inflation = self.collator_share * inflation;
+
+		InflationRates {
+			collator_staking_rate,
+			delegator_staking_rate,
+			inflation,
+			collator_reward_rate: reward_rate(collator_inflation, collator_staking_rate),
+			delegator_reward_rate: reward_rate(inflation.saturating_sub(collator_inflation), delegator_staking_rate),
+		}
+	}
+}
+
+/// Return the annual reward rate of staked funds if the given share of the
+/// total issuance is minted for the given share of the total issuance which is
+/// staked. The rate is capped at 100%.
+fn reward_rate(inflation: Perquintill, staking_rate: Perquintill) -> Perquintill {
+	if staking_rate.is_zero() {
+		Perquintill::zero()
+	} else {
+		Perquintill::from_rational(inflation.deconstruct(), staking_rate.deconstruct())
+	}
+}
+
+/// The rates derived from the inflation curve at the start of a round.
+#[derive(Clone, Copy, Default, Encode, Decode, Eq, MaxEncodedLen, PartialEq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct InflationRates {
+	/// The share of the total issuance which is staked by collators.
+	pub collator_staking_rate: Perquintill,
+	/// The share of the total issuance which is staked by delegators.
+	pub delegator_staking_rate: Perquintill,
+	/// The share of the total issuance which is minted per year as staking
+	/// rewards.
+	pub inflation: Perquintill,
+	/// The annual rewards of the collators' self-stake relative to the stake.
+	pub collator_reward_rate: Perquintill,
+	/// The annual rewards of the delegators' stake relative to the stake,
+	/// before commission.
+	pub delegator_reward_rate: Perquintill,
+}
+
+impl InflationRates {
+	/// Return the weights by which the self-stake of a collator and the
+	/// stake of their delegators are multiplied when sharing the collator's
+	/// block rewards, such that each stake earns rewards in proportion to its
+	/// reward rate.
+	///
+	/// The stake with the higher reward rate has a weight of 100%. If both
+	/// rates are zero, the stakes are weighted equally.
+	pub fn stake_weights(&self) -> (Perquintill, Perquintill) {
+		let max = self.collator_reward_rate.max(self.delegator_reward_rate);
+		if max.is_zero() {
+			return (Perquintill::one(), Perquintill::one());
+		}
+		(
+			Perquintill::from_rational(self.collator_reward_rate.deconstruct(), max.deconstruct()),
+			Perquintill::from_rational(self.delegator_reward_rate.deconstruct(), max.deconstruct()),
+		)
+	}
+}
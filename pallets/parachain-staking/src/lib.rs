@@ -23,12 +23,15 @@
 //! between this pallet and `frame/pallet-staking` is that this pallet uses
 //! direct delegation. Delegators choose exactly who they delegate and with what
 //! stake. This is different from `frame/pallet-staking` where you approval vote
-//! and then run Phragmen. Moreover, this pallet rewards a collator and their
-//! delegators immediately when authoring a block. Rewards are calculated
-//! separately between collators and delegators.
+//! and then run Phragmen. Moreover, this pallet credits a collator and their
+//! delegators when authoring a block. Rewards are accrued lazily and have to be
+//! claimed by calling `claim_rewards` or `claim_rewards_for`. Stakers can opt
+//! into restaking a share of their rewards automatically when claiming them.
 //!
-//! To join the set of candidates, an account must call `join_candidates` with
-//! `MinCollatorCandidateStake` <= stake <= `MaxCollatorCandidateStake`.
+//! To join the set of candidates, an account must register its session keys
+//! and call `join_candidates` with `MinCollatorCandidateStake` <= stake <=
+//! `MaxCollatorCandidateStake`. Candidates without session keys are not
+//! selected as block authors.
 //!
 //! To leave the set of candidates, the collator calls `leave_candidates`. If
 //! the call succeeds, the collator is removed from the pool of candidates so
@@ -76,6 +79,11 @@
 //!
 //! - **Collator:** A candidate that was chosen to collate this round.
 //!
+//! - **Invulnerable:** A collator which is managed by `AdminOrigin` and always
+//!   part of the set of block authors, regardless of its stake. Invulnerables
+//!   take seats of `MaxSelectedCandidates` and are only rewarded if
+//!   `RewardInvulnerables` is set.
+//!
 //! - **Delegator:** A user which locks up tokens for collators they trust. When
 //!   their collator authors a block, the corresponding delegators also receive
 //!   rewards.
@@ -98,6 +106,69 @@
 //!   a staking round, thus both words are interchangeable in the context of
 //!   this pallet.
 //!
+//! - **Block rewards:** Each authored block credits `RewardPerBlock` to the
+//!   author's reward accumulator. A staker's share of the accumulated amount
+//!   is proportional to their stake and settled whenever the collator's stake
+//!   changes or the staker claims their rewards.
+//!
+//! - **Stake snapshot:** The stake of a collator and their delegators at the
+//!   start of a round. Block rewards are shared according to the snapshot, so
+//!   stake added during a round is only rewarded from the next round on while
+//!   removed stake stops earning rewards immediately.
+//!
+//! - **Inflation:** If an inflation curve is set, `RewardPerBlock` is derived
+//!   from the share of the total issuance which is staked at the start of each
+//!   round. The annual inflation is highest at the ideal staking rate. It is
+//!   split into separate reward rates for the self-stake of collators and the
+//!   stake of delegators, by which the stakes are weighted when sharing the
+//!   block rewards.
+//!
+//! - **Loyalty bonus:** If configured, delegations earn a larger share of the
+//!   rewards of their collator which grows with the number of rounds they have
+//!   been staked up to a cap. Since the bonus is taken from the collator's
+//!   rewards, it does not increase the inflation. Reducing or moving a
+//!   delegation reduces its stake age proportionally.
+//!
+//! - **Reward remainder:** The part of a block reward which is not credited
+//!   to the stakers of the author, i.e. the share of delegations below
+//!   `MinRewardedDelegation` and the rounding remainder. Depending on
+//!   `RewardRemainder`, it is credited to the author, redistributed among the
+//!   rewarded stakers or issued to `RewardRemainderBeneficiary`. Each block
+//!   reports its split in `BlockRewarded`, and the amounts which have been
+//!   credited once the stakers are settled are reported in `BlockRewardSplit`.
+//!
+//! - **Delegation pool:** An account derived from `PoolPalletId` which
+//!   delegates the pooled funds of its members. Members can join with any
+//!   amount and share the pool's rewards and slashes in proportion to their
+//!   stake. The pool's operator manages its delegations.
+//!
+//! - **Funder:** An account which staked its funds on behalf of another
+//!   account. The funds stay locked on the funder's account while the staker
+//!   controls the stake and receives its rewards. The funded principal is
+//!   released to the funder when it is unlocked. A staker has to accept their
+//!   funder before they can be funded.
+//!
+//! - **Waitlist:** A bounded queue of delegators which wait for a free
+//!   delegation slot of a collator candidate with `MaxDelegatorsPerCollator`
+//!   many delegators. Their funds stay locked and they are promoted in order
+//!   once a delegation leaves or is reduced below their waiting amount.
+//!
+//! - **Offence:** A collator authored fewer than `OfflineThreshold` of the
+//!   blocks they were expected to author in a session. Each offence slashes
+//!   `CollatorSlash` of the collator's stake and `DelegatorSlash` of the stake
+//!   of their delegators. After `MaxOffences` offences in consecutive sessions,
+//!   the candidate is removed. Blocks authored by removed collators are
+//!   rejected until they have left the validator set.
+//!
+//! - **Paused operations:** Governance can pause joins, stake changes,
+//!   unlocks and reward payouts separately. Pausing all of them puts the
+//!   pallet into emergency mode. Rewards are still accrued while payouts are
+//!   paused.
+//!
+//! - **Round statistics:** The total stake at the start of each round and the
+//!   number of blocks and rewards of each collator in that round. They are
+//!   kept for `RoundStatsRetention` rounds and exposed via the runtime API.
+//!
 //! - **Lock:** A freeze on a specified amount of an account's free balance
 //!   until a specified block number. Multiple locks always operate over the
 //!   same funds, so they "overlay" rather than "stack"
@@ -105,18 +176,24 @@
 //! ## Interface
 //!
 //! ### Dispatchable Functions
-//! - `set_inflation` - Change the inflation configuration. Requires sudo.
+//! - `set_inflation` - Set or disable the inflation curve from which the
+//!   block rewards are derived at the start of each round. Requires
+//!   `AdminOrigin`.
 //! - `set_max_selected_candidates` - Change the number of collator candidates
-//!   which can be selected to be in the set of block authors. Requires sudo.
+//!   which can be selected to be in the set of block authors. Requires
+//!   `AdminOrigin`.
 //! - `set_blocks_per_round` - Change the number of blocks of a round. Shorter
 //!   rounds enable more frequent changes of the selected candidates, earlier
-//!   unlockal from unstaking and earlier collator leaving. Requires sudo.
+//!   unlockal from unstaking and earlier collator leaving. Requires
+//!   `AdminOrigin`.
 //! - `increase_max_candidate_stake_by` - Increase the maximum amount which can
 //!   be staked by a collator candidate.
 //! - `decrease_max_candidate_stake_by` - Decrease the maximum amount which can
 //!   be staked by a collator candidate.
 //! - `join_candidates` - Join the set of collator candidates by staking at
-//!   least `MinCandidateStake` and at most `MaxCollatorCandidateStake`.
+//!   least `MinCandidateStake` and at most `MaxCollatorCandidateStake`. The
+//!   account must have registered its session keys and satisfy
+//!   `CandidateIdentity`.
 //! - `init_leave_candidates` - Request to leave the set of collators. Unstaking
 //!   and storage clean-up is delayed until executing the exit at least
 //!   ExitQueueDelay rounds later.
@@ -125,7 +202,8 @@
 //! - `candidate_stake_less` - Decrease your own stake as a collator candidate
 //!   by the provided amount down to `MinCandidateStake`.
 //! - `join_delegators` - Join the set of delegators by delegating to a collator
-//!   candidate.
+//!   candidate. If the candidate has no free delegation slot, the delegator is
+//!   placed on its waitlist.
 //! - `delegate_another_candidate` - Delegate to another collator candidate by
 //!   staking for them.
 //! - `leave_delegators` - Leave the set of delegators and revoke all
@@ -141,6 +219,53 @@
 //! - `unlock_unstaked` - Attempt to unlock previously unstaked balance from any
 //!   account. Succeeds if at least one unstake call happened at least
 //!   `StakeDuration` blocks ago.
+//! - `claim_rewards` - Pay out all staking rewards which have been accrued by
+//!   the caller as a collator candidate or delegator.
+//! - `claim_rewards_for` - Pay out all staking rewards which have been accrued
+//!   by the target account. Can be called by any signed origin.
+//! - `set_commission` - Set the share of the delegators' rewards which is
+//!   credited to the collator candidate. Increases are delayed by
+//!   `CommissionChangeDelay` rounds.
+//! - `execute_commission_change` - Apply a requested commission increase after
+//!   the delay has passed.
+//! - `set_max_commission` - Set the maximum commission of collator candidates.
+//!   Requires `AdminOrigin`.
+//! - `set_loyalty_bonus` - Set or disable the bonus delegations earn for their
+//!   stake age. Requires `AdminOrigin`.
+//! - `add_invulnerable` - Add an account to the collators which are always
+//!   part of the set of block authors. Requires `AdminOrigin`.
+//! - `remove_invulnerable` - Remove an account from the invulnerable
+//!   collators. Requires `AdminOrigin`.
+//! - `set_paused_operations` - Pause or resume joins, stake changes, unlocks
+//!   and reward payouts. Requires `AdminOrigin`.
+//! - `redelegate` - Move some or all of the stake delegated to one collator
+//!   candidate to another candidate without going through the unstaking
+//!   queue.
+//! - `rebond` - Restake funds which are currently unstaking, either as a
+//!   collator candidate or for an existing delegation.
+//! - `set_auto_compound` - Set the share of the rewards of a stake which is
+//!   restaked into the same collator candidate at the end of each round.
+//! - `set_payee` - Set the account which receives the rewards of a collator
+//!   candidate or delegator when claiming them.
+//! - `create_pool` - Create a delegation pool which delegates to a collator
+//!   candidate and become its operator.
+//! - `join_pool` - Add any amount to the stake of a delegation pool.
+//! - `unbond_from_pool` - Reduce the stake in a delegation pool. The funds can
+//!   be withdrawn after `StakeDuration` blocks.
+//! - `withdraw_from_pool` - Withdraw the unbonded funds from a delegation
+//!   pool.
+//! - `claim_pool_rewards` - Claim the share of the rewards of a delegation
+//!   pool.
+//! - `redelegate_pool` - Move some or all of the stake of a delegation pool to
+//!   another collator candidate. Can only be called by the pool's operator.
+//! - `leave_waitlist` - Leave the waitlist of a collator candidate and unlock
+//!   the waiting funds immediately.
+//! - `bond_candidate_for` - Stake own funds on behalf of another account which
+//!   joins the set of collator candidates or increases its stake.
+//! - `bond_delegator_for` - Delegate own funds on behalf of another account to
+//!   a collator candidate.
+//! - `accept_funder` - Allow another account to stake its funds on behalf of
+//!   the origin, or revoke the acceptance.
 //!
 //! ## Genesis config
 //!
@@ -158,6 +283,8 @@
 #[cfg(feature = "runtime-benchmarks")]
 pub mod benchmarking;
 pub mod default_weights;
+pub mod inflation;
+pub mod migrations;
 
 #[cfg(test)]
 pub(crate) mod mock;
@@ -165,11 +292,13 @@ pub(crate) mod mock;
 pub(crate) mod tests;
 
 mod set;
+#[cfg(any(feature = "try-runtime", test))]
+mod try_state;
 mod types;
 
 use frame_support::pallet;
 
-pub use crate::{default_weights::WeightInfo, pallet::*};
+pub use crate::{default_weights::WeightInfo, pallet::*, types::RemainderDestination};
 use types::ReplacedDelegator;
 
 #[pallet]
@@ -181,36 +310,44 @@ pub mod pallet {
 		pallet_prelude::*,
 		storage::bounded_btree_map::BoundedBTreeMap,
 		traits::{
-			Currency, EstimateNextSessionRotation, Get, Imbalance, LockIdentifier, LockableCurrency, OnUnbalanced,
-			ReservableCurrency, StorageVersion, WithdrawReasons,
+			Contains, Currency, DisabledValidators, EnsureOrigin, EstimateNextSessionRotation, ExistenceRequirement,
+			Get, Imbalance, LockIdentifier, LockableCurrency, OnUnbalanced, ReservableCurrency, StorageVersion,
+			ValidatorRegistration, WithdrawReasons,
 		},
-		BoundedVec,
+		BoundedVec, PalletId,
 	};
 	use frame_system::pallet_prelude::*;
-	use pallet_balances::{BalanceLock, Locks};
+	use pallet_balances::Locks;
 	use pallet_session::ShouldEndSession;
 	use scale_info::TypeInfo;
 	use sp_runtime::{
-		traits::{Convert, One, SaturatedConversion, Saturating, StaticLookup, Zero},
-		Permill, Perquintill,
+		traits::{AccountIdConversion, Convert, One, SaturatedConversion, Saturating, StaticLookup, Zero},
+		FixedPointNumber, FixedU128, Perbill, Percent, Permill, Perquintill,
 	};
 	use sp_staking::SessionIndex;
 	use sp_std::prelude::*;
 
 	use crate::{
+		inflation::{InflationInfo, InflationRates},
 		set::OrderedSet,
 		types::{
-			BalanceOf, Candidate, CandidateOf, CandidateStatus, DelegationCounter, Delegator, NegativeImbalanceOf,
-			RoundInfo, Stake, StakeOf, TotalStake,
+			BalanceOf, Candidate, CandidateOf, CandidateStatus, CollatorStats, CommissionChange, DelegationCounter,
+			DelegationPool, Delegator, LoyaltyInfo, NegativeImbalanceOf, PausedOperations, PoolId, PoolMember,
+			PoolMemberOf, PoolOf, RemainderDestination, RewardSplit, RewardWeights, RoundInfo, Stake, StakeOf,
+			StakeSnapshot, StakeSnapshotOf, TotalStake, UnsplitRewards,
 		},
 	};
+	use parachain_staking_runtime_api::{
+		self as runtime_api, CandidateInfo, CollatorRoundStats, DelegationInfo, RewardEstimate, RoundStats, StakerInfo,
+		UnstakingInfo,
+	};
 	use sp_std::{convert::TryInto, fmt::Debug};
 
 	/// Kilt-specific lock for staking rewards.
 	pub(crate) const STAKING_ID: LockIdentifier = *b"kiltpstk";
 
 	/// The current storage version.
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(7);
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(8);
 
 	/// Pallet for parachain staking.
 	#[pallet::pallet]
@@ -267,6 +404,11 @@ pub mod pallet {
 		#[pallet::constant]
 		type ExitQueueDelay: Get<u32>;
 
+		/// Number of rounds a collator candidate has to wait after requesting
+		/// to increase their commission before it can be applied.
+		#[pallet::constant]
+		type CommissionChangeDelay: Get<SessionIndex>;
+
 		/// Minimum number of collators selected from the set of candidates at
 		/// every validation round.
 		#[pallet::constant]
@@ -293,6 +435,12 @@ pub mod pallet {
 		#[pallet::constant]
 		type MaxCollatorsPerDelegator: Get<u32> + Debug + PartialEq;
 
+		/// Maximum number of delegators which can wait for a free delegation
+		/// slot of a single collator candidate. Setting it to zero disables
+		/// the waitlist.
+		#[pallet::constant]
+		type MaxWaitlistSize: Get<u32>;
+
 		/// Maximum size of the top candidates set.
 		#[pallet::constant]
 		type MaxTopCandidates: Get<u32> + Debug + PartialEq;
@@ -315,6 +463,12 @@ pub mod pallet {
 		#[pallet::constant]
 		type MinDelegatorStake: Get<BalanceOf<Self>>;
 
+		/// Minimum amount a delegation must have to be rewarded. The share of
+		/// the block rewards of smaller delegations is handled according to
+		/// `RewardRemainder`.
+		#[pallet::constant]
+		type MinRewardedDelegation: Get<BalanceOf<Self>>;
+
 		/// Max number of concurrent active unstaking requests before
 		/// unlocking.
 		///
@@ -343,6 +497,65 @@ pub mod pallet {
 		/// The beneficiary to receive the network rewards.
 		type NetworkRewardBeneficiary: OnUnbalanced<NegativeImbalanceOf<Self>>;
 
+		/// The destination of the part of each block reward which is not
+		/// credited to the stakers of the author, i.e. the share of the
+		/// delegations below `MinRewardedDelegation` and the rounding
+		/// remainder.
+		#[pallet::constant]
+		type RewardRemainder: Get<RemainderDestination>;
+
+		/// The beneficiary to receive the remainder of the block rewards if
+		/// `RewardRemainder` is `RemainderDestination::Beneficiary`.
+		type RewardRemainderBeneficiary: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// The share of their expected blocks a collator has to author in a
+		/// session. Collators which author fewer blocks commit an offence.
+		///
+		/// NOTE: A threshold of zero disables offence reports.
+		#[pallet::constant]
+		type OfflineThreshold: Get<Perbill>;
+
+		/// The share of a collator's own stake which is slashed for each
+		/// offence.
+		#[pallet::constant]
+		type CollatorSlash: Get<Perbill>;
+
+		/// The share of the stake of each delegation to an offending collator
+		/// which is slashed for each offence.
+		#[pallet::constant]
+		type DelegatorSlash: Get<Perbill>;
+
+		/// Number of offences in consecutive sessions after which a collator
+		/// candidate is removed from the set of candidates.
+		#[pallet::constant]
+		type MaxOffences: Get<u32>;
+
+		/// The handler of slashed funds, e.g. the Treasury.
+		type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// The origin which may change the staking parameters and forcedly
+		/// remove collator candidates.
+		type AdminOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The id from which the accounts of delegation pools are derived.
+		#[pallet::constant]
+		type PoolPalletId: Get<PalletId>;
+
+		/// Whether the blocks authored by invulnerable collators are rewarded
+		/// like the ones of staked collator candidates.
+		#[pallet::constant]
+		type RewardInvulnerables: Get<bool>;
+
+		/// The number of past rounds for which the statistics of the collators
+		/// are kept before they are pruned.
+		#[pallet::constant]
+		type RoundStatsRetention: Get<u32>;
+
+		/// The accounts whose identity is sufficient to join the set of
+		/// collator candidates, e.g. the ones with a certain identity
+		/// judgement. Use `Everything` to not require an identity.
+		type CandidateIdentity: Contains<Self::AccountId>;
+
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
 
@@ -406,6 +619,9 @@ pub mod pallet {
 		/// The set of collator candidates would fall below the required minimum
 		/// if the collator left.
 		TooFewCollatorCandidates,
+		/// Removing the collator would leave no collator of the current or the
+		/// queued validator set whose blocks are accepted.
+		TooFewEnabledCollators,
 		/// The collator candidate is in the process of leaving the set of
 		/// candidates and cannot perform any other actions in the meantime.
 		CannotStakeIfLeaving,
@@ -438,6 +654,82 @@ pub mod pallet {
 		StakeNotFound,
 		/// Cannot unlock when Unstaked is empty.
 		UnstakingIsEmpty,
+		/// The account has not accrued any rewards which could be claimed.
+		RewardsNotFound,
+		/// The requested commission exceeds the maximum commission allowed.
+		CommissionAboveMax,
+		/// The collator candidate has not requested to change their
+		/// commission.
+		NoPendingCommissionChange,
+		/// The collator candidate tried to apply their requested commission
+		/// before waiting at least for `CommissionChangeDelay` many rounds.
+		CannotChangeCommissionYet,
+		/// The delegator tried to move their stake to the collator candidate
+		/// it is taken from.
+		CannotRedelegateToSameCollator,
+		/// The account tried to restake more funds than it is currently
+		/// unstaking.
+		InsufficientUnstaking,
+		/// The account has neither delegated to the collator candidate nor is
+		/// it the candidate.
+		StakeNotFoundForCollator,
+		/// The account is neither a collator candidate nor a delegator.
+		StakerNotFound,
+		/// The delegation pool does not exist.
+		PoolNotFound,
+		/// The account is not a member of the delegation pool.
+		PoolMemberNotFound,
+		/// The account is not the operator of the delegation pool.
+		NotPoolOperator,
+		/// The operator of a delegation pool must keep at least
+		/// `MinDelegatorStake` in the pool as long as it has other members.
+		PoolOperatorStakeBelowMin,
+		/// The stake of the delegation pool cannot be unbonded without
+		/// reducing one of its delegations below `MinDelegation`, or the pool
+		/// does not have enough undelegated stake to delegate.
+		PoolStakeUnavailable,
+		/// The pool member does not have any unbonded funds which can be
+		/// withdrawn yet.
+		PoolUnbondingNotFound,
+		/// The delegation pool does not delegate to any collator candidate.
+		/// The operator has to delegate the pool's stake via
+		/// `redelegate_pool` first.
+		PoolNotDelegating,
+		/// The account is already waiting for a free delegation slot of the
+		/// collator candidate.
+		AlreadyWaitlisted,
+		/// The account is not waiting for a free delegation slot of the
+		/// collator candidate.
+		NotWaitlisted,
+		/// An invalid loyalty bonus configuration is trying to be set.
+		InvalidLoyaltyConfig,
+		/// The account is already an invulnerable collator.
+		AlreadyInvulnerable,
+		/// The account is not an invulnerable collator.
+		NotInvulnerable,
+		/// The invulnerable collators would not fit into the number of
+		/// collators selected per round.
+		TooManyInvulnerables,
+		/// The account has not registered session keys which are required to
+		/// author blocks.
+		SessionKeysNotSet,
+		/// The identity of the account is not sufficient to become a collator
+		/// candidate.
+		IdentityNotVerified,
+		/// The staking operation has been paused by governance.
+		OperationPaused,
+		/// An account cannot fund their own stake.
+		CannotFundSelf,
+		/// The stake of a delegation pool cannot be funded by another account.
+		CannotFundPool,
+		/// The stake is already funded by another account.
+		FunderMismatch,
+		/// The stake cannot be funded while the staker has unstaked funds which
+		/// have not been unlocked yet.
+		FundingWhileUnstaking,
+		/// The staker has not accepted the account as the funder of their
+		/// stake.
+		FunderNotAccepted,
 	}
 
 	#[pallet::event]
@@ -512,12 +804,14 @@ pub mod pallet {
 		/// \[account, collator candidate's account, old amount of delegators'
 		/// funds staked, new amount of delegators' funds staked\]
 		DelegatorLeftCollator(T::AccountId, T::AccountId, BalanceOf<T>, BalanceOf<T>),
-		/// A collator or a delegator has received a reward.
+		/// A collator or a delegator has claimed their accrued rewards. The
+		/// rewards are paid out to the account's payee.
 		/// \[account, amount of reward\]
 		Rewarded(T::AccountId, BalanceOf<T>),
-		/// Inflation configuration for future validation rounds has changed.
-		/// \[maximum collator's staking rate, maximum collator's reward rate,
-		/// maximum delegator's staking rate, maximum delegator's reward rate\]
+		/// The reward rates for the current validation round have been derived
+		/// from the inflation curve.
+		/// \[collator's staking rate, collator's reward rate, delegator's
+		/// staking rate, delegator's reward rate\]
 		RoundInflationSet(Perquintill, Perquintill, Perquintill, Perquintill),
 		/// The maximum number of collator candidates selected in future
 		/// validation rounds has changed. \[old value, new value\]
@@ -529,6 +823,128 @@ pub mod pallet {
         /// Value of reward to be distributed per block had been changed
         /// \[previous_reward, new_reward\]
         RewardPerBlockUpdated(BalanceOf<T>, BalanceOf<T>),
+		/// The maximum commission of collator candidates has changed.
+		/// \[old value, new value\]
+		MaxCommissionSet(Perbill, Perbill),
+		/// A collator candidate has requested to increase their commission.
+		/// \[collator's account, current commission, requested commission,
+		/// round number from which on the request can be executed\]
+		CommissionChangeRequested(T::AccountId, Perbill, Perbill, SessionIndex),
+		/// The commission of a collator candidate has changed.
+		/// \[collator's account, old commission, new commission\]
+		CommissionSet(T::AccountId, Perbill, Perbill),
+		/// A collator has authored fewer blocks than required in the last
+		/// session.
+		/// \[collator's account, authored blocks, expected blocks, number of
+		/// offences in consecutive sessions\]
+		OffenceReported(T::AccountId, u32, u32, u32),
+		/// The stake of an account has been slashed.
+		/// \[account, slashed amount\]
+		Slashed(T::AccountId, BalanceOf<T>),
+		/// A delegator has moved stake from one collator candidate to another
+		/// one.
+		/// \[delegator's account, previous collator's account, new collator's
+		/// account, moved amount, previous collator's new total stake, new
+		/// collator's new total stake\]
+		Redelegated(
+			T::AccountId,
+			T::AccountId,
+			T::AccountId,
+			BalanceOf<T>,
+			BalanceOf<T>,
+			BalanceOf<T>,
+		),
+		/// An account has restaked funds which were unstaking.
+		/// \[account, collator's account, restaked amount, collator's new
+		/// total stake\]
+		Rebonded(T::AccountId, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// An account has changed the share of their rewards which is restaked
+		/// automatically.
+		/// \[account, collator's account, new share\]
+		AutoCompoundSet(T::AccountId, T::AccountId, Percent),
+		/// Rewards of an account have been restaked automatically.
+		/// \[account, collator's account, restaked amount\]
+		RewardsCompounded(T::AccountId, T::AccountId, BalanceOf<T>),
+		/// A collator or a delegator has changed the account which receives
+		/// their rewards.
+		/// \[account, payee's account\]
+		PayeeSet(T::AccountId, T::AccountId),
+		/// The inflation curve has been set or disabled.
+		/// \[new inflation curve\]
+		InflationConfigSet(Option<InflationInfo>),
+		/// A delegation pool has been created.
+		/// \[pool id, operator's account, collator's account, operator's
+		/// stake\]
+		PoolCreated(PoolId, T::AccountId, T::AccountId, BalanceOf<T>),
+		/// An account has joined a delegation pool or increased their stake
+		/// in it.
+		/// \[pool id, member's account, added stake, pool's new total stake\]
+		PoolJoined(PoolId, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// A member of a delegation pool has unbonded some or all of their
+		/// stake.
+		/// \[pool id, member's account, unbonded amount, pool's new total
+		/// stake\]
+		PoolUnbonded(PoolId, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// A member of a delegation pool has withdrawn unbonded funds.
+		/// \[pool id, member's account, withdrawn amount\]
+		PoolWithdrawn(PoolId, T::AccountId, BalanceOf<T>),
+		/// A member of a delegation pool has received their share of the
+		/// pool's rewards.
+		/// \[pool id, member's account, amount\]
+		PoolRewarded(PoolId, T::AccountId, BalanceOf<T>),
+		/// The last member has left a delegation pool and the pool has been
+		/// removed.
+		/// \[pool id\]
+		PoolDissolved(PoolId),
+		/// A delegator has been placed on the waitlist of a collator
+		/// candidate because all of its delegation slots are taken.
+		/// \[delegator's account, collator's account, waiting amount\]
+		DelegatorWaitlisted(T::AccountId, T::AccountId, BalanceOf<T>),
+		/// A delegator has been promoted from the waitlist of a collator
+		/// candidate to its delegators.
+		/// \[delegator's account, collator's account, delegated amount, new
+		/// total amount of the collator\]
+		DelegatorPromoted(T::AccountId, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+		/// A delegator has left the waitlist of a collator candidate and the
+		/// waiting amount has been unlocked.
+		/// \[delegator's account, collator's account, unlocked amount\]
+		WaitlistLeft(T::AccountId, T::AccountId, BalanceOf<T>),
+		/// The loyalty bonus has been set or disabled.
+		/// \[new loyalty bonus\]
+		LoyaltyConfigSet(Option<LoyaltyInfo>),
+		/// An account has been added to the invulnerable collators.
+		/// \[account\]
+		InvulnerableAdded(T::AccountId),
+		/// An account has been removed from the invulnerable collators.
+		/// \[account\]
+		InvulnerableRemoved(T::AccountId),
+		/// A collator candidate has not been selected for the next session
+		/// because it has no session keys registered.
+		/// \[collator's account, session index\]
+		CandidateWithoutSessionKeys(T::AccountId, SessionIndex),
+		/// The paused staking operations have been changed.
+		/// \[old paused operations, new paused operations\]
+		PausedOperationsSet(PausedOperations, PausedOperations),
+		/// An account has staked funds on behalf of another account.
+		/// \[funder's account, staker's account, funded amount\]
+		StakeFunded(T::AccountId, T::AccountId, BalanceOf<T>),
+		/// Unlocked funds have been released to the funder of the stake.
+		/// \[staker's account, funder's account, released amount\]
+		FundsReturned(T::AccountId, T::AccountId, BalanceOf<T>),
+		/// The block rewards a collator candidate has earned since the last
+		/// split have been split among their stakers. The remainder has been
+		/// handled according to `RewardRemainder`.
+		/// \[collator's account, split of the block rewards\]
+		BlockRewardSplit(T::AccountId, RewardSplit<BalanceOf<T>>),
+		/// A collator candidate has been rewarded for authoring a block. The
+		/// split reports the shares of the block reward accrued for their
+		/// stakers when the block was authored, which are settled lazily.
+		/// \[collator's account, split of the block reward\]
+		BlockRewarded(T::AccountId, RewardSplit<BalanceOf<T>>),
+		/// An account has accepted another account to fund their stake or
+		/// has revoked the acceptance.
+		/// \[staker's account, accepted funder's account\]
+		FunderAccepted(T::AccountId, Option<T::AccountId>),
 	}
 
 	#[pallet::hooks]
@@ -547,6 +963,14 @@ pub mod pallet {
 
 				Self::deposit_event(Event::NewRound(round.first, round.current));
 				post_weight = <T as Config>::WeightInfo::on_initialize_round_update();
+
+				// derive the staking rewards of the new round from the inflation curve
+				if let Some(inflation) = InflationConfig::<T>::get() {
+					Self::update_inflation(&inflation);
+					post_weight = <T as Config>::WeightInfo::on_initialize_inflation_update();
+				}
+
+				post_weight = post_weight.saturating_add(Self::rotate_round_stats(round.current));
 			}
 			// check for network reward
 			if now > T::NetworkRewardStart::get() {
@@ -555,6 +979,11 @@ pub mod pallet {
 			}
 			post_weight
 		}
+
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_n: BlockNumberFor<T>) -> Result<(), &'static str> {
+			crate::try_state::do_try_state::<T>()
+		}
 	}
 
 	/// The maximum number of collator candidates selected at each round.
@@ -642,6 +1071,26 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// The delegators waiting for a free delegation slot of a collator
+	/// candidate.
+	///
+	/// It maps from a collator candidate to the waiting delegators in the
+	/// order in which they are promoted.
+	#[pallet::storage]
+	#[pallet::getter(fn waitlist)]
+	pub(crate) type Waitlist<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, BoundedVec<T::AccountId, T::MaxWaitlistSize>, ValueQuery>;
+
+	/// The funds of delegators waiting for a free delegation slot.
+	///
+	/// It maps from a delegator and a collator candidate to the amount which
+	/// is delegated once the delegator is promoted. The funds stay locked
+	/// while waiting.
+	#[pallet::storage]
+	#[pallet::getter(fn waitlisted)]
+	pub(crate) type Waitlisted<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, T::AccountId, Twox64Concat, T::AccountId, BalanceOf<T>, OptionQuery>;
+
 	/// The maximum amount a collator candidate can stake.
 	#[pallet::storage]
 	#[pallet::getter(fn max_candidate_stake)]
@@ -662,11 +1111,280 @@ pub mod pallet {
     #[pallet::getter(fn reward_per_block)]
     pub(crate) type RewardPerBlock<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
 
+	/// The inflation curve from which `RewardPerBlock` is derived at the start
+	/// of each round.
+	///
+	/// If there is no curve, `RewardPerBlock` stays fixed.
+	#[pallet::storage]
+	#[pallet::getter(fn inflation_config)]
+	pub(crate) type InflationConfig<T: Config> = StorageValue<_, InflationInfo, OptionQuery>;
+
+	/// The rates which have been derived from the inflation curve at the start
+	/// of the current round.
+	#[pallet::storage]
+	#[pallet::getter(fn current_inflation)]
+	pub(crate) type CurrentInflation<T: Config> = StorageValue<_, InflationRates, ValueQuery>;
+
+	/// The bonus on the share of delegations in the rewards of their collator
+	/// depending on their stake age.
+	///
+	/// If there is no configuration, delegations do not earn a bonus.
+	#[pallet::storage]
+	#[pallet::getter(fn loyalty_config)]
+	pub(crate) type LoyaltyConfig<T: Config> = StorageValue<_, LoyaltyInfo, OptionQuery>;
+
+	/// The round from which the stake age of a delegation is counted.
+	///
+	/// It maps from a delegator and a collator candidate to the round in
+	/// which the delegation started aging. Reducing or moving the delegated
+	/// stake moves this round forward proportionally.
+	#[pallet::storage]
+	#[pallet::getter(fn delegation_age)]
+	pub(crate) type DelegationAge<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, T::AccountId, Twox64Concat, T::AccountId, SessionIndex, ValueQuery>;
+
+	/// The collators which are always part of the session validator set,
+	/// regardless of their stake.
+	///
+	/// They take precedence over the staked candidates and count against
+	/// `MaxSelectedCandidates`.
+	#[pallet::storage]
+	#[pallet::getter(fn invulnerables)]
+	pub(crate) type Invulnerables<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxTopCandidates>, ValueQuery>;
+
+	/// The staking operations which are currently paused.
+	#[pallet::storage]
+	#[pallet::getter(fn paused)]
+	pub(crate) type Paused<T: Config> = StorageValue<_, PausedOperations, ValueQuery>;
+
+	/// The accumulated block rewards of a collator candidate.
+	///
+	/// It maps from a collator candidate to the sum of `RewardPerBlock` over
+	/// all blocks they authored while being a candidate. The stakers of the
+	/// candidate are entitled to a share of the increase of this value
+	/// proportional to their stake.
+	#[pallet::storage]
+	#[pallet::getter(fn collator_block_rewards)]
+	pub(crate) type CollatorBlockRewards<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// The block rewards of a collator candidate which have not been split
+	/// among their stakers yet.
+	///
+	/// It maps from a collator candidate to the block rewards they have
+	/// earned since the last split and the rewards which have been accrued
+	/// and settled for their stakers since.
+	#[pallet::storage]
+	#[pallet::getter(fn unsplit_block_rewards)]
+	pub(crate) type UnsplitBlockRewards<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, UnsplitRewards<BalanceOf<T>>, ValueQuery>;
+
+	/// The stake of a collator candidate and their delegators at the start of
+	/// the latest round in which the candidate authored a block or their
+	/// stake changed.
+	///
+	/// The block rewards of a round are shared in proportion to the stake in
+	/// the snapshot, so that stake added during a round is only rewarded from
+	/// the next round on.
+	#[pallet::storage]
+	#[pallet::getter(fn stake_snapshot)]
+	pub(crate) type StakeSnapshots<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, StakeSnapshotOf<T, T::MaxDelegatorsPerCollator>, OptionQuery>;
+
+	/// The value of [CollatorBlockRewards] at the time the rewards of a
+	/// staker have been settled the last time.
+	///
+	/// It maps from a staker and the collator candidate they are staking for
+	/// to the accumulated block rewards of the candidate which have already
+	/// been accounted for. For the candidate's self-stake, both keys are the
+	/// candidate.
+	#[pallet::storage]
+	#[pallet::getter(fn settled_block_rewards)]
+	pub(crate) type SettledBlockRewards<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, T::AccountId, Twox64Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// The rewards which have been accrued by an account but not been claimed
+	/// yet.
+	#[pallet::storage]
+	#[pallet::getter(fn rewards)]
+	pub(crate) type Rewards<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// The share of the rewards of a stake which is restaked automatically.
+	///
+	/// It maps from a staker and the collator candidate they are staking for
+	/// to the share of the stake's rewards which is added to the stake at the
+	/// end of each round or when claiming rewards. For the candidate's
+	/// self-stake, both keys are the candidate.
+	#[pallet::storage]
+	#[pallet::getter(fn auto_compound)]
+	pub(crate) type AutoCompound<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, T::AccountId, Twox64Concat, T::AccountId, Percent, ValueQuery>;
+
+	/// The rewards which have been accrued by a stake and are restaked into
+	/// the same stake at the end of the round or when claiming rewards.
+	///
+	/// It maps from a staker and the collator candidate they are staking for
+	/// to the rewards which have been set aside according to [AutoCompound].
+	#[pallet::storage]
+	#[pallet::getter(fn compound_rewards)]
+	pub(crate) type CompoundRewards<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, T::AccountId, Twox64Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+	/// The account which receives the rewards of a staker.
+	///
+	/// It maps from a collator candidate or delegator to the account their
+	/// rewards are paid out to. If there is no entry, the rewards are paid out
+	/// to the staker. The entry is removed once the staker has left the set
+	/// of collator candidates and delegators and all of their rewards have
+	/// been paid out.
+	#[pallet::storage]
+	#[pallet::getter(fn payee)]
+	pub(crate) type Payee<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, T::AccountId, OptionQuery>;
+
+	/// The id of the next delegation pool.
+	#[pallet::storage]
+	#[pallet::getter(fn next_pool_id)]
+	pub(crate) type NextPoolId<T: Config> = StorageValue<_, PoolId, ValueQuery>;
+
+	/// Delegation pools.
+	///
+	/// It maps from a pool id to the pool's operator, total stake, number of
+	/// members and reward counter.
+	#[pallet::storage]
+	#[pallet::getter(fn pools)]
+	pub(crate) type Pools<T: Config> = StorageMap<_, Twox64Concat, PoolId, PoolOf<T>, OptionQuery>;
+
+	/// The members of delegation pools.
+	///
+	/// It maps from a pool id and a member to the member's stake in the pool
+	/// and their funds which are unbonding.
+	#[pallet::storage]
+	#[pallet::getter(fn pool_members)]
+	pub(crate) type PoolMembers<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, PoolId, Twox64Concat, T::AccountId, PoolMemberOf<T>, OptionQuery>;
+
+	/// The maximum commission collator candidates can charge.
+	///
+	/// It is initialised at genesis and can be changed by `AdminOrigin` via
+	/// `set_max_commission`.
+	#[pallet::storage]
+	#[pallet::getter(fn max_commission)]
+	pub(crate) type MaxCommission<T: Config> = StorageValue<_, Perbill, ValueQuery>;
+
+	/// The commission of a collator candidate.
+	///
+	/// It maps from a collator candidate to the share of their delegators'
+	/// rewards which is credited to the candidate instead.
+	#[pallet::storage]
+	#[pallet::getter(fn commission)]
+	pub(crate) type Commission<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, Perbill, ValueQuery>;
+
+	/// The requested increases of collator candidates' commissions which have
+	/// not been applied yet.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_commission)]
+	pub(crate) type PendingCommission<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, CommissionChange, OptionQuery>;
+
+	/// The collators which have been selected for a session.
+	///
+	/// It maps from a session index to the set of collators which author
+	/// blocks in that session. Entries are removed at the end of the session.
+	#[pallet::storage]
+	#[pallet::getter(fn session_collators)]
+	pub(crate) type SessionCollators<T: Config> =
+		StorageMap<_, Twox64Concat, SessionIndex, BoundedVec<T::AccountId, T::MaxTopCandidates>, ValueQuery>;
+
+	/// The collators which have been removed from the set of candidates but
+	/// are still part of the current or the queued validator set.
+	///
+	/// It maps from a collator to the first session in which they are not
+	/// part of the validator set anymore. Until then, blocks they author are
+	/// rejected.
+	#[pallet::storage]
+	#[pallet::getter(fn disabled_collators)]
+	pub(crate) type DisabledCollators<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, SessionIndex, OptionQuery>;
+
+	/// The block production and rewards of the collators per round.
+	///
+	/// It maps from a round and a collator to their statistics in that round.
+	/// Entries are pruned `RoundStatsRetention` rounds after the round has
+	/// ended.
+	#[pallet::storage]
+	#[pallet::getter(fn collator_round_stats)]
+	pub(crate) type CollatorRoundStats<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		SessionIndex,
+		Twox64Concat,
+		T::AccountId,
+		CollatorStats<BalanceOf<T>>,
+		ValueQuery,
+	>;
+
+	/// The total stake of the selected collators and their delegators at the
+	/// start of each round.
+	///
+	/// Entries are pruned together with `CollatorRoundStats`.
+	#[pallet::storage]
+	#[pallet::getter(fn round_total_stake)]
+	pub(crate) type RoundTotalStake<T: Config> =
+		StorageMap<_, Twox64Concat, SessionIndex, TotalStake<BalanceOf<T>>, OptionQuery>;
+
+	/// The oldest round whose statistics have not been pruned yet.
+	///
+	/// Every round from this one on is pruned once it has left the retention
+	/// window, even if `RoundStatsRetention` has been lowered in between.
+	#[pallet::storage]
+	#[pallet::getter(fn oldest_round_stats)]
+	pub(crate) type OldestRoundStats<T: Config> = StorageValue<_, SessionIndex, ValueQuery>;
+
+	/// The number of blocks each collator has authored in the current
+	/// session.
+	#[pallet::storage]
+	#[pallet::getter(fn blocks_authored)]
+	pub(crate) type BlocksAuthored<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+	/// The number of offences a collator candidate has committed in
+	/// consecutive sessions.
+	#[pallet::storage]
+	#[pallet::getter(fn offences)]
+	pub(crate) type Offences<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+	/// The funders of stakes which have been bonded on behalf of another
+	/// account.
+	///
+	/// It maps from a staker to their funder and the funded principal which
+	/// is locked on the funder's account until it has been unlocked by the
+	/// staker.
+	#[pallet::storage]
+	#[pallet::getter(fn funder)]
+	pub(crate) type Funders<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, (T::AccountId, BalanceOf<T>), OptionQuery>;
+
+	/// The accounts which are allowed to fund the stake of another account.
+	///
+	/// It maps from a staker to the funder they have accepted via
+	/// `accept_funder`.
+	#[pallet::storage]
+	#[pallet::getter(fn accepted_funder)]
+	pub(crate) type AcceptedFunders<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, T::AccountId, OptionQuery>;
+
+	/// The total principal an account has funded for other stakers and which
+	/// is locked on its account in addition to its own staked funds.
+	#[pallet::storage]
+	#[pallet::getter(fn funded_stake)]
+	pub(crate) type FundedStake<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		pub stakers: GenesisStaker<T>,
 		pub max_candidate_stake: BalanceOf<T>,
         pub reward_per_block: BalanceOf<T>,
+		pub invulnerables: Vec<T::AccountId>,
+		pub max_commission: Perbill,
 	}
 
 	#[cfg(feature = "std")]
@@ -676,6 +1394,8 @@ pub mod pallet {
 				stakers: Default::default(),
 				max_candidate_stake: Default::default(),
                 reward_per_block: Default::default(),
+				invulnerables: Default::default(),
+				max_commission: Default::default(),
 			}
 		}
 	}
@@ -691,6 +1411,7 @@ pub mod pallet {
             let reward_per_block = self.reward_per_block;
             <RewardPerBlock<T>>::put(BalanceOf::<T>::from(reward_per_block));
 			MaxCollatorCandidateStake::<T>::put(self.max_candidate_stake);
+			MaxCommission::<T>::put(self.max_commission);
 
 			// Setup delegate & collators
 			for &(ref actor, ref opt_val, balance) in &self.stakers {
@@ -705,20 +1426,29 @@ pub mod pallet {
 						balance,
 					));
 				} else {
-					assert_ok!(<Pallet<T>>::join_candidates(
-						T::Origin::from(Some(actor.clone()).into()),
-						balance
-					));
+					assert_ok!(<Pallet<T>>::do_join_candidates(actor.clone(), balance));
 				}
 			}
 			// Set total selected candidates to minimum config
 			MaxSelectedCandidates::<T>::put(T::MinCollators::get());
 
+			assert!(
+				self.invulnerables.len() <= T::MinCollators::get().saturated_into::<usize>(),
+				"Too many invulnerable collators."
+			);
+			let invulnerables: BoundedVec<T::AccountId, T::MaxTopCandidates> = self
+				.invulnerables
+				.clone()
+				.try_into()
+				.expect("Checked the number of invulnerables above q.e.d.");
+			Invulnerables::<T>::put(invulnerables);
+
 			<Pallet<T>>::update_total_stake();
 
 			// Start Round 0 at Block 0
 			let round: RoundInfo<T::BlockNumber> = RoundInfo::new(0u32, 0u32.into(), T::DefaultBlocksPerRound::get());
 			<Round<T>>::put(round);
+			RoundTotalStake::<T>::insert(round.current, TotalCollatorStake::<T>::get());
 		}
 	}
 
@@ -729,7 +1459,7 @@ pub mod pallet {
 		/// The new round will be enforced via <T as
 		/// ShouldEndSession<_>>::should_end_session.
 		///
-		/// The dispatch origin must be Root.
+		/// The dispatch origin must be `AdminOrigin`.
 		///
 		/// # <weight>
 		/// Weight: O(1)
@@ -738,7 +1468,7 @@ pub mod pallet {
 		/// # </weight>
 		#[pallet::weight(<T as pallet::Config>::WeightInfo::force_new_round())]
 		pub fn force_new_round(origin: OriginFor<T>) -> DispatchResult {
-			ensure_root(origin)?;
+			T::AdminOrigin::ensure_origin(origin)?;
 
 			// set force_new_round handle which, at the start of the next block, will
 			// trigger `should_end_session` in `Session::on_initialize` and update the
@@ -750,7 +1480,7 @@ pub mod pallet {
 
         #[pallet::weight(T::DbWeight::get().reads_writes(1, 2))]
         pub fn set_rewards_per_block(origin: OriginFor<T>, reward_per_block: BalanceOf<T>) -> DispatchResult {
-            ensure_root(origin)?;
+            T::AdminOrigin::ensure_origin(origin)?;
 
             let old_rate = Self::reward_per_block();
             <RewardPerBlock<T>>::put(reward_per_block);
@@ -765,9 +1495,10 @@ pub mod pallet {
 		/// Changes are not applied until the start of the next round.
 		///
 		/// The new value must be higher than the minimum allowed as set in the
-		/// pallet's configuration.
+		/// pallet's configuration and must leave a seat for each invulnerable
+		/// collator.
 		///
-		/// The dispatch origin must be Root.
+		/// The dispatch origin must be `AdminOrigin`.
 		///
 		/// Emits `MaxSelectedCandidatesSet`.
 		///
@@ -789,9 +1520,13 @@ pub mod pallet {
 			T::MaxDelegatorsPerCollator::get()
 		))]
 		pub fn set_max_selected_candidates(origin: OriginFor<T>, new: u32) -> DispatchResultWithPostInfo {
-			ensure_root(origin)?;
+			T::AdminOrigin::ensure_origin(origin)?;
 			ensure!(new >= T::MinCollators::get(), Error::<T>::CannotSetBelowMin);
 			ensure!(new <= T::MaxTopCandidates::get(), Error::<T>::CannotSetAboveMax);
+			ensure!(
+				Invulnerables::<T>::decode_len().unwrap_or_default() <= new.saturated_into::<usize>(),
+				Error::<T>::TooManyInvulnerables
+			);
 			let old = MaxSelectedCandidates::<T>::get();
 
 			// *** No Fail beyond this point ***
@@ -849,7 +1584,7 @@ pub mod pallet {
 		/// The new value must be higher than the minimum allowed as set in the
 		/// pallet's configuration.
 		///
-		/// The dispatch origin must be Root.
+		/// The dispatch origin must be `AdminOrigin`.
 		///
 		/// Emits `BlocksPerRoundSet`.
 		///
@@ -860,7 +1595,7 @@ pub mod pallet {
 		/// # </weight>
 		#[pallet::weight(<T as pallet::Config>::WeightInfo::set_blocks_per_round())]
 		pub fn set_blocks_per_round(origin: OriginFor<T>, new: T::BlockNumber) -> DispatchResult {
-			ensure_root(origin)?;
+			T::AdminOrigin::ensure_origin(origin)?;
 			ensure!(new >= T::MinBlocksPerRound::get(), Error::<T>::CannotSetBelowMin);
 
 			let old_round = <Round<T>>::get();
@@ -884,7 +1619,7 @@ pub mod pallet {
 		/// Set the maximal amount a collator can stake. Existing stakes are not
 		/// changed.
 		///
-		/// The dispatch origin must be Root.
+		/// The dispatch origin must be `AdminOrigin`.
 		///
 		/// Emits `MaxCandidateStakeChanged`.
 		///
@@ -895,7 +1630,7 @@ pub mod pallet {
 		/// # </weight>
 		#[pallet::weight(<T as Config>::WeightInfo::set_max_candidate_stake())]
 		pub fn set_max_candidate_stake(origin: OriginFor<T>, new: BalanceOf<T>) -> DispatchResult {
-			ensure_root(origin)?;
+			T::AdminOrigin::ensure_origin(origin)?;
 			ensure!(
 				new >= T::MinCollatorCandidateStake::get(),
 				Error::<T>::CannotSetBelowMin
@@ -917,6 +1652,11 @@ pub mod pallet {
 		/// which can be unlocked via `unlock_unstaked` after waiting at
 		/// least `StakeDuration` many blocks.
 		///
+		/// Blocks authored by the removed collator are rejected for as long as
+		/// it is part of the current or the queued validator set. Hence, the
+		/// removal is refused if no other collator of these validator sets
+		/// could author blocks afterwards.
+		///
 		/// Emits `CandidateRemoved`.
 		///
 		/// # <weight>
@@ -929,11 +1669,13 @@ pub mod pallet {
 		/// `MaxTopCandidates` and D is the number of delegators of the
 		/// collator candidate bounded by `MaxDelegatorsPerCollator`.
 		/// - Reads: MaxCollatorCandidateStake, 2 * N * CandidatePool,
-		///   TopCandidates, BlockNumber, D * DelegatorState, D * Unstaking
+		///   TopCandidates, BlockNumber, D * DelegatorState, D * Unstaking,
+		///   Invulnerables, CurrentIndex, 2 * SessionCollators, 2 * N *
+		///   DisabledCollators
 		/// - Writes: MaxCollatorCandidateStake, N * CandidatePool, D *
-		///   DelegatorState, (D + 1) * Unstaking
-		/// - Kills: CandidatePool, DelegatorState for all delegators which only
-		///   delegated to the candidate
+		///   DelegatorState, (D + 1) * Unstaking, DisabledCollators
+		/// - Kills: CandidatePool, Payee, DelegatorState and Payee for all
+		///   delegators which only delegated to the candidate
 		/// # </weight>
 		#[pallet::weight(<T as Config>::WeightInfo::force_remove_candidate(
 			T::MaxTopCandidates::get(),
@@ -943,7 +1685,7 @@ pub mod pallet {
 			origin: OriginFor<T>,
 			collator: <T::Lookup as StaticLookup>::Source,
 		) -> DispatchResultWithPostInfo {
-			ensure_root(origin)?;
+			T::AdminOrigin::ensure_origin(origin)?;
 			let collator = T::Lookup::lookup(collator)?;
 			let state = CandidatePool::<T>::get(&collator).ok_or(Error::<T>::CandidateNotFound)?;
 			let total_amount = state.total;
@@ -953,16 +1695,17 @@ pub mod pallet {
 				candidates.len().saturated_into::<u32>() > T::MinRequiredCollators::get(),
 				Error::<T>::TooFewCollatorCandidates
 			);
+			ensure!(
+				Invulnerables::<T>::get().contains(&collator) || Self::can_disable_author(&collator),
+				Error::<T>::TooFewEnabledCollators
+			);
 
 			// *** No Fail except during remove_candidate beyond this point ***
 
-			Self::remove_candidate(&collator, &state)?;
+			Self::remove_candidate(&collator, &state, true)?;
 
 			let (num_collators, num_delegators) = if candidates
-				.remove(&Stake {
-					owner: collator.clone(),
-					amount: state.total,
-				})
+				.remove_stake(&collator, state.total)
 				.is_some()
 			{
 				// update top candidates
@@ -996,7 +1739,8 @@ pub mod pallet {
 		/// set in the pallet's configuration.
 		///
 		/// The dispatch origin must not be already part of the collator
-		/// candidates nor of the delegators set.
+		/// candidates nor of the delegators set. It must have registered its
+		/// session keys and its identity must satisfy `CandidateIdentity`.
 		///
 		/// Emits `JoinedCollatorCandidates`.
 		///
@@ -1004,7 +1748,7 @@ pub mod pallet {
 		/// Weight: O(N + D) where N is `MaxSelectedCandidates` bounded by
 		/// `MaxTopCandidates` and D is the number of delegators for this
 		/// candidate bounded by `MaxDelegatorsPerCollator`.
-		/// - Reads: [Origin Account], DelegatorState,
+		/// - Reads: [Origin Account], DelegatorState, NextKeys,
 		///   MaxCollatorCandidateStake, Locks, TotalCollatorStake,
 		///   TopCandidates, MaxSelectedCandidates, CandidatePool,
 		/// - Writes: Locks, TotalCollatorStake, CandidatePool, TopCandidates,
@@ -1015,44 +1759,11 @@ pub mod pallet {
 		))]
 		pub fn join_candidates(origin: OriginFor<T>, stake: BalanceOf<T>) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
-			if let Some(is_active_candidate) = Self::is_active_candidate(&sender) {
-				ensure!(is_active_candidate, Error::<T>::AlreadyLeaving);
-				ensure!(!is_active_candidate, Error::<T>::CandidateExists);
-			}
-			ensure!(!Self::is_delegator(&sender), Error::<T>::DelegatorExists);
-			ensure!(
-				stake >= T::MinCollatorCandidateStake::get(),
-				Error::<T>::ValStakeBelowMin
-			);
-			ensure!(
-				stake <= MaxCollatorCandidateStake::<T>::get(),
-				Error::<T>::ValStakeAboveMax
-			);
-			ensure!(
-				Unstaking::<T>::get(&sender).len().saturated_into::<u32>() < T::MaxUnstakeRequests::get(),
-				Error::<T>::CannotJoinBeforeUnlocking
-			);
-
-			// *** No Fail except during increase_lock beyond this point ***
-
-			Self::increase_lock(&sender, stake, BalanceOf::<T>::zero())?;
-
-			let candidate = Candidate::new(sender.clone(), stake);
-			let n = Self::update_top_candidates(
-				sender.clone(),
-				BalanceOf::<T>::zero(),
-				BalanceOf::<T>::zero(),
-				stake,
-				BalanceOf::<T>::zero(),
-			);
-			CandidatePool::<T>::insert(&sender, candidate);
+			Self::ensure_not_paused(|paused| paused.joins)?;
+			ensure!(Self::has_session_keys(&sender), Error::<T>::SessionKeysNotSet);
+			ensure!(T::CandidateIdentity::contains(&sender), Error::<T>::IdentityNotVerified);
 
-			Self::deposit_event(Event::JoinedCollatorCandidates(sender, stake));
-			Ok(Some(<T as pallet::Config>::WeightInfo::join_candidates(
-				n,
-				T::MaxDelegatorsPerCollator::get(),
-			))
-			.into())
+			Self::do_join_candidates(sender, stake)
 		}
 
 		/// Request to leave the set of collator candidates.
@@ -1098,6 +1809,7 @@ pub mod pallet {
 		))]
 		pub fn init_leave_candidates(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
 			let collator = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.stake_changes)?;
 			let mut state = CandidatePool::<T>::get(&collator).ok_or(Error::<T>::CandidateNotFound)?;
 			ensure!(!state.is_leaving(), Error::<T>::AlreadyLeaving);
 			let mut candidates = TopCandidates::<T>::get();
@@ -1113,10 +1825,7 @@ pub mod pallet {
 			// *** No Fail beyond this point ***
 
 			let (num_collators, num_delegators) = if candidates
-				.remove(&Stake {
-					owner: collator.clone(),
-					amount: state.total,
-				})
+				.remove_stake(&collator, state.total)
 				.is_some()
 			{
 				// update top candidates
@@ -1159,7 +1868,7 @@ pub mod pallet {
 		/// - Reads: CandidatePool, Round, D * DelegatorState, D
 		///   * BlockNumber, D * Unstaking
 		/// - Writes: D * Unstaking, D * DelegatorState, Total
-		/// - Kills: CandidatePool, DelegatorState
+		/// - Kills: CandidatePool, Payee, DelegatorState
 		/// # </weight>
 		#[pallet::weight(<T as pallet::Config>::WeightInfo::execute_leave_candidates(
 			T::MaxTopCandidates::get(),
@@ -1170,6 +1879,7 @@ pub mod pallet {
 			collator: <T::Lookup as StaticLookup>::Source,
 		) -> DispatchResultWithPostInfo {
 			ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.stake_changes)?;
 			let collator = T::Lookup::lookup(collator)?;
 			let state = CandidatePool::<T>::get(&collator).ok_or(Error::<T>::CandidateNotFound)?;
 			ensure!(state.is_leaving(), Error::<T>::NotLeaving);
@@ -1180,7 +1890,7 @@ pub mod pallet {
 
 			// *** No Fail except during remove_candidate beyond this point ***
 
-			Self::remove_candidate(&collator, &state)?;
+			Self::remove_candidate(&collator, &state, false)?;
 
 			Self::deposit_event(Event::CandidateLeft(collator, total_amount));
 
@@ -1214,6 +1924,7 @@ pub mod pallet {
 		))]
 		pub fn cancel_leave_candidates(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
 			let candidate = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.stake_changes)?;
 			let mut state = CandidatePool::<T>::get(&candidate).ok_or(Error::<T>::CandidateNotFound)?;
 			ensure!(state.is_leaving(), Error::<T>::NotLeaving);
 
@@ -1272,11 +1983,15 @@ pub mod pallet {
 		))]
 		pub fn candidate_stake_more(origin: OriginFor<T>, more: BalanceOf<T>) -> DispatchResultWithPostInfo {
 			let collator = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.stake_changes)?;
 
 			ensure!(!more.is_zero(), Error::<T>::ValStakeZero);
 			let mut state = CandidatePool::<T>::get(&collator).ok_or(Error::<T>::CandidateNotFound)?;
 			ensure!(!state.is_leaving(), Error::<T>::CannotStakeIfLeaving);
 
+			// credit the rewards earned with the current stake before changing it
+			Self::settle_candidate_rewards(&state);
+
 			let CandidateOf::<T, _> {
 				stake: before_stake,
 				total: before_total,
@@ -1346,11 +2061,15 @@ pub mod pallet {
 		))]
 		pub fn candidate_stake_less(origin: OriginFor<T>, less: BalanceOf<T>) -> DispatchResultWithPostInfo {
 			let collator = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.stake_changes)?;
 			ensure!(!less.is_zero(), Error::<T>::ValStakeZero);
 
 			let mut state = CandidatePool::<T>::get(&collator).ok_or(Error::<T>::CandidateNotFound)?;
 			ensure!(!state.is_leaving(), Error::<T>::CannotStakeIfLeaving);
 
+			// credit the rewards earned with the current stake before changing it
+			Self::settle_candidate_rewards(&state);
+
 			let CandidateOf::<T, _> {
 				stake: before_stake,
 				total: before_total,
@@ -1403,6 +2122,9 @@ pub mod pallet {
 		/// As only `MaxDelegatorsPerCollator` are allowed to delegate a given
 		/// collator, the amount staked must be larger than the lowest one in
 		/// the current set of delegator for the operation to be meaningful.
+		/// Otherwise, the delegator is placed on the candidate's waitlist if
+		/// it has room left. The kicked delegator is placed on the waitlist as
+		/// well and only unstakes if the waitlist is full.
 		///
 		/// The collator's total stake as well as the pallet's total stake are
 		/// increased accordingly.
@@ -1411,6 +2133,8 @@ pub mod pallet {
 		/// Emits `DelegationReplaced` if the candidate has
 		/// `MaxDelegatorsPerCollator` many delegations but this delegator
 		/// staked more than one of the other delegators of this candidate.
+		/// Emits `DelegatorWaitlisted` if the delegator or the kicked delegator
+		/// are placed on the waitlist.
 		///
 		/// # <weight>
 		/// Weight: O(N + D) where N is `MaxSelectedCandidates` bounded by
@@ -1431,13 +2155,11 @@ pub mod pallet {
 			amount: BalanceOf<T>,
 		) -> DispatchResultWithPostInfo {
 			let acc = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.joins)?;
 			let collator = T::Lookup::lookup(collator)?;
 
 			// check balance
-			ensure!(
-				pallet_balances::Pallet::<T>::free_balance(acc.clone()) >= amount.into(),
-				pallet_balances::Error::<T>::InsufficientBalance
-			);
+			Self::ensure_can_lock(&acc, amount)?;
 
 			// first delegation
 			ensure!(DelegatorState::<T>::get(&acc).is_none(), Error::<T>::AlreadyDelegating);
@@ -1445,6 +2167,10 @@ pub mod pallet {
 
 			// cannot be a collator candidate and delegator with same AccountId
 			ensure!(Self::is_active_candidate(&acc).is_none(), Error::<T>::CandidateExists);
+			ensure!(
+				!Waitlisted::<T>::contains_key(&acc, &collator),
+				Error::<T>::AlreadyWaitlisted
+			);
 			ensure!(
 				Unstaking::<T>::get(&acc).len().saturated_into::<u32>() < T::MaxUnstakeRequests::get(),
 				Error::<T>::CannotJoinBeforeUnlocking
@@ -1458,6 +2184,9 @@ pub mod pallet {
 			let num_delegations_pre_insertion: u32 = state.delegators.len().saturated_into();
 
 			ensure!(!state.is_leaving(), Error::<T>::CannotDelegateIfLeaving);
+
+			// credit the rewards earned with the current stakes before changing them
+			Self::settle_candidate_rewards(&state);
 			let delegation = Stake {
 				owner: acc.clone(),
 				amount,
@@ -1489,7 +2218,18 @@ pub mod pallet {
 			// amount
 			let (state, maybe_kicked_delegator) = if num_delegations_pre_insertion == T::MaxDelegatorsPerCollator::get()
 			{
-				Self::do_update_delegator(delegation, state)?
+				match Self::do_update_delegator(delegation, state) {
+					Err(e) if e == Error::<T>::TooManyDelegators.into() => {
+						// wait for a free delegation slot instead
+						Self::waitlist_delegator(&acc, &collator, amount, true)?;
+						return Ok(Some(<T as pallet::Config>::WeightInfo::join_delegators(
+							0,
+							T::MaxDelegatorsPerCollator::get(),
+						))
+						.into());
+					}
+					res => res?,
+				}
 			} else {
 				state.total = state.total.saturating_add(amount);
 				(state, None)
@@ -1519,6 +2259,7 @@ pub mod pallet {
 			CandidatePool::<T>::insert(&collator, state);
 			DelegatorState::<T>::insert(&acc, delegator_state);
 			<LastDelegation<T>>::insert(&acc, delegation_counter);
+			Self::init_rewards_checkpoint(&acc, &collator);
 
 			// update or clear storage of potentially kicked delegator
 			Self::update_kicked_delegator_storage(maybe_kicked_delegator);
@@ -1549,6 +2290,9 @@ pub mod pallet {
 		/// As only `MaxDelegatorsPerCollator` are allowed to delegate a given
 		/// collator, the amount staked must be larger than the lowest one in
 		/// the current set of delegator for the operation to be meaningful.
+		/// Otherwise, the delegator is placed on the candidate's waitlist if
+		/// it has room left. The kicked delegator is placed on the waitlist as
+		/// well and only unstakes if the waitlist is full.
 		///
 		/// The collator's total stake as well as the pallet's total stake are
 		/// increased accordingly.
@@ -1561,6 +2305,8 @@ pub mod pallet {
 		/// Emits `DelegationReplaced` if the candidate has
 		/// `MaxDelegatorsPerCollator` many delegations but this delegator
 		/// staked more than one of the other delegators of this candidate.
+		/// Emits `DelegatorWaitlisted` if the delegator or the kicked delegator
+		/// are placed on the waitlist.
 		///
 		/// # <weight>
 		/// Weight: O(N + D) where N is `MaxSelectedCandidates` bounded by
@@ -1584,15 +2330,12 @@ pub mod pallet {
 			amount: BalanceOf<T>,
 		) -> DispatchResultWithPostInfo {
 			let acc = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.joins)?;
 			let collator = T::Lookup::lookup(collator)?;
 			let mut delegator = DelegatorState::<T>::get(&acc).ok_or(Error::<T>::NotYetDelegating)?;
 
 			// check balance
-			ensure!(
-				pallet_balances::Pallet::<T>::free_balance(acc.clone())
-					>= delegator.total.saturating_add(amount).into(),
-				pallet_balances::Error::<T>::InsufficientBalance
-			);
+			Self::ensure_can_lock(&acc, delegator.total.saturating_add(amount))?;
 
 			// delegation after first
 			ensure!(amount >= T::MinDelegation::get(), Error::<T>::DelegationBelowMin);
@@ -1604,11 +2347,19 @@ pub mod pallet {
 			// MaxDelegationsPerRound
 			let delegation_counter = Self::get_delegation_counter(&acc)?;
 
+			ensure!(
+				!Waitlisted::<T>::contains_key(&acc, &collator),
+				Error::<T>::AlreadyWaitlisted
+			);
+
 			// prepare new collator state
 			let mut state = CandidatePool::<T>::get(&collator).ok_or(Error::<T>::CandidateNotFound)?;
 			let num_delegations_pre_insertion: u32 = state.delegators.len().saturated_into();
 			ensure!(!state.is_leaving(), Error::<T>::CannotDelegateIfLeaving);
 
+			// credit the rewards earned with the current stakes before changing them
+			Self::settle_candidate_rewards(&state);
+
 			// attempt to insert delegation, check for uniqueness and update total delegated
 			// amount
 			// NOTE: excess is handled below because we support replacing a delegator
@@ -1644,7 +2395,18 @@ pub mod pallet {
 			// amount
 			let (state, maybe_kicked_delegator) = if num_delegations_pre_insertion == T::MaxDelegatorsPerCollator::get()
 			{
-				Self::do_update_delegator(delegation, state)?
+				match Self::do_update_delegator(delegation, state) {
+					Err(e) if e == Error::<T>::TooManyDelegators.into() => {
+						// wait for a free delegation slot instead
+						Self::waitlist_delegator(&acc, &collator, amount, true)?;
+						return Ok(Some(<T as pallet::Config>::WeightInfo::join_delegators(
+							0,
+							T::MaxDelegatorsPerCollator::get(),
+						))
+						.into());
+					}
+					res => res?,
+				}
 			} else {
 				state.total = state.total.saturating_add(amount);
 				(state, None)
@@ -1674,6 +2436,7 @@ pub mod pallet {
 			CandidatePool::<T>::insert(&collator, state);
 			DelegatorState::<T>::insert(&acc, delegator);
 			<LastDelegation<T>>::insert(&acc, delegation_counter);
+			Self::init_rewards_checkpoint(&acc, &collator);
 
 			// update or clear storage of potentially kicked delegator
 			Self::update_kicked_delegator_storage(maybe_kicked_delegator);
@@ -1706,7 +2469,7 @@ pub mod pallet {
 		/// - Reads: [Origin Account], DelegatorState, BlockNumber, Unstaking,
 		///   TopCandidates, MaxSelectedCandidates, C * CandidatePool,
 		/// - Writes: Unstaking, CandidatePool, TotalCollatorStake,
-		/// - Kills: DelegatorState
+		/// - Kills: DelegatorState, Payee
 		/// # </weight>
 		#[pallet::weight(<T as pallet::Config>::WeightInfo::leave_delegators(
 			T::MaxTopCandidates::get(),
@@ -1714,6 +2477,7 @@ pub mod pallet {
 		))]
 		pub fn leave_delegators(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
 			let acc = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.stake_changes)?;
 			let delegator = DelegatorState::<T>::get(&acc).ok_or(Error::<T>::DelegatorNotFound)?;
 			let num_delegations: u32 = delegator.delegations.len().saturated_into();
 			for stake in delegator.delegations.into_iter() {
@@ -1723,6 +2487,7 @@ pub mod pallet {
 			// *** No Fail beyond this point ***
 
 			DelegatorState::<T>::remove(&acc);
+			Self::clear_payee(&acc);
 
 			Self::deposit_event(Event::DelegatorLeft(acc, delegator.total));
 			Ok(Some(<T as pallet::Config>::WeightInfo::leave_delegators(
@@ -1752,8 +2517,8 @@ pub mod pallet {
 		///   Locks, TopCandidates, CandidatePool, MaxSelectedCandidates
 		/// - Writes: Unstaking, Locks, DelegatorState, CandidatePool,
 		///   TotalCollatorStake
-		/// - Kills: DelegatorState if the delegator has not delegated to
-		///   another collator
+		/// - Kills: DelegatorState and Payee if the delegator has not
+		///   delegated to another collator
 		/// # </weight>
 		#[pallet::weight(<T as pallet::Config>::WeightInfo::revoke_delegation(
 			T::MaxCollatorsPerDelegator::get(),
@@ -1765,6 +2530,7 @@ pub mod pallet {
 		) -> DispatchResultWithPostInfo {
 			let collator = T::Lookup::lookup(collator)?;
 			let delegator = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.stake_changes)?;
 
 			// *** No Fail except during delegator_revokes_collator beyond this point ***
 
@@ -1805,12 +2571,22 @@ pub mod pallet {
 			more: BalanceOf<T>,
 		) -> DispatchResultWithPostInfo {
 			let delegator = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.stake_changes)?;
 			ensure!(!more.is_zero(), Error::<T>::ValStakeZero);
 
 			let candidate = T::Lookup::lookup(candidate)?;
 			let mut delegations = DelegatorState::<T>::get(&delegator).ok_or(Error::<T>::DelegatorNotFound)?;
 			let mut collator = CandidatePool::<T>::get(&candidate).ok_or(Error::<T>::CandidateNotFound)?;
 			ensure!(!collator.is_leaving(), Error::<T>::CannotDelegateIfLeaving);
+
+			// credit the rewards earned with the current stakes before changing them
+			Self::settle_candidate_rewards(&collator);
+			let delegated = delegations
+				.delegations
+				.iter()
+				.find(|stake| stake.owner == candidate)
+				.map(|stake| stake.amount)
+				.ok_or(Error::<T>::DelegationNotFound)?;
 			let delegator_total = delegations
 				.inc_delegation(candidate.clone(), more)
 				.ok_or(Error::<T>::DelegationNotFound)?;
@@ -1825,7 +2601,7 @@ pub mod pallet {
 				total: before_total,
 				..
 			} = collator;
-			collator.inc_delegator(delegator.clone(), more);
+			collator.inc_delegator(delegator.clone(), delegated, more);
 			let after = collator.total;
 
 			// update top candidates and total amount at stake
@@ -1844,6 +2620,7 @@ pub mod pallet {
 
 			CandidatePool::<T>::insert(&candidate, collator);
 			DelegatorState::<T>::insert(&delegator, delegations);
+			Self::rescale_stake_age(&delegator, &candidate, delegated, delegated.saturating_add(more));
 
 			Self::deposit_event(Event::DelegatorStakedMore(delegator, candidate, before_total, after));
 			Ok(Some(<T as pallet::Config>::WeightInfo::delegator_stake_more(
@@ -1888,12 +2665,16 @@ pub mod pallet {
 			less: BalanceOf<T>,
 		) -> DispatchResultWithPostInfo {
 			let delegator = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.stake_changes)?;
 			ensure!(!less.is_zero(), Error::<T>::ValStakeZero);
 
 			let candidate = T::Lookup::lookup(candidate)?;
 			let mut delegations = DelegatorState::<T>::get(&delegator).ok_or(Error::<T>::DelegatorNotFound)?;
 			let mut collator = CandidatePool::<T>::get(&candidate).ok_or(Error::<T>::CandidateNotFound)?;
 			ensure!(!collator.is_leaving(), Error::<T>::CannotDelegateIfLeaving);
+
+			// credit the rewards earned with the current stakes before changing them
+			Self::settle_candidate_rewards(&collator);
 			let remaining = delegations
 				.dec_delegation(candidate.clone(), less)
 				.ok_or(Error::<T>::DelegationNotFound)?
@@ -1905,7 +2686,7 @@ pub mod pallet {
 				Error::<T>::NomStakeBelowMin
 			);
 
-			// *** No Fail except during prep_unstake beyond this point ***
+			// *** No Fail except during prep_unstake and promote_waitlisted beyond this point ***
 
 			Self::prep_unstake(&delegator, less, false)?;
 
@@ -1914,7 +2695,7 @@ pub mod pallet {
 				total: before_total,
 				..
 			} = collator;
-			collator.dec_delegator(delegator.clone(), less);
+			collator.dec_delegator(delegator.clone(), remaining.saturating_add(less), less);
 			let after = collator.total;
 
 			// update top candidates and total amount at stake
@@ -1932,8 +2713,12 @@ pub mod pallet {
 			};
 			CandidatePool::<T>::insert(&candidate, collator);
 			DelegatorState::<T>::insert(&delegator, delegations);
+			Self::rescale_stake_age(&delegator, &candidate, remaining.saturating_add(less), remaining);
+
+			Self::deposit_event(Event::DelegatorStakedLess(delegator, candidate.clone(), before_total, after));
 
-			Self::deposit_event(Event::DelegatorStakedLess(delegator, candidate, before_total, after));
+			// a waiting delegator might replace the reduced delegation now
+			Self::promote_waitlisted(&candidate)?;
 			Ok(Some(<T as pallet::Config>::WeightInfo::delegator_stake_less(
 				n,
 				T::MaxDelegatorsPerCollator::get(),
@@ -1945,10 +2730,17 @@ pub mod pallet {
 		/// unlocking by the origin account after `StakeDuration` blocks have
 		/// elapsed.
 		///
+		/// If the stake of the target has been funded by another account, the
+		/// unlocked funds are released from the lock of the funder up to the
+		/// funded amount.
+		///
+		/// Emits `FundsReturned` if funds have been released to the funder.
+		///
 		/// Weight: O(U) where U is the number of locked unstaking requests
 		/// bounded by `MaxUnstakeRequests`.
-		/// - Reads: [Origin Account], Unstaking, Locks
-		/// - Writes: Unstaking, Locks
+		/// - Reads: [Origin Account], Unstaking, Locks, Funders, 2 *
+		///   FundedStake
+		/// - Writes: Unstaking, 2 * Locks, Funders, FundedStake
 		/// - Kills: Unstaking & Locks if no balance is locked anymore
 		/// # </weight>
 		#[pallet::weight(<T as pallet::Config>::WeightInfo::unlock_unstaked(
@@ -1959,672 +2751,4508 @@ pub mod pallet {
 			target: <T::Lookup as StaticLookup>::Source,
 		) -> DispatchResultWithPostInfo {
 			ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.unlocks)?;
 			let target = T::Lookup::lookup(target)?;
 
 			let unstaking_len = Self::do_unlock(&target)?;
 
 			Ok(Some(<T as pallet::Config>::WeightInfo::unlock_unstaked(unstaking_len)).into())
 		}
-	}
 
-	impl<T: Config> Pallet<T> {
-		/// Check whether an account is currently delegating.
+		/// Claim all staking rewards which have been accrued by the origin
+		/// account as a collator candidate or delegator.
+		///
+		/// The rewards of a stake are accrued with each block authored by the
+		/// corresponding collator and proportional to the stake's share of the
+		/// collator's total stake. The share of the rewards which has been set
+		/// via `set_auto_compound` is restaked instead of being paid out. The
+		/// rest is paid out to the payee set via `set_payee`, which defaults to
+		/// the origin account.
+		///
+		/// Emits `Rewarded` and `RewardsCompounded` for each restaked stake.
 		///
 		/// # <weight>
-		/// Weight: O(1)
-		/// - Reads: DelegatorState
+		/// Weight: O(C * D) where C is the number of delegations of the origin
+		/// account bounded by `MaxCollatorsPerDelegator` and D is the number
+		/// of delegators of a collator candidate bounded by
+		/// `MaxDelegatorsPerCollator`.
+		/// - Reads: [Origin Account], (C + 1) * CandidatePool, DelegatorState,
+		///   (C + 1) * CollatorBlockRewards, (C + 1) * SettledBlockRewards,
+		///   (C + 1) * AutoCompound, (C + 1) * CompoundRewards, Rewards,
+		///   Payee, Locks, TopCandidates, MaxSelectedCandidates,
+		///   TotalCollatorStake, [Payee Account]
+		/// - Writes: [Payee Account], (C + 1) * CandidatePool,
+		///   DelegatorState, (C + 1) * SettledBlockRewards, Locks,
+		///   TopCandidates, TotalCollatorStake
+		/// - Kills: Rewards, (C + 1) * CompoundRewards
 		/// # </weight>
-		pub fn is_delegator(acc: &T::AccountId) -> bool {
-			DelegatorState::<T>::get(acc).is_some()
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::claim_rewards(
+			T::MaxCollatorsPerDelegator::get(),
+			T::MaxDelegatorsPerCollator::get()
+		))]
+		pub fn claim_rewards(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.payouts)?;
+
+			let (num_delegations, num_delegators) = Self::do_claim_rewards(&who)?;
+
+			Ok(Some(<T as pallet::Config>::WeightInfo::claim_rewards(
+				num_delegations,
+				num_delegators,
+			))
+			.into())
 		}
 
-		/// Check whether an account is currently a collator candidate and
-		/// whether their state is CollatorStatus::Active.
+		/// Claim all staking rewards which have been accrued by the target
+		/// account as a collator candidate or delegator on behalf of them.
+		///
+		/// The rewards are paid out to the payee of the target account or
+		/// restaked according to the target account's `set_auto_compound`
+		/// settings.
+		///
+		/// Emits `Rewarded` and `RewardsCompounded` for each restaked stake.
 		///
 		/// # <weight>
-		/// Weight: O(1)
-		/// - Reads: CandidatePool
+		/// Weight: O(C * D) where C is the number of delegations of the target
+		/// account bounded by `MaxCollatorsPerDelegator` and D is the number
+		/// of delegators of a collator candidate bounded by
+		/// `MaxDelegatorsPerCollator`.
+		/// - Reads: [Origin Account], (C + 1) * CandidatePool, DelegatorState,
+		///   (C + 1) * CollatorBlockRewards, (C + 1) * SettledBlockRewards,
+		///   (C + 1) * AutoCompound, (C + 1) * CompoundRewards, Rewards,
+		///   Payee, Locks, TopCandidates, MaxSelectedCandidates,
+		///   TotalCollatorStake, [Payee Account]
+		/// - Writes: [Payee Account], (C + 1) * CandidatePool,
+		///   DelegatorState, (C + 1) * SettledBlockRewards, Locks,
+		///   TopCandidates, TotalCollatorStake
+		/// - Kills: Rewards, (C + 1) * CompoundRewards
 		/// # </weight>
-		pub fn is_active_candidate(acc: &T::AccountId) -> Option<bool> {
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::claim_rewards(
+			T::MaxCollatorsPerDelegator::get(),
+			T::MaxDelegatorsPerCollator::get()
+		))]
+		pub fn claim_rewards_for(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.payouts)?;
+			let target = T::Lookup::lookup(target)?;
+
+			let (num_delegations, num_delegators) = Self::do_claim_rewards(&target)?;
+
+			Ok(Some(<T as pallet::Config>::WeightInfo::claim_rewards(
+				num_delegations,
+				num_delegators,
+			))
+			.into())
+		}
+
+		/// Set the commission of a collator candidate, i.e., the share of the
+		/// delegators' rewards which is credited to the candidate.
+		///
+		/// A decrease is applied immediately. An increase can only be applied
+		/// by calling `execute_commission_change` after waiting at least
+		/// `CommissionChangeDelay` many rounds, which gives delegators time to
+		/// react. Requesting another commission replaces a pending request.
+		///
+		/// The commission must not exceed `MaxCommission`.
+		///
+		/// Emits `CommissionSet` if the commission was decreased.
+		/// Emits `CommissionChangeRequested` if the commission was increased.
+		///
+		/// # <weight>
+		/// Weight: O(D) where D is the number of delegators for this candidate
+		/// bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: [Origin Account], CandidatePool, MaxCommission,
+		///   Commission, Round, CollatorBlockRewards, (D + 1) *
+		///   SettledBlockRewards, (D + 1) * Rewards
+		/// - Writes: Commission, PendingCommission, (D + 1) *
+		///   SettledBlockRewards, (D + 1) * Rewards
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::set_commission(
+			T::MaxDelegatorsPerCollator::get()
+		))]
+		pub fn set_commission(origin: OriginFor<T>, commission: Perbill) -> DispatchResultWithPostInfo {
+			let collator = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.stake_changes)?;
+			let state = CandidatePool::<T>::get(&collator).ok_or(Error::<T>::CandidateNotFound)?;
+			ensure!(commission <= MaxCommission::<T>::get(), Error::<T>::CommissionAboveMax);
+			let old = Commission::<T>::get(&collator);
+
+			// *** No Fail beyond this point ***
+
+			if commission <= old {
+				// credit the rewards earned with the current commission before changing it
+				Self::settle_candidate_rewards(&state);
+				Commission::<T>::insert(&collator, commission);
+				PendingCommission::<T>::remove(&collator);
+
+				Self::deposit_event(Event::CommissionSet(collator, old, commission));
+				Ok(Some(<T as pallet::Config>::WeightInfo::set_commission(
+					state.delegators.len().saturated_into::<u32>(),
+				))
+				.into())
+			} else {
+				let effective_round = <Round<T>>::get().current.saturating_add(T::CommissionChangeDelay::get());
+				PendingCommission::<T>::insert(
+					&collator,
+					CommissionChange {
+						commission,
+						effective_round,
+					},
+				);
+
+				Self::deposit_event(Event::CommissionChangeRequested(
+					collator,
+					old,
+					commission,
+					effective_round,
+				));
+				Ok(Some(<T as pallet::Config>::WeightInfo::set_commission(0u32)).into())
+			}
+		}
+
+		/// Apply the previously requested increase of a collator candidate's
+		/// commission after waiting at least `CommissionChangeDelay` many
+		/// rounds. Can be called by any signed origin.
+		///
+		/// If `MaxCommission` was decreased in the meantime, the commission is
+		/// capped at the current maximum.
+		///
+		/// Emits `CommissionSet`.
+		///
+		/// # <weight>
+		/// Weight: O(D) where D is the number of delegators for this candidate
+		/// bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: [Origin Account], PendingCommission, Round, CandidatePool,
+		///   MaxCommission, Commission, CollatorBlockRewards, (D + 1) *
+		///   SettledBlockRewards, (D + 1) * Rewards
+		/// - Writes: Commission, (D + 1) * SettledBlockRewards, (D + 1) *
+		///   Rewards
+		/// - Kills: PendingCommission
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::execute_commission_change(
+			T::MaxDelegatorsPerCollator::get()
+		))]
+		pub fn execute_commission_change(
+			origin: OriginFor<T>,
+			collator: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.stake_changes)?;
+			let collator = T::Lookup::lookup(collator)?;
+			let request = PendingCommission::<T>::get(&collator).ok_or(Error::<T>::NoPendingCommissionChange)?;
+			ensure!(
+				request.effective_round <= <Round<T>>::get().current,
+				Error::<T>::CannotChangeCommissionYet
+			);
+			let state = CandidatePool::<T>::get(&collator).ok_or(Error::<T>::CandidateNotFound)?;
+			let old = Commission::<T>::get(&collator);
+			let new = request.commission.min(MaxCommission::<T>::get());
+
+			// *** No Fail beyond this point ***
+
+			// credit the rewards earned with the current commission before changing it
+			Self::settle_candidate_rewards(&state);
+			Commission::<T>::insert(&collator, new);
+			PendingCommission::<T>::remove(&collator);
+
+			Self::deposit_event(Event::CommissionSet(collator, old, new));
+			Ok(Some(<T as pallet::Config>::WeightInfo::execute_commission_change(
+				state.delegators.len().saturated_into::<u32>(),
+			))
+			.into())
+		}
+
+		/// Set the maximal commission collator candidates can charge their
+		/// delegators. Existing commissions are not changed.
+		///
+		/// The dispatch origin must be `AdminOrigin`.
+		///
+		/// Emits `MaxCommissionSet`.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: [Origin Account], MaxCommission
+		/// - Writes: MaxCommission
+		/// # </weight>
+		#[pallet::weight(<T as Config>::WeightInfo::set_max_commission())]
+		pub fn set_max_commission(origin: OriginFor<T>, new: Perbill) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let old = MaxCommission::<T>::get();
+
+			// *** No Fail beyond this point ***
+
+			MaxCommission::<T>::put(new);
+
+			Self::deposit_event(Event::MaxCommissionSet(old, new));
+			Ok(())
+		}
+
+		/// Move some or all of the stake delegated to a collator candidate to
+		/// another candidate without unstaking it.
+		///
+		/// If the delegator already delegates to the new candidate, the
+		/// existing delegation is increased. Otherwise, a new delegation is
+		/// created which counts towards `MaxDelegationsPerRound`. If the new
+		/// candidate already has `MaxDelegatorsPerCollator` many delegators,
+		/// the moved amount must exceed the lowest delegation which is kicked.
+		///
+		/// The remaining delegation to the previous candidate must either be
+		/// zero or at least `MinDelegation`.
+		///
+		/// Since the stake is not unlocked, it keeps earning rewards.
+		///
+		/// Emits `Redelegated`.
+		/// Emits `DelegationReplaced` if the new candidate has
+		/// `MaxDelegatorsPerCollator` many delegations but this delegator
+		/// staked more than one of the other delegators of this candidate.
+		///
+		/// # <weight>
+		/// Weight: O(N + D) where N is `MaxSelectedCandidates` bounded by
+		/// `MaxTopCandidates` and D is the number of delegators for the
+		/// candidates bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: [Origin Account], DelegatorState, 2 * CandidatePool,
+		///   LastDelegation, Round, TopCandidates, MaxSelectedCandidates,
+		///   2 * CollatorBlockRewards, 2 * (D + 1) * SettledBlockRewards,
+		///   2 * (D + 1) * Rewards
+		/// - Writes: DelegatorState, 2 * CandidatePool, LastDelegation,
+		///   TopCandidates, TotalCollatorStake, 2 * (D + 1) *
+		///   SettledBlockRewards, 2 * (D + 1) * Rewards
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::redelegate(
+			T::MaxTopCandidates::get(),
+			T::MaxDelegatorsPerCollator::get()
+		))]
+		pub fn redelegate(
+			origin: OriginFor<T>,
+			from: <T::Lookup as StaticLookup>::Source,
+			to: <T::Lookup as StaticLookup>::Source,
+			amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let acc = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.stake_changes)?;
+			ensure!(!amount.is_zero(), Error::<T>::ValStakeZero);
+			let from = T::Lookup::lookup(from)?;
+			let to = T::Lookup::lookup(to)?;
+			ensure!(from != to, Error::<T>::CannotRedelegateToSameCollator);
+
+			let mut delegator = DelegatorState::<T>::get(&acc).ok_or(Error::<T>::DelegatorNotFound)?;
+			let delegated = delegator
+				.delegations
+				.iter()
+				.find(|stake| stake.owner == from)
+				.map(|stake| stake.amount)
+				.ok_or(Error::<T>::DelegationNotFound)?;
+			ensure!(amount <= delegated, Error::<T>::Underflow);
+			let remaining = delegated.saturating_sub(amount);
+			ensure!(
+				remaining.is_zero() || remaining >= T::MinDelegation::get(),
+				Error::<T>::DelegationBelowMin
+			);
+
+			let to_delegated = delegator
+				.delegations
+				.iter()
+				.find(|stake| stake.owner == to)
+				.map(|stake| stake.amount);
+
+			let mut from_state = CandidatePool::<T>::get(&from).ok_or(Error::<T>::CandidateNotFound)?;
+			let mut to_state = CandidatePool::<T>::get(&to).ok_or(Error::<T>::CandidateNotFound)?;
+			ensure!(!to_state.is_leaving(), Error::<T>::CannotDelegateIfLeaving);
+			ensure!(!Waitlisted::<T>::contains_key(&acc, &to), Error::<T>::AlreadyWaitlisted);
+
+			// credit the rewards earned with the current stakes before changing them
+			Self::settle_candidate_rewards(&from_state);
+			Self::settle_candidate_rewards(&to_state);
+
+			// take the stake from the previous candidate
+			let CandidateOf::<T, _> {
+				stake: from_old_stake,
+				total: from_old_total,
+				..
+			} = from_state;
+			if remaining.is_zero() {
+				delegator.rm_delegation(&from);
+				from_state.delegators.remove_stake(&acc, delegated);
+				from_state.total = from_state.total.saturating_sub(amount);
+			} else {
+				delegator.dec_delegation(from.clone(), amount);
+				from_state.dec_delegator(acc.clone(), delegated, amount);
+			}
+
+			// add the stake to the new candidate
+			let CandidateOf::<T, _> {
+				stake: to_old_stake,
+				total: to_old_total,
+				..
+			} = to_state;
+			let (to_state, maybe_kicked_delegator, delegation_counter) =
+				if let Some(delegated_to) = delegator.inc_delegation(to.clone(), amount) {
+					to_state.inc_delegator(acc.clone(), delegated_to.saturating_sub(amount), amount);
+					(to_state, None, None)
+				} else {
+					ensure!(amount >= T::MinDelegation::get(), Error::<T>::DelegationBelowMin);
+					// cannot delegate if number of delegations in this round exceeds
+					// MaxDelegationsPerRound
+					let delegation_counter = Self::get_delegation_counter(&acc)?;
+					ensure!(
+						delegator
+							.add_delegation(Stake {
+								owner: to.clone(),
+								amount
+							})
+							.is_ok(),
+						Error::<T>::MaxCollatorsPerDelegatorExceeded
+					);
+
+					let delegation = Stake {
+						owner: acc.clone(),
+						amount,
+					};
+					let num_delegations_pre_insertion: u32 = to_state.delegators.len().saturated_into();
+					// throws if delegation insertion exceeds bounded vec limit which we will handle
+					// below in Self::do_update_delegator
+					ensure!(
+						to_state.delegators.try_insert(delegation.clone()).unwrap_or(true),
+						Error::<T>::DelegatorExists
+					);
+					let (to_state, maybe_kicked_delegator) =
+						if num_delegations_pre_insertion == T::MaxDelegatorsPerCollator::get() {
+							Self::do_update_delegator(delegation, to_state)?
+						} else {
+							to_state.total = to_state.total.saturating_add(amount);
+							(to_state, None)
+						};
+					(to_state, maybe_kicked_delegator, Some(delegation_counter))
+				};
+
+			// *** No Fail beyond this point ***
+
+			// update top candidates and total amount at stake one candidate after another
+			let n = if from_state.is_active() {
+				Self::update_top_candidates(
+					from.clone(),
+					from_old_stake,
+					// safe because total >= stake
+					from_old_total - from_old_stake,
+					from_state.stake,
+					from_state.total - from_state.stake,
+				)
+			} else {
+				0u32
+			};
+			let from_total = from_state.total;
+			CandidatePool::<T>::insert(&from, from_state);
+
+			let n = if to_state.is_active() {
+				Self::update_top_candidates(
+					to.clone(),
+					to_old_stake,
+					// safe because total >= stake
+					to_old_total - to_old_stake,
+					to_state.stake,
+					to_state.total - to_state.stake,
+				)
+				.max(n)
+			} else {
+				n
+			};
+
+			let to_total = to_state.total;
+			CandidatePool::<T>::insert(&to, to_state);
+			DelegatorState::<T>::insert(&acc, delegator);
+			if remaining.is_zero() {
+				Self::remove_rewards_checkpoint(&acc, &from);
+			} else {
+				Self::rescale_stake_age(&acc, &from, delegated, remaining);
+			}
+			if let Some(delegation_counter) = delegation_counter {
+				<LastDelegation<T>>::insert(&acc, delegation_counter);
+				Self::init_rewards_checkpoint(&acc, &to);
+			} else if let Some(to_delegated) = to_delegated {
+				Self::rescale_stake_age(&acc, &to, to_delegated, to_delegated.saturating_add(amount));
+			}
+
+			// update or clear storage of potentially kicked delegator
+			Self::update_kicked_delegator_storage(maybe_kicked_delegator);
+
+			Self::deposit_event(Event::Redelegated(acc, from, to, amount, from_total, to_total));
+
+			// fill the delegation slot which might have become available
+			Self::promote_waitlisted(&from)?;
+			Ok(Some(<T as pallet::Config>::WeightInfo::redelegate(
+				n,
+				T::MaxDelegatorsPerCollator::get(),
+			))
+			.into())
+		}
+
+		/// Restake funds which are currently unstaking, i.e., waiting to be
+		/// unlocked after `StakeDuration` blocks.
+		///
+		/// The funds are taken from the most recent unstaking requests first.
+		/// If the collator is the caller, the stake of the collator candidate
+		/// is increased. Otherwise, the existing delegation of the caller to
+		/// the collator is increased.
+		///
+		/// Since unstaking funds remain locked, the lock is not changed.
+		///
+		/// Emits `Rebonded`.
+		///
+		/// # <weight>
+		/// Weight: O(N + D + U) where N is `MaxSelectedCandidates` bounded by
+		/// `MaxTopCandidates`, D is the number of delegators for this
+		/// candidate bounded by `MaxDelegatorsPerCollator` and U is the
+		/// number of locked unstaking requests bounded by `MaxUnstakeRequests`.
+		/// - Reads: [Origin Account], Unstaking, CandidatePool,
+		///   DelegatorState, MaxCollatorCandidateStake, TopCandidates,
+		///   MaxSelectedCandidates, TotalCollatorStake, CollatorBlockRewards,
+		///   (D + 1) * SettledBlockRewards, (D + 1) * Rewards, Round,
+		///   DelegationAge
+		/// - Writes: Unstaking, CandidatePool, DelegatorState, TopCandidates,
+		///   TotalCollatorStake, (D + 1) * SettledBlockRewards, (D + 1) *
+		///   Rewards, DelegationAge
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::rebond(
+			T::MaxTopCandidates::get(),
+			T::MaxDelegatorsPerCollator::get(),
+			T::MaxUnstakeRequests::get().saturated_into::<u32>()
+		))]
+		pub fn rebond(
+			origin: OriginFor<T>,
+			collator: <T::Lookup as StaticLookup>::Source,
+			amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let acc = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.stake_changes)?;
+			ensure!(!amount.is_zero(), Error::<T>::ValStakeZero);
+			let collator = T::Lookup::lookup(collator)?;
+
+			let mut state = CandidatePool::<T>::get(&collator).ok_or(Error::<T>::CandidateNotFound)?;
+			let maybe_delegator = if acc == collator {
+				ensure!(!state.is_leaving(), Error::<T>::CannotStakeIfLeaving);
+				None
+			} else {
+				ensure!(!state.is_leaving(), Error::<T>::CannotDelegateIfLeaving);
+				Some(DelegatorState::<T>::get(&acc).ok_or(Error::<T>::DelegatorNotFound)?)
+			};
+
+			// credit the rewards earned with the current stakes before changing them
+			Self::settle_candidate_rewards(&state);
+
+			let CandidateOf::<T, _> {
+				stake: before_stake,
+				total: before_total,
+				..
+			} = state;
+			let maybe_delegator = if let Some(mut delegator) = maybe_delegator {
+				let delegated = delegator
+					.inc_delegation(collator.clone(), amount)
+					.ok_or(Error::<T>::DelegationNotFound)?;
+				state.inc_delegator(acc.clone(), delegated.saturating_sub(amount), amount);
+				Some((delegator, delegated))
+			} else {
+				state.stake_more(amount);
+				ensure!(
+					state.stake <= MaxCollatorCandidateStake::<T>::get(),
+					Error::<T>::ValStakeAboveMax
+				);
+				None
+			};
+
+			// *** No Fail except during rebond_unstaking beyond this point ***
+
+			let unstaking_len = Self::rebond_unstaking(&acc, amount)?;
+
+			let n = if state.is_active() {
+				Self::update_top_candidates(
+					collator.clone(),
+					before_stake,
+					// safe because total >= stake
+					before_total - before_stake,
+					state.stake,
+					state.total - state.stake,
+				)
+			} else {
+				0u32
+			};
+			let new_total = state.total;
+			CandidatePool::<T>::insert(&collator, state);
+			if let Some((delegator, delegated)) = maybe_delegator {
+				DelegatorState::<T>::insert(&acc, delegator);
+				Self::rescale_stake_age(&acc, &collator, delegated.saturating_sub(amount), delegated);
+			}
+
+			Self::deposit_event(Event::Rebonded(acc, collator, amount, new_total));
+			Ok(Some(<T as pallet::Config>::WeightInfo::rebond(
+				n,
+				T::MaxDelegatorsPerCollator::get(),
+				unstaking_len,
+			))
+			.into())
+		}
+
+		/// Set the share of the rewards of a stake which is restaked into the
+		/// same stake.
+		///
+		/// The rewards of the collators of a session are settled and restaked
+		/// at its end, i.e., at the end of each round, without having to claim
+		/// them. Restaking is deferred while payouts are paused.
+		///
+		/// If the collator is the caller, the share applies to the collator
+		/// candidate's self-stake. Otherwise, it applies to the delegation of
+		/// the caller to the collator. A share of zero disables restaking.
+		///
+		/// The rewards accrued up to now are settled with the previous share.
+		/// When restaking, the self-stake of a collator candidate cannot
+		/// exceed `MaxCollatorCandidateStake`. Rewards above this limit are
+		/// paid out instead.
+		///
+		/// Emits `AutoCompoundSet`.
+		///
+		/// # <weight>
+		/// Weight: O(D) where D is the number of delegators of the collator
+		/// bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: [Origin Account], CandidatePool, DelegatorState,
+		///   StakeSnapshots, Round, CurrentInflation, LoyaltyConfig, D *
+		///   DelegationAge, CollatorBlockRewards, SettledBlockRewards,
+		///   Commission, AutoCompound, CompoundRewards, Rewards
+		/// - Writes: SettledBlockRewards, AutoCompound, CompoundRewards,
+		///   Rewards
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::set_auto_compound(
+			T::MaxDelegatorsPerCollator::get()
+		))]
+		pub fn set_auto_compound(
+			origin: OriginFor<T>,
+			collator: <T::Lookup as StaticLookup>::Source,
+			percent: Percent,
+		) -> DispatchResultWithPostInfo {
+			let acc = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.payouts)?;
+			let collator = T::Lookup::lookup(collator)?;
+
+			let state = CandidatePool::<T>::get(&collator).ok_or(Error::<T>::CandidateNotFound)?;
+			if acc != collator {
+				let delegator = DelegatorState::<T>::get(&acc).ok_or(Error::<T>::DelegatorNotFound)?;
+				ensure!(
+					delegator.delegations.into_iter().any(|stake| stake.owner == collator),
+					Error::<T>::StakeNotFoundForCollator
+				);
+			}
+
+			// *** No Fail beyond this point ***
+
+			// credit the rewards earned up to now with the previous share
+			Self::settle_rewards(&acc, &collator, &Self::weighted_stake(&collator, &Self::rewarded_stake(&state)));
+
+			if percent.is_zero() {
+				AutoCompound::<T>::remove(&acc, &collator);
+			} else {
+				AutoCompound::<T>::insert(&acc, &collator, percent);
+			}
+
+			Self::deposit_event(Event::AutoCompoundSet(acc, collator, percent));
+			Ok(Some(<T as pallet::Config>::WeightInfo::set_auto_compound(
+				state.delegators.len().saturated_into::<u32>(),
+			))
+			.into())
+		}
+
+		/// Set the account which receives the rewards of the origin account
+		/// when claiming them via `claim_rewards` or `claim_rewards_for`.
+		///
+		/// The origin has to be a collator candidate or a delegator. Setting
+		/// the origin account itself as payee restores the default. The payee
+		/// account has to exist when rewards are claimed. Rewards which are
+		/// restaked automatically are not affected. The payee is reset once
+		/// the origin leaves the set of collator candidates or delegators.
+		///
+		/// Emits `PayeeSet`.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: [Origin Account], CandidatePool, DelegatorState
+		/// - Writes: Payee
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::set_payee())]
+		pub fn set_payee(origin: OriginFor<T>, payee: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
+			let acc = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.payouts)?;
+			let payee = T::Lookup::lookup(payee)?;
+			ensure!(
+				CandidatePool::<T>::contains_key(&acc) || DelegatorState::<T>::contains_key(&acc),
+				Error::<T>::StakerNotFound
+			);
+
+			// *** No Fail beyond this point ***
+
+			if payee == acc {
+				Payee::<T>::remove(&acc);
+			} else {
+				Payee::<T>::insert(&acc, &payee);
+			}
+
+			Self::deposit_event(Event::PayeeSet(acc, payee));
+			Ok(())
+		}
+
+		/// Set the inflation curve from which the staking rewards are derived
+		/// at the start of each round, depending on the share of the total
+		/// issuance which is staked.
+		///
+		/// The rewards are updated immediately. If the curve is disabled by
+		/// passing `None`, `RewardPerBlock` stays at its current value and can
+		/// only be changed via `set_rewards_per_block`. Otherwise, changes
+		/// via `set_rewards_per_block` only last until the next round.
+		///
+		/// The rewards of all collator candidates and their delegators which
+		/// have been earned up to now are settled with the previous stake
+		/// weights.
+		///
+		/// The dispatch origin must be `AdminOrigin`.
+		///
+		/// Emits `InflationConfigSet`, and `RoundInflationSet` and
+		/// `RewardPerBlockUpdated` if a curve is set.
+		///
+		/// # <weight>
+		/// Weight: O(C * D) where C is the number of collator candidates and
+		/// D is the number of delegators of a collator candidate bounded by
+		/// `MaxDelegatorsPerCollator`.
+		/// - Reads: [Origin Account], TotalCollatorStake, TotalIssuance,
+		///   RewardPerBlock, CurrentInflation, LoyaltyConfig, Round, C *
+		///   CandidatePool, C * StakeSnapshots, C * D * DelegationAge, C *
+		///   CollatorBlockRewards, C * (D + 1) * SettledBlockRewards, C * (D +
+		///   1) * Rewards, C * UnsplitBlockRewards
+		/// - Writes: InflationConfig, CurrentInflation, RewardPerBlock, C *
+		///   StakeSnapshots, C * (D + 1) * SettledBlockRewards, C * (D + 1) *
+		///   Rewards, C * UnsplitBlockRewards
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::set_inflation(
+			CandidatePool::<T>::count(),
+			T::MaxDelegatorsPerCollator::get()
+		))]
+		pub fn set_inflation(origin: OriginFor<T>, inflation: Option<InflationInfo>) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			if let Some(inflation) = inflation {
+				ensure!(inflation.is_valid(), Error::<T>::InvalidSchedule);
+			}
+
+			// *** No Fail beyond this point ***
+
+			InflationConfig::<T>::set(inflation);
+			Self::deposit_event(Event::InflationConfigSet(inflation));
+
+			if let Some(inflation) = inflation {
+				Self::update_inflation(&inflation);
+			} else {
+				CurrentInflation::<T>::kill();
+			}
+			// credit the rewards earned up to now with the previous stake weights
+			Self::apply_reward_weights();
+			Ok(())
+		}
+
+		/// Create a delegation pool which delegates to the given collator
+		/// candidate and join it as its operator.
+		///
+		/// The stake is transferred to the pool's account which delegates it
+		/// like any other delegator. Therefore, the stake has to be at least
+		/// `MinDelegatorStake`. The operator can move the pool's stake to other
+		/// collator candidates via `redelegate_pool` and has to keep at least
+		/// `MinDelegatorStake` in the pool as long as it has other members.
+		///
+		/// Emits `PoolCreated` and `Delegation`.
+		///
+		/// # <weight>
+		/// Weight: O(N + D) where N is `MaxSelectedCandidates` bounded by
+		/// `MaxTopCandidates` and D is the number of delegators for this
+		/// collator bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: [Origin Account], NextPoolId, 2 * System.Account,
+		///   DelegatorState, CandidatePool, LastDelegation, Round, Unstaking,
+		///   Locks, TopCandidates, MaxSelectedCandidates, CollatorBlockRewards
+		/// - Writes: NextPoolId, Pools, PoolMembers, 2 * System.Account,
+		///   DelegatorState, CandidatePool, LastDelegation, Locks,
+		///   TopCandidates, TotalCollatorStake, SettledBlockRewards
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::create_pool(
+			T::MaxTopCandidates::get(),
+			T::MaxDelegatorsPerCollator::get()
+		))]
+		pub fn create_pool(
+			origin: OriginFor<T>,
+			collator: <T::Lookup as StaticLookup>::Source,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let operator = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.joins)?;
+			let collator = T::Lookup::lookup(collator)?;
+			ensure!(amount >= T::MinDelegatorStake::get(), Error::<T>::NomStakeBelowMin);
+
+			let pool_id = NextPoolId::<T>::get();
+			let pool_acc = Self::pool_account(pool_id);
+
+			// *** No Fail except during the transfer and delegation beyond this point ***
+
+			T::Currency::transfer(&operator, &pool_acc, amount, ExistenceRequirement::KeepAlive)?;
+			Self::join_delegators(
+				Self::pool_origin(&pool_acc),
+				T::Lookup::unlookup(collator.clone()),
+				amount,
+			)
+			.map_err(|e| e.error)?;
+
+			NextPoolId::<T>::put(pool_id.saturating_add(1));
+			Pools::<T>::insert(
+				pool_id,
+				DelegationPool {
+					operator: operator.clone(),
+					stake: amount,
+					points: amount,
+					members: 1,
+					reward_counter: FixedU128::zero(),
+					pending_rewards: BalanceOf::<T>::zero(),
+				},
+			);
+			PoolMembers::<T>::insert(
+				pool_id,
+				&operator,
+				PoolMember {
+					points: amount,
+					reward_counter: FixedU128::zero(),
+					unbonding: BoundedBTreeMap::default(),
+					pending_rewards: BalanceOf::<T>::zero(),
+				},
+			);
+
+			Self::deposit_event(Event::PoolCreated(pool_id, operator, collator, amount));
+			Ok(())
+		}
+
+		/// Join a delegation pool or increase the stake in it.
+		///
+		/// The funds are transferred to the pool's account and added to the
+		/// pool's largest delegation. There is no minimum amount. The member
+		/// receives points in proportion to the pool's stake after slashes.
+		/// The share of the pool's rewards which is due to the member is paid
+		/// out before the stake is changed, or deferred while payouts are
+		/// paused.
+		///
+		/// If the pool does not delegate anymore, e.g. because its collator
+		/// candidate has left, the operator has to delegate the pool's stake
+		/// via `redelegate_pool` first.
+		///
+		/// Emits `PoolJoined`.
+		///
+		/// # <weight>
+		/// Weight: O(N + D) where N is `MaxSelectedCandidates` bounded by
+		/// `MaxTopCandidates` and D is the number of delegators for the
+		/// collator bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: [Origin Account], Pools, PoolMembers, 2 * System.Account,
+		///   DelegatorState, CandidatePool, Unstaking, Locks, TopCandidates,
+		///   MaxSelectedCandidates, CollatorBlockRewards, (D + 1) *
+		///   SettledBlockRewards, (D + 1) * Rewards, Paused
+		/// - Writes: Pools, PoolMembers, 2 * System.Account, DelegatorState,
+		///   CandidatePool, Locks, TopCandidates, TotalCollatorStake, (D + 1) *
+		///   SettledBlockRewards, (D + 1) * Rewards
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::join_pool(
+			T::MaxTopCandidates::get(),
+			T::MaxDelegatorsPerCollator::get()
+		))]
+		pub fn join_pool(origin: OriginFor<T>, pool_id: PoolId, amount: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.joins)?;
+			ensure!(!amount.is_zero(), Error::<T>::ValStakeZero);
+
+			let mut pool = Pools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			let pool_acc = Self::pool_account(pool_id);
+			let collator = DelegatorState::<T>::get(&pool_acc)
+				.and_then(|delegator| {
+					delegator
+						.delegations
+						.into_iter()
+						.max_by(|a, b| a.amount.cmp(&b.amount))
+						.map(|stake| stake.owner)
+				})
+				.ok_or(Error::<T>::PoolNotDelegating)?;
+			let points = pool.points_of(amount);
+			let (mut member, is_new) = match PoolMembers::<T>::get(pool_id, &who) {
+				Some(member) => (member, false),
+				None => (
+					PoolMember {
+						points: BalanceOf::<T>::zero(),
+						reward_counter: pool.reward_counter,
+						unbonding: BoundedBTreeMap::default(),
+						pending_rewards: BalanceOf::<T>::zero(),
+					},
+					true,
+				),
+			};
+
+			// *** No Fail except during the transfers and delegation beyond this point ***
+
+			Self::sync_pool_rewards(&pool_acc, &mut pool)?;
+			Self::pay_pool_member(pool_id, &pool_acc, &pool, &who, &mut member)?;
+			T::Currency::transfer(&who, &pool_acc, amount, ExistenceRequirement::KeepAlive)?;
+			Self::delegator_stake_more(Self::pool_origin(&pool_acc), T::Lookup::unlookup(collator), amount)
+				.map_err(|e| e.error)?;
+
+			member.points = member.points.saturating_add(points);
+			pool.points = pool.points.saturating_add(points);
+			pool.stake = pool.stake.saturating_add(amount);
+			if is_new {
+				pool.members = pool.members.saturating_add(1);
+			}
+			let pool_stake = pool.stake;
+			PoolMembers::<T>::insert(pool_id, &who, member);
+			Pools::<T>::insert(pool_id, pool);
+
+			Self::deposit_event(Event::PoolJoined(pool_id, who, amount, pool_stake));
+			Ok(())
+		}
+
+		/// Reduce the stake in a delegation pool.
+		///
+		/// The pool's delegations are reduced, starting with the largest one,
+		/// and the funds can be withdrawn via `withdraw_from_pool` after
+		/// `StakeDuration` blocks. The unlock block is rounded up to a multiple
+		/// of `StakeDuration` such that all members share at most two
+		/// unstaking requests of the pool's account. The share of the pool's
+		/// rewards which is due to the member is paid out before the stake is
+		/// changed, or deferred while payouts are paused.
+		///
+		/// The operator can only unbond all of their stake if they are the
+		/// last member. In that case, the pool stops delegating and is removed
+		/// once the funds have been withdrawn.
+		///
+		/// Emits `PoolUnbonded`.
+		///
+		/// # <weight>
+		/// Weight: O(C * (N + D) + U) where C is the number of delegations of
+		/// the pool bounded by `MaxCollatorsPerDelegator`, N is
+		/// `MaxSelectedCandidates` bounded by `MaxTopCandidates`, D is the
+		/// number of delegators for the collators bounded by
+		/// `MaxDelegatorsPerCollator` and U is the number of locked unstaking
+		/// requests bounded by `MaxUnstakeRequests`.
+		/// - Reads: [Origin Account], Pools, PoolMembers, 2 * System.Account,
+		///   DelegatorState, C * CandidatePool, Unstaking, Locks,
+		///   TopCandidates, MaxSelectedCandidates, C * CollatorBlockRewards,
+		///   C * (D + 1) * SettledBlockRewards, C * (D + 1) * Rewards, Paused
+		/// - Writes: Pools, PoolMembers, 2 * System.Account, DelegatorState,
+		///   C * CandidatePool, Unstaking, Locks, TopCandidates,
+		///   TotalCollatorStake, C * (D + 1) * SettledBlockRewards, C * (D +
+		///   1) * Rewards
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::unbond_from_pool(
+			T::MaxTopCandidates::get(),
+			T::MaxDelegatorsPerCollator::get(),
+			T::MaxUnstakeRequests::get().saturated_into::<u32>()
+		))]
+		pub fn unbond_from_pool(origin: OriginFor<T>, pool_id: PoolId, amount: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.stake_changes)?;
+			ensure!(!amount.is_zero(), Error::<T>::ValStakeZero);
+
+			let mut pool = Pools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			let mut member = PoolMembers::<T>::get(pool_id, &who).ok_or(Error::<T>::PoolMemberNotFound)?;
+			let stake = pool.stake_of(member.points);
+			ensure!(stake >= amount, Error::<T>::Underflow);
+			let remaining = stake.saturating_sub(amount);
+			if who == pool.operator {
+				ensure!(
+					remaining >= T::MinDelegatorStake::get() || (remaining.is_zero() && pool.stake == amount),
+					Error::<T>::PoolOperatorStakeBelowMin
+				);
+			}
+			let points = if remaining.is_zero() {
+				member.points
+			} else {
+				pool.points_of(amount).min(member.points)
+			};
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			let unlock_block = Self::pool_unlock_block(now);
+			// if existent, we have to add the current amount of same unlock_block, because
+			// insert overwrites the current value
+			let unbonding = amount.saturating_add(
+				*member
+					.unbonding
+					.get(&unlock_block)
+					.unwrap_or(&BalanceOf::<T>::zero()),
+			);
+			member
+				.unbonding
+				.try_insert(unlock_block, unbonding)
+				.map_err(|_| Error::<T>::NoMoreUnstaking)?;
+
+			// *** No Fail except during the transfers and unstaking beyond this point ***
+
+			let pool_acc = Self::pool_account(pool_id);
+			Self::sync_pool_rewards(&pool_acc, &mut pool)?;
+			Self::pay_pool_member(pool_id, &pool_acc, &pool, &who, &mut member)?;
+			// free the slots of expired unstaking requests of the pool
+			if !Unstaking::<T>::get(&pool_acc).is_empty() {
+				Self::do_unlock(&pool_acc)?;
+			}
+			Self::unbond_pool_stake(&pool_acc, pool.stake, amount)?;
+			Self::postpone_unstaking(&pool_acc, now.saturating_add(T::StakeDuration::get()), unlock_block)?;
+
+			member.points = member.points.saturating_sub(points);
+			pool.points = pool.points.saturating_sub(points);
+			pool.stake = pool.stake.saturating_sub(amount);
+			let pool_stake = pool.stake;
+			PoolMembers::<T>::insert(pool_id, &who, member);
+			Pools::<T>::insert(pool_id, pool);
+
+			Self::deposit_event(Event::PoolUnbonded(pool_id, who, amount, pool_stake));
+			Ok(())
+		}
+
+		/// Withdraw the funds which have been unbonded from a delegation pool
+		/// at least `StakeDuration` blocks ago.
+		///
+		/// If the member does not have any stake or unbonding funds left, they
+		/// leave the pool. If they were the last member, the pool is removed
+		/// and they receive all remaining funds of the pool's account once
+		/// none of them are locked anymore. Until then, the last member stays
+		/// in the pool and can call this again without unbonding funds.
+		///
+		/// Rewards which have been deferred while payouts were paused are paid
+		/// out when the member leaves. If payouts are still paused, the member
+		/// stays in the pool until they have claimed them via
+		/// `claim_pool_rewards`, and the deferred rewards of a dissolved pool
+		/// are credited to the last member's rewards.
+		///
+		/// Emits `PoolWithdrawn` and `PoolDissolved` if the pool is removed.
+		///
+		/// # <weight>
+		/// Weight: O(U) where U is the number of locked unstaking requests
+		/// bounded by `MaxUnstakeRequests`.
+		/// - Reads: [Origin Account], Pools, PoolMembers, 2 * System.Account,
+		///   Unstaking, Locks, Paused
+		/// - Writes: Pools, PoolMembers, 2 * System.Account, Unstaking, Locks,
+		///   Rewards
+		/// - Kills: Pools & PoolMembers if the pool is dissolved
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::withdraw_from_pool(
+			T::MaxUnstakeRequests::get().saturated_into::<u32>()
+		))]
+		pub fn withdraw_from_pool(origin: OriginFor<T>, pool_id: PoolId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.unlocks)?;
+
+			let mut pool = Pools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			let mut member = PoolMembers::<T>::get(pool_id, &who).ok_or(Error::<T>::PoolMemberNotFound)?;
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			let expired: Vec<T::BlockNumber> = member
+				.unbonding
+				.iter()
+				.filter(|(block_number, _)| **block_number <= now)
+				.map(|(block_number, _)| *block_number)
+				.collect();
+			let mut withdrawn = BalanceOf::<T>::zero();
+			for block_number in expired {
+				if let Some(amount) = member.unbonding.remove(&block_number) {
+					withdrawn = withdrawn.saturating_add(amount);
+				}
+			}
+			let is_leaving = member.points.is_zero() && member.unbonding.is_empty();
+			let is_last = is_leaving && pool.members <= 1;
+			ensure!(!withdrawn.is_zero() || is_last, Error::<T>::PoolUnbondingNotFound);
+
+			let pool_acc = Self::pool_account(pool_id);
+			if !Unstaking::<T>::get(&pool_acc).is_empty() {
+				Self::do_unlock(&pool_acc)?;
+			}
+			let dissolve = is_last && Self::staking_lock(&pool_acc).is_zero();
+			ensure!(!withdrawn.is_zero() || dissolve, Error::<T>::PoolUnbondingNotFound);
+
+			// *** No Fail except during the transfer beyond this point ***
+
+			if dissolve {
+				// the last member receives everything which is left, e.g. rounding dust
+				Self::sync_pool_rewards(&pool_acc, &mut pool)?;
+				if !pool.pending_rewards.is_zero() {
+					// payouts are paused, hence the pool's rewards have not been minted yet
+					Rewards::<T>::mutate(&who, |rewards| *rewards = rewards.saturating_add(pool.pending_rewards));
+				}
+				let remaining = T::Currency::free_balance(&pool_acc);
+				T::Currency::transfer(&pool_acc, &who, remaining, ExistenceRequirement::AllowDeath)?;
+				PoolMembers::<T>::remove(pool_id, &who);
+				Pools::<T>::remove(pool_id);
+
+				Self::deposit_event(Event::PoolWithdrawn(pool_id, who, remaining));
+				Self::deposit_event(Event::PoolDissolved(pool_id));
+				return Ok(());
+			}
+
+			T::Currency::transfer(&pool_acc, &who, withdrawn, ExistenceRequirement::KeepAlive)?;
+			if is_leaving && !member.pending_rewards.is_zero() {
+				Self::sync_pool_rewards(&pool_acc, &mut pool)?;
+				Self::pay_pool_member(pool_id, &pool_acc, &pool, &who, &mut member)?;
+			}
+			if is_leaving && !is_last && member.pending_rewards.is_zero() {
+				PoolMembers::<T>::remove(pool_id, &who);
+				pool.members = pool.members.saturating_sub(1);
+			} else {
+				PoolMembers::<T>::insert(pool_id, &who, member);
+			}
+			Pools::<T>::insert(pool_id, pool);
+
+			Self::deposit_event(Event::PoolWithdrawn(pool_id, who, withdrawn));
+			Ok(())
+		}
+
+		/// Claim the share of the rewards of a delegation pool which is due to
+		/// the member.
+		///
+		/// The rewards of all delegations of the pool are claimed first and
+		/// then shared between all members in proportion to their stake,
+		/// including the rewards which have been deferred while payouts were
+		/// paused. A member without any stake or unbonding funds leaves the
+		/// pool afterwards unless they are the last member.
+		///
+		/// Emits `PoolRewarded`.
+		///
+		/// # <weight>
+		/// Weight: O(C * D) where C is the number of delegations of the pool
+		/// bounded by `MaxCollatorsPerDelegator` and D is the number of
+		/// delegators for the collators bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: [Origin Account], Pools, PoolMembers, DelegatorState, C *
+		///   CandidatePool, C * CollatorBlockRewards, C * (D + 1) *
+		///   SettledBlockRewards, C * (D + 1) * Rewards, 2 * System.Account
+		/// - Writes: Pools, PoolMembers, C * (D + 1) * SettledBlockRewards, C
+		///   * (D + 1) * Rewards, 2 * System.Account
+		/// - Kills: PoolMembers if the member leaves the pool
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::claim_pool_rewards(
+			T::MaxCollatorsPerDelegator::get(),
+			T::MaxDelegatorsPerCollator::get()
+		))]
+		pub fn claim_pool_rewards(origin: OriginFor<T>, pool_id: PoolId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.payouts)?;
+
+			let mut pool = Pools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			let mut member = PoolMembers::<T>::get(pool_id, &who).ok_or(Error::<T>::PoolMemberNotFound)?;
+			let pool_acc = Self::pool_account(pool_id);
+
+			Self::sync_pool_rewards(&pool_acc, &mut pool)?;
+			let rewards = Self::pay_pool_member(pool_id, &pool_acc, &pool, &who, &mut member)?;
+			ensure!(!rewards.is_zero(), Error::<T>::RewardsNotFound);
+
+			// *** No Fail beyond this point ***
+
+			if member.points.is_zero() && member.unbonding.is_empty() && pool.members > 1 {
+				PoolMembers::<T>::remove(pool_id, &who);
+				pool.members = pool.members.saturating_sub(1);
+			} else {
+				PoolMembers::<T>::insert(pool_id, &who, member);
+			}
+			Pools::<T>::insert(pool_id, pool);
+			Ok(())
+		}
+
+		/// Move some or all of the stake of a delegation pool from one
+		/// collator candidate to another without unstaking it.
+		///
+		/// Works like `redelegate` for the pool's account and can only be
+		/// called by the pool's operator.
+		///
+		/// If the pool does not delegate to `from` anymore, e.g. because the
+		/// candidate has left or the pool's delegation was removed, the given
+		/// amount of the pool's undelegated stake is delegated to `to`
+		/// instead.
+		///
+		/// Emits `Redelegated` or `Delegation`.
+		///
+		/// # <weight>
+		/// Weight: O(N + D) where N is `MaxSelectedCandidates` bounded by
+		/// `MaxTopCandidates` and D is the number of delegators for the
+		/// candidates bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: [Origin Account], Pools, DelegatorState, 2 *
+		///   CandidatePool, LastDelegation, Round, TopCandidates,
+		///   MaxSelectedCandidates, 2 * CollatorBlockRewards, 2 * (D + 1) *
+		///   SettledBlockRewards, 2 * (D + 1) * Rewards, Waitlisted,
+		///   Unstaking, Locks
+		/// - Writes: DelegatorState, 2 * CandidatePool, LastDelegation,
+		///   TopCandidates, TotalCollatorStake, 2 * (D + 1) *
+		///   SettledBlockRewards, 2 * (D + 1) * Rewards, Unstaking, Locks
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::redelegate_pool(
+			T::MaxTopCandidates::get(),
+			T::MaxDelegatorsPerCollator::get()
+		))]
+		pub fn redelegate_pool(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			from: <T::Lookup as StaticLookup>::Source,
+			to: <T::Lookup as StaticLookup>::Source,
+			amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.stake_changes)?;
+			let pool = Pools::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(who == pool.operator, Error::<T>::NotPoolOperator);
+
+			let pool_acc = Self::pool_account(pool_id);
+			let from = T::Lookup::lookup(from)?;
+			let delegator = DelegatorState::<T>::get(&pool_acc);
+			let is_delegated = |collator: &T::AccountId| {
+				delegator
+					.as_ref()
+					.map_or(false, |state| state.delegations.iter().any(|stake| &stake.owner == collator))
+			};
+			if is_delegated(&from) {
+				return Self::redelegate(Self::pool_origin(&pool_acc), T::Lookup::unlookup(from), to, amount);
+			}
+
+			// the undelegated stake is still locked in the unstaking requests of the pool
+			ensure!(!amount.is_zero(), Error::<T>::ValStakeZero);
+			ensure!(
+				amount <= Self::undelegated_pool_stake(&pool_acc, &pool),
+				Error::<T>::PoolStakeUnavailable
+			);
+			let to_acc = T::Lookup::lookup(to.clone())?;
+			if delegator.is_none() {
+				Self::join_delegators(Self::pool_origin(&pool_acc), to, amount)
+			} else if is_delegated(&to_acc) {
+				Self::delegator_stake_more(Self::pool_origin(&pool_acc), to, amount)
+			} else {
+				Self::delegate_another_candidate(Self::pool_origin(&pool_acc), to, amount)
+			}
+		}
+
+		/// Leave the waitlist of a collator candidate.
+		///
+		/// The waiting funds are unlocked immediately since they have not been
+		/// staked.
+		///
+		/// Emits `WaitlistLeft`.
+		///
+		/// # <weight>
+		/// Weight: O(W) where W is the size of the waitlist bounded by
+		/// `MaxWaitlistSize`.
+		/// - Reads: [Origin Account], Waitlisted, Waitlist, Locks
+		/// - Writes: Waitlist, Locks
+		/// - Kills: Waitlisted, Locks if no balance is locked anymore
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::leave_waitlist(T::MaxWaitlistSize::get()))]
+		pub fn leave_waitlist(origin: OriginFor<T>, collator: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_not_paused(|paused| paused.stake_changes)?;
+			let collator = T::Lookup::lookup(collator)?;
+			let amount = Waitlisted::<T>::get(&who, &collator).ok_or(Error::<T>::NotWaitlisted)?;
+
+			// *** No Fail beyond this point ***
+
+			Waitlisted::<T>::remove(&who, &collator);
+			Waitlist::<T>::mutate(&collator, |waitlist| waitlist.retain(|acc| acc != &who));
+			Self::release_waitlisted(&who, &collator, amount);
+			Ok(())
+		}
+
+		/// Set the bonus delegations earn depending on the number of rounds
+		/// they have been staked.
+		///
+		/// The bonus increases the weight of a delegation in the rewards of
+		/// its collator candidate and is thus paid out of the candidate's
+		/// rewards. It grows linearly with the stake age up to `max_bonus`
+		/// once a delegation has been staked for `maturity` rounds. Increasing
+		/// a delegation averages its age over the added stake while reducing
+		/// or moving a delegation reduces its age proportionally. By passing
+		/// `None`, delegations do not earn a bonus anymore.
+		///
+		/// The rewards of all collator candidates and their delegators which
+		/// have been earned up to now are settled with the previous bonus.
+		///
+		/// The dispatch origin must be `AdminOrigin`.
+		///
+		/// Emits `LoyaltyConfigSet`.
+		///
+		/// # <weight>
+		/// Weight: O(C * D) where C is the number of collator candidates and
+		/// D is the number of delegators of a collator candidate bounded by
+		/// `MaxDelegatorsPerCollator`.
+		/// - Reads: [Origin Account], C * CandidatePool, C * StakeSnapshots,
+		///   Round, CurrentInflation, LoyaltyConfig, C * D * DelegationAge, C *
+		///   CollatorBlockRewards, C * (D + 1) * SettledBlockRewards, C * (D +
+		///   1) * Rewards
+		/// - Writes: LoyaltyConfig, C * StakeSnapshots, C * (D + 1) *
+		///   SettledBlockRewards, C * (D + 1) * Rewards
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::set_loyalty_bonus(
+			CandidatePool::<T>::count(),
+			T::MaxDelegatorsPerCollator::get()
+		))]
+		pub fn set_loyalty_bonus(origin: OriginFor<T>, loyalty: Option<LoyaltyInfo>) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			if let Some(loyalty) = loyalty {
+				ensure!(loyalty.is_valid(), Error::<T>::InvalidLoyaltyConfig);
+			}
+
+			// *** No Fail beyond this point ***
+
+			LoyaltyConfig::<T>::set(loyalty);
+			// credit the rewards earned up to now with the previous bonus
+			Self::apply_reward_weights();
+
+			Self::deposit_event(Event::LoyaltyConfigSet(loyalty));
+			Ok(())
+		}
+
+		/// Add an account to the invulnerable collators.
+		///
+		/// Invulnerable collators are part of the validator set of every
+		/// session, even without being a collator candidate, and take
+		/// precedence over the staked candidates. Unless
+		/// `RewardInvulnerables` is set, the blocks they author are not
+		/// rewarded.
+		///
+		/// The number of invulnerable collators cannot exceed
+		/// `MaxSelectedCandidates`.
+		///
+		/// The dispatch origin must be `AdminOrigin`.
+		///
+		/// Emits `InvulnerableAdded`.
+		///
+		/// # <weight>
+		/// Weight: O(N) where N is the number of invulnerables bounded by
+		/// `MaxSelectedCandidates`.
+		/// - Reads: [Origin Account], Invulnerables, MaxSelectedCandidates
+		/// - Writes: Invulnerables
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::add_invulnerable(MaxSelectedCandidates::<T>::get()))]
+		pub fn add_invulnerable(origin: OriginFor<T>, who: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			let mut invulnerables = Invulnerables::<T>::get();
+			ensure!(!invulnerables.contains(&who), Error::<T>::AlreadyInvulnerable);
+			ensure!(
+				invulnerables.len() < MaxSelectedCandidates::<T>::get().saturated_into::<usize>(),
+				Error::<T>::TooManyInvulnerables
+			);
+			invulnerables
+				.try_push(who.clone())
+				.map_err(|_| Error::<T>::TooManyInvulnerables)?;
+
+			// *** No Fail beyond this point ***
+
+			Invulnerables::<T>::put(invulnerables);
+
+			Self::deposit_event(Event::InvulnerableAdded(who));
+			Ok(())
+		}
+
+		/// Remove an account from the invulnerable collators.
+		///
+		/// The account leaves the validator set at the start of the next
+		/// session unless it is selected as a staked collator candidate.
+		///
+		/// The dispatch origin must be `AdminOrigin`.
+		///
+		/// Emits `InvulnerableRemoved`.
+		///
+		/// # <weight>
+		/// Weight: O(N) where N is the number of invulnerables bounded by
+		/// `MaxSelectedCandidates`.
+		/// - Reads: [Origin Account], Invulnerables
+		/// - Writes: Invulnerables
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::remove_invulnerable(MaxSelectedCandidates::<T>::get()))]
+		pub fn remove_invulnerable(origin: OriginFor<T>, who: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let who = T::Lookup::lookup(who)?;
+
+			let mut invulnerables = Invulnerables::<T>::get();
+			let index = invulnerables
+				.iter()
+				.position(|acc| acc == &who)
+				.ok_or(Error::<T>::NotInvulnerable)?;
+
+			// *** No Fail beyond this point ***
+
+			invulnerables.remove(index);
+			Invulnerables::<T>::put(invulnerables);
+
+			Self::deposit_event(Event::InvulnerableRemoved(who));
+			Ok(())
+		}
+
+		/// Pause or resume kinds of staking operations.
+		///
+		/// - `joins`: joining the collator candidates, delegating and joining
+		///   pools.
+		/// - `stake_changes`: increasing, decreasing, moving and withdrawing
+		///   stake, including leaving the candidates, delegators, pools and
+		///   waitlists.
+		/// - `unlocks`: unlocking unstaked funds and withdrawing from pools.
+		/// - `payouts`: claiming rewards. Block rewards are still accrued and
+		///   can be claimed once payouts are resumed.
+		///
+		/// Pausing all operations puts the pallet into emergency mode. The
+		/// collator selection, block authoring and administrative calls are
+		/// not affected.
+		///
+		/// The dispatch origin must be `AdminOrigin`.
+		///
+		/// Emits `PausedOperationsSet`.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: [Origin Account], Paused
+		/// - Writes: Paused
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::set_paused_operations())]
+		pub fn set_paused_operations(origin: OriginFor<T>, paused: PausedOperations) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			// *** No Fail beyond this point ***
+
+			let old = Paused::<T>::get();
+			Paused::<T>::put(paused);
+
+			Self::deposit_event(Event::PausedOperationsSet(old, paused));
+			Ok(())
+		}
+
+		/// Stake funds of the origin account on behalf of the beneficiary as a
+		/// collator candidate.
+		///
+		/// The funds stay locked on the origin account and are staked for the
+		/// beneficiary via `join_candidates` or, if the beneficiary already is
+		/// a candidate, `candidate_stake_more`. Hence, the beneficiary has to
+		/// meet the requirements of these calls except for the balance. The
+		/// beneficiary controls the stake and receives its rewards. The origin
+		/// is recorded as the funder and the funded principal is released from
+		/// its lock once the beneficiary has unstaked and unlocked it. Slashes
+		/// of the stake are taken from the funded principal once the
+		/// beneficiary's own staked funds have been slashed.
+		///
+		/// The beneficiary has to accept the origin as their funder via
+		/// `accept_funder` beforehand. A staker can only be funded by a single
+		/// account at a time. They must not have unstaked funds which have not
+		/// been unlocked yet since those would be staked before the funded
+		/// amount.
+		///
+		/// Emits `StakeFunded` and the events of the staking call.
+		///
+		/// # <weight>
+		/// Weight: O(N + D) where N is `MaxSelectedCandidates` bounded by
+		/// `MaxTopCandidates` and D is the number of delegators for this
+		/// candidate bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: [Origin Account], AcceptedFunders, Funders, 2 *
+		///   FundedStake, Unstaking, Locks, see `join_candidates` and
+		///   `candidate_stake_more`
+		/// - Writes: Funders, FundedStake, Locks, see `join_candidates` and
+		///   `candidate_stake_more`
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::bond_candidate_for(
+			T::MaxTopCandidates::get(),
+			T::MaxDelegatorsPerCollator::get()
+		))]
+		pub fn bond_candidate_for(
+			origin: OriginFor<T>,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let funder = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+			Self::ensure_can_fund(&funder, &beneficiary, amount)?;
+
+			// *** No Fail except during the staking beyond this point ***
+
+			// the funding has to be recorded first such that the beneficiary does not need to
+			// lock the funded amount
+			Self::record_funding(&funder, &beneficiary, amount);
+			let origin: OriginFor<T> = frame_system::RawOrigin::Signed(beneficiary.clone()).into();
+			if CandidatePool::<T>::contains_key(&beneficiary) {
+				Self::candidate_stake_more(origin, amount)
+			} else {
+				Self::join_candidates(origin, amount)
+			}
+			.map_err(|e| e.error)?;
+
+			Self::deposit_event(Event::StakeFunded(funder, beneficiary, amount));
+			Ok(())
+		}
+
+		/// Delegate funds of the origin account on behalf of the beneficiary
+		/// to a collator candidate.
+		///
+		/// The funds stay locked on the origin account and are delegated for
+		/// the beneficiary via `join_delegators`, `delegate_another_candidate`
+		/// or, if the beneficiary already delegates to the collator candidate,
+		/// `delegator_stake_more`. The same rules as for `bond_candidate_for`
+		/// apply to the beneficiary and the funded principal.
+		///
+		/// Emits `StakeFunded` and the events of the staking call.
+		///
+		/// # <weight>
+		/// Weight: O(N + D) where N is `MaxSelectedCandidates` bounded by
+		/// `MaxTopCandidates` and D is the number of delegators for this
+		/// candidate bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: [Origin Account], AcceptedFunders, Funders, 2 *
+		///   FundedStake, Unstaking, Locks, see `join_delegators` and
+		///   `delegator_stake_more`
+		/// - Writes: Funders, FundedStake, Locks, see `join_delegators` and
+		///   `delegator_stake_more`
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::bond_delegator_for(
+			T::MaxTopCandidates::get(),
+			T::MaxDelegatorsPerCollator::get()
+		))]
+		pub fn bond_delegator_for(
+			origin: OriginFor<T>,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+			collator: <T::Lookup as StaticLookup>::Source,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let funder = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+			let collator = T::Lookup::lookup(collator)?;
+			Self::ensure_can_fund(&funder, &beneficiary, amount)?;
+			let is_delegating = DelegatorState::<T>::get(&beneficiary)
+				.map(|delegator| delegator.delegations.iter().any(|stake| stake.owner == collator));
+
+			// *** No Fail except during the staking beyond this point ***
+
+			// the funding has to be recorded first such that the beneficiary does not need to
+			// lock the funded amount
+			Self::record_funding(&funder, &beneficiary, amount);
+			let origin: OriginFor<T> = frame_system::RawOrigin::Signed(beneficiary.clone()).into();
+			let collator = T::Lookup::unlookup(collator);
+			match is_delegating {
+				None => Self::join_delegators(origin, collator, amount),
+				Some(false) => Self::delegate_another_candidate(origin, collator, amount),
+				Some(true) => Self::delegator_stake_more(origin, collator, amount),
+			}
+			.map_err(|e| e.error)?;
+
+			Self::deposit_event(Event::StakeFunded(funder, beneficiary, amount));
+			Ok(())
+		}
+
+		/// Accept an account to fund the stake of the origin account via
+		/// `bond_candidate_for` and `bond_delegator_for`, or revoke the
+		/// acceptance by passing `None`.
+		///
+		/// Accepting another funder replaces the previous one. Stake which has
+		/// already been funded is not affected, and it has to be unlocked
+		/// before another account can fund the stake of the origin.
+		///
+		/// Emits `FunderAccepted`.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: [Origin Account]
+		/// - Writes: AcceptedFunders
+		/// # </weight>
+		#[pallet::weight(<T as pallet::Config>::WeightInfo::accept_funder())]
+		pub fn accept_funder(
+			origin: OriginFor<T>,
+			funder: Option<<T::Lookup as StaticLookup>::Source>,
+		) -> DispatchResult {
+			let acc = ensure_signed(origin)?;
+			let funder = funder.map(T::Lookup::lookup).transpose()?;
+			ensure!(funder.as_ref() != Some(&acc), Error::<T>::CannotFundSelf);
+
+			// *** No Fail beyond this point ***
+
+			if let Some(funder) = funder.as_ref() {
+				AcceptedFunders::<T>::insert(&acc, funder);
+			} else {
+				AcceptedFunders::<T>::remove(&acc);
+			}
+
+			Self::deposit_event(Event::FunderAccepted(acc, funder));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Check whether an account is currently delegating.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: DelegatorState
+		/// # </weight>
+		pub fn is_delegator(acc: &T::AccountId) -> bool {
+			DelegatorState::<T>::get(acc).is_some()
+		}
+
+		/// Check whether an account is currently a collator candidate and
+		/// whether their state is CollatorStatus::Active.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: CandidatePool
+		/// # </weight>
+		pub fn is_active_candidate(acc: &T::AccountId) -> Option<bool> {
+			if let Some(state) = CandidatePool::<T>::get(acc) {
+				Some(state.status == CandidateStatus::Active)
+			} else {
+				None
+			}
+		}
+
+		/// Update the top candidates and total amount at stake after mutating
+		/// an active candidate's stake.
+		///
+		/// NOTE: It is assumed that the calling context checks whether the
+		/// collator candidate is currently active before calling this function.
+		///
+		/// The position of the candidate is found by a binary search over
+		/// [TopCandidates] using the old and new total stake of the candidate.
+		///
+		/// # <weight>
+		/// Weight: O(N) where N is the number of `TopCandidates` bounded by
+		/// `MaxTopCandidates` since the whole set is decoded, shifted and
+		/// encoded, but only O(log N) stakes are compared.
+		/// - Reads: TopCandidates, CandidatePool, TotalCollatorStake
+		/// - Writes: TopCandidates, TotalCollatorStake
+		/// # </weight>
+		pub(crate) fn update_top_candidates(
+			candidate: T::AccountId,
+			old_self: BalanceOf<T>,
+			old_delegators: BalanceOf<T>,
+			new_self: BalanceOf<T>,
+			new_delegators: BalanceOf<T>,
+		) -> u32 {
+			let mut top_candidates = TopCandidates::<T>::get();
+			let num_top_candidates: u32 = top_candidates.len().saturated_into();
+			let old_amount = old_self.saturating_add(old_delegators);
+			let new_amount = new_self.saturating_add(new_delegators);
+
+			// update TopCandidates set
+			let maybe_top_candidate_update =
+				if let Some((old_idx, new_idx)) = top_candidates.update_stake(&candidate, old_amount, new_amount) {
+					// case 1: candidate is member of TopCandidates with old stake
+					Some((Some(old_idx), new_idx, top_candidates))
+				} else if top_candidates
+					.try_insert_replace_stake(Stake {
+						owner: candidate.clone(),
+						amount: new_amount,
+					})
+					.is_ok()
+				{
+					// case 2: candidate ascends into TopCandidates with new stake
+					// and might replace another candidate if TopCandidates is full
+					let new_idx = top_candidates
+						.binary_search_stake(&candidate, new_amount)
+						.unwrap_or_else(|idx| idx);
+					Self::deposit_event(Event::EnteredTopCandidates(candidate));
+					Some((None, new_idx, top_candidates))
+				} else {
+					// case 3: candidate neither was nor will be member of TopCandidates
+					None
+				};
+
+			// update storage for TotalCollatorStake and TopCandidates
+			if let Some((maybe_old_idx, new_idx, top_candidates)) = maybe_top_candidate_update {
+				let max_selected_candidates = MaxSelectedCandidates::<T>::get().saturated_into::<usize>();
+				let was_collating = maybe_old_idx.map(|i| i < max_selected_candidates).unwrap_or(false);
+				let is_collating = new_idx < max_selected_candidates;
+
+				// update TopCollatorStake storage iff candidate was or will be a collator
+				match (was_collating, is_collating) {
+					(true, true) => {
+						Self::update_total_stake_by(new_self, new_delegators, old_self, old_delegators);
+					}
+					(true, false) => {
+						// candidate left the collator set because they staked less and have been
+						// replaced by the next candidate in the queue at position
+						// min(max_selected_candidates, top_candidates) - 1 in TopCandidates
+						let new_col_idx = max_selected_candidates.min(top_candidates.len()).saturating_sub(1);
+
+						// get displacer
+						let (add_collators, add_delegators) =
+							Self::get_top_candidate_stake_at(&top_candidates, new_col_idx)
+								// shouldn't be possible to fail, but we handle it gracefully
+								.unwrap_or((new_self, new_delegators));
+						Self::update_total_stake_by(add_collators, add_delegators, old_self, old_delegators);
+					}
+					(false, true) => {
+						// candidate pushed out the least staked collator which is now at position
+						// min(max_selected_top_candidates, top_candidates - 1) in TopCandidates
+						let old_col_idx = max_selected_candidates.min(top_candidates.len().saturating_sub(1));
+
+						// get amount to subtract from TotalCollatorStake
+						let (drop_self, drop_delegators) =
+							Self::get_top_candidate_stake_at(&top_candidates, old_col_idx)
+								// default to zero if candidate DNE, e.g. TopCandidates is not full
+								.unwrap_or((BalanceOf::<T>::zero(), BalanceOf::<T>::zero()));
+						Self::update_total_stake_by(new_self, new_delegators, drop_self, drop_delegators);
+					}
+					_ => {}
+				}
+
+				// update TopCandidates storage
+				TopCandidates::<T>::put(top_candidates);
+			}
+
+			num_top_candidates
+		}
+
+		/// Retrieve the staked amounts (self, sum of delegators) of member of
+		/// [TopCandidates] at the given index, if it exists.
+		fn get_top_candidate_stake_at(
+			top_candidates: &OrderedSet<StakeOf<T>, T::MaxTopCandidates>,
+			index: usize,
+		) -> Option<(BalanceOf<T>, BalanceOf<T>)> {
+			top_candidates
+				.get(index)
+				.and_then(|stake| CandidatePool::<T>::get(&stake.owner))
+				// SAFETY: the total is always more than the stake
+				.map(|state| (state.stake, state.total - state.stake))
+		}
+
+		/// Mutate the [TotalCollatorStake] by both incrementing and decreasing
+		/// it by the provided values.
+		fn update_total_stake_by(
+			add_collators: BalanceOf<T>,
+			add_delegators: BalanceOf<T>,
+			sub_collators: BalanceOf<T>,
+			sub_delegators: BalanceOf<T>,
+		) {
+			TotalCollatorStake::<T>::mutate(|total| {
+				total.collators = total
+					.collators
+					.saturating_sub(sub_collators)
+					.saturating_add(add_collators);
+				total.delegators = total
+					.delegators
+					.saturating_sub(sub_delegators)
+					.saturating_add(add_delegators);
+			});
+		}
+
+		/// Iterate over the top `MaxSelectedCandidates` many collators in terms
+		/// of cumulated stake (self + from delegators) from the [TopCandidates]
+		/// and recalculate the [TotalCollatorStake] from scratch.
+		///
+		/// NOTE: Should only be called in rare circumstances in which we cannot
+		/// guarantee a single candidate's stake has changed, e.g. on genesis or
+		/// when a collator leaves. Otherwise, please use
+		/// [update_total_stake_by].
+		///
+		/// # <weight>
+		/// Weight: O(N) where N is `MaxSelectedCandidates` bounded by
+		/// `MaxTopCandidates`
+		/// - Reads: TopCandidates, MaxSelectedCandidates, N * CandidatePool,
+		///   TotalCollatorStake
+		/// - Writes: TotalCollatorStake
+		/// # </weight>
+		fn update_total_stake() -> (u32, u32) {
+			let mut num_of_delegators = 0u32;
+			let mut collator_stake = BalanceOf::<T>::zero();
+			let mut delegator_stake = BalanceOf::<T>::zero();
+
+			let collators = Self::selected_candidates();
+
+			// Snapshot exposure for round for weighting reward distribution
+			for account in collators.iter() {
+				let state =
+					CandidatePool::<T>::get(&account).expect("all members of TopCandidates must be candidates q.e.d");
+				num_of_delegators = num_of_delegators.max(state.delegators.len().saturated_into::<u32>());
+
+				// sum up total stake and amount of collators, delegators
+				let amount_collator = state.stake;
+				collator_stake = collator_stake.saturating_add(state.stake);
+				// safe to subtract because total >= stake
+				let amount_delegators = state.total - amount_collator;
+				delegator_stake = delegator_stake.saturating_add(amount_delegators);
+			}
+
+			TotalCollatorStake::<T>::mutate(|total| {
+				total.collators = collator_stake;
+				total.delegators = delegator_stake;
+			});
+
+			// return number of selected candidates and the corresponding number of their
+			// delegators for post-weight correction
+			(collators.len().saturated_into(), num_of_delegators)
+		}
+
+		/// Update the delegator's state by removing the collator candidate from
+		/// the set of ongoing delegations.
+		///
+		/// # <weight>
+		/// Weight: O(D) where D is the number of total delegators for
+		/// this collator bounded by `MaxCollatorsPerDelegator`.
+		/// - Reads: [Origin Account], DelegatorState, BlockNumber, Unstaking,
+		///   Locks, TopCandidates, D * CandidatePool, MaxSelectedCandidates
+		/// - Writes: Unstaking, Locks, DelegatorState, CandidatePool,
+		///   TotalCollatorStake
+		/// - Kills: DelegatorState and Payee if the delegator has not
+		///   delegated to another collator
+		/// # </weight>
+		fn delegator_revokes_collator(acc: T::AccountId, collator: T::AccountId) -> Result<u32, DispatchError> {
+			let mut delegator = DelegatorState::<T>::get(&acc).ok_or(Error::<T>::DelegatorNotFound)?;
+			let old_total = delegator.total;
+			let num_delegations: u32 = delegator.delegations.len().saturated_into::<u32>();
+			let remaining = delegator
+				.rm_delegation(&collator)
+				.ok_or(Error::<T>::DelegationNotFound)?;
+
+			// edge case; if no delegations remaining, leave set of delegators
+			if delegator.delegations.is_empty() {
+				// leave the set of delegators because no delegations left
+				Self::delegator_leaves_collator(acc.clone(), collator)?;
+				DelegatorState::<T>::remove(&acc);
+				Self::clear_payee(&acc);
+				Self::deposit_event(Event::DelegatorLeft(acc, old_total));
+			} else {
+				// can never fail iff MinDelegatorStake == MinDelegation
+				ensure!(remaining >= T::MinDelegatorStake::get(), Error::<T>::NomStakeBelowMin);
+				Self::delegator_leaves_collator(acc.clone(), collator)?;
+				DelegatorState::<T>::insert(&acc, delegator);
+			}
+			Ok(num_delegations)
+		}
+
+		/// Update the collator's state by removing the delegator's stake and
+		/// starting the process to unlock the delegator's staked funds.
+		///
+		/// This operation affects the pallet's total stake.
+		///
+		/// The freed delegation slot is taken by the first delegator on the
+		/// waitlist of the collator.
+		///
+		/// # <weight>
+		/// Weight: O(D + W) where D is the number of delegators for this
+		/// collator bounded by `MaxDelegatorsPerCollator` and W is the size of
+		/// the collator's waitlist bounded by `MaxWaitlistSize`.
+		/// - Reads: CandidatePool, BlockNumber, Unstaking, Waitlist
+		/// - Writes: Unstaking, TotalCollatorStake, CandidatePool
+		/// # </weight>
+		fn delegator_leaves_collator(delegator: T::AccountId, collator: T::AccountId) -> DispatchResult {
+			let mut state = CandidatePool::<T>::get(&collator).ok_or(Error::<T>::CandidateNotFound)?;
+
+			// credit the rewards earned with the current stakes before changing them
+			Self::settle_candidate_rewards(&state);
+
+			let delegator_stake = state
+				.delegators
+				.remove(&Stake {
+					owner: delegator.clone(),
+					// amount is irrelevant for removal
+					amount: BalanceOf::<T>::one(),
+				})
+				.map(|nom| nom.amount)
+				.ok_or(Error::<T>::DelegatorNotFound)?;
+
+			let CandidateOf::<T, _> {
+				stake: old_stake,
+				total: old_total,
+				..
+			} = state;
+			state.total = state.total.saturating_sub(delegator_stake);
+			let new_total = state.total;
+
+			// we don't unlock immediately
+			Self::prep_unstake(&delegator, delegator_stake, false)?;
+
+			// update top candidates and total amount at stake
+			if state.is_active() {
+				Self::update_top_candidates(
+					collator.clone(),
+					old_stake,
+					// safe because total >= stake
+					old_total - old_stake,
+					state.stake,
+					state.total - state.stake,
+				);
+			}
+			CandidatePool::<T>::insert(&collator, state);
+			Self::remove_rewards_checkpoint(&delegator, &collator);
+
+			Self::deposit_event(Event::DelegatorLeftCollator(
+				delegator,
+				collator.clone(),
+				delegator_stake,
+				new_total,
+			));
+
+			// fill the delegation slot which has become available
+			Self::promote_waitlisted(&collator)
+		}
+
+		/// Check for remaining delegations of the delegator which has been
+		/// removed from the given collator.
+		///
+		/// Returns the removed delegator's address and
+		/// * Either the updated delegator state if other delegations are still
+		///   remaining
+		/// * Or `None`, signalling the delegator state should be cleared once
+		///   the transaction cannot fail anymore.
+		fn prep_kick_delegator(
+			delegation: &StakeOf<T>,
+			collator: &T::AccountId,
+		) -> Result<ReplacedDelegator<T>, DispatchError> {
+			let mut state = DelegatorState::<T>::get(&delegation.owner).ok_or(Error::<T>::DelegatorNotFound)?;
+			state.rm_delegation(collator);
+
+			// either wait for a free slot or unstake
+			Self::waitlist_or_unstake(delegation, collator)?;
+
+			// the rewards of the kicked delegation have been settled by the caller
+			Self::remove_rewards_checkpoint(&delegation.owner, collator);
+
+			// return state if not empty for later removal after all checks have passed
+			if state.delegations.is_empty() {
+				Ok(ReplacedDelegator {
+					who: delegation.owner.clone(),
+					state: None,
+				})
+			} else {
+				Ok(ReplacedDelegator {
+					who: delegation.owner.clone(),
+					state: Some(state),
+				})
+			}
+		}
+
+		/// Either clear the storage of a kicked delegator or update its
+		/// delegation state if it still contains other delegations.
+		fn update_kicked_delegator_storage(delegator: Option<ReplacedDelegator<T>>) {
+			match delegator {
+				Some(ReplacedDelegator {
+					who,
+					state: Some(state),
+				}) => DelegatorState::<T>::insert(who, state),
+				Some(ReplacedDelegator { who, .. }) => {
+					DelegatorState::<T>::remove(&who);
+					Self::clear_payee(&who);
+				}
+				_ => (),
+			}
+		}
+
+		/// Place the delegator on the waitlist of the collator candidate such
+		/// that they are promoted once a delegation slot becomes available.
+		///
+		/// If `lock` is set, the waiting amount is added to the staking lock of
+		/// the delegator. Otherwise, the amount is expected to be locked
+		/// already, e.g., because it was staked in a kicked delegation.
+		///
+		/// Throws `TooManyDelegators` if the waitlist of the candidate is full
+		/// or disabled.
+		///
+		/// # <weight>
+		/// Weight: O(C + W) where C is the number of waitlist entries of the
+		/// delegator bounded by `MaxCollatorsPerDelegator` and W is the size
+		/// of the waitlist bounded by `MaxWaitlistSize`.
+		/// - Reads: C * Waitlisted, Waitlist, DelegatorState, Unstaking, Locks,
+		///   Funders, FundedStake
+		/// - Writes: Waitlisted, Waitlist, Locks
+		/// # </weight>
+		fn waitlist_delegator(
+			who: &T::AccountId,
+			collator: &T::AccountId,
+			amount: BalanceOf<T>,
+			lock: bool,
+		) -> DispatchResult {
+			ensure!(
+				!Waitlisted::<T>::contains_key(who, collator),
+				Error::<T>::AlreadyWaitlisted
+			);
+			ensure!(
+				Waitlisted::<T>::iter_prefix(who).count().saturated_into::<u32>() < T::MaxCollatorsPerDelegator::get(),
+				Error::<T>::MaxCollatorsPerDelegatorExceeded
+			);
+			let mut waitlist = Waitlist::<T>::get(collator);
+			waitlist
+				.try_push(who.clone())
+				.map_err(|_| Error::<T>::TooManyDelegators)?;
+
+			// the staking lock is not used as the base since it already includes funded
+			// principal which has been recorded before the funded stake is waitlisted
+			let locked = DelegatorState::<T>::get(who)
+				.map_or_else(Zero::zero, |delegator| delegator.total)
+				.saturating_add(Self::unstaking_total(who))
+				.saturating_add(Self::waitlisted_total(who))
+				.saturating_add(amount);
+			if lock {
+				Self::ensure_can_lock(who, locked)?;
+			}
+
+			// *** No Fail beyond this point ***
+
+			if lock {
+				Self::set_staking_lock(who, locked);
+			}
+			Waitlist::<T>::insert(collator, waitlist);
+			Waitlisted::<T>::insert(who, collator, amount);
+
+			Self::deposit_event(Event::DelegatorWaitlisted(who.clone(), collator.clone(), amount));
+			Ok(())
+		}
+
+		/// Move a kicked delegation to the waitlist of the collator candidate.
+		///
+		/// If the waitlist is full or disabled, the stake is prepared for
+		/// unstaking instead.
+		fn waitlist_or_unstake(delegation: &StakeOf<T>, collator: &T::AccountId) -> DispatchResult {
+			if Self::waitlist_delegator(&delegation.owner, collator, delegation.amount, false).is_err() {
+				// we don't unlock immediately
+				Self::prep_unstake(&delegation.owner, delegation.amount, true)?;
+			}
+			Ok(())
+		}
+
+		/// Unlock the funds of a delegator which has been removed from the
+		/// waitlist of the collator candidate and release them to their
+		/// funder.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: 2 * Locks, Funders, 2 * FundedStake
+		/// - Writes: 2 * Locks, Funders, FundedStake
+		/// - Kills: Locks if no balance is locked anymore
+		/// # </weight>
+		fn release_waitlisted(who: &T::AccountId, collator: &T::AccountId, amount: BalanceOf<T>) {
+			let locked = Self::staking_lock(who).saturating_sub(amount);
+			Self::repay_funder(who, amount, locked.is_zero());
+			Self::set_staking_lock(who, locked);
+
+			Self::deposit_event(Event::WaitlistLeft(who.clone(), collator.clone(), amount));
+		}
+
+		/// Promote delegators from the waitlist of the collator candidate as
+		/// long as delegation slots are available.
+		///
+		/// If the candidate has `MaxDelegatorsPerCollator` many delegators, the
+		/// first waiting delegator replaces the lowest delegation if they wait
+		/// with a higher amount. The replaced delegation is kicked and
+		/// potentially placed on the waitlist.
+		///
+		/// Waiting delegators which cannot delegate anymore, e.g., because they
+		/// have become a collator candidate or have reached
+		/// `MaxCollatorsPerDelegator`, are removed from the waitlist and their
+		/// funds are unlocked.
+		///
+		/// Emits `DelegatorPromoted` for each promoted delegator.
+		///
+		/// # <weight>
+		/// Weight: O(W * (C + D) + N) where W is the size of the waitlist
+		/// bounded by `MaxWaitlistSize`, C is the number of delegations of the
+		/// waiting delegators bounded by `MaxCollatorsPerDelegator`, D is the
+		/// number of delegators for this collator bounded by
+		/// `MaxDelegatorsPerCollator` and N is `MaxSelectedCandidates` bounded
+		/// by `MaxTopCandidates`.
+		/// - Reads: Waitlist, CandidatePool, W * Waitlisted, W *
+		///   DelegatorState, W * Locks, TopCandidates, MaxSelectedCandidates
+		/// - Writes: Waitlist, CandidatePool, W * Waitlisted, W *
+		///   DelegatorState, W * Locks, TopCandidates, TotalCollatorStake
+		/// # </weight>
+		fn promote_waitlisted(collator: &T::AccountId) -> DispatchResult {
+			let mut waitlist = Waitlist::<T>::get(collator);
+			if waitlist.is_empty() {
+				return Ok(());
+			}
+			let mut state = match CandidatePool::<T>::get(collator) {
+				Some(state) if !state.is_leaving() => state,
+				_ => return Ok(()),
+			};
+
+			// credit the rewards earned with the current stakes before changing them
+			Self::settle_candidate_rewards(&state);
+
+			let CandidateOf::<T, _> {
+				stake: old_stake,
+				total: old_total,
+				..
+			} = state;
+			let mut maybe_kicked_delegator = None;
+			while let Some(who) = waitlist.first().cloned() {
+				let amount = Waitlisted::<T>::get(&who, collator).unwrap_or_else(Zero::zero);
+				let num_delegations: u32 = state.delegators.len().saturated_into();
+				if num_delegations >= T::MaxDelegatorsPerCollator::get() {
+					// only replace the lowest delegation if the waiting delegator staked more
+					let lowest = state
+						.delegators
+						.get(state.delegators.len().saturating_sub(1))
+						.map(|stake| stake.amount)
+						.unwrap_or_else(Zero::zero);
+					if amount <= lowest {
+						break;
+					}
+				}
+				waitlist.remove(0);
+				Waitlisted::<T>::remove(&who, collator);
+
+				// build the delegator state including the promoted delegation
+				let delegation = Stake {
+					owner: collator.clone(),
+					amount,
+				};
+				let delegator = if Self::is_active_candidate(&who).is_some() {
+					None
+				} else if let Some(mut delegator) = DelegatorState::<T>::get(&who) {
+					match delegator.add_delegation(delegation) {
+						Ok(true) => Some(delegator),
+						_ => None,
+					}
+				} else {
+					Delegator::try_new(collator.clone(), amount).ok()
+				};
+				let delegator = match delegator {
+					Some(delegator) if delegator.total >= T::MinDelegatorStake::get() => delegator,
+					_ => {
+						Self::release_waitlisted(&who, collator, amount);
+						continue;
+					}
+				};
+
+				let replaced = match state.delegators.try_insert_replace(Stake {
+					owner: who.clone(),
+					amount,
+				}) {
+					Ok(replaced) => replaced,
+					Err(_) => {
+						// should never occur because we checked the size and uniqueness above
+						Self::release_waitlisted(&who, collator, amount);
+						continue;
+					}
+				};
+				state.total = state.total.saturating_add(amount);
+				DelegatorState::<T>::insert(&who, delegator);
+				Self::init_rewards_checkpoint(&who, collator);
+
+				if let Some(replaced) = replaced {
+					state.total = state.total.saturating_sub(replaced.amount);
+					// the kicked delegation might be placed on the waitlist again
+					Waitlist::<T>::insert(collator, waitlist.clone());
+					maybe_kicked_delegator = Some(Self::prep_kick_delegator(&replaced, collator)?);
+					Self::deposit_event(Event::DelegationReplaced(
+						who.clone(),
+						amount,
+						replaced.owner,
+						replaced.amount,
+						collator.clone(),
+						state.total,
+					));
+				}
+				Self::deposit_event(Event::DelegatorPromoted(who, collator.clone(), amount, state.total));
+				if maybe_kicked_delegator.is_some() {
+					break;
+				}
+			}
+			if maybe_kicked_delegator.is_none() {
+				Waitlist::<T>::insert(collator, waitlist);
+			}
+
+			// update top candidates and total amount at stake
+			if state.is_active() {
+				Self::update_top_candidates(
+					collator.clone(),
+					old_stake,
+					// safe because total >= stake
+					old_total - old_stake,
+					state.stake,
+					state.total - state.stake,
+				);
+			}
+			CandidatePool::<T>::insert(collator, state);
+
+			// update or clear storage of potentially kicked delegator
+			Self::update_kicked_delegator_storage(maybe_kicked_delegator);
+			Ok(())
+		}
+
+		/// Return the total amount the account waits with for free delegation
+		/// slots.
+		pub(crate) fn waitlisted_total(who: &T::AccountId) -> BalanceOf<T> {
+			Waitlisted::<T>::iter_prefix_values(who)
+				.fold(BalanceOf::<T>::zero(), |acc, amount| acc.saturating_add(amount))
+		}
+
+		/// Return the total amount the account has unstaked but not unlocked
+		/// yet.
+		fn unstaking_total(who: &T::AccountId) -> BalanceOf<T> {
+			Unstaking::<T>::get(who)
+				.values()
+				.fold(BalanceOf::<T>::zero(), |acc, amount| acc.saturating_add(*amount))
+		}
+
+		/// Return the amount which is locked for staking by the account.
+		///
+		/// This includes the principal which has been funded for the account
+		/// and is locked on the funder's account, but not the principal the
+		/// account has funded for other stakers.
+		pub(crate) fn staking_lock(who: &T::AccountId) -> BalanceOf<T> {
+			Locks::<T>::get(who)
+				.iter()
+				.find(|l| l.id == STAKING_ID)
+				.map(|l| l.amount.into())
+				.unwrap_or_else(Zero::zero)
+				.saturating_add(Self::funded_principal(who))
+				.saturating_sub(FundedStake::<T>::get(who))
+		}
+
+		/// Return the principal which has been funded for the staker.
+		fn funded_principal(who: &T::AccountId) -> BalanceOf<T> {
+			Funders::<T>::get(who).map_or_else(Zero::zero, |(_, funded)| funded)
+		}
+
+		/// Return the amount which has to be locked on the account if it
+		/// stakes the given amount, see [Pallet::staking_lock].
+		fn own_lock(who: &T::AccountId, locked: BalanceOf<T>) -> BalanceOf<T> {
+			locked
+				.saturating_add(FundedStake::<T>::get(who))
+				.saturating_sub(Self::funded_principal(who))
+		}
+
+		/// Check that the account can lock the given amount for staking, see
+		/// [Pallet::staking_lock].
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: Funders, FundedStake, System.Account
+		/// # </weight>
+		fn ensure_can_lock(who: &T::AccountId, locked: BalanceOf<T>) -> DispatchResult {
+			ensure!(
+				pallet_balances::Pallet::<T>::free_balance(who) >= Self::own_lock(who, locked).into(),
+				pallet_balances::Error::<T>::InsufficientBalance
+			);
+			Ok(())
+		}
+
+		/// Set the staking lock of the account to the given amount, see
+		/// [Pallet::staking_lock].
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: Funders, FundedStake
+		/// - Writes: Locks
+		/// - Kills: Locks if no balance is locked anymore
+		/// # </weight>
+		fn set_staking_lock(who: &T::AccountId, locked: BalanceOf<T>) {
+			let locked = Self::own_lock(who, locked);
+			if locked.is_zero() {
+				T::Currency::remove_lock(STAKING_ID, who);
+			} else {
+				T::Currency::set_lock(STAKING_ID, who, locked, WithdrawReasons::all());
+			}
+		}
+
+		/// Return the best `MaxSelectedCandidates` many candidates.
+		///
+		/// In case a collator from last round was replaced by a candidate with
+		/// the same total stake during sorting, we revert this swap to
+		/// prioritize collators over candidates.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: TopCandidates, MaxSelectedCandidates
+		/// # </weight>
+		pub fn selected_candidates() -> BoundedVec<T::AccountId, T::MaxTopCandidates> {
+			let candidates = TopCandidates::<T>::get();
+
+			// Should never fail since WASM usize are 32bits and native are either 32 or 64
+			let top_n = MaxSelectedCandidates::<T>::get().saturated_into::<usize>();
+
+			log::trace!("{} Candidates for {} Collator seats", candidates.len(), top_n);
+
+			// Choose the top MaxSelectedCandidates qualified candidates
+			let collators = candidates
+				.into_iter()
+				.take(top_n)
+				.filter(|x| x.amount >= T::MinCollatorStake::get())
+				.map(|x| x.owner)
+				.collect::<Vec<T::AccountId>>();
+
+			collators.try_into().expect("Did not extend Collators q.e.d.")
+		}
+
+		/// Return the validator set for the next session.
+		///
+		/// The invulnerable collators are always part of it. The remaining
+		/// `MaxSelectedCandidates` seats are filled with the best staked
+		/// candidates which are not invulnerable. Candidates without registered
+		/// session keys are skipped since they cannot author blocks.
+		///
+		/// # <weight>
+		/// Weight: O(N) where N is `MaxSelectedCandidates` bounded by
+		/// `MaxTopCandidates`.
+		/// - Reads: Invulnerables, TopCandidates, MaxSelectedCandidates, N *
+		///   NextKeys
+		/// # </weight>
+		pub fn next_session_collators() -> BoundedVec<T::AccountId, T::MaxTopCandidates> {
+			let mut collators = Invulnerables::<T>::get();
+			let top_n = MaxSelectedCandidates::<T>::get().saturated_into::<usize>();
+
+			for candidate in Self::selected_candidates() {
+				if collators.len() >= top_n {
+					break;
+				}
+				if collators.contains(&candidate) || !Self::has_session_keys(&candidate) {
+					continue;
+				}
+				if collators.try_push(candidate).is_err() {
+					break;
+				}
+			}
+
+			collators
+		}
+
+		/// Add the account to the set of collator candidates without checking
+		/// its session keys and identity, see [join_candidates].
+		///
+		/// The genesis stakers are added this way since the session keys are
+		/// not necessarily set when building the genesis of this pallet.
+		///
+		/// # <weight>
+		/// Weight: O(N + D) where N is `MaxSelectedCandidates` bounded by
+		/// `MaxTopCandidates` and D is the number of delegators for this
+		/// candidate bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: DelegatorState, MaxCollatorCandidateStake, Locks,
+		///   TotalCollatorStake, TopCandidates, MaxSelectedCandidates,
+		///   CandidatePool,
+		/// - Writes: Locks, TotalCollatorStake, CandidatePool, TopCandidates,
+		/// # </weight>
+		fn do_join_candidates(sender: T::AccountId, stake: BalanceOf<T>) -> DispatchResultWithPostInfo {
+			if let Some(is_active_candidate) = Self::is_active_candidate(&sender) {
+				ensure!(is_active_candidate, Error::<T>::AlreadyLeaving);
+				ensure!(!is_active_candidate, Error::<T>::CandidateExists);
+			}
+			ensure!(!Self::is_delegator(&sender), Error::<T>::DelegatorExists);
+			ensure!(
+				stake >= T::MinCollatorCandidateStake::get(),
+				Error::<T>::ValStakeBelowMin
+			);
+			ensure!(
+				stake <= MaxCollatorCandidateStake::<T>::get(),
+				Error::<T>::ValStakeAboveMax
+			);
+			ensure!(
+				Unstaking::<T>::get(&sender).len().saturated_into::<u32>() < T::MaxUnstakeRequests::get(),
+				Error::<T>::CannotJoinBeforeUnlocking
+			);
+
+			// *** No Fail except during increase_lock beyond this point ***
+
+			Self::increase_lock(&sender, stake, BalanceOf::<T>::zero())?;
+
+			let candidate = Candidate::new(sender.clone(), stake);
+			let n = Self::update_top_candidates(
+				sender.clone(),
+				BalanceOf::<T>::zero(),
+				BalanceOf::<T>::zero(),
+				stake,
+				BalanceOf::<T>::zero(),
+			);
+			CandidatePool::<T>::insert(&sender, candidate);
+			Self::init_rewards_checkpoint(&sender, &sender);
+
+			Self::deposit_event(Event::JoinedCollatorCandidates(sender, stake));
+			Ok(Some(<T as pallet::Config>::WeightInfo::join_candidates(
+				n,
+				T::MaxDelegatorsPerCollator::get(),
+			))
+			.into())
+		}
+
+		/// Check that the funder is allowed to stake the amount on behalf of
+		/// the beneficiary and can lock it.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: AcceptedFunders, Funders, Unstaking, Locks, FundedStake,
+		///   System.Account
+		/// # </weight>
+		fn ensure_can_fund(funder: &T::AccountId, beneficiary: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+			ensure!(funder != beneficiary, Error::<T>::CannotFundSelf);
+			ensure!(!Self::is_pool_account(beneficiary), Error::<T>::CannotFundPool);
+			ensure!(
+				AcceptedFunders::<T>::get(beneficiary).as_ref() == Some(funder),
+				Error::<T>::FunderNotAccepted
+			);
+			if let Some((recorded_funder, _)) = Funders::<T>::get(beneficiary) {
+				ensure!(recorded_funder == *funder, Error::<T>::FunderMismatch);
+			}
+			ensure!(
+				Unstaking::<T>::get(beneficiary).is_empty(),
+				Error::<T>::FundingWhileUnstaking
+			);
+			Self::ensure_can_lock(funder, Self::staking_lock(funder).saturating_add(amount))
+		}
+
+		/// Add the amount to the principal the funder has funded for the
+		/// staker and lock it on the funder's account.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: Funders, 2 * FundedStake, Locks
+		/// - Writes: Funders, FundedStake, Locks
+		/// # </weight>
+		fn record_funding(funder: &T::AccountId, staker: &T::AccountId, amount: BalanceOf<T>) {
+			let locked = Self::staking_lock(funder);
+			Funders::<T>::mutate(staker, |funding| {
+				let funded = funding.as_ref().map_or_else(Zero::zero, |(_, funded)| *funded);
+				*funding = Some((funder.clone(), funded.saturating_add(amount)));
+			});
+			FundedStake::<T>::mutate(funder, |funded| *funded = funded.saturating_add(amount));
+			Self::set_staking_lock(funder, locked);
+		}
+
+		/// Release the funded principal of a staker from the lock of their
+		/// funder after funds of the staker have been unlocked.
+		///
+		/// At most the unlocked amount is released unless nothing is locked
+		/// for the staker anymore. The funding is removed once all of the
+		/// principal has been released.
+		///
+		/// The caller has to update the staking lock of the staker afterwards
+		/// since the released principal is not locked on their account.
+		///
+		/// Emits `FundsReturned`.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: Funders, 2 * FundedStake, Locks
+		/// - Writes: Funders, FundedStake, Locks
+		/// # </weight>
+		fn repay_funder(staker: &T::AccountId, unlocked: BalanceOf<T>, is_unlocked: bool) {
+			let (funder, funded) = if let Some(funding) = Funders::<T>::get(staker) {
+				funding
+			} else {
+				return;
+			};
+			let released = if is_unlocked { funded } else { funded.min(unlocked) };
+			if released.is_zero() {
+				return;
+			}
+			Self::release_funding(staker, &funder, funded, released);
+
+			Self::deposit_event(Event::FundsReturned(staker.clone(), funder, released));
+		}
+
+		/// Reduce the funded principal of a staker by the released amount and
+		/// unlock it on the account of their funder.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: 2 * FundedStake, Locks
+		/// - Writes: Funders, FundedStake, Locks
+		/// # </weight>
+		fn release_funding(
+			staker: &T::AccountId,
+			funder: &T::AccountId,
+			funded: BalanceOf<T>,
+			released: BalanceOf<T>,
+		) {
+			let locked = Self::staking_lock(funder);
+			let remaining = funded.saturating_sub(released);
+			if remaining.is_zero() {
+				Funders::<T>::remove(staker);
+			} else {
+				Funders::<T>::insert(staker, (funder.clone(), remaining));
+			}
+			FundedStake::<T>::mutate_exists(funder, |funded| {
+				*funded = funded
+					.map(|funded| funded.saturating_sub(released))
+					.filter(|funded| !funded.is_zero());
+			});
+			Self::set_staking_lock(funder, locked);
+		}
+
+		/// Return whether the account has registered session keys in the
+		/// session pallet.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: NextKeys
+		/// # </weight>
+		pub(crate) fn has_session_keys(who: &T::AccountId) -> bool {
+			<T as pallet_session::Config>::ValidatorIdOf::convert(who.clone())
+				.map_or(false, |id| pallet_session::Pallet::<T>::is_registered(&id))
+		}
+
+		/// Check that the kind of staking operations selected by `operation`
+		/// has not been paused via `set_paused_operations`.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: Paused
+		/// # </weight>
+		fn ensure_not_paused(operation: impl FnOnce(PausedOperations) -> bool) -> DispatchResult {
+			ensure!(!operation(Paused::<T>::get()), Error::<T>::OperationPaused);
+			Ok(())
+		}
+
+		/// Return whether the blocks authored by the given collator are rejected
+		/// because it has been removed from the set of collator candidates but
+		/// is still part of the validator set.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: DisabledCollators, CurrentIndex
+		/// # </weight>
+		pub fn is_disabled_author(author: &T::AccountId) -> bool {
+			DisabledCollators::<T>::get(author)
+				.map_or(false, |until| pallet_session::Pallet::<T>::current_index() < until)
+		}
+
+		/// Return whether the blocks of the given collator can be rejected
+		/// without halting the block production, i.e. whether another
+		/// collator of the current and of the queued validator set can still
+		/// author blocks.
+		///
+		/// # <weight>
+		/// Weight: O(N) where N is `MaxSelectedCandidates` bounded by
+		/// `MaxTopCandidates`.
+		/// - Reads: CurrentIndex, 2 * SessionCollators, 2 * N *
+		///   DisabledCollators
+		/// # </weight>
+		fn can_disable_author(collator: &T::AccountId) -> bool {
+			let current = pallet_session::Pallet::<T>::current_index();
+			[current, current.saturating_add(1)].iter().all(|session| {
+				let authors = SessionCollators::<T>::get(session);
+				// the queued validator set might not have been assembled yet
+				authors.is_empty()
+					|| authors
+						.iter()
+						.any(|author| author != collator && !Self::is_disabled_author(author))
+			})
+		}
+
+		/// Return whether the blocks authored by the given collator are
+		/// rewarded.
+		///
+		/// # <weight>
+		/// Weight: O(N) where N is the number of invulnerables bounded by
+		/// `MaxSelectedCandidates`.
+		/// - Reads: Invulnerables
+		/// # </weight>
+		fn is_rewarded(collator: &T::AccountId) -> bool {
+			T::RewardInvulnerables::get() || !Invulnerables::<T>::get().contains(collator)
+		}
+
+		/// Attempts to add the stake to the set of delegators of a collator
+		/// which already reached its maximum size by removing an already
+		/// existing delegator with less staked value. If the given staked
+		/// amount is at most the minimum staked value of the original delegator
+		/// set, an error is returned.
+		///
+		/// Returns a tuple which contains the updated candidate state as well
+		/// as the potentially replaced delegation which will be used later when
+		/// updating the storage of the replaced delegator.
+		///
+		/// Emits `DelegationReplaced` if the stake exceeds one of the current
+		/// delegations.
+		///
+		/// # <weight>
+		/// Weight: O(D) where D is the number of delegators for this collator
+		/// bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads/Writes: 0
+		/// # </weight>
+		#[allow(clippy::type_complexity)]
+		fn do_update_delegator(
+			stake: Stake<T::AccountId, BalanceOf<T>>,
+			mut state: Candidate<T::AccountId, BalanceOf<T>, T::MaxDelegatorsPerCollator>,
+		) -> Result<
+			(
+				CandidateOf<T, T::MaxDelegatorsPerCollator>,
+				Option<ReplacedDelegator<T>>,
+			),
+			DispatchError,
+		> {
+			// attempt to replace the last element of the set
+			let stake_to_remove = state
+				.delegators
+				.try_insert_replace(stake.clone())
+				.map_err(|err_too_many| {
+					if err_too_many {
+						Error::<T>::TooManyDelegators
+					} else {
+						// should never occur because we previously check this case, but let's be sure
+						Error::<T>::AlreadyDelegating
+					}
+				})?;
+
+			state.total = state.total.saturating_add(stake.amount);
+
+			if let Some(stake_to_remove) = stake_to_remove {
+				// update total stake
+				state.total = state.total.saturating_sub(stake_to_remove.amount);
+
+				// update storage of kicked delegator
+				let kicked_delegator = Self::prep_kick_delegator(&stake_to_remove, &state.id)?;
+
+				Self::deposit_event(Event::DelegationReplaced(
+					stake.owner,
+					stake.amount,
+					stake_to_remove.owner,
+					stake_to_remove.amount,
+					state.id.clone(),
+					state.total,
+				));
+
+				Ok((state, Some(kicked_delegator)))
+			} else {
+				Ok((state, None))
+			}
+		}
+
+		/// Either set or increase the BalanceLock of target account to
+		/// amount.
+		///
+		/// Consumes unstaked balance which can be unlocked in the future up to
+		/// amount and updates `Unstaking` storage accordingly.
+		///
+		/// The funds the account waits with on waitlists stay locked on top of
+		/// the amount.
+		///
+		/// # <weight>
+		/// Weight: O(U + C) where U is the number of locked unstaking requests
+		/// bounded by `MaxUnstakeRequests` and C is the number of waitlist
+		/// entries of the account bounded by `MaxCollatorsPerDelegator`.
+		/// - Reads: Unstaking, Locks, C * Waitlisted
+		/// - Writes: Unstaking, Locks
+		/// # </weight>
+		fn increase_lock(who: &T::AccountId, amount: BalanceOf<T>, more: BalanceOf<T>) -> Result<u32, DispatchError> {
+			let locked = amount.saturating_add(Self::waitlisted_total(who));
+			Self::ensure_can_lock(who, locked)?;
+
+			let mut unstaking_len = 0u32;
+
+			// *** No Fail except during Unstaking mutation beyond this point ***
+
+			// update Unstaking by consuming up to {amount | more}
+			<Unstaking<T>>::try_mutate(who, |unstaking| -> DispatchResult {
+				// reduce {amount | more} by unstaking until either {amount | more} is zero or
+				// no unstaking is left
+				// if more is set, we only want to reduce by more to achieve 100 - 40 + 30 = 90
+				// locked
+				let mut amt_consuming_unstaking = if more.is_zero() { amount } else { more };
+				unstaking_len = unstaking.len().saturated_into();
+				for (block_number, locked_balance) in unstaking.clone() {
+					if amt_consuming_unstaking.is_zero() {
+						break;
+					} else if locked_balance > amt_consuming_unstaking {
+						// amount is only reducible by locked_balance - amt_consuming_unstaking
+						let delta = locked_balance.saturating_sub(amt_consuming_unstaking);
+						// replace old entry with delta
+						unstaking
+							.try_insert(block_number, delta)
+							.map_err(|_| Error::<T>::NoMoreUnstaking)?;
+						amt_consuming_unstaking = Zero::zero();
+					} else {
+						// amount is either still reducible or reached
+						amt_consuming_unstaking = amt_consuming_unstaking.saturating_sub(locked_balance);
+						unstaking.remove(&block_number);
+					}
+				}
+				Ok(())
+			})?;
+
+			// Either set a new lock or potentially extend the existing one if amount
+			// exceeds the currently locked amount
+			T::Currency::extend_lock(STAKING_ID, who, Self::own_lock(who, locked), WithdrawReasons::all());
+
+			Ok(unstaking_len)
+		}
+
+		/// Set the unlocking block for the account and corresponding amount
+		/// which can be unlocked via `unlock_unstaked` after waiting at
+		/// least for `StakeDuration` many blocks.
+		///
+		/// Throws if the amount is zero (unlikely) or if active unlocking
+		/// requests exceed limit. The latter defends against stake reduction
+		/// spamming.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: BlockNumber, Unstaking
+		/// - Writes: Unstaking
+		/// # </weight>
+		fn prep_unstake(who: &T::AccountId, amount: BalanceOf<T>, is_removal: bool) -> DispatchResult {
+			// should never occur but let's be safe
+			ensure!(!amount.is_zero(), Error::<T>::StakeNotFound);
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			let unlock_block = now.saturating_add(T::StakeDuration::get());
+			let mut unstaking = <Unstaking<T>>::get(who);
+
+			let allowed_unstakings = if is_removal {
+				// the account was forcedly removed and we allow to fill all unstake requests
+				T::MaxUnstakeRequests::get()
+			} else {
+				// we need to reserve a free slot for a forced removal of the account
+				T::MaxUnstakeRequests::get().saturating_sub(1)
+			};
+			ensure!(
+				unstaking.len().saturated_into::<u32>() < allowed_unstakings,
+				Error::<T>::NoMoreUnstaking,
+			);
+
+			// if existent, we have to add the current amount of same unlock_block, because
+			// insert overwrites the current value
+			let amount = amount.saturating_add(*unstaking.get(&unlock_block).unwrap_or(&BalanceOf::<T>::zero()));
+			unstaking
+				.try_insert(unlock_block, amount)
+				.map_err(|_| Error::<T>::NoMoreUnstaking)?;
+			<Unstaking<T>>::insert(who, unstaking);
+			Ok(())
+		}
+
+		/// Take the given amount from the unstaking requests of the account,
+		/// starting with the most recent ones, such that it can be staked
+		/// again.
+		///
+		/// Returns the number of unstaking requests before the operation.
+		///
+		/// # <weight>
+		/// Weight: O(U) where U is the number of locked unstaking requests
+		/// bounded by `MaxUnstakeRequests`.
+		/// - Reads: Unstaking
+		/// - Writes: Unstaking
+		/// - Kills: Unstaking if all requests have been consumed
+		/// # </weight>
+		fn rebond_unstaking(who: &T::AccountId, amount: BalanceOf<T>) -> Result<u32, DispatchError> {
+			let mut unstaking = <Unstaking<T>>::get(who);
+			let unstaking_len = unstaking.len().saturated_into::<u32>();
+			ensure!(!unstaking.is_empty(), Error::<T>::UnstakingIsEmpty);
+			let total_unstaking = unstaking
+				.values()
+				.fold(BalanceOf::<T>::zero(), |acc, locked_balance| acc.saturating_add(*locked_balance));
+			ensure!(total_unstaking >= amount, Error::<T>::InsufficientUnstaking);
+
+			let mut amt_consuming_unstaking = amount;
+			for (block_number, locked_balance) in unstaking.clone().into_iter().rev() {
+				if amt_consuming_unstaking.is_zero() {
+					break;
+				} else if locked_balance > amt_consuming_unstaking {
+					// replace old entry with the remaining amount
+					unstaking
+						.try_insert(block_number, locked_balance.saturating_sub(amt_consuming_unstaking))
+						.map_err(|_| Error::<T>::NoMoreUnstaking)?;
+					amt_consuming_unstaking = Zero::zero();
+				} else {
+					amt_consuming_unstaking = amt_consuming_unstaking.saturating_sub(locked_balance);
+					unstaking.remove(&block_number);
+				}
+			}
+
+			if unstaking.is_empty() {
+				<Unstaking<T>>::remove(who);
+			} else {
+				<Unstaking<T>>::insert(who, unstaking);
+			}
+
+			Ok(unstaking_len)
+		}
+
+		/// Clear the CandidatePool of the candidate and remove all delegations
+		/// to the candidate. Moreover, prepare unstaking for the candidate and
+		/// their former delegations.
+		///
+		/// If `disable` is set, the blocks authored by the candidate are
+		/// rejected for as long as it is part of the validator set, unless it
+		/// is invulnerable or no other collator could author blocks anymore.
+		///
+		/// # <weight>
+		/// Weight: O(N + D + U) where N is `MaxSelectedCandidates` bounded by
+		/// `MaxTopCandidates`, D is the number of delegators of the collator
+		/// candidate bounded by `MaxDelegatorsPerCollator` and U is the
+		/// number of locked unstaking requests bounded by `MaxUnstakeRequests`.
+		/// - Reads: BlockNumber, D * DelegatorState, D * Unstaking,
+		///   [Invulnerables, CurrentIndex, 2 * SessionCollators, 2 * N *
+		///   DisabledCollators]
+		/// - Writes: D * DelegatorState, (D + 1) * Unstaking,
+		///   [DisabledCollators]
+		/// - Kills: CandidatePool, Payee, DelegatorState and Payee for all
+		///   delegators which only delegated to the candidate
+		/// # </weight>
+		fn remove_candidate(
+			collator: &T::AccountId,
+			state: &CandidateOf<T, T::MaxDelegatorsPerCollator>,
+			disable: bool,
+		) -> DispatchResult {
+			// credit all rewards earned up to now before removing the stakes
+			Self::settle_candidate_rewards(state);
+
+			// iterate over delegators
+			for stake in &state.delegators[..] {
+				// prepare unstaking of delegator
+				Self::prep_unstake(&stake.owner, stake.amount, true)?;
+				Self::remove_rewards_checkpoint(&stake.owner, collator);
+				// remove delegation from delegator state
+				if let Some(mut delegator) = DelegatorState::<T>::get(&stake.owner) {
+					if let Some(remaining) = delegator.rm_delegation(collator) {
+						if remaining.is_zero() {
+							DelegatorState::<T>::remove(&stake.owner);
+							Self::clear_payee(&stake.owner);
+						} else {
+							DelegatorState::<T>::insert(&stake.owner, delegator);
+						}
+					}
+				}
+			}
+			// prepare unstaking of collator candidate
+			Self::prep_unstake(&state.id, state.stake, true)?;
+
+			// *** No Fail beyond this point ***
+
+			// disable validator for next session if they were in the set of validators
+			pallet_session::Pallet::<T>::validators()
+				.into_iter()
+				.enumerate()
+				.find_map(|(i, id)| {
+					if <T as pallet_session::Config>::ValidatorIdOf::convert(collator.clone()) == Some(id) {
+						Some(i)
+					} else {
+						None
+					}
+				})
+				.map(u32::saturated_from::<usize>)
+				.map(pallet_session::Pallet::<T>::disable_index);
+
+			// Disabling the session index does not prevent the collator from authoring
+			// blocks, see https://github.com/paritytech/substrate/issues/8004. Thus, we
+			// reject their blocks until they have left the queued validator set.
+			if disable && !Invulnerables::<T>::get().contains(collator) {
+				if Self::can_disable_author(collator) {
+					let until = pallet_session::Pallet::<T>::current_index().saturating_add(2);
+					DisabledCollators::<T>::insert(collator, until);
+				} else {
+					log::warn!(
+						"not disabling removed collator {:?} because no other collator could author blocks",
+						collator
+					);
+				}
+			}
+
+			// the waiting delegators do not need to wait for the unstaking delay
+			for who in Waitlist::<T>::take(collator) {
+				if let Some(amount) = Waitlisted::<T>::take(&who, collator) {
+					Self::release_waitlisted(&who, collator, amount);
+				}
+			}
+
+			Self::remove_rewards_checkpoint(collator, collator);
+			CollatorBlockRewards::<T>::remove(collator);
+			StakeSnapshots::<T>::remove(collator);
+			Commission::<T>::remove(collator);
+			PendingCommission::<T>::remove(collator);
+			Offences::<T>::remove(collator);
+			CandidatePool::<T>::remove(&collator);
+			Self::clear_payee(collator);
+			Ok(())
+		}
+
+		/// Withdraw all staked currency which was unstaked at least
+		/// `StakeDuration` blocks ago and release the funded principal to the
+		/// funder of the account, if any.
+		///
+		/// # <weight>
+		/// Weight: O(U) where U is the number of locked unstaking
+		/// requests bounded by `MaxUnstakeRequests`.
+		/// - Reads: Unstaking, 2 * Locks, Funders, 2 * FundedStake
+		/// - Writes: Unstaking, 2 * Locks, Funders, FundedStake
+		/// - Kills: Unstaking & Locks if no balance is locked anymore
+		/// # </weight>
+		fn do_unlock(who: &T::AccountId) -> Result<u32, DispatchError> {
+			let now = <frame_system::Pallet<T>>::block_number();
+			let mut unstaking = <Unstaking<T>>::get(who);
+			let unstaking_len = unstaking.len().saturated_into::<u32>();
+			ensure!(!unstaking.is_empty(), Error::<T>::UnstakingIsEmpty);
+
+			let mut total_unlocked: BalanceOf<T> = Zero::zero();
+			let mut total_locked: BalanceOf<T> = Zero::zero();
+			let mut expired = Vec::new();
+
+			// check potential unlocks
+			for (block_number, locked_balance) in unstaking.clone().into_iter() {
+				if block_number <= now {
+					expired.push(block_number);
+					total_unlocked = total_unlocked.saturating_add(locked_balance);
+				} else {
+					total_locked = total_locked.saturating_add(locked_balance);
+				}
+			}
+			for block_number in expired {
+				unstaking.remove(&block_number);
+			}
+
+			// release the funded principal first since the lock of the account depends on it
+			total_locked = Self::staking_lock(who).saturating_sub(total_unlocked);
+			Self::repay_funder(who, total_unlocked, total_locked.is_zero());
+			Self::set_staking_lock(who, total_locked);
+
+			if total_locked.is_zero() {
+				<Unstaking<T>>::remove(who);
+			} else {
+				<Unstaking<T>>::insert(who, unstaking);
+			}
+
+			Ok(unstaking_len)
+		}
+
+		/// Calculate the rewards a single stake for the given collator
+		/// candidate has earned since its rewards have been settled the last
+		/// time.
+		///
+		/// The rewards are shared in proportion to the given weighted stake of
+		/// the candidate, see [Self::weighted_stake].
+		///
+		/// Returns the due rewards and the current value of the candidate's
+		/// [CollatorBlockRewards].
+		///
+		/// The collator's commission is deducted from the rewards of each
+		/// delegation and credited to the collator.
+		///
+		/// NOTE: Delegations below `MinRewardedDelegation` are not rewarded.
+		///
+		/// # <weight>
+		/// Weight: O(D) where D is the number of delegators of the collator
+		/// bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: CollatorBlockRewards, SettledBlockRewards, Commission
+		/// # </weight>
+		fn unsettled_rewards(
+			staker: &T::AccountId,
+			collator: &T::AccountId,
+			weighted: &StakeSnapshotOf<T, T::MaxDelegatorsPerCollator>,
+		) -> (BalanceOf<T>, BalanceOf<T>) {
+			let accumulated = CollatorBlockRewards::<T>::get(collator);
+			let settled = SettledBlockRewards::<T>::get(staker, collator);
+			let unsettled = accumulated.saturating_sub(settled);
+			if unsettled.is_zero() || weighted.total.is_zero() {
+				return (BalanceOf::<T>::zero(), accumulated);
+			}
+
+			let commission = Commission::<T>::get(collator);
+			let due = if staker == collator {
+				let commissions = weighted.delegators.iter().fold(BalanceOf::<T>::zero(), |acc, stake| {
+					let (_, fee) = Self::delegation_reward(stake.amount, weighted.total, unsettled, commission);
+					acc.saturating_add(fee)
+				});
+				(Perquintill::from_rational(weighted.stake, weighted.total) * unsettled).saturating_add(commissions)
+			} else {
+				weighted.delegation(staker).map_or_else(BalanceOf::<T>::zero, |amount| {
+					Self::delegation_reward(amount, weighted.total, unsettled, commission).0
+				})
+			};
+
+			(due, accumulated)
+		}
+
+		/// Return the stake of the collator candidate which is rewarded for
+		/// the blocks they author, i.e. the part of their current stake which
+		/// has been bonded since the latest [StakeSnapshots] entry.
+		///
+		/// Without a snapshot, the entire current stake is rewarded with the
+		/// current reward weights.
+		///
+		/// # <weight>
+		/// Weight: O(D) where D is the number of delegators of the collator
+		/// bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: StakeSnapshots, [Round, CurrentInflation, LoyaltyConfig]
+		/// # </weight>
+		fn rewarded_stake(
+			state: &CandidateOf<T, T::MaxDelegatorsPerCollator>,
+		) -> StakeSnapshotOf<T, T::MaxDelegatorsPerCollator> {
+			StakeSnapshots::<T>::get(&state.id).map_or_else(
+				|| StakeSnapshot::new(state, Round::<T>::get().current, Self::reward_weights()),
+				|snapshot| snapshot.rewarded(state),
+			)
+		}
+
+		/// Move the start of the stake age of a delegation forward in
+		/// proportion to the change of the delegated amount.
+		///
+		/// Reducing a delegation keeps the share of its age which equals the
+		/// share of the remaining stake. Increasing a delegation averages the
+		/// age over the previous and the added stake.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: Round, DelegationAge
+		/// - Writes: DelegationAge
+		/// # </weight>
+		fn rescale_stake_age(
+			delegator: &T::AccountId,
+			collator: &T::AccountId,
+			before: BalanceOf<T>,
+			after: BalanceOf<T>,
+		) {
+			let now = Round::<T>::get().current;
+			let age = now.saturating_sub(DelegationAge::<T>::get(delegator, collator));
+			if age.is_zero() || before == after || before.is_zero() || after.is_zero() {
+				return;
+			}
+
+			let kept = Perquintill::from_rational(before.min(after), before.max(after)) * age;
+			DelegationAge::<T>::insert(delegator, collator, now.saturating_sub(kept));
+		}
+
+		/// Split the share of a delegation in the given block rewards into the
+		/// part for the delegator and the commission for the collator.
+		fn delegation_reward(
+			amount: BalanceOf<T>,
+			total: BalanceOf<T>,
+			rewards: BalanceOf<T>,
+			commission: Perbill,
+		) -> (BalanceOf<T>, BalanceOf<T>) {
+			let gross = Perquintill::from_rational(amount, total) * rewards;
+			let commission = commission * gross;
+			(gross.saturating_sub(commission), commission)
+		}
+
+		/// Return the amount to add to the [CollatorBlockRewards] of the author
+		/// of a block with the given reward.
+		///
+		/// If `RewardRemainder` is `RemainderDestination::Stakers`, the share
+		/// of the delegations below `MinRewardedDelegation` is redistributed
+		/// by accruing proportionally more rewards for the rewarded stakers.
+		///
+		/// # <weight>
+		/// Weight: O(D) where D is the number of delegators of the collator
+		/// bounded by `MaxDelegatorsPerCollator`.
+		/// # </weight>
+		fn accrued_block_reward(
+			weighted: &StakeSnapshotOf<T, T::MaxDelegatorsPerCollator>,
+			reward: BalanceOf<T>,
+		) -> BalanceOf<T> {
+			let total = weighted.total;
+			let eligible = weighted
+				.delegators
+				.iter()
+				.fold(weighted.stake, |eligible, stake| eligible.saturating_add(stake.amount));
+
+			if T::RewardRemainder::get() == RemainderDestination::Stakers && eligible < total && !eligible.is_zero() {
+				Perquintill::from_rational(eligible, total).saturating_reciprocal_mul_floor(reward)
+			} else {
+				reward
+			}
+		}
+
+		/// Return the shares of the collator including the commission and of
+		/// the delegators in the given accrued rewards, see
+		/// [Self::weighted_stake].
+		///
+		/// Delegations below `MinRewardedDelegation` do not receive a share.
+		///
+		/// # <weight>
+		/// Weight: O(D) where D is the number of delegators of the collator
+		/// bounded by `MaxDelegatorsPerCollator`.
+		/// # </weight>
+		fn split_block_reward(
+			weighted: &StakeSnapshotOf<T, T::MaxDelegatorsPerCollator>,
+			accrued: BalanceOf<T>,
+			commission: Perbill,
+		) -> (BalanceOf<T>, BalanceOf<T>) {
+			weighted.delegators.iter().fold(
+				(Perquintill::from_rational(weighted.stake, weighted.total) * accrued, BalanceOf::<T>::zero()),
+				|(collator, delegators), stake| {
+					let (reward, fee) = Self::delegation_reward(stake.amount, weighted.total, accrued, commission);
+					(collator.saturating_add(fee), delegators.saturating_add(reward))
+				},
+			)
+		}
+
+		/// Return the split of the block rewards the collator candidate has
+		/// earned given the rewards which have been accrued and credited for
+		/// their stakers.
+		///
+		/// The part of the block rewards which has not been credited to the
+		/// stakers consists of the share of the delegations below
+		/// `MinRewardedDelegation`, unless it is redistributed, and the
+		/// rounding remainder. The rewards credited in excess of the block
+		/// rewards due to rounding are reported separately.
+		///
+		/// # <weight>
+		/// Weight: O(D) where D is the number of delegators of the collator
+		/// bounded by `MaxDelegatorsPerCollator`.
+		/// # </weight>
+		fn reward_split(
+			weighted: &StakeSnapshotOf<T, T::MaxDelegatorsPerCollator>,
+			bonus_stake: BalanceOf<T>,
+			rewards: &UnsplitRewards<BalanceOf<T>>,
+			commission: Perbill,
+		) -> RewardSplit<BalanceOf<T>> {
+			let credited = rewards.collator.saturating_add(rewards.delegators);
+			let remainder = rewards.earned.saturating_sub(credited);
+			let skipped = if T::RewardRemainder::get() == RemainderDestination::Stakers {
+				BalanceOf::<T>::zero()
+			} else {
+				let eligible = weighted
+					.delegators
+					.iter()
+					.fold(weighted.stake, |eligible, stake| eligible.saturating_add(stake.amount));
+				let skipped = weighted.total.saturating_sub(eligible);
+				(Perquintill::from_rational(skipped, weighted.total) * rewards.earned).min(remainder)
+			};
+
+			RewardSplit {
+				collator: rewards.collator,
+				delegators: rewards.delegators,
+				loyalty_bonus: Self::delegation_reward(bonus_stake, weighted.total, rewards.accrued, commission).0,
+				skipped,
+				dust: remainder.saturating_sub(skipped),
+				excess: credited.saturating_sub(rewards.earned),
+			}
+		}
+
+		/// Split the block rewards the collator candidate has earned since the
+		/// last split among their stakers and handle the remainder according
+		/// to `RewardRemainder`.
+		///
+		/// Since the stakers settle their shares lazily and the rounding of
+		/// their shares depends on when they settle, the remainder is the
+		/// part of the earned block rewards which has not been settled for the
+		/// stakers. Thus, this has to be called after the rewards of all
+		/// stakers of the candidate have been settled.
+		///
+		/// Emits `BlockRewardSplit` if the candidate has earned block rewards.
+		///
+		/// # <weight>
+		/// Weight: O(D) where D is the number of delegators of the collator
+		/// bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: UnsplitBlockRewards, Commission, [Rewards and
+		///   CollatorRoundStats or TotalIssuance]
+		/// - Writes: UnsplitBlockRewards, [Rewards and CollatorRoundStats or
+		///   TotalIssuance]
+		/// # </weight>
+		fn split_candidate_rewards(
+			collator: &T::AccountId,
+			weighted: &StakeSnapshotOf<T, T::MaxDelegatorsPerCollator>,
+			bonus_stake: BalanceOf<T>,
+		) {
+			let unsplit = UnsplitBlockRewards::<T>::take(collator);
+			if unsplit.earned.is_zero() {
+				return;
+			}
+
+			let split = Self::reward_split(weighted, bonus_stake, &unsplit, Commission::<T>::get(collator));
+			let remainder = split.remainder();
+			if !remainder.is_zero() {
+				if T::RewardRemainder::get() == RemainderDestination::Beneficiary {
+					T::RewardRemainderBeneficiary::on_unbalanced(T::Currency::issue(remainder));
+				} else {
+					Rewards::<T>::mutate(collator, |rewards| *rewards = rewards.saturating_add(remainder));
+					// all split blocks have been authored in the same round
+					CollatorRoundStats::<T>::mutate_exists(unsplit.round, collator, |stats| {
+						if let Some(stats) = stats {
+							stats.collator_rewards = stats.collator_rewards.saturating_add(remainder);
+						}
+					});
+				}
+			}
+
+			Self::deposit_event(Event::BlockRewardSplit(collator.clone(), split));
+		}
+
+		/// Credit the rewards a single stake for the given collator candidate
+		/// has earned since the last settlement to the staker's [Rewards].
+		///
+		/// The share of the rewards set in [AutoCompound] is credited to the
+		/// stake's [CompoundRewards] instead. The settled rewards are recorded
+		/// in the candidate's [UnsplitBlockRewards].
+		///
+		/// # <weight>
+		/// Weight: O(D) where D is the number of delegators of the collator
+		/// bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: CollatorBlockRewards, SettledBlockRewards, Commission,
+		///   UnsplitBlockRewards, AutoCompound, CompoundRewards, Rewards
+		/// - Writes: SettledBlockRewards, UnsplitBlockRewards, CompoundRewards,
+		///   Rewards
+		/// # </weight>
+		fn settle_rewards(
+			staker: &T::AccountId,
+			collator: &T::AccountId,
+			weighted: &StakeSnapshotOf<T, T::MaxDelegatorsPerCollator>,
+		) {
+			let (due, accumulated) = Self::unsettled_rewards(staker, collator, weighted);
+			if !due.is_zero() {
+				UnsplitBlockRewards::<T>::mutate(collator, |unsplit| {
+					if staker == collator {
+						unsplit.collator = unsplit.collator.saturating_add(due);
+					} else {
+						unsplit.delegators = unsplit.delegators.saturating_add(due);
+					}
+				});
+				let compound = AutoCompound::<T>::get(staker, collator) * due;
+				if !compound.is_zero() {
+					CompoundRewards::<T>::mutate(staker, collator, |rewards| {
+						*rewards = rewards.saturating_add(compound)
+					});
+				}
+				let payout = due.saturating_sub(compound);
+				if !payout.is_zero() {
+					Rewards::<T>::mutate(staker, |rewards| *rewards = rewards.saturating_add(payout));
+				}
+			}
+			// avoid writing if nothing has been accrued since the last settlement
+			if SettledBlockRewards::<T>::get(staker, collator) != accumulated {
+				SettledBlockRewards::<T>::insert(staker, collator, accumulated);
+			}
+		}
+
+		/// Credit the outstanding rewards of the collator candidate and all of
+		/// their delegators.
+		///
+		/// Since the share of each staker depends on the total stake of the
+		/// candidate, this has to be called before any stake of the candidate
+		/// is changed.
+		///
+		/// Afterwards, the block rewards the candidate has earned since the
+		/// last split are split among the stakers, see
+		/// [Self::split_candidate_rewards].
+		///
+		/// If the candidate has no snapshot of their stake for the current
+		/// round yet, the current stake is recorded as the snapshot after the
+		/// rewards of the previous rounds have been settled.
+		///
+		/// Returns the snapshot of the current round.
+		///
+		/// # <weight>
+		/// Weight: O(D) where D is the number of delegators of the collator
+		/// candidate bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: StakeSnapshots, Round, CurrentInflation, LoyaltyConfig, D *
+		///   DelegationAge, CollatorBlockRewards, (D + 1) *
+		///   SettledBlockRewards, (D + 1) * Rewards, UnsplitBlockRewards,
+		///   Commission, [CollatorRoundStats or TotalIssuance]
+		/// - Writes: StakeSnapshots, (D + 1) * SettledBlockRewards, (D + 1) *
+		///   Rewards, UnsplitBlockRewards, [CollatorRoundStats or
+		///   TotalIssuance]
+		/// # </weight>
+		fn settle_candidate_rewards(
+			state: &CandidateOf<T, T::MaxDelegatorsPerCollator>,
+		) -> StakeSnapshotOf<T, T::MaxDelegatorsPerCollator> {
+			let round = Round::<T>::get().current;
+			let snapshot = StakeSnapshots::<T>::get(&state.id);
+			let rewarded = snapshot.as_ref().map_or_else(
+				|| StakeSnapshot::new(state, round, Self::reward_weights()),
+				|snapshot| snapshot.rewarded(state),
+			);
+			let (weighted, bonus_stake) = Self::weighted_stake_with_bonus(&state.id, &rewarded);
+
+			Self::settle_rewards(&state.id, &state.id, &weighted);
+			for stake in state.delegators.iter() {
+				Self::settle_rewards(&stake.owner, &state.id, &weighted);
+			}
+			Self::split_candidate_rewards(&state.id, &weighted, bonus_stake);
+
+			match snapshot {
+				Some(snapshot) if snapshot.round == round => snapshot,
+				_ => {
+					let snapshot = StakeSnapshot::new(state, round, Self::reward_weights());
+					StakeSnapshots::<T>::insert(&state.id, snapshot.clone());
+					snapshot
+				}
+			}
+		}
+
+		/// Mark all accumulated block rewards of the collator candidate as
+		/// settled for a new stake of the given staker.
+		///
+		/// The stake age of a new delegation starts at the current round.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: CollatorBlockRewards, Round
+		/// - Writes: SettledBlockRewards, DelegationAge
+		/// # </weight>
+		fn init_rewards_checkpoint(staker: &T::AccountId, collator: &T::AccountId) {
+			SettledBlockRewards::<T>::insert(staker, collator, CollatorBlockRewards::<T>::get(collator));
+			if staker != collator {
+				DelegationAge::<T>::insert(staker, collator, Round::<T>::get().current);
+			}
+		}
+
+		/// Clear the reward checkpoint and the restaking share of a stake
+		/// which has been removed.
+		///
+		/// The rewards which were set aside for restaking are credited to the
+		/// staker's [Rewards] instead.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: CompoundRewards, Rewards
+		/// - Writes: Rewards
+		/// - Kills: SettledBlockRewards, AutoCompound, CompoundRewards,
+		///   DelegationAge
+		/// # </weight>
+		fn remove_rewards_checkpoint(staker: &T::AccountId, collator: &T::AccountId) {
+			SettledBlockRewards::<T>::remove(staker, collator);
+			DelegationAge::<T>::remove(staker, collator);
+			AutoCompound::<T>::remove(staker, collator);
+			let compound = CompoundRewards::<T>::take(staker, collator);
+			if !compound.is_zero() {
+				Rewards::<T>::mutate(staker, |rewards| *rewards = rewards.saturating_add(compound));
+			}
+		}
+
+		/// Remove the [Payee] of an account which neither stakes nor has any
+		/// rewards left to be paid out.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: CandidatePool, DelegatorState, Rewards, CompoundRewards
+		/// - Kills: Payee
+		/// # </weight>
+		fn clear_payee(who: &T::AccountId) {
+			if !CandidatePool::<T>::contains_key(who)
+				&& !DelegatorState::<T>::contains_key(who)
+				&& Rewards::<T>::get(who).is_zero()
+				&& CompoundRewards::<T>::iter_prefix_values(who).next().is_none()
+			{
+				Payee::<T>::remove(who);
+			}
+		}
+
+		/// Credit the outstanding rewards of all stakes of the given account,
+		/// either as a collator candidate or as a delegator.
+		///
+		/// Returns the number of delegations of the account.
+		///
+		/// # <weight>
+		/// Weight: O(C * D) where C is the number of delegations of the
+		/// account bounded by `MaxCollatorsPerDelegator` and D is the number
+		/// of delegators of a collator candidate bounded by
+		/// `MaxDelegatorsPerCollator`.
+		/// - Reads: (C + 1) * CandidatePool, DelegatorState, (C + 1) *
+		///   StakeSnapshots, CurrentInflation, LoyaltyConfig, (C + 1) * D *
+		///   DelegationAge, (C + 1) * CollatorBlockRewards, (C + 1) *
+		///   SettledBlockRewards, Rewards
+		/// - Writes: (C + 1) * SettledBlockRewards, Rewards
+		/// # </weight>
+		fn settle_rewards_of(acc: &T::AccountId) -> u32 {
 			if let Some(state) = CandidatePool::<T>::get(acc) {
-				Some(state.status == CandidateStatus::Active)
+				Self::settle_rewards(acc, acc, &Self::weighted_stake(acc, &Self::rewarded_stake(&state)));
+			}
+
+			if let Some(delegator) = DelegatorState::<T>::get(acc) {
+				let num_delegations = delegator.delegations.len().saturated_into::<u32>();
+				for Stake { owner, .. } in delegator.delegations.into_iter() {
+					if let Some(state) = CandidatePool::<T>::get(&owner) {
+						let weighted = Self::weighted_stake(&owner, &Self::rewarded_stake(&state));
+						Self::settle_rewards(acc, &owner, &weighted);
+					}
+				}
+				num_delegations
 			} else {
-				None
+				0u32
+			}
+		}
+
+		/// Settle all rewards which have been accrued by the given account,
+		/// restake the share set in [AutoCompound] and pay out the rest to the
+		/// account's [Payee].
+		///
+		/// Returns the number of delegations of the account and the highest
+		/// number of delegators of a collator candidate whose stake was
+		/// increased.
+		///
+		/// # <weight>
+		/// Weight: O(C * D) where C is the number of delegations of the
+		/// account bounded by `MaxCollatorsPerDelegator` and D is the number
+		/// of delegators of a collator candidate bounded by
+		/// `MaxDelegatorsPerCollator`.
+		/// - Reads: (C + 1) * CandidatePool, DelegatorState, (C + 1) *
+		///   CollatorBlockRewards, (C + 1) * SettledBlockRewards, (C + 1) *
+		///   CompoundRewards, Rewards, Payee, Balance, see `restake_rewards`
+		///   for each restaked stake
+		/// - Writes: (C + 1) * SettledBlockRewards, Rewards, Balance, see
+		///   `restake_rewards` for each restaked stake
+		/// - Kills: (C + 1) * CompoundRewards
+		/// # </weight>
+		fn do_claim_rewards(who: &T::AccountId) -> Result<(u32, u32), DispatchError> {
+			let num_delegations = Self::settle_rewards_of(who);
+
+			let mut compounded = BalanceOf::<T>::zero();
+			let mut num_delegators = 0u32;
+			let compound_rewards: Vec<(T::AccountId, BalanceOf<T>)> =
+				CompoundRewards::<T>::drain_prefix(who).collect();
+			for (collator, amount) in compound_rewards {
+				let (restaked, delegators) = Self::restake_rewards(who, &collator, amount)?;
+				compounded = compounded.saturating_add(restaked);
+				num_delegators = num_delegators.max(delegators);
+				// pay out what could not be restaked
+				let remaining = amount.saturating_sub(restaked);
+				if !remaining.is_zero() {
+					Rewards::<T>::mutate(who, |rewards| *rewards = rewards.saturating_add(remaining));
+				}
+			}
+
+			let rewards = Rewards::<T>::get(who);
+			ensure!(
+				!rewards.is_zero() || !compounded.is_zero(),
+				Error::<T>::RewardsNotFound
+			);
+
+			if !rewards.is_zero() {
+				// mint
+				let payee = Payee::<T>::get(who).unwrap_or_else(|| who.clone());
+				let imb = T::Currency::deposit_into_existing(&payee, rewards)?;
+				Rewards::<T>::remove(who);
+
+				Self::deposit_event(Event::Rewarded(who.clone(), imb.peek()));
 			}
+			// the payee of a former staker is kept until all of their rewards are paid out
+			Self::clear_payee(who);
+			Ok((num_delegations, num_delegators))
 		}
 
-		/// Update the top candidates and total amount at stake after mutating
-		/// an active candidate's stake.
+		/// Restake the given rewards of an account into their stake for the
+		/// collator candidate.
 		///
-		/// NOTE: It is assumed that the calling context checks whether the
-		/// collator candidate is currently active before calling this function.
+		/// The rewards are minted and locked. For the candidate's self-stake,
+		/// at most the amount up to `MaxCollatorCandidateStake` is restaked.
+		/// Nothing is restaked if the candidate is leaving or does not exist
+		/// anymore.
+		///
+		/// Returns the restaked amount and the number of delegators of the
+		/// collator candidate.
 		///
 		/// # <weight>
-		/// Weight: O(1)
-		/// - Reads: TopCandidates, CandidatePool, TotalCollatorStake
-		/// - Writes: TopCandidates, TotalCollatorStake
+		/// Weight: O(D) where D is the number of delegators of the collator
+		/// candidate bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: CandidatePool, DelegatorState, MaxCollatorCandidateStake,
+		///   CollatorBlockRewards, (D + 1) * SettledBlockRewards, (D + 1) *
+		///   Rewards, Round, DelegationAge, Balance, Locks, TopCandidates,
+		///   MaxSelectedCandidates, TotalCollatorStake
+		/// - Writes: CandidatePool, DelegatorState, (D + 1) *
+		///   SettledBlockRewards, (D + 1) * Rewards, DelegationAge, Balance,
+		///   Locks, TopCandidates, TotalCollatorStake
 		/// # </weight>
-		fn update_top_candidates(
-			candidate: T::AccountId,
-			old_self: BalanceOf<T>,
-			old_delegators: BalanceOf<T>,
-			new_self: BalanceOf<T>,
-			new_delegators: BalanceOf<T>,
-		) -> u32 {
-			let mut top_candidates = TopCandidates::<T>::get();
-			let num_top_candidates: u32 = top_candidates.len().saturated_into();
-			let old_stake = Stake {
-				owner: candidate.clone(),
-				amount: old_self.saturating_add(old_delegators),
-			};
-			let new_stake = Stake {
-				owner: candidate.clone(),
-				amount: new_self.saturating_add(new_delegators),
+		fn restake_rewards(
+			who: &T::AccountId,
+			collator: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> Result<(BalanceOf<T>, u32), DispatchError> {
+			let mut state = match CandidatePool::<T>::get(collator) {
+				Some(state) if !state.is_leaving() => state,
+				_ => return Ok((BalanceOf::<T>::zero(), 0u32)),
 			};
+			let num_delegators = state.delegators.len().saturated_into::<u32>();
 
-			// update TopCandidates set
-			let maybe_top_candidate_update = if let Ok(i) = top_candidates.linear_search(&old_stake) {
-				// case 1: candidate is member of TopCandidates with old stake
-				top_candidates.mutate(|vec| {
-					if let Some(stake) = vec.get_mut(i) {
-						stake.amount = new_stake.amount;
-					}
-				});
-				Some((Some(i), top_candidates))
-			} else if top_candidates.try_insert_replace(new_stake.clone()).is_ok() {
-				// case 2: candidate ascends into TopCandidates with new stake
-				// and might replace another candidate if TopCandidates is full
-				Self::deposit_event(Event::EnteredTopCandidates(candidate));
-				Some((None, top_candidates))
+			// credit the rewards earned with the current stakes before changing them
+			Self::settle_candidate_rewards(&state);
+
+			let CandidateOf::<T, _> {
+				stake: before_stake,
+				total: before_total,
+				..
+			} = state;
+			let restaked = if who == collator {
+				let restaked = amount.min(MaxCollatorCandidateStake::<T>::get().saturating_sub(state.stake));
+				state.stake_more(restaked);
+				restaked
 			} else {
-				// case 3: candidate neither was nor will be member of TopCandidates
-				None
+				let mut delegator = DelegatorState::<T>::get(who).ok_or(Error::<T>::DelegatorNotFound)?;
+				let delegated = delegator
+					.inc_delegation(collator.clone(), amount)
+					.ok_or(Error::<T>::DelegationNotFound)?;
+				state.inc_delegator(who.clone(), delegated.saturating_sub(amount), amount);
+				DelegatorState::<T>::insert(who, delegator);
+				Self::rescale_stake_age(who, collator, delegated.saturating_sub(amount), delegated);
+				amount
 			};
+			if restaked.is_zero() {
+				return Ok((BalanceOf::<T>::zero(), num_delegators));
+			}
 
-			// update storage for TotalCollatorStake and TopCandidates
-			if let Some((maybe_old_idx, top_candidates)) = maybe_top_candidate_update {
-				let max_selected_candidates = MaxSelectedCandidates::<T>::get().saturated_into::<usize>();
-				let was_collating = maybe_old_idx.map(|i| i < max_selected_candidates).unwrap_or(false);
-				let is_collating = top_candidates
-					.linear_search(&new_stake)
-					.map(|i| i < max_selected_candidates)
-					.unwrap_or(false);
+			// mint and lock the restaked rewards
+			T::Currency::deposit_into_existing(who, restaked)?;
+			Self::set_staking_lock(who, Self::staking_lock(who).saturating_add(restaked));
 
-				// update TopCollatorStake storage iff candidate was or will be a collator
-				match (was_collating, is_collating) {
-					(true, true) => {
-						Self::update_total_stake_by(new_self, new_delegators, old_self, old_delegators);
-					}
-					(true, false) => {
-						// candidate left the collator set because they staked less and have been
-						// replaced by the next candidate in the queue at position
-						// min(max_selected_candidates, top_candidates) - 1 in TopCandidates
-						let new_col_idx = max_selected_candidates.min(top_candidates.len()).saturating_sub(1);
+			if state.is_active() {
+				Self::update_top_candidates(
+					collator.clone(),
+					before_stake,
+					// safe because total >= stake
+					before_total - before_stake,
+					state.stake,
+					state.total - state.stake,
+				);
+			}
+			CandidatePool::<T>::insert(collator, state);
 
-						// get displacer
-						let (add_collators, add_delegators) =
-							Self::get_top_candidate_stake_at(&top_candidates, new_col_idx)
-								// shouldn't be possible to fail, but we handle it gracefully
-								.unwrap_or((new_self, new_delegators));
-						Self::update_total_stake_by(add_collators, add_delegators, old_self, old_delegators);
-					}
-					(false, true) => {
-						// candidate pushed out the least staked collator which is now at position
-						// min(max_selected_top_candidates, top_candidates - 1) in TopCandidates
-						let old_col_idx = max_selected_candidates.min(top_candidates.len().saturating_sub(1));
+			Self::deposit_event(Event::RewardsCompounded(who.clone(), collator.clone(), restaked));
+			Ok((restaked, num_delegators))
+		}
 
-						// get amount to subtract from TotalCollatorStake
-						let (drop_self, drop_delegators) =
-							Self::get_top_candidate_stake_at(&top_candidates, old_col_idx)
-								// default to zero if candidate DNE, e.g. TopCandidates is not full
-								.unwrap_or((BalanceOf::<T>::zero(), BalanceOf::<T>::zero()));
-						Self::update_total_stake_by(new_self, new_delegators, drop_self, drop_delegators);
+		/// Settle the rewards of the collator candidate and all of their
+		/// delegators and restake the share set in [AutoCompound] of each
+		/// stake.
+		///
+		/// Unlike `restake_rewards`, the candidate's stakes are settled only
+		/// once and the top candidates are updated only once. The rewards
+		/// which cannot be restaked are credited to the staker's [Rewards].
+		/// Nothing is restaked if the candidate is leaving.
+		///
+		/// Emits `RewardsCompounded` for each restaked stake.
+		///
+		/// Returns the consumed weight.
+		///
+		/// # <weight>
+		/// Weight: O(D) where D is the number of delegators of the collator
+		/// candidate bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: CandidatePool, StakeSnapshots, Round, MaxCollatorCandidateStake,
+		///   CurrentInflation, LoyaltyConfig, CollatorBlockRewards, (D + 1) *
+		///   SettledBlockRewards, (D + 1) * AutoCompound, (D + 1) *
+		///   CompoundRewards, (D + 1) * Rewards, D * DelegatorState, D *
+		///   DelegationAge, (D + 1) * Balance, (D + 1) * Locks, TopCandidates,
+		///   MaxSelectedCandidates, TotalCollatorStake
+		/// - Writes: CandidatePool, (D + 1) * SettledBlockRewards, (D + 1) *
+		///   Rewards, D * DelegatorState, D * DelegationAge, (D + 1) * Balance,
+		///   (D + 1) * Locks, TopCandidates, TotalCollatorStake
+		/// - Kills: (D + 1) * CompoundRewards
+		/// # </weight>
+		fn compound_candidate_rewards(collator: &T::AccountId) -> Weight {
+			let mut state = match CandidatePool::<T>::get(collator) {
+				Some(state) if !state.is_leaving() => state,
+				_ => return T::DbWeight::get().reads(1),
+			};
+			let num_stakers = state.delegators.len().saturating_add(1) as u64;
+
+			// credit the rewards earned with the current stakes before changing them
+			Self::settle_candidate_rewards(&state);
+
+			let CandidateOf::<T, _> {
+				stake: before_stake,
+				total: before_total,
+				..
+			} = state;
+			let stakers: Vec<T::AccountId> = sp_std::iter::once(collator.clone())
+				.chain(state.delegators.iter().map(|stake| stake.owner.clone()))
+				.collect();
+			for who in stakers.into_iter() {
+				let amount = CompoundRewards::<T>::take(&who, collator);
+				if amount.is_zero() {
+					continue;
+				}
+				let mut delegator = DelegatorState::<T>::get(&who);
+				let restaked = if &who == collator {
+					amount.min(MaxCollatorCandidateStake::<T>::get().saturating_sub(state.stake))
+				} else if delegator.is_some() {
+					amount
+				} else {
+					BalanceOf::<T>::zero()
+				};
+
+				// mint and lock the restaked rewards
+				let restaked = if !restaked.is_zero() && T::Currency::deposit_into_existing(&who, restaked).is_ok() {
+					if &who == collator {
+						state.stake_more(restaked);
+					} else if let Some(mut delegator) = delegator.take() {
+						// cannot fail because the delegator is one of the candidate's delegators
+						if let Some(delegated) = delegator.inc_delegation(collator.clone(), restaked) {
+							let before = delegated.saturating_sub(restaked);
+							state.inc_delegator(who.clone(), before, restaked);
+							Self::rescale_stake_age(&who, collator, before, delegated);
+						}
+						DelegatorState::<T>::insert(&who, delegator);
 					}
-					_ => {}
+					Self::set_staking_lock(&who, Self::staking_lock(&who).saturating_add(restaked));
+					Self::deposit_event(Event::RewardsCompounded(who.clone(), collator.clone(), restaked));
+					restaked
+				} else {
+					BalanceOf::<T>::zero()
+				};
+
+				// pay out what could not be restaked
+				let remaining = amount.saturating_sub(restaked);
+				if !remaining.is_zero() {
+					Rewards::<T>::mutate(&who, |rewards| *rewards = rewards.saturating_add(remaining));
 				}
+			}
 
-				// update TopCandidates storage
-				TopCandidates::<T>::put(top_candidates);
+			if state.total != before_total {
+				if state.is_active() {
+					Self::update_top_candidates(
+						collator.clone(),
+						before_stake,
+						// safe because total >= stake
+						before_total - before_stake,
+						state.stake,
+						state.total - state.stake,
+					);
+				}
+				CandidatePool::<T>::insert(collator, state);
 			}
 
-			num_top_candidates
+			T::DbWeight::get().reads_writes(
+				8u64.saturating_add(num_stakers.saturating_mul(9)),
+				3u64.saturating_add(num_stakers.saturating_mul(8)),
+			)
 		}
 
-		/// Retrieve the staked amounts (self, sum of delegators) of member of
-		/// [TopCandidates] at the given index, if it exists.
-		fn get_top_candidate_stake_at(
-			top_candidates: &OrderedSet<StakeOf<T>, T::MaxTopCandidates>,
-			index: usize,
-		) -> Option<(BalanceOf<T>, BalanceOf<T>)> {
-			top_candidates
-				.get(index)
-				.and_then(|stake| CandidatePool::<T>::get(&stake.owner))
-				// SAFETY: the total is always more than the stake
-				.map(|state| (state.stake, state.total - state.stake))
-		}
+		/// Return the rewards of the given account which can currently be
+		/// claimed, including the ones which have not been settled yet and the
+		/// ones which are going to be restaked.
+		///
+		/// # <weight>
+		/// Weight: O(C * D) where C is the number of delegations of the
+		/// account bounded by `MaxCollatorsPerDelegator` and D is the number
+		/// of delegators of a collator candidate bounded by
+		/// `MaxDelegatorsPerCollator`.
+		/// - Reads: (C + 1) * CandidatePool, DelegatorState, (C + 1) *
+		///   StakeSnapshots, CurrentInflation, LoyaltyConfig, (C + 1) * D *
+		///   DelegationAge, (C + 1) * CollatorBlockRewards, (C + 1) *
+		///   SettledBlockRewards, (C + 1) * CompoundRewards, Rewards
+		/// # </weight>
+		pub fn pending_rewards(acc: &T::AccountId) -> BalanceOf<T> {
+			let mut rewards = CompoundRewards::<T>::iter_prefix_values(acc)
+				.fold(Rewards::<T>::get(acc), |sum, compound| sum.saturating_add(compound));
 
-		/// Mutate the [TotalCollatorStake] by both incrementing and decreasing
-		/// it by the provided values.
-		fn update_total_stake_by(
-			add_collators: BalanceOf<T>,
-			add_delegators: BalanceOf<T>,
-			sub_collators: BalanceOf<T>,
-			sub_delegators: BalanceOf<T>,
-		) {
-			TotalCollatorStake::<T>::mutate(|total| {
-				total.collators = total
-					.collators
-					.saturating_sub(sub_collators)
-					.saturating_add(add_collators);
-				total.delegators = total
-					.delegators
-					.saturating_sub(sub_delegators)
-					.saturating_add(add_delegators);
-			});
+			if let Some(state) = CandidatePool::<T>::get(acc) {
+				let weighted = Self::weighted_stake(acc, &Self::rewarded_stake(&state));
+				rewards = rewards.saturating_add(Self::unsettled_rewards(acc, acc, &weighted).0);
+			}
+			if let Some(delegator) = DelegatorState::<T>::get(acc) {
+				for Stake { owner, .. } in delegator.delegations.into_iter() {
+					if let Some(state) = CandidatePool::<T>::get(&owner) {
+						let weighted = Self::weighted_stake(&owner, &Self::rewarded_stake(&state));
+						rewards = rewards.saturating_add(Self::unsettled_rewards(acc, &owner, &weighted).0);
+					}
+				}
+			}
+
+			rewards
 		}
 
-		/// Iterate over the top `MaxSelectedCandidates` many collators in terms
-		/// of cumulated stake (self + from delegators) from the [TopCandidates]
-		/// and recalculate the [TotalCollatorStake] from scratch.
-		///
-		/// NOTE: Should only be called in rare circumstances in which we cannot
-		/// guarantee a single candidate's stake has changed, e.g. on genesis or
-		/// when a collator leaves. Otherwise, please use
-		/// [update_total_stake_by].
+		/// Return the collator candidates which are selected for the next
+		/// session together with their stake and commission.
 		///
 		/// # <weight>
 		/// Weight: O(N) where N is `MaxSelectedCandidates` bounded by
-		/// `MaxTopCandidates`
-		/// - Reads: TopCandidates, MaxSelectedCandidates, N * CandidatePool,
-		///   TotalCollatorStake
-		/// - Writes: TotalCollatorStake
+		/// `MaxTopCandidates`.
+		/// - Reads: Invulnerables, TopCandidates, MaxSelectedCandidates, N *
+		///   CandidatePool, N * Commission
 		/// # </weight>
-		fn update_total_stake() -> (u32, u32) {
-			let mut num_of_delegators = 0u32;
-			let mut collator_stake = BalanceOf::<T>::zero();
-			let mut delegator_stake = BalanceOf::<T>::zero();
-
-			let collators = Self::selected_candidates();
-
-			// Snapshot exposure for round for weighting reward distribution
-			for account in collators.iter() {
-				let state =
-					CandidatePool::<T>::get(&account).expect("all members of TopCandidates must be candidates q.e.d");
-				num_of_delegators = num_of_delegators.max(state.delegators.len().saturated_into::<u32>());
+		pub fn selected_candidates_info() -> Vec<CandidateInfo<T::AccountId, BalanceOf<T>>> {
+			Self::next_session_collators()
+				.into_iter()
+				.filter_map(|id| {
+					CandidatePool::<T>::get(&id).map(|state| CandidateInfo {
+						commission: Commission::<T>::get(&id),
+						id,
+						stake: state.stake,
+						total: state.total,
+					})
+				})
+				.collect()
+		}
 
-				// sum up total stake and amount of collators, delegators
-				let amount_collator = state.stake;
-				collator_stake = collator_stake.saturating_add(state.stake);
-				// safe to subtract because total >= stake
-				let amount_delegators = state.total - amount_collator;
-				delegator_stake = delegator_stake.saturating_add(amount_delegators);
+		/// Return the self-stake, delegations, unstaking requests and pending
+		/// rewards of the given account.
+		///
+		/// # <weight>
+		/// Weight: O(C) where C is the number of delegations of the account
+		/// bounded by `MaxCollatorsPerDelegator`.
+		/// - Reads: CandidatePool, DelegatorState, Unstaking, see
+		///   `pending_rewards`
+		/// # </weight>
+		pub fn staker_info(acc: &T::AccountId) -> StakerInfo<T::AccountId, BalanceOf<T>, T::BlockNumber> {
+			StakerInfo {
+				self_stake: CandidatePool::<T>::get(acc).map(|state| state.stake),
+				delegations: DelegatorState::<T>::get(acc)
+					.map(|delegator| {
+						delegator
+							.delegations
+							.into_iter()
+							.map(|Stake { owner, amount }| DelegationInfo {
+								collator: owner,
+								amount,
+							})
+							.collect()
+					})
+					.unwrap_or_default(),
+				unstaking: Unstaking::<T>::get(acc)
+					.into_iter()
+					.map(|(unlock_block, amount)| UnstakingInfo { unlock_block, amount })
+					.collect(),
+				pending_rewards: Self::pending_rewards(acc),
 			}
-
-			TotalCollatorStake::<T>::mutate(|total| {
-				total.collators = collator_stake;
-				total.delegators = delegator_stake;
-			});
-
-			// return number of selected candidates and the corresponding number of their
-			// delegators for post-weight correction
-			(collators.len().saturated_into(), num_of_delegators)
 		}
 
-		/// Update the delegator's state by removing the collator candidate from
-		/// the set of ongoing delegations.
+		/// Estimate the rewards of a new stake of the given amount for the
+		/// collator candidate, based on the current `RewardPerBlock`.
+		///
+		/// Selected collators are expected to author an equal share of the
+		/// blocks. Thus, the rewards per block are the stake's share of the
+		/// block reward divided by the number of selected candidates. If the
+		/// candidate is not selected or an unrewarded invulnerable collator, no
+		/// rewards are expected.
+		///
+		/// Returns `None` if the collator candidate does not exist.
 		///
 		/// # <weight>
-		/// Weight: O(D) where D is the number of total delegators for
-		/// this collator bounded by `MaxCollatorsPerDelegator`.
-		/// - Reads: [Origin Account], DelegatorState, BlockNumber, Unstaking,
-		///   Locks, TopCandidates, D * CandidatePool, MaxSelectedCandidates
-		/// - Writes: Unstaking, Locks, DelegatorState, CandidatePool,
-		///   TotalCollatorStake
-		/// - Kills: DelegatorState if the delegator has not delegated to
-		///   another collator
+		/// Weight: O(N + D) where N is `MaxSelectedCandidates` bounded by
+		/// `MaxTopCandidates` and D is the number of delegators of the
+		/// collator candidate bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: CandidatePool, Commission, RewardPerBlock, Invulnerables,
+		///   TopCandidates, MaxSelectedCandidates, CurrentInflation, Round,
+		///   LoyaltyConfig, D * DelegationAge
 		/// # </weight>
-		fn delegator_revokes_collator(acc: T::AccountId, collator: T::AccountId) -> Result<u32, DispatchError> {
-			let mut delegator = DelegatorState::<T>::get(&acc).ok_or(Error::<T>::DelegatorNotFound)?;
-			let old_total = delegator.total;
-			let num_delegations: u32 = delegator.delegations.len().saturated_into::<u32>();
-			let remaining = delegator
-				.rm_delegation(&collator)
-				.ok_or(Error::<T>::DelegationNotFound)?;
+		pub fn estimate_rewards(collator: &T::AccountId, stake: BalanceOf<T>) -> Option<RewardEstimate<BalanceOf<T>>> {
+			let state = CandidatePool::<T>::get(collator)?;
+			let selected = Self::next_session_collators();
 
-			// edge case; if no delegations remaining, leave set of delegators
-			if delegator.delegations.is_empty() {
-				// leave the set of delegators because no delegations left
-				Self::delegator_leaves_collator(acc.clone(), collator)?;
-				DelegatorState::<T>::remove(&acc);
-				Self::deposit_event(Event::DelegatorLeft(acc, old_total));
+			let per_block = if !selected.contains(collator)
+				|| !Self::is_rewarded(collator)
+				|| stake < T::MinRewardedDelegation::get()
+			{
+				BalanceOf::<T>::zero()
 			} else {
-				// can never fail iff MinDelegatorStake == MinDelegation
-				ensure!(remaining >= T::MinDelegatorStake::get(), Error::<T>::NomStakeBelowMin);
-				Self::delegator_leaves_collator(acc.clone(), collator)?;
-				DelegatorState::<T>::insert(&acc, delegator);
-			}
-			Ok(num_delegations)
+				let weights = Self::reward_weights();
+				let delegator_weight = weights.delegator;
+				let snapshot = StakeSnapshot::new(&state, Round::<T>::get().current, weights);
+				let total = Self::weighted_stake(collator, &snapshot).total;
+				let (reward, _) = Self::delegation_reward(
+					delegator_weight * stake,
+					total.saturating_add(delegator_weight * stake),
+					RewardPerBlock::<T>::get(),
+					Commission::<T>::get(collator),
+				);
+				reward / BalanceOf::<T>::saturated_from(selected.len())
+			};
+			Some(RewardEstimate {
+				per_block,
+				per_year: per_block.saturating_mul(Self::blocks_per_year()),
+			})
 		}
 
-		/// Update the collator's state by removing the delegator's stake and
-		/// starting the process to unlock the delegator's staked funds.
+		/// Return the total stake at the start of the given round and the
+		/// block production and rewards of each collator in that round.
 		///
-		/// This operation affects the pallet's total stake.
+		/// Returns `None` if the round has not started yet or has been pruned.
 		///
 		/// # <weight>
-		/// Weight: O(D) where D is the number of delegators for this
-		/// collator bounded by `MaxDelegatorsPerCollator`.
-		/// - Reads: CandidatePool, BlockNumber, Unstaking
-		/// - Writes: Unstaking, TotalCollatorStake, CandidatePool
+		/// Weight: O(N) where N is the number of collators which authored
+		/// blocks in the round bounded by `MaxTopCandidates`.
+		/// - Reads: RoundTotalStake, N * CollatorRoundStats
 		/// # </weight>
-		fn delegator_leaves_collator(delegator: T::AccountId, collator: T::AccountId) -> DispatchResult {
-			let mut state = CandidatePool::<T>::get(&collator).ok_or(Error::<T>::CandidateNotFound)?;
-
-			let delegator_stake = state
-				.delegators
-				.remove(&Stake {
-					owner: delegator.clone(),
-					// amount is irrelevant for removal
-					amount: BalanceOf::<T>::one(),
+		pub fn round_stats(round: SessionIndex) -> Option<RoundStats<T::AccountId, BalanceOf<T>>> {
+			let total = RoundTotalStake::<T>::get(round)?;
+			let collators = CollatorRoundStats::<T>::iter_prefix(round)
+				.map(|(collator, stats)| CollatorRoundStats {
+					collator,
+					blocks_authored: stats.blocks_authored,
+					collator_rewards: stats.collator_rewards,
+					delegator_rewards: stats.delegator_rewards,
 				})
-				.map(|nom| nom.amount)
-				.ok_or(Error::<T>::DelegatorNotFound)?;
+				.collect();
+			Some(RoundStats {
+				round,
+				collator_stake: total.collators,
+				delegator_stake: total.delegators,
+				collators,
+			})
+		}
+
+		/// Snapshot the total stake at the start of the given round and prune
+		/// the statistics of all rounds which left the retention window.
+		///
+		/// # <weight>
+		/// Weight: O(R * N) where R is the number of pruned rounds, which is
+		/// one unless `RoundStatsRetention` has been lowered, and N is the
+		/// number of collators which authored blocks in a pruned round
+		/// bounded by `MaxTopCandidates`.
+		/// - Reads: TotalCollatorStake, OldestRoundStats
+		/// - Writes: RoundTotalStake, [OldestRoundStats]
+		/// - Kills: R * RoundTotalStake, R * N * CollatorRoundStats
+		/// # </weight>
+		fn rotate_round_stats(round: SessionIndex) -> Weight {
+			RoundTotalStake::<T>::insert(round, TotalCollatorStake::<T>::get());
+
+			let retained = round.saturating_sub(T::RoundStatsRetention::get());
+			let oldest = OldestRoundStats::<T>::get();
+			let mut writes = 1u64;
+			for pruned in oldest..retained {
+				RoundTotalStake::<T>::remove(pruned);
+				let removed = CollatorRoundStats::<T>::clear_prefix(pruned, T::MaxTopCandidates::get(), None).unique;
+				writes = writes.saturating_add(u64::from(removed).saturating_add(1));
+			}
+			if oldest < retained {
+				OldestRoundStats::<T>::put(retained);
+				writes = writes.saturating_add(1);
+			}
+			T::DbWeight::get().reads_writes(2, writes)
+		}
 
-			let CandidateOf::<T, _> {
-				stake: old_stake,
-				total: old_total,
-				..
-			} = state;
-			state.total = state.total.saturating_sub(delegator_stake);
-			let new_total = state.total;
+		/// Return the current staking round.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: Round
+		/// # </weight>
+		pub fn round_info() -> runtime_api::RoundInfo<T::BlockNumber> {
+			let RoundInfo { current, first, length } = Round::<T>::get();
+			runtime_api::RoundInfo { current, first, length }
+		}
 
-			// we don't unlock immediately
-			Self::prep_unstake(&delegator, delegator_stake, false)?;
+		/// Derive `RewardPerBlock` from the inflation curve and the current
+		/// share of the total issuance which is staked by the top candidates
+		/// and their delegators.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: TotalCollatorStake, TotalIssuance
+		/// - Writes: CurrentInflation, RewardPerBlock
+		/// # </weight>
+		fn update_inflation(inflation: &InflationInfo) {
+			let TotalStake { collators, delegators } = TotalCollatorStake::<T>::get();
+			let issuance = T::Currency::total_issuance();
+			let rates = inflation.rates(
+				Perquintill::from_rational(collators, issuance),
+				Perquintill::from_rational(delegators, issuance),
+			);
 
-			// update top candidates and total amount at stake
-			if state.is_active() {
-				Self::update_top_candidates(
-					collator.clone(),
-					old_stake,
-					// safe because total >= stake
-					old_total - old_stake,
-					state.stake,
-					state.total - state.stake,
-				);
-			}
-			CandidatePool::<T>::insert(&collator, state);
+			let old_reward_per_block = RewardPerBlock::<T>::get();
+			let reward_per_block = (rates.inflation * issuance) / Self::blocks_per_year();
+			RewardPerBlock::<T>::put(reward_per_block);
+			CurrentInflation::<T>::put(rates);
 
-			Self::deposit_event(Event::DelegatorLeftCollator(
-				delegator,
-				collator,
-				delegator_stake,
-				new_total,
+			Self::deposit_event(Event::RoundInflationSet(
+				rates.collator_staking_rate,
+				rates.collator_reward_rate,
+				rates.delegator_staking_rate,
+				rates.delegator_reward_rate,
 			));
-			Ok(())
+			Self::deposit_event(Event::RewardPerBlockUpdated(old_reward_per_block, reward_per_block));
 		}
 
-		/// Check for remaining delegations of the delegator which has been
-		/// removed from the given collator.
+		/// Return the rewarded stake of a collator candidate weighted by the
+		/// reward rates of collators and delegators, see
+		/// [InflationRates::stake_weights], which determines the share of each
+		/// stake in the block rewards of the candidate.
 		///
-		/// Returns the removed delegator's address and
-		/// * Either the updated delegator state if other delegations are still
-		///   remaining
-		/// * Or `None`, signalling the delegator state should be cleared once
-		///   the transaction cannot fail anymore.
-		fn prep_kick_delegator(
-			delegation: &StakeOf<T>,
+		/// Each delegation is additionally weighted by its loyalty bonus for
+		/// its stake age in the round of the rewarded stake. Hence, the bonus
+		/// is a share of the candidate's rewards rather than being minted on
+		/// top of them.
+		///
+		/// The weights and the loyalty bonus are taken from the snapshot of
+		/// the rewarded stake, so that all stakers settle the rewards earned
+		/// with the snapshot with the same weights.
+		///
+		/// Delegations below `MinRewardedDelegation` are left out of the
+		/// delegators but still count towards the total.
+		///
+		/// # <weight>
+		/// Weight: O(D) where D is the number of delegators of the collator
+		/// bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: D * DelegationAge
+		/// # </weight>
+		fn weighted_stake(
 			collator: &T::AccountId,
-		) -> Result<ReplacedDelegator<T>, DispatchError> {
-			let mut state = DelegatorState::<T>::get(&delegation.owner).ok_or(Error::<T>::DelegatorNotFound)?;
-			state.rm_delegation(collator);
+			rewarded: &StakeSnapshotOf<T, T::MaxDelegatorsPerCollator>,
+		) -> StakeSnapshotOf<T, T::MaxDelegatorsPerCollator> {
+			Self::weighted_stake_with_bonus(collator, rewarded).0
+		}
 
-			// we don't unlock immediately
-			Self::prep_unstake(&delegation.owner, delegation.amount, true)?;
+		/// Return the weighted stake of a collator candidate, see
+		/// [Self::weighted_stake], and the part of it which stems from the
+		/// loyalty bonus of the rewarded delegations.
+		///
+		/// # <weight>
+		/// Weight: O(D) where D is the number of delegators of the collator
+		/// bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: D * DelegationAge
+		/// # </weight>
+		fn weighted_stake_with_bonus(
+			collator: &T::AccountId,
+			rewarded: &StakeSnapshotOf<T, T::MaxDelegatorsPerCollator>,
+		) -> (StakeSnapshotOf<T, T::MaxDelegatorsPerCollator>, BalanceOf<T>) {
+			let RewardWeights {
+				collator: collator_weight,
+				delegator: delegator_weight,
+				loyalty,
+			} = rewarded.weights;
+			let stake = collator_weight * rewarded.stake;
+			let mut total = stake;
+			let mut total_bonus = BalanceOf::<T>::zero();
+			let mut delegators = rewarded.delegators.clone();
+			delegators.mutate(|stakes| {
+				for stake in stakes[..].iter_mut() {
+					let mut weighted = delegator_weight * stake.amount;
+					let mut bonus = BalanceOf::<T>::zero();
+					if let Some(loyalty) = loyalty {
+						let age = rewarded
+							.round
+							.saturating_sub(DelegationAge::<T>::get(&stake.owner, collator));
+						bonus = loyalty.bonus(age) * weighted;
+						weighted = weighted.saturating_add(bonus);
+					}
+					total = total.saturating_add(weighted);
+					stake.amount = if stake.amount >= T::MinRewardedDelegation::get() {
+						total_bonus = total_bonus.saturating_add(bonus);
+						weighted
+					} else {
+						BalanceOf::<T>::zero()
+					};
+				}
+				stakes.retain(|stake| !stake.amount.is_zero());
+			});
 
-			// return state if not empty for later removal after all checks have passed
-			if state.delegations.is_empty() {
-				Ok(ReplacedDelegator {
-					who: delegation.owner.clone(),
-					state: None,
-				})
-			} else {
-				Ok(ReplacedDelegator {
-					who: delegation.owner.clone(),
-					state: Some(state),
-				})
-			}
+			let weighted = StakeSnapshot {
+				round: rewarded.round,
+				stake,
+				delegators,
+				total,
+				weights: rewarded.weights,
+			};
+			(weighted, total_bonus)
 		}
 
-		/// Either clear the storage of a kicked delegator or update its
-		/// delegation state if it still contains other delegations.
-		fn update_kicked_delegator_storage(delegator: Option<ReplacedDelegator<T>>) {
-			match delegator {
-				Some(ReplacedDelegator {
-					who,
-					state: Some(state),
-				}) => DelegatorState::<T>::insert(who, state),
-				Some(ReplacedDelegator { who, .. }) => DelegatorState::<T>::remove(who),
-				_ => (),
+		/// Return the current weights of the stakes in the rewards of a
+		/// collator candidate which apply to new stake snapshots.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: CurrentInflation, LoyaltyConfig
+		/// # </weight>
+		fn reward_weights() -> RewardWeights {
+			let (collator, delegator) = CurrentInflation::<T>::get().stake_weights();
+			RewardWeights {
+				collator,
+				delegator,
+				loyalty: LoyaltyConfig::<T>::get(),
 			}
 		}
 
-		/// Return the best `MaxSelectedCandidates` many candidates.
+		/// Settle the rewards of all collator candidates and apply the current
+		/// reward weights to their stake snapshots.
 		///
-		/// In case a collator from last round was replaced by a candidate with
-		/// the same total stake during sorting, we revert this swap to
-		/// prioritize collators over candidates.
+		/// The rewards earned up to now are settled with the previous weights
+		/// while the rewards earned from now on are settled with the current
+		/// weights.
 		///
 		/// # <weight>
-		/// Weight: O(1)
-		/// - Reads: TopCandidates, MaxSelectedCandidates
+		/// Weight: O(C * D) where C is the number of collator candidates and
+		/// D is the number of delegators of a collator candidate bounded by
+		/// `MaxDelegatorsPerCollator`.
+		/// - Reads: CurrentInflation, LoyaltyConfig, C * CandidatePool, see
+		///   `settle_candidate_rewards`
+		/// - Writes: C * StakeSnapshots, see `settle_candidate_rewards`
 		/// # </weight>
-		pub fn selected_candidates() -> BoundedVec<T::AccountId, T::MaxTopCandidates> {
-			let candidates = TopCandidates::<T>::get();
+		fn apply_reward_weights() {
+			let weights = Self::reward_weights();
+			for state in CandidatePool::<T>::iter_values() {
+				let mut snapshot = Self::settle_candidate_rewards(&state);
+				if snapshot.weights != weights {
+					snapshot.weights = weights;
+					StakeSnapshots::<T>::insert(&state.id, snapshot);
+				}
+			}
+		}
 
-			// Should never fail since WASM usize are 32bits and native are either 32 or 64
-			let top_n = MaxSelectedCandidates::<T>::get().saturated_into::<usize>();
+		/// Return `BLOCKS_PER_YEAR` as balance, which is at least one.
+		fn blocks_per_year() -> BalanceOf<T> {
+			BalanceOf::<T>::saturated_from(T::BLOCKS_PER_YEAR.saturated_into::<u128>()).max(One::one())
+		}
 
-			log::trace!("{} Candidates for {} Collator seats", candidates.len(), top_n);
+		/// Return the account of the delegation pool with the given id.
+		pub fn pool_account(pool_id: PoolId) -> T::AccountId {
+			T::PoolPalletId::get().into_sub_account_truncating(pool_id)
+		}
 
-			// Choose the top MaxSelectedCandidates qualified candidates
-			let collators = candidates
-				.into_iter()
-				.take(top_n)
-				.filter(|x| x.amount >= T::MinCollatorStake::get())
-				.map(|x| x.owner)
-				.collect::<Vec<T::AccountId>>();
+		/// Return whether the account belongs to a delegation pool.
+		///
+		/// If the account id is too short to hold the pool id, the accounts of
+		/// all pools equal the truncated account of `PoolPalletId`.
+		fn is_pool_account(who: &T::AccountId) -> bool {
+			PalletId::try_from_sub_account::<PoolId>(who).map_or(false, |(pallet_id, _)| {
+				pallet_id == T::PoolPalletId::get()
+			}) || *who == T::PoolPalletId::get().into_account_truncating()
+		}
 
-			collators.try_into().expect("Did not extend Collators q.e.d.")
+		/// Return the origin which is used to stake on behalf of a delegation
+		/// pool.
+		fn pool_origin(pool_acc: &T::AccountId) -> OriginFor<T> {
+			frame_system::RawOrigin::Signed(pool_acc.clone()).into()
 		}
 
-		/// Attempts to add the stake to the set of delegators of a collator
-		/// which already reached its maximum size by removing an already
-		/// existing delegator with less staked value. If the given staked
-		/// amount is at most the minimum staked value of the original delegator
-		/// set, an error is returned.
+		/// Claim the rewards of all delegations of a delegation pool into the
+		/// pool's account and add them to the pool's reward counter.
 		///
-		/// Returns a tuple which contains the updated candidate state as well
-		/// as the potentially replaced delegation which will be used later when
-		/// updating the storage of the replaced delegator.
+		/// If the pool does not have any points, e.g. when it is dissolved, the
+		/// rewards are claimed without updating the reward counter.
 		///
-		/// Emits `DelegationReplaced` if the stake exceeds one of the current
-		/// delegations.
+		/// While payouts are paused, the rewards are only added to the reward
+		/// counter and minted once payouts are resumed.
 		///
 		/// # <weight>
-		/// Weight: O(D) where D is the number of delegators for this collator
-		/// bounded by `MaxDelegatorsPerCollator`.
-		/// - Reads/Writes: 0
+		/// Weight: O(C * D) where C is the number of delegations of the pool
+		/// bounded by `MaxCollatorsPerDelegator` and D is the number of
+		/// delegators for the collators bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: DelegatorState, C * CandidatePool, C *
+		///   CollatorBlockRewards, C * (D + 1) * SettledBlockRewards, C * (D +
+		///   1) * Rewards, Paused, System.Account
+		/// - Writes: C * (D + 1) * SettledBlockRewards, C * (D + 1) * Rewards,
+		///   System.Account
 		/// # </weight>
-		#[allow(clippy::type_complexity)]
-		fn do_update_delegator(
-			stake: Stake<T::AccountId, BalanceOf<T>>,
-			mut state: Candidate<T::AccountId, BalanceOf<T>, T::MaxDelegatorsPerCollator>,
-		) -> Result<
-			(
-				CandidateOf<T, T::MaxDelegatorsPerCollator>,
-				Option<ReplacedDelegator<T>>,
-			),
-			DispatchError,
-		> {
-			// attempt to replace the last element of the set
-			let stake_to_remove = state
-				.delegators
-				.try_insert_replace(stake.clone())
-				.map_err(|err_too_many| {
-					if err_too_many {
-						Error::<T>::TooManyDelegators
-					} else {
-						// should never occur because we previously check this case, but let's be sure
-						Error::<T>::AlreadyDelegating
-					}
-				})?;
-
-			state.total = state.total.saturating_add(stake.amount);
+		fn sync_pool_rewards(pool_acc: &T::AccountId, pool: &mut PoolOf<T>) -> DispatchResult {
+			Self::settle_rewards_of(pool_acc);
+			let rewards = Rewards::<T>::take(pool_acc);
+			if !rewards.is_zero() && !pool.points.is_zero() {
+				pool.reward_counter = pool.reward_counter.saturating_add(FixedU128::saturating_from_rational(
+					rewards.saturated_into::<u128>(),
+					pool.points.saturated_into::<u128>(),
+				));
+			}
 
-			if let Some(stake_to_remove) = stake_to_remove {
-				// update total stake
-				state.total = state.total.saturating_sub(stake_to_remove.amount);
+			let unminted = pool.pending_rewards.saturating_add(rewards);
+			if Paused::<T>::get().payouts {
+				pool.pending_rewards = unminted;
+				return Ok(());
+			}
+			if unminted.is_zero() {
+				return Ok(());
+			}
 
-				// update storage of kicked delegator
-				let kicked_delegator = Self::prep_kick_delegator(&stake_to_remove, &state.id)?;
+			let imb = T::Currency::deposit_into_existing(pool_acc, unminted)?;
+			pool.pending_rewards = BalanceOf::<T>::zero();
 
-				Self::deposit_event(Event::DelegationReplaced(
-					stake.owner,
-					stake.amount,
-					stake_to_remove.owner,
-					stake_to_remove.amount,
-					state.id.clone(),
-					state.total,
-				));
+			Self::deposit_event(Event::Rewarded(pool_acc.clone(), imb.peek()));
+			Ok(())
+		}
 
-				Ok((state, Some(kicked_delegator)))
-			} else {
-				Ok((state, None))
+		/// Pay out the share of the pool's rewards which the member has earned
+		/// since the last payout and update the member's reward counter.
+		///
+		/// While payouts are paused, the share is added to the member's
+		/// pending rewards instead, which are paid out with the next payout.
+		///
+		/// Returns the paid out amount.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: Paused, 2 * System.Account
+		/// - Writes: 2 * System.Account
+		/// # </weight>
+		fn pay_pool_member(
+			pool_id: PoolId,
+			pool_acc: &T::AccountId,
+			pool: &PoolOf<T>,
+			who: &T::AccountId,
+			member: &mut PoolMemberOf<T>,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			let earned: BalanceOf<T> = pool
+				.reward_counter
+				.saturating_sub(member.reward_counter)
+				.saturating_mul_int(member.points.saturated_into::<u128>())
+				.saturated_into();
+			member.reward_counter = pool.reward_counter;
+			let rewards = member.pending_rewards.saturating_add(earned);
+
+			if Paused::<T>::get().payouts {
+				member.pending_rewards = rewards;
+				return Ok(BalanceOf::<T>::zero());
 			}
+			if !rewards.is_zero() {
+				T::Currency::transfer(pool_acc, who, rewards, ExistenceRequirement::KeepAlive)?;
+				member.pending_rewards = BalanceOf::<T>::zero();
+				Self::deposit_event(Event::PoolRewarded(pool_id, who.clone(), rewards));
+			}
+			Ok(rewards)
 		}
 
-		/// Either set or increase the BalanceLock of target account to
-		/// amount.
+		/// Unbond the given amount from the delegations of a delegation pool.
 		///
-		/// Consumes unstaked balance which can be unlocked in the future up to
-		/// amount and updates `Unstaking` storage accordingly.
+		/// Funds which are not delegated anymore, e.g. because the pool was
+		/// kicked by another delegator, are unbonded first. Afterwards, the
+		/// delegations are reduced starting with the largest one. Delegations
+		/// are revoked completely if the remaining amount covers them and the
+		/// pool leaves the delegators if all of its stake is unbonded.
 		///
 		/// # <weight>
-		/// Weight: O(U) where U is the number of locked unstaking requests
-		/// bounded by `MaxUnstakeRequests`.
-		/// - Reads: Unstaking, Locks
-		/// - Writes: Unstaking, Locks
+		/// Weight: O(C * (N + D)) where C is the number of delegations of the
+		/// pool bounded by `MaxCollatorsPerDelegator`, N is
+		/// `MaxSelectedCandidates` bounded by `MaxTopCandidates` and D is the
+		/// number of delegators for the collators bounded by
+		/// `MaxDelegatorsPerCollator`.
+		/// - Reads: DelegatorState, C * CandidatePool, Unstaking, TopCandidates,
+		///   MaxSelectedCandidates, C * CollatorBlockRewards, C * (D + 1) *
+		///   SettledBlockRewards, C * (D + 1) * Rewards
+		/// - Writes: DelegatorState, C * CandidatePool, Unstaking,
+		///   TopCandidates, TotalCollatorStake, C * (D + 1) *
+		///   SettledBlockRewards, C * (D + 1) * Rewards
 		/// # </weight>
-		fn increase_lock(who: &T::AccountId, amount: BalanceOf<T>, more: BalanceOf<T>) -> Result<u32, DispatchError> {
-			ensure!(
-				pallet_balances::Pallet::<T>::free_balance(who) >= amount.into(),
-				pallet_balances::Error::<T>::InsufficientBalance
-			);
+		fn unbond_pool_stake(
+			pool_acc: &T::AccountId,
+			pool_stake: BalanceOf<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			// funds waiting for a free delegation slot are unbonded first
+			for (collator, waiting) in Waitlisted::<T>::drain_prefix(pool_acc) {
+				Waitlist::<T>::mutate(&collator, |waitlist| waitlist.retain(|who| who != pool_acc));
+				Self::prep_unstake(pool_acc, waiting, true)?;
+			}
 
-			let mut unstaking_len = 0u32;
+			let delegator = if let Some(delegator) = DelegatorState::<T>::get(pool_acc) {
+				delegator
+			} else {
+				// nothing is delegated anymore
+				return Ok(());
+			};
 
-			// *** No Fail except during Unstaking mutation beyond this point ***
+			let undelegated = pool_stake.saturating_sub(delegator.total);
+			let mut remaining = amount.saturating_sub(undelegated);
+			if remaining.is_zero() {
+				return Ok(());
+			}
+			if remaining >= delegator.total {
+				return Self::leave_delegators(Self::pool_origin(pool_acc))
+					.map(|_| ())
+					.map_err(|e| e.error);
+			}
 
-			// update Unstaking by consuming up to {amount | more}
-			<Unstaking<T>>::try_mutate(who, |unstaking| -> DispatchResult {
-				// reduce {amount | more} by unstaking until either {amount | more} is zero or
-				// no unstaking is left
-				// if more is set, we only want to reduce by more to achieve 100 - 40 + 30 = 90
-				// locked
-				let mut amt_consuming_unstaking = if more.is_zero() { amount } else { more };
-				unstaking_len = unstaking.len().saturated_into();
-				for (block_number, locked_balance) in unstaking.clone() {
-					if amt_consuming_unstaking.is_zero() {
-						break;
-					} else if locked_balance > amt_consuming_unstaking {
-						// amount is only reducible by locked_balance - amt_consuming_unstaking
-						let delta = locked_balance.saturating_sub(amt_consuming_unstaking);
-						// replace old entry with delta
-						unstaking
-							.try_insert(block_number, delta)
-							.map_err(|_| Error::<T>::NoMoreUnstaking)?;
-						amt_consuming_unstaking = Zero::zero();
-					} else {
-						// amount is either still reducible or reached
-						amt_consuming_unstaking = amt_consuming_unstaking.saturating_sub(locked_balance);
-						unstaking.remove(&block_number);
+			let mut delegations: Vec<StakeOf<T>> = delegator.delegations.into_iter().collect();
+			delegations.sort_by(|a, b| b.amount.cmp(&a.amount));
+			let mut num_delegations = delegations.len();
+			for Stake { owner, amount: delegated } in delegations {
+				if remaining.is_zero() {
+					break;
+				}
+				if remaining >= delegated && num_delegations > 1 {
+					Self::revoke_delegation(Self::pool_origin(pool_acc), T::Lookup::unlookup(owner))
+						.map_err(|e| e.error)?;
+					remaining = remaining.saturating_sub(delegated);
+					num_delegations = num_delegations.saturating_sub(1);
+				} else {
+					let less = remaining.min(delegated.saturating_sub(T::MinDelegation::get()));
+					if !less.is_zero() {
+						Self::delegator_stake_less(Self::pool_origin(pool_acc), T::Lookup::unlookup(owner), less)
+							.map_err(|e| e.error)?;
+						remaining = remaining.saturating_sub(less);
+					}
+				}
+			}
+			ensure!(remaining.is_zero(), Error::<T>::PoolStakeUnavailable);
+			Ok(())
+		}
+
+		/// Return the stake of a delegation pool which is neither delegated
+		/// nor waiting for a delegation slot, e.g. because the pool's
+		/// delegation was removed when the collator candidate left.
+		///
+		/// # <weight>
+		/// Weight: O(W) where W is the number of waitlists the pool has joined.
+		/// - Reads: DelegatorState, W * Waitlisted
+		/// # </weight>
+		fn undelegated_pool_stake(pool_acc: &T::AccountId, pool: &PoolOf<T>) -> BalanceOf<T> {
+			let delegated = DelegatorState::<T>::get(pool_acc).map_or_else(Zero::zero, |delegator| delegator.total);
+			pool.stake.saturating_sub(delegated).saturating_sub(Self::waitlisted_total(pool_acc))
+		}
+
+		/// Return the block from which funds which are unbonded from a
+		/// delegation pool at the given block can be withdrawn.
+		///
+		/// The block is rounded up to a multiple of `StakeDuration`. Thus, the
+		/// unbonding requests of all members of a pool are covered by at most
+		/// two unstaking requests of the pool's account, no matter how many
+		/// members unbond.
+		fn pool_unlock_block(now: T::BlockNumber) -> T::BlockNumber {
+			let duration = T::StakeDuration::get();
+			let unlock_block = now.saturating_add(duration);
+			if duration.is_zero() {
+				return unlock_block;
+			}
+			let rest = unlock_block % duration;
+			if rest.is_zero() {
+				unlock_block
+			} else {
+				unlock_block.saturating_add(duration.saturating_sub(rest))
+			}
+		}
+
+		/// Move the unstaking request of the account which unlocks at `from`
+		/// to the later block `to`.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: Unstaking
+		/// - Writes: Unstaking
+		/// # </weight>
+		fn postpone_unstaking(who: &T::AccountId, from: T::BlockNumber, to: T::BlockNumber) -> DispatchResult {
+			if from >= to {
+				return Ok(());
+			}
+			<Unstaking<T>>::try_mutate_exists(who, |maybe_unstaking| -> DispatchResult {
+				if let Some(unstaking) = maybe_unstaking {
+					if let Some(amount) = unstaking.remove(&from) {
+						let amount = amount.saturating_add(*unstaking.get(&to).unwrap_or(&BalanceOf::<T>::zero()));
+						unstaking
+							.try_insert(to, amount)
+							.map_err(|_| Error::<T>::NoMoreUnstaking)?;
 					}
 				}
 				Ok(())
-			})?;
+			})
+		}
 
-			// Either set a new lock or potentially extend the existing one if amount
-			// exceeds the currently locked amount
-			T::Currency::extend_lock(STAKING_ID, who, amount, WithdrawReasons::all());
+		/// Return the id of the delegation pool the account belongs to.
+		fn pool_id_of(who: &T::AccountId) -> Option<PoolId> {
+			PalletId::try_from_sub_account::<PoolId>(who)
+				.and_then(|(pallet_id, pool_id)| (pallet_id == T::PoolPalletId::get()).then(|| pool_id))
+		}
 
-			Ok(unstaking_len)
+		/// Calculate the share of the rewards of a delegation pool which is
+		/// due to the member, including the rewards which have not been
+		/// claimed by the pool yet.
+		pub fn pending_pool_rewards(pool_id: PoolId, who: &T::AccountId) -> BalanceOf<T> {
+			let (pool, member) = match (Pools::<T>::get(pool_id), PoolMembers::<T>::get(pool_id, who)) {
+				(Some(pool), Some(member)) => (pool, member),
+				_ => return BalanceOf::<T>::zero(),
+			};
+
+			let pending = Self::pending_rewards(&Self::pool_account(pool_id));
+			let reward_counter = if pool.points.is_zero() {
+				pool.reward_counter
+			} else {
+				pool.reward_counter.saturating_add(FixedU128::saturating_from_rational(
+					pending.saturated_into::<u128>(),
+					pool.points.saturated_into::<u128>(),
+				))
+			};
+			let earned: BalanceOf<T> = reward_counter
+				.saturating_sub(member.reward_counter)
+				.saturating_mul_int(member.points.saturated_into::<u128>())
+				.saturated_into();
+			member.pending_rewards.saturating_add(earned)
 		}
 
-		/// Set the unlocking block for the account and corresponding amount
-		/// which can be unlocked via `unlock_unstaked` after waiting at
-		/// least for `StakeDuration` many blocks.
+		/// Compare the number of blocks each collator of the given session has
+		/// authored with the number of blocks they were expected to author,
+		/// which is the average over all collators of the session.
 		///
-		/// Throws if the amount is zero (unlikely) or if active unlocking
-		/// requests exceed limit. The latter defends against stake reduction
-		/// spamming.
+		/// Collators which authored fewer than `OfflineThreshold` of their
+		/// expected blocks commit an offence and are slashed. After
+		/// `MaxOffences` offences in consecutive sessions, the collator
+		/// candidate is removed.
+		///
+		/// Returns the consumed weight.
 		///
 		/// # <weight>
-		/// Weight: O(1)
-		/// - Reads: BlockNumber, Unstaking
-		/// - Writes: Unstaking
+		/// Weight: O(N * D) where N is `MaxSelectedCandidates` bounded by
+		/// `MaxTopCandidates` and D is the number of delegators of an
+		/// offending collator bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: SessionCollators, N * BlocksAuthored, N * Offences, see
+		///   `slash_candidate` and `remove_offline_candidate` for each offender
+		/// - Writes: N * Offences, see `slash_candidate` and
+		///   `remove_offline_candidate` for each offender
+		/// - Kills: SessionCollators, N * BlocksAuthored
 		/// # </weight>
-		fn prep_unstake(who: &T::AccountId, amount: BalanceOf<T>, is_removal: bool) -> DispatchResult {
-			// should never occur but let's be safe
-			ensure!(!amount.is_zero(), Error::<T>::StakeNotFound);
+		fn check_block_production(session_index: SessionIndex) -> Weight {
+			let collators = SessionCollators::<T>::take(session_index);
+			let num_collators = collators.len().saturated_into::<u32>();
+			let mut weight = T::DbWeight::get().reads_writes(
+				1u64.saturating_add(num_collators.into()),
+				1u64.saturating_add(num_collators.into()),
+			);
 
-			let now = <frame_system::Pallet<T>>::block_number();
-			let unlock_block = now.saturating_add(T::StakeDuration::get());
-			let mut unstaking = <Unstaking<T>>::get(who);
+			let authored: Vec<(T::AccountId, u32)> = collators
+				.into_iter()
+				.map(|collator| {
+					let blocks = BlocksAuthored::<T>::take(&collator);
+					(collator, blocks)
+				})
+				.collect();
+			let total_blocks = authored
+				.iter()
+				.fold(0u32, |acc, (_, blocks)| acc.saturating_add(*blocks));
+			let expected = total_blocks.checked_div(num_collators).unwrap_or_default();
+			let threshold = T::OfflineThreshold::get() * expected;
+
+			for (collator, blocks) in authored {
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+				if blocks >= threshold {
+					Offences::<T>::remove(&collator);
+					continue;
+				}
+				// collators which are not candidates anymore cannot be punished
+				let state = if let Some(state) = CandidatePool::<T>::get(&collator) {
+					state
+				} else {
+					Offences::<T>::remove(&collator);
+					continue;
+				};
 
-			let allowed_unstakings = if is_removal {
-				// the account was forcedly removed and we allow to fill all unstake requests
-				T::MaxUnstakeRequests::get()
-			} else {
-				// we need to reserve a free slot for a forced removal of the account
-				T::MaxUnstakeRequests::get().saturating_sub(1)
-			};
-			ensure!(
-				unstaking.len().saturated_into::<u32>() < allowed_unstakings,
-				Error::<T>::NoMoreUnstaking,
-			);
+				let offences = Offences::<T>::mutate(&collator, |offences| {
+					*offences = offences.saturating_add(1);
+					*offences
+				});
+				Self::deposit_event(Event::OffenceReported(collator.clone(), blocks, expected, offences));
 
-			// if existent, we have to add the current amount of same unlock_block, because
-			// insert overwrites the current value
-			let amount = amount.saturating_add(*unstaking.get(&unlock_block).unwrap_or(&BalanceOf::<T>::zero()));
-			unstaking
-				.try_insert(unlock_block, amount)
-				.map_err(|_| Error::<T>::NoMoreUnstaking)?;
-			<Unstaking<T>>::insert(who, unstaking);
-			Ok(())
+				let num_delegators = state.delegators.len().saturated_into::<u32>();
+				let underbonded = Self::slash_candidate(&collator, state);
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(
+					4u64.saturating_add(num_delegators.saturating_add(1).saturating_mul(4).into()),
+					3u64.saturating_add(num_delegators.saturating_add(1).saturating_mul(4).into()),
+				));
+
+				// delegations cannot stay below the minimum stake
+				for delegator in underbonded.iter() {
+					weight = weight.saturating_add(Self::kick_underbonded_delegator(delegator, &collator));
+				}
+
+				// neither can the candidate
+				let below_min = CandidatePool::<T>::get(&collator)
+					.map(|state| state.stake < T::MinCollatorCandidateStake::get())
+					.unwrap_or(false);
+				weight = weight.saturating_add(T::DbWeight::get().reads(1));
+
+				if offences >= T::MaxOffences::get() || below_min {
+					weight = weight.saturating_add(Self::remove_offline_candidate(&collator));
+				}
+			}
+
+			weight
 		}
 
-		/// Clear the CandidatePool of the candidate and remove all delegations
-		/// to the candidate. Moreover, prepare unstaking for the candidate and
-		/// their former delegations.
+		/// Slash the collator candidate's own stake by `CollatorSlash` and the
+		/// stake of each of their delegations by `DelegatorSlash`. The slashed
+		/// funds are handed over to `Slash`. The slash of a delegation pool
+		/// reduces the stake of all of its members proportionally.
+		///
+		/// Returns the delegators whose delegation fell below `MinDelegation`
+		/// or whose total stake fell below `MinDelegatorStake`.
+		///
+		/// Emits `Slashed` for each slashed account.
 		///
 		/// # <weight>
-		/// Weight: O(D + U) where D is the number of delegators of the collator
-		/// candidate bounded by `MaxDelegatorsPerCollator` and U is the
-		/// number of locked unstaking requests bounded by `MaxUnstakeRequests`.
-		/// - Reads: BlockNumber, D * DelegatorState, D * Unstaking
-		/// - Writes: D * DelegatorState, (D + 1) * Unstaking
-		/// - Kills: CandidatePool, DelegatorState for all delegators which only
-		///   delegated to the candidate
+		/// Weight: O(D) where D is the number of delegators of the collator
+		/// candidate bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: CollatorBlockRewards, (D + 1) * SettledBlockRewards, (D +
+		///   1) * Rewards, (D + 1) * Balance, (D + 1) * Locks, D *
+		///   DelegatorState, D * Pools, TopCandidates, MaxSelectedCandidates,
+		///   TotalCollatorStake
+		/// - Writes: (D + 1) * SettledBlockRewards, (D + 1) * Rewards, (D + 1)
+		///   * Balance, (D + 1) * Locks, D * DelegatorState, D * Pools,
+		///   CandidatePool, TopCandidates, TotalCollatorStake
 		/// # </weight>
-		fn remove_candidate(
+		fn slash_candidate(
 			collator: &T::AccountId,
-			state: &CandidateOf<T, T::MaxDelegatorsPerCollator>,
-		) -> DispatchResult {
-			// iterate over delegators
-			for stake in &state.delegators[..] {
-				// prepare unstaking of delegator
-				Self::prep_unstake(&stake.owner, stake.amount, true)?;
-				// remove delegation from delegator state
-				if let Some(mut delegator) = DelegatorState::<T>::get(&stake.owner) {
-					if let Some(remaining) = delegator.rm_delegation(collator) {
-						if remaining.is_zero() {
-							DelegatorState::<T>::remove(&stake.owner);
-						} else {
-							DelegatorState::<T>::insert(&stake.owner, delegator);
+			mut state: CandidateOf<T, T::MaxDelegatorsPerCollator>,
+		) -> Vec<T::AccountId> {
+			// credit the rewards earned with the current stakes before changing them
+			Self::settle_candidate_rewards(&state);
+
+			let CandidateOf::<T, _> {
+				stake: old_stake,
+				total: old_total,
+				..
+			} = state;
+
+			let imb = Self::slash_stake(collator, T::CollatorSlash::get() * state.stake);
+			state.stake = state.stake.saturating_sub(imb.peek());
+			state.total = state.total.saturating_sub(imb.peek());
+			let mut slashed = imb;
+			let mut underbonded = Vec::new();
+
+			let delegator_slash = T::DelegatorSlash::get();
+			if !delegator_slash.is_zero() {
+				for stake in state.delegators.clone().into_iter() {
+					let imb = Self::slash_stake(&stake.owner, delegator_slash * stake.amount);
+					let amount = imb.peek();
+					if amount.is_zero() {
+						continue;
+					}
+					state.dec_delegator(stake.owner.clone(), stake.amount, amount);
+					if let Some(mut delegator) = DelegatorState::<T>::get(&stake.owner) {
+						// cannot underflow since the slashed amount is at most the delegated amount
+						let _ = delegator.dec_delegation(collator.clone(), amount);
+						if stake.amount.saturating_sub(amount) < T::MinDelegation::get()
+							|| delegator.total < T::MinDelegatorStake::get()
+						{
+							underbonded.push(stake.owner.clone());
 						}
+						DelegatorState::<T>::insert(&stake.owner, delegator);
 					}
+					// the members of a delegation pool share the slash
+					if let Some(pool_id) = Self::pool_id_of(&stake.owner) {
+						Pools::<T>::mutate(pool_id, |maybe_pool| {
+							if let Some(pool) = maybe_pool {
+								pool.stake = pool.stake.saturating_sub(amount);
+							}
+						});
+					}
+					slashed.subsume(imb);
 				}
 			}
-			// prepare unstaking of collator candidate
-			Self::prep_unstake(&state.id, state.stake, true)?;
 
-			// *** No Fail beyond this point ***
+			// update top candidates and total amount at stake
+			if state.is_active() {
+				Self::update_top_candidates(
+					collator.clone(),
+					old_stake,
+					// safe because total >= stake
+					old_total - old_stake,
+					state.stake,
+					state.total - state.stake,
+				);
+			}
+			CandidatePool::<T>::insert(collator, state);
 
-			// disable validator for next session if they were in the set of validators
-			pallet_session::Pallet::<T>::validators()
-				.into_iter()
-				.enumerate()
-				.find_map(|(i, id)| {
-					if <T as pallet_session::Config>::ValidatorIdOf::convert(collator.clone()) == Some(id) {
-						Some(i)
-					} else {
-						None
-					}
-				})
-				.map(u32::saturated_from::<usize>)
-				// FIXME: Does not prevent the collator from being able to author a block in this (or potentially the next) session. See https://github.com/paritytech/substrate/issues/8004
-				.map(pallet_session::Pallet::<T>::disable_index);
+			T::Slash::on_unbalanced(slashed);
 
-			CandidatePool::<T>::remove(&collator);
-			Ok(())
+			underbonded
 		}
 
-		/// Withdraw all staked currency which was unstaked at least
-		/// `StakeDuration` blocks ago.
+		/// Remove the delegation of a delegator whose stake fell below the
+		/// minimum because of a slash of the collator candidate.
+		///
+		/// If the remaining stake of the delegator would be below
+		/// `MinDelegatorStake`, all of their delegations are removed and they
+		/// leave the set of delegators. The funds are unstaked as if the
+		/// delegator had left themselves.
+		///
+		/// Emits `DelegatorLeftCollator` and potentially `DelegatorLeft`.
+		///
+		/// Returns the consumed weight.
 		///
 		/// # <weight>
-		/// Weight: O(U) where U is the number of locked unstaking
-		/// requests bounded by `MaxUnstakeRequests`.
-		/// - Reads: Unstaking, Locks
-		/// - Writes: Unstaking, Locks
-		/// - Kills: Unstaking & Locks if no balance is locked anymore
+		/// Weight: O(C * D) where C is the number of delegations of the
+		/// delegator bounded by `MaxCollatorsPerDelegator` and D is the number
+		/// of delegators of a collator bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads/Writes: see `leave_delegators`
 		/// # </weight>
-		fn do_unlock(who: &T::AccountId) -> Result<u32, DispatchError> {
-			let now = <frame_system::Pallet<T>>::block_number();
-			let mut unstaking = <Unstaking<T>>::get(who);
-			let unstaking_len = unstaking.len().saturated_into::<u32>();
-			ensure!(!unstaking.is_empty(), Error::<T>::UnstakingIsEmpty);
-
-			let mut total_unlocked: BalanceOf<T> = Zero::zero();
-			let mut total_locked: BalanceOf<T> = Zero::zero();
-			let mut expired = Vec::new();
-
-			// check potential unlocks
-			for (block_number, locked_balance) in unstaking.clone().into_iter() {
-				if block_number <= now {
-					expired.push(block_number);
-					total_unlocked = total_unlocked.saturating_add(locked_balance);
+		fn kick_underbonded_delegator(delegator: &T::AccountId, collator: &T::AccountId) -> Weight {
+			let state = if let Some(state) = DelegatorState::<T>::get(delegator) {
+				state
+			} else {
+				return T::DbWeight::get().reads(1);
+			};
+			let num_delegations: u32 = state.delegations.len().saturated_into();
+			let amount = state
+				.delegations
+				.iter()
+				.find(|stake| &stake.owner == collator)
+				.map(|stake| stake.amount)
+				.unwrap_or_default();
+
+			// roll back all changes if the delegator cannot be removed
+			let res = frame_support::storage::with_storage_layer(|| -> DispatchResult {
+				if num_delegations > 1 && state.total.saturating_sub(amount) >= T::MinDelegatorStake::get() {
+					Self::delegator_revokes_collator(delegator.clone(), collator.clone())?;
 				} else {
-					total_locked = total_locked.saturating_add(locked_balance);
+					for stake in state.delegations.iter() {
+						Self::delegator_leaves_collator(delegator.clone(), stake.owner.clone())?;
+					}
+					DelegatorState::<T>::remove(delegator);
+					Self::clear_payee(delegator);
+					Self::deposit_event(Event::DelegatorLeft(delegator.clone(), state.total));
 				}
-			}
-			for block_number in expired {
-				unstaking.remove(&block_number);
+				Ok(())
+			});
+			if let Err(err) = res {
+				log::error!("failed to remove underbonded delegator {:?}: {:?}", delegator, err);
+				return T::DbWeight::get().reads(1);
 			}
 
-			// iterate balance locks to retrieve amount of locked balance
-			let locks = Locks::<T>::get(who);
-			total_locked = if let Some(BalanceLock { amount, .. }) = locks.iter().find(|l| l.id == STAKING_ID) {
-				amount.saturating_sub(total_unlocked.into()).into()
-			} else {
-				// should never fail to find the lock since we checked whether unstaking is not
-				// empty but let's be safe
-				Zero::zero()
-			};
+			<T as Config>::WeightInfo::leave_delegators(num_delegations, T::MaxDelegatorsPerCollator::get())
+		}
 
-			if total_locked.is_zero() {
-				T::Currency::remove_lock(STAKING_ID, who);
-				<Unstaking<T>>::remove(who);
-			} else {
-				T::Currency::set_lock(STAKING_ID, who, total_locked, WithdrawReasons::all());
-				<Unstaking<T>>::insert(who, unstaking);
+		/// Slash the given amount of the staked funds of the account and reduce
+		/// the staking lock accordingly.
+		///
+		/// If the stake has been funded by another account, the funded
+		/// principal is slashed from the funder once the funds which are
+		/// locked on the account itself have been slashed.
+		///
+		/// Emits `Slashed` if any funds have been slashed.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// - Reads: 2 * Balance, 2 * Locks, Funders, 2 * FundedStake
+		/// - Writes: 2 * Balance, 2 * Locks, Funders, FundedStake
+		/// # </weight>
+		fn slash_stake(who: &T::AccountId, amount: BalanceOf<T>) -> NegativeImbalanceOf<T> {
+			if amount.is_zero() {
+				return NegativeImbalanceOf::<T>::zero();
 			}
 
-			Ok(unstaking_len)
+			let locked = Self::staking_lock(who);
+			let own = Self::own_lock(who, locked);
+			let (mut imb, _) = T::Currency::slash(who, amount.min(own));
+			if let Some((funder, funded)) = Funders::<T>::get(who) {
+				let (funder_imb, _) = T::Currency::slash(&funder, amount.saturating_sub(imb.peek()).min(funded));
+				Self::release_funding(who, &funder, funded, funder_imb.peek());
+				imb.subsume(funder_imb);
+			}
+			let slashed = imb.peek();
+
+			// the slashed funds do not need to be locked anymore
+			Self::set_staking_lock(who, locked.saturating_sub(slashed));
+
+			if !slashed.is_zero() {
+				Self::deposit_event(Event::Slashed(who.clone(), slashed));
+			}
+			imb
 		}
 
-		/// Process the coinbase rewards for the production of a new block.
+		/// Remove a collator candidate which has committed `MaxOffences`
+		/// offences in consecutive sessions or whose stake fell below
+		/// `MinCollatorCandidateStake` from the set of candidates.
+		///
+		/// The candidate is kept if there would not be enough candidates left
+		/// afterwards.
+		///
+		/// The blocks of the removed candidate are rejected unless no other
+		/// collator of the current or the queued validator set could author
+		/// blocks anymore.
+		///
+		/// Emits `CollatorRemoved`.
+		///
+		/// Returns the consumed weight.
 		///
 		/// # <weight>
-		/// Weight: O(1)
-		/// - Reads: Balance
-		/// - Writes: Balance
+		/// Weight: O(N + D) where N is `MaxSelectedCandidates` bounded by
+		/// `MaxTopCandidates` and D is the number of delegators of the
+		/// collator candidate bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads/Writes: see `force_remove_candidate`
 		/// # </weight>
-		fn do_reward(who: &T::AccountId, reward: BalanceOf<T>) {
-			// mint
-			if let Ok(imb) = T::Currency::deposit_into_existing(who, reward) {
-				Self::deposit_event(Event::Rewarded(who.clone(), imb.peek()));
+		fn remove_offline_candidate(collator: &T::AccountId) -> Weight {
+			let state = if let Some(state) = CandidatePool::<T>::get(collator) {
+				state
+			} else {
+				return T::DbWeight::get().reads(1);
+			};
+			let mut candidates = TopCandidates::<T>::get();
+			if candidates.len().saturated_into::<u32>() <= T::MinRequiredCollators::get() {
+				log::warn!(
+					"keeping offline collator {:?} because there are too few collator candidates",
+					collator
+				);
+				return T::DbWeight::get().reads(2);
+			}
+
+			// roll back all changes if the candidate cannot be removed
+			if let Err(err) =
+				frame_support::storage::with_storage_layer(|| Self::remove_candidate(collator, &state, true))
+			{
+				log::error!("failed to remove offline collator {:?}: {:?}", collator, err);
+				return T::DbWeight::get().reads(2);
 			}
+
+			let (num_collators, num_delegators) = if candidates
+				.remove_stake(collator, state.total)
+				.is_some()
+			{
+				// update top candidates
+				TopCandidates::<T>::put(candidates);
+				// update total amount at stake from scratch
+				Self::update_total_stake()
+			} else {
+				(0u32, 0u32)
+			};
+
+			Self::deposit_event(Event::CollatorRemoved(collator.clone(), state.total));
+
+			<T as Config>::WeightInfo::force_remove_candidate(num_collators, num_delegators)
 		}
 
 		/// Checks whether a delegator can still delegate in this round, e.g.,
@@ -2695,35 +7323,124 @@ pub mod pallet {
 	where
 		T: Config + pallet_authorship::Config + pallet_session::Config,
 	{
-        fn note_author(author: T::AccountId) {
-            let mut reads = 1u64;
-            let mut writes = 0u64;
-
-            if let Some(state) = CandidatePool::<T>::get(author.clone()) {
-                let reward_per_block = Self::reward_per_block();
-                if state.total >= reward_per_block {
-                    let collator_reward_ratio = Perquintill::from_rational(reward_per_block, state.total);
-                    let collator_due_amount = collator_reward_ratio * state.stake;
-                    Self::do_reward(&author, collator_due_amount);
-                    writes = writes.saturating_add(1);
-
-                    for Stake { owner, amount } in state.delegators {
-                        if amount >= T::MinDelegatorStake::get() {
-                            let due = collator_reward_ratio * amount;
-                            Self::do_reward(&owner, due);
-                        }
-                        writes = writes.saturating_add(1);
-                    }
-                    reads = reads.saturating_add(1);
-                }
-                reads = reads.saturating_add(1);
-            }
-
-            frame_system::Pallet::<T>::register_extra_weight_unchecked(
-                T::DbWeight::get().reads_writes(reads, writes),
-                DispatchClass::Mandatory,
-            );
-        }
+		/// Credit the block reward to the author's reward accumulator and
+		/// count the block towards the author's block production in the
+		/// current session and the statistics of the current round.
+		///
+		/// The collator and their delegators are not paid out immediately.
+		/// Instead, their shares are settled lazily and can be claimed via
+		/// `claim_rewards`. The shares are based on the stake snapshot of the
+		/// current round, which is taken at the first block of the author in
+		/// the round after the rewards of the previous rounds have been
+		/// settled.
+		///
+		/// The part of the block rewards which is not credited to the stakers,
+		/// i.e. the share of the delegations below `MinRewardedDelegation` and
+		/// the rounding remainder, is handled according to `RewardRemainder`
+		/// once the rewards of the snapshot are settled.
+		///
+		/// Emits `BlockRewarded` with the shares of the block reward accrued
+		/// for the stakers and the part which is not credited to them, and
+		/// `BlockRewardSplit` with the amounts which have actually been
+		/// credited if the rewards of a previous snapshot are settled.
+		///
+		/// The blocks of an author which has been removed from the set of
+		/// collator candidates in the current or the previous session are
+		/// rejected by the `DisabledValidators` check of AuRa. Should such a
+		/// block be noted nevertheless, it is neither counted nor rewarded.
+		///
+		/// # <weight>
+		/// Weight: O(D) where D is the number of delegators of the author
+		/// bounded by `MaxDelegatorsPerCollator`.
+		/// - Reads: DisabledCollators, CurrentIndex, BlocksAuthored, Round,
+		///   CollatorRoundStats, CandidatePool, StakeSnapshots,
+		///   RewardPerBlock, CurrentInflation, LoyaltyConfig, D *
+		///   DelegationAge, CollatorBlockRewards, UnsplitBlockRewards,
+		///   Commission, [(D + 1) * SettledBlockRewards, (D + 1) * Rewards,
+		///   CollatorRoundStats or TotalIssuance]
+		/// - Writes: BlocksAuthored, CollatorRoundStats, CollatorBlockRewards,
+		///   UnsplitBlockRewards, [StakeSnapshots, (D + 1) *
+		///   SettledBlockRewards, (D + 1) * Rewards, CollatorRoundStats or
+		///   TotalIssuance]
+		/// # </weight>
+		fn note_author(author: T::AccountId) {
+			if Pallet::<T>::is_disabled_author(&author) {
+				log::warn!("not rewarding block authored by removed collator {:?}", author);
+				frame_system::Pallet::<T>::register_extra_weight_unchecked(
+					T::DbWeight::get().reads(2),
+					DispatchClass::Mandatory,
+				);
+				return;
+			}
+
+			let mut reads = 3u64;
+			let mut writes = 1u64;
+
+			BlocksAuthored::<T>::mutate(&author, |blocks| *blocks = blocks.saturating_add(1));
+
+			let round = Round::<T>::get().current;
+			let mut stats = CollatorRoundStats::<T>::get(round, &author);
+			stats.blocks_authored = stats.blocks_authored.saturating_add(1);
+
+			if let Some(state) = CandidatePool::<T>::get(&author) {
+				// the stake changes of the previous rounds are rewarded from the first
+				// block of the candidate in a new round on, and the rewards of the blocks
+				// of a previous round are split before
+				let unsplit = UnsplitBlockRewards::<T>::get(&author);
+				let unsplit_current = unsplit.earned.is_zero() || unsplit.round == round;
+				let snapshot = match StakeSnapshots::<T>::get(&author) {
+					Some(snapshot) if snapshot.round == round && unsplit_current => snapshot,
+					_ => {
+						let stakers = state.delegators.len().saturating_add(1) as u64;
+						reads = reads.saturating_add(stakers.saturating_mul(3).saturating_add(7));
+						writes = writes.saturating_add(stakers.saturating_mul(2).saturating_add(4));
+						Self::settle_candidate_rewards(&state)
+					}
+				};
+				let rewarded = snapshot.rewarded(&state);
+
+				let reward_per_block = Self::reward_per_block();
+				reads = reads.saturating_add(2);
+				if rewarded.total >= reward_per_block && Self::is_rewarded(&author) {
+					let (weighted, bonus_stake) = Self::weighted_stake_with_bonus(&author, &rewarded);
+					let accrued = Self::accrued_block_reward(&weighted, reward_per_block);
+					let commission = Commission::<T>::get(&author);
+					let (collator_rewards, delegator_rewards) =
+						Self::split_block_reward(&weighted, accrued, commission);
+					CollatorBlockRewards::<T>::mutate(&author, |rewards| *rewards = rewards.saturating_add(accrued));
+					UnsplitBlockRewards::<T>::mutate(&author, |unsplit| {
+						unsplit.round = round;
+						unsplit.earned = unsplit.earned.saturating_add(reward_per_block);
+						unsplit.accrued = unsplit.accrued.saturating_add(accrued);
+					});
+					stats.delegator_rewards = stats.delegator_rewards.saturating_add(delegator_rewards);
+					stats.collator_rewards = stats.collator_rewards.saturating_add(collator_rewards);
+
+					let block_rewards = UnsplitRewards {
+						round,
+						earned: reward_per_block,
+						accrued,
+						collator: collator_rewards,
+						delegators: delegator_rewards,
+					};
+					let split = Self::reward_split(&weighted, bonus_stake, &block_rewards, commission);
+					Self::deposit_event(Event::BlockRewarded(author.clone(), split));
+					let delegators = rewarded.delegators.len() as u64;
+					reads = reads.saturating_add(delegators.saturating_add(5));
+					writes = writes.saturating_add(2);
+				}
+				reads = reads.saturating_add(1);
+			}
+
+			CollatorRoundStats::<T>::insert(round, &author, stats);
+			reads = reads.saturating_add(2);
+			writes = writes.saturating_add(1);
+
+			frame_system::Pallet::<T>::register_extra_weight_unchecked(
+				T::DbWeight::get().reads_writes(reads, writes),
+				DispatchClass::Mandatory,
+			);
+		}
 
 		fn note_uncle(_author: T::AccountId, _age: T::BlockNumber) {
 			// we too are not caring.
@@ -2732,7 +7449,8 @@ pub mod pallet {
 
 	impl<T: Config> pallet_session::SessionManager<T::AccountId> for Pallet<T> {
 		/// 1. A new session starts.
-		/// 2. In hook new_session: Read the current top n candidates from the
+		/// 2. In hook new_session: Read the invulnerable collators and fill
+		///    the remaining seats with the current top n candidates from the
 		///    TopCandidates and assign this set to author blocks for the next
 		///    session.
 		/// 3. AuRa queries the authorities from the session pallet for
@@ -2745,27 +7463,76 @@ pub mod pallet {
 				<frame_system::Pallet<T>>::block_number(),
 			);
 
+			let candidates = Pallet::<T>::selected_candidates();
+			for candidate in candidates.iter() {
+				if !Pallet::<T>::has_session_keys(candidate) {
+					Pallet::<T>::deposit_event(Event::CandidateWithoutSessionKeys(candidate.clone(), new_index));
+				}
+			}
+
 			frame_system::Pallet::<T>::register_extra_weight_unchecked(
-				T::DbWeight::get().reads(2),
+				T::DbWeight::get().reads(3u64.saturating_add(candidates.len().saturating_mul(2) as u64)),
 				DispatchClass::Mandatory,
 			);
 
-			let collators = Pallet::<T>::selected_candidates().to_vec();
+			let collators = Pallet::<T>::next_session_collators();
 			if collators.is_empty() {
 				// we never want to pass an empty set of collators. This would brick the chain.
 				log::error!("💥 keeping old session because of empty collator set!");
+				// the collators of the previous session keep authoring blocks, so their block
+				// production still needs to be evaluated and their authored blocks cleared
+				let collators = SessionCollators::<T>::get(new_index.saturating_sub(1));
+				SessionCollators::<T>::insert(new_index, collators);
+				frame_system::Pallet::<T>::register_extra_weight_unchecked(
+					T::DbWeight::get().reads_writes(1, 1),
+					DispatchClass::Mandatory,
+				);
 				None
 			} else {
-				Some(collators)
+				// remember the collators to evaluate their block production at the end of the
+				// session
+				SessionCollators::<T>::insert(new_index, collators.clone());
+				frame_system::Pallet::<T>::register_extra_weight_unchecked(
+					T::DbWeight::get().writes(1),
+					DispatchClass::Mandatory,
+				);
+				Some(collators.to_vec())
 			}
 		}
 
-		fn end_session(_end_index: SessionIndex) {
-			// we too are not caring.
+		/// Evaluate the block production of the collators of the ending
+		/// session and punish the ones which have been offline.
+		fn end_session(end_index: SessionIndex) {
+			let collators = SessionCollators::<T>::get(end_index);
+			let mut weight = Pallet::<T>::check_block_production(end_index);
+
+			// restake the rewards which have been accrued in the session
+			if !Pallet::<T>::paused().payouts {
+				for collator in collators.iter() {
+					weight = weight.saturating_add(Pallet::<T>::compound_candidate_rewards(collator));
+				}
+			}
+			weight = weight.saturating_add(T::DbWeight::get().reads(2));
+			frame_system::Pallet::<T>::register_extra_weight_unchecked(weight, DispatchClass::Mandatory);
 		}
 
-		fn start_session(_start_index: SessionIndex) {
-			// we too are not caring.
+		/// Stop rejecting the blocks of removed collators which are not part
+		/// of the validator set anymore.
+		fn start_session(start_index: SessionIndex) {
+			let expired: Vec<T::AccountId> = DisabledCollators::<T>::iter()
+				.filter(|(_, until)| *until <= start_index)
+				.map(|(collator, _)| collator)
+				.collect();
+			for collator in expired.iter() {
+				DisabledCollators::<T>::remove(collator);
+			}
+			frame_system::Pallet::<T>::register_extra_weight_unchecked(
+				T::DbWeight::get().reads_writes(
+					expired.len().saturating_add(1) as u64,
+					expired.len() as u64,
+				),
+				DispatchClass::Mandatory,
+			);
 		}
 	}
 
@@ -2797,6 +7564,29 @@ pub mod pallet {
 		}
 	}
 
+	impl<T: Config> DisabledValidators for Pallet<T> {
+		/// Return whether the authority at the given index of the current
+		/// validator set is a removed collator whose blocks are rejected.
+		///
+		/// # <weight>
+		/// Weight: O(R) where R is the number of removed collators which are
+		/// still part of the validator set.
+		/// - Reads: Validators, CurrentIndex, R * DisabledCollators
+		/// # </weight>
+		fn is_disabled(index: u32) -> bool {
+			let validators = pallet_session::Pallet::<T>::validators();
+			let validator = match validators.get(index as usize) {
+				Some(validator) => validator,
+				None => return false,
+			};
+			let current = pallet_session::Pallet::<T>::current_index();
+			DisabledCollators::<T>::iter().any(|(collator, until)| {
+				current < until
+					&& <T as pallet_session::Config>::ValidatorIdOf::convert(collator).as_ref() == Some(validator)
+			})
+		}
+	}
+
 	impl<T: Config> EstimateNextSessionRotation<T::BlockNumber> for Pallet<T> {
 		fn average_session_length() -> T::BlockNumber {
 			<Round<T>>::get().length
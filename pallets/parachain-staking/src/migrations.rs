@@ -0,0 +1,118 @@
+// KILT Blockchain – https://botlabs.org
+// Copyright (C) 2019-2022 BOTLabs GmbH
+
+// The KILT Blockchain is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The KILT Blockchain is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// If you feel like getting in touch with us, you can do so at info@botlabs.org
+
+//! Storage migrations of the parachain staking pallet.
+//!
+//! Each storage version has its own module with a migration implementing
+//! `OnRuntimeUpgrade`. A migration is applied once when the on-chain storage
+//! version is below the version the migration upgrades to, and checks the
+//! migrated storage with `try-runtime`. The runtime applies [Migrations] when
+//! it is upgraded.
+
+use frame_support::{
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+use sp_std::marker::PhantomData;
+
+use crate::{Config, Pallet};
+
+/// All migrations of the pallet in the order in which they have to be
+/// applied.
+pub type Migrations<T> = (v8::MigrateToV8<T>,);
+
+/// Start counting the stake age of all existing delegations and the round
+/// statistics.
+pub mod v8 {
+	use super::*;
+	use crate::{DelegationAge, DelegatorState, OldestRoundStats, Round};
+
+	/// Migrate the storage to version 8 by starting the stake age of all
+	/// existing delegations and the round statistics in the current round.
+	pub struct MigrateToV8<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV8<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() < 8 {
+				migrate::<T>()
+			} else {
+				log::info!("🔗 Stake ages have already been migrated to storage version 8");
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			if Pallet::<T>::on_chain_storage_version() < 8 {
+				pre_migrate::<T>()?;
+			}
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			post_migrate::<T>()?;
+			if Pallet::<T>::on_chain_storage_version() < 8 {
+				return Err("On-chain storage version has not been migrated to version 8");
+			}
+			Ok(())
+		}
+	}
+
+	/// Set the current round as the start of the stake age of each existing
+	/// delegation such that no delegation earns a loyalty bonus for the time
+	/// before the upgrade. The round statistics are recorded from the
+	/// current round on, so there are no older rounds to prune.
+	pub fn migrate<T: Config>() -> Weight {
+		let current = Round::<T>::get().current;
+		OldestRoundStats::<T>::put(current);
+		let mut reads = 1u64;
+		let mut writes = 2u64;
+		for (delegator, state) in DelegatorState::<T>::iter() {
+			reads = reads.saturating_add(1);
+			for stake in state.delegations.into_iter() {
+				DelegationAge::<T>::insert(&delegator, &stake.owner, current);
+				writes = writes.saturating_add(1);
+			}
+		}
+		StorageVersion::new(8).put::<Pallet<T>>();
+
+		log::info!("🔗 Migrated the stake age of {} delegations to storage version 8", writes - 2);
+		T::DbWeight::get().reads_writes(reads, writes)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+		if DelegationAge::<T>::iter().next().is_some() {
+			return Err("Stake ages exist before the migration");
+		}
+		Ok(())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+		for (delegator, state) in DelegatorState::<T>::iter() {
+			for stake in state.delegations.into_iter() {
+				if !DelegationAge::<T>::contains_key(&delegator, &stake.owner) {
+					return Err("Delegation without stake age");
+				}
+			}
+		}
+		Ok(())
+	}
+}
@@ -22,9 +22,10 @@
 use super::*;
 use crate::{self as stake, types::NegativeImbalanceOf};
 use frame_support::{
-	construct_runtime, parameter_types,
-	traits::{Currency, GenesisBuild, OnFinalize, OnInitialize, OnUnbalanced},
+	assert_ok, construct_runtime, parameter_types,
+	traits::{Contains, Currency, GenesisBuild, OnFinalize, OnInitialize, OnUnbalanced},
 	weights::Weight,
+	PalletId,
 };
 use pallet_authorship::EventHandler;
 use sp_consensus_aura::sr25519::AuthorityId;
@@ -116,7 +117,7 @@ impl pallet_balances::Config for Test {
 
 impl pallet_aura::Config for Test {
 	type AuthorityId = AuthorityId;
-	type DisabledValidators = ();
+	type DisabledValidators = StakePallet;
 	type MaxAuthorities = MaxCollatorCandidates;
 }
 
@@ -131,6 +132,7 @@ parameter_types! {
 	pub const MinBlocksPerRound: BlockNumber = 3;
 	pub const StakeDuration: u32 = 2;
 	pub const ExitQueueDelay: u32 = 2;
+	pub const CommissionChangeDelay: u32 = 2;
 	pub const DefaultBlocksPerRound: BlockNumber = BLOCKS_PER_ROUND;
 	pub const MinCollators: u32 = 2;
 	#[derive(Debug, Eq, PartialEq)]
@@ -145,6 +147,29 @@ parameter_types! {
 	pub const MaxUnstakeRequests: u32 = 6;
 	pub const NetworkRewardRate: Balance = 20 * DECIMALS;
 	pub const NetworkRewardStart: BlockNumber = 5 * 5 * 60 * 24 * 36525 / 100;
+	pub const CollatorSlash: Perbill = Perbill::from_percent(10);
+	pub const MaxOffences: u32 = 2;
+	pub const PoolPalletId: PalletId = PalletId(*b"dhx/pool");
+	// lowered by the round statistics tests
+	pub static RoundStatsRetention: u32 = 2;
+	// disabled by default, enabled by the slashing tests
+	pub static OfflineThreshold: Perbill = Perbill::zero();
+	pub static DelegatorSlash: Perbill = Perbill::zero();
+	// disabled by default, enabled by the waitlist tests
+	pub static MaxWaitlistSize: u32 = 0;
+	// disabled by default, enabled by the invulnerables tests
+	pub static RewardInvulnerables: bool = false;
+	// the issued remainder is dropped by default, changed by the reward remainder tests
+	pub static RewardRemainder: RemainderDestination = RemainderDestination::Beneficiary;
+	// empty by default, set by the identity tests
+	pub static UnverifiedIdentities: Vec<AccountId> = vec![];
+}
+
+pub struct VerifiedIdentity;
+impl Contains<AccountId> for VerifiedIdentity {
+	fn contains(who: &AccountId) -> bool {
+		!UnverifiedIdentities::get().contains(who)
+	}
 }
 
 pub struct ToBeneficiary();
@@ -163,20 +188,35 @@ impl Config for Test {
 	type DefaultBlocksPerRound = DefaultBlocksPerRound;
 	type StakeDuration = StakeDuration;
 	type ExitQueueDelay = ExitQueueDelay;
+	type CommissionChangeDelay = CommissionChangeDelay;
 	type MinCollators = MinCollators;
 	type MinRequiredCollators = MinCollators;
 	type MaxDelegationsPerRound = MaxDelegatorsPerCollator;
 	type MaxDelegatorsPerCollator = MaxDelegatorsPerCollator;
 	type MaxCollatorsPerDelegator = MaxCollatorsPerDelegator;
+	type MaxWaitlistSize = MaxWaitlistSize;
 	type MinCollatorStake = MinCollatorStake;
 	type MinCollatorCandidateStake = MinCollatorStake;
 	type MaxTopCandidates = MaxCollatorCandidates;
 	type MinDelegatorStake = MinDelegatorStake;
+	type MinRewardedDelegation = MinDelegatorStake;
 	type MinDelegation = MinDelegation;
 	type MaxUnstakeRequests = MaxUnstakeRequests;
 	type NetworkRewardRate = NetworkRewardRate;
 	type NetworkRewardStart = NetworkRewardStart;
 	type NetworkRewardBeneficiary = ToBeneficiary;
+	type RewardRemainder = RewardRemainder;
+	type RewardRemainderBeneficiary = ();
+	type OfflineThreshold = OfflineThreshold;
+	type CollatorSlash = CollatorSlash;
+	type DelegatorSlash = DelegatorSlash;
+	type MaxOffences = MaxOffences;
+	type Slash = ToBeneficiary;
+	type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+	type PoolPalletId = PoolPalletId;
+	type RewardInvulnerables = RewardInvulnerables;
+	type RoundStatsRetention = RoundStatsRetention;
+	type CandidateIdentity = VerifiedIdentity;
 	type WeightInfo = ();
 	const BLOCKS_PER_YEAR: Self::BlockNumber = 5 * 60 * 24 * 36525 / 100;
 }
@@ -225,6 +265,8 @@ pub(crate) struct ExtBuilder {
 	blocks_per_round: BlockNumber,
     // reward per block
     reward_per_block: Balance,
+	// [invulnerable collator]
+	invulnerables: Vec<AccountId>,
 }
 
 impl Default for ExtBuilder {
@@ -235,6 +277,7 @@ impl Default for ExtBuilder {
 			collators: vec![],
             reward_per_block: 20 * DECIMALS,
 			blocks_per_round: BLOCKS_PER_ROUND,
+			invulnerables: vec![],
 		}
 	}
 }
@@ -278,6 +321,18 @@ impl ExtBuilder {
 		self
 	}
 
+	#[must_use]
+	pub(crate) fn with_invulnerables(mut self, invulnerables: Vec<AccountId>) -> Self {
+		self.invulnerables = invulnerables;
+		self
+	}
+
+	#[must_use]
+	pub(crate) fn with_waitlist_size(self, size: u32) -> Self {
+		MaxWaitlistSize::set(size);
+		self
+	}
+
 	pub(crate) fn build(self) -> sp_io::TestExternalities {
 		let mut t = frame_system::GenesisConfig::default()
 			.build_storage::<Test>()
@@ -300,15 +355,22 @@ impl ExtBuilder {
 			stakers,
 			max_candidate_stake: 160_000_000 * DECIMALS,
             reward_per_block: 1 * DECIMALS,
+			invulnerables: self.invulnerables.clone(),
+			max_commission: Perbill::from_percent(10),
 		}
 		.assimilate_storage(&mut t)
 		.expect("Parachain Staking's storage can be assimilated");
 
 		// stashes are the AccountId
-		let session_keys: Vec<_> = self
-			.collators
+		let mut validators: Vec<AccountId> = self.collators.iter().map(|(k, _)| *k).collect();
+		for k in self.invulnerables.iter() {
+			if !validators.contains(k) {
+				validators.push(*k);
+			}
+		}
+		let session_keys: Vec<_> = validators
 			.iter()
-			.map(|(k, _)| {
+			.map(|k| {
 				(
 					*k,
 					*k,
@@ -337,6 +399,15 @@ impl ExtBuilder {
 		ext.execute_with(|| System::set_block_number(1));
 		ext
 	}
+
+	/// Build the externalities, execute the test and check the invariants of
+	/// the staking storage afterwards.
+	pub(crate) fn build_and_execute_with_sanity_tests(self, test: impl FnOnce()) {
+		self.build().execute_with(|| {
+			test();
+			crate::try_state::do_try_state::<Test>().expect("Staking invariants hold after the test");
+		})
+	}
 }
 
 /// Compare whether the difference of both sides is at most `precision * left`.
@@ -356,6 +427,18 @@ pub(crate) fn roll_to(n: BlockNumber, authors: Vec<Option<AccountId>>) {
 	}
 }
 
+/// Register the session keys of the account which are required to join the
+/// set of collator candidates.
+pub(crate) fn set_session_keys(who: AccountId) {
+	assert_ok!(Session::set_keys(
+		Origin::signed(who),
+		MockSessionKeys {
+			aura: UintAuthorityId(who).to_public_key(),
+		},
+		vec![]
+	));
+}
+
 pub(crate) fn last_event() -> Event {
 	System::events().pop().expect("Event expected").event
 }
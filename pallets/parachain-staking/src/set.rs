@@ -29,6 +29,8 @@ use sp_std::{
 #[cfg(feature = "std")]
 use sp_std::prelude::*;
 
+use crate::types::Stake;
+
 /// An ordered set backed by `BoundedVec`.
 #[derive(PartialEq, Eq, Encode, Decode, DefaultNoBound, Clone, TypeInfo, MaxEncodedLen, RuntimeDebug)]
 #[scale_info(skip_type_params(S))]
@@ -224,12 +226,126 @@ impl<T: Ord + Clone, S: Get<u32>> OrderedSet<T, S> {
 		self.0.get(index)
 	}
 
+	/// Returns an iterator over the elements of the set.
+	pub fn iter(&self) -> sp_std::slice::Iter<'_, T> {
+		self.0.iter()
+	}
+
 	/// Sorts from greatest to lowest.
 	pub fn sort_greatest_to_lowest(&mut self) {
 		(self.0[..]).sort_by(|a, b| b.cmp(a));
 	}
 }
 
+impl<A: Ord + Clone, B: Ord + Copy, S: Get<u32>> OrderedSet<Stake<A, B>, S> {
+	/// Binary searches this (from greatest to lowest) ordered set for the
+	/// stake of `owner` which is expected to amount to `amount`.
+	///
+	/// Since stakes are primarily ordered by their amount, only the stakes
+	/// which amount to exactly `amount` have to be compared by their owner.
+	///
+	/// 1. If the stake is found, then Result::Ok is returned, containing the
+	/// index of the matching element.
+	/// 2. If the stake is not found, then Result::Err is returned, containing
+	/// the index where a stake of `amount` would be inserted, i.e. behind all
+	/// existing stakes with the same amount.
+	pub fn binary_search_stake(&self, owner: &A, amount: B) -> Result<usize, usize> {
+		let start = self.0.partition_point(|stake| stake.amount > amount);
+		let end = start.saturating_add(self.0[start..].partition_point(|stake| stake.amount == amount));
+		self.0[start..end]
+			.iter()
+			.position(|stake| stake.owner == *owner)
+			.map(|i| start.saturating_add(i))
+			.ok_or(end)
+	}
+
+	/// Inserts a stake like [OrderedSet::try_insert_replace] but only
+	/// requires a logarithmic number of comparisons.
+	///
+	/// The caller has to ensure that the owner of `stake` is not a member of
+	/// the set with a different amount.
+	///
+	/// Returns
+	/// * Ok(Some(old_element)) if the new stake was added and the lowest stake
+	///   had to be removed.
+	/// * Ok(None) if the stake was added without removing a stake.
+	/// * Err(true) if the set is full and the new stake has a lower rank than
+	///   the lowest stake in the set.
+	/// * Err(false) if the stake is already in the set.
+	pub fn try_insert_replace_stake(&mut self, stake: Stake<A, B>) -> Result<Option<Stake<A, B>>, bool> {
+		let max_len: usize = S::get().saturated_into();
+		let loc = match self.binary_search_stake(&stake.owner, stake.amount) {
+			Ok(_) => return Err(false),
+			Err(loc) if loc >= max_len => return Err(true),
+			Err(loc) => loc,
+		};
+		let old = if self.len() >= max_len {
+			// the last-in-least-priority-rule is ensured by inserting behind equal stakes
+			Some(self.0.remove(self.len().saturating_sub(1)))
+		} else {
+			None
+		};
+		// cannot fail since we either removed an element or the set was not full
+		self.0.try_insert(loc, stake).map_err(|_| true)?;
+		Ok(old)
+	}
+
+	/// Sets the amount of the stake at `index` and moves the stake to its new
+	/// position, which is found by a binary search. Stakes with equal amounts
+	/// keep their relative order as they would when using a stable sort.
+	///
+	/// Returns the new index of the stake or None if `index` is out of bounds.
+	pub fn update_stake_at(&mut self, index: usize, amount: B) -> Option<usize> {
+		let slice = &mut self.0[..];
+		let old_amount = slice.get(index)?.amount;
+		let new_index = match amount.cmp(&old_amount) {
+			Ordering::Greater => {
+				// move in front of all lower stakes but behind all equal stakes
+				let new_index = slice[..index].partition_point(|stake| stake.amount >= amount);
+				slice[new_index..=index].rotate_right(1);
+				new_index
+			}
+			Ordering::Less => {
+				// move behind all higher stakes but in front of all equal stakes
+				let next = index.saturating_add(1);
+				let new_index = index.saturating_add(slice[next..].partition_point(|stake| stake.amount > amount));
+				slice[index..=new_index].rotate_left(1);
+				new_index
+			}
+			Ordering::Equal => index,
+		};
+		slice[new_index].amount = amount;
+		Some(new_index)
+	}
+
+	/// Updates the amount of the stake of `owner` from `old_amount` to
+	/// `new_amount`, see [OrderedSet::update_stake_at].
+	///
+	/// Returns the old and the new index of the stake or None if the owner
+	/// does not have a stake of `old_amount` in the set.
+	pub fn update_stake(&mut self, owner: &A, old_amount: B, new_amount: B) -> Option<(usize, usize)> {
+		let index = self.binary_search_stake(owner, old_amount).ok()?;
+		self.update_stake_at(index, new_amount).map(|new_index| (index, new_index))
+	}
+
+	/// Removes the stake of `owner` which is expected to amount to `amount`.
+	///
+	/// Returns the removed stake or None if the owner does not have a stake of
+	/// `amount` in the set.
+	pub fn remove_stake(&mut self, owner: &A, amount: B) -> Option<Stake<A, B>> {
+		let index = self.binary_search_stake(owner, amount).ok()?;
+		Some(self.0.remove(index))
+	}
+
+	/// Returns the index of the stake of `owner` by iterating over the set.
+	///
+	/// Only meant for small sets like the delegations of a delegator. Prefer
+	/// [OrderedSet::binary_search_stake] if the amount is known.
+	pub fn position_of(&self, owner: &A) -> Option<usize> {
+		self.0.iter().position(|stake| stake.owner == *owner)
+	}
+}
+
 impl<T: Ord + Clone, S: Get<u32>> From<BoundedVec<T, S>> for OrderedSet<T, S> {
 	fn from(bv: BoundedVec<T, S>) -> Self {
 		Self::from(bv)
@@ -482,6 +598,131 @@ mod tests {
 		assert_eq!(set.linear_search(&StakeOf::<Test> { owner: 2, amount: 59 }), Err(6));
 	}
 
+	#[test]
+	fn binary_search_stake() {
+		let set: OrderedSet<StakeOf<Test>, Eight> = OrderedSet::from(
+			vec![
+				StakeOf::<Test> { owner: 1, amount: 100 },
+				StakeOf::<Test> { owner: 3, amount: 90 },
+				StakeOf::<Test> { owner: 5, amount: 80 },
+				StakeOf::<Test> { owner: 7, amount: 70 },
+				StakeOf::<Test> { owner: 8, amount: 70 },
+				StakeOf::<Test> { owner: 9, amount: 60 },
+			]
+			.try_into()
+			.unwrap(),
+		);
+		assert_eq!(set.binary_search_stake(&1, 100), Ok(0));
+		assert_eq!(set.binary_search_stake(&7, 70), Ok(3));
+		assert_eq!(set.binary_search_stake(&8, 70), Ok(4));
+		assert_eq!(set.binary_search_stake(&9, 60), Ok(5));
+		// wrong amount
+		assert_eq!(set.binary_search_stake(&7, 100), Err(1));
+		assert_eq!(set.binary_search_stake(&7, 50), Err(6));
+		assert_eq!(set.binary_search_stake(&2, 100), Err(1));
+		assert_eq!(set.binary_search_stake(&2, 90), Err(2));
+		assert_eq!(set.binary_search_stake(&2, 70), Err(5));
+		assert_eq!(set.binary_search_stake(&2, 65), Err(5));
+		assert_eq!(set.binary_search_stake(&2, 60), Err(6));
+		assert_eq!(set.binary_search_stake(&2, 59), Err(6));
+	}
+
+	#[test]
+	fn try_insert_replace_stake_logarithmic() {
+		let mut set: OrderedSet<StakeOf<Test>, Five> = OrderedSet::from(
+			vec![
+				StakeOf::<Test> { owner: 1, amount: 100 },
+				StakeOf::<Test> { owner: 3, amount: 90 },
+				StakeOf::<Test> { owner: 5, amount: 70 },
+			]
+			.try_into()
+			.unwrap(),
+		);
+		assert_eq!(
+			set.try_insert_replace_stake(StakeOf::<Test> { owner: 3, amount: 90 }),
+			Err(false)
+		);
+		assert_eq!(
+			set.try_insert_replace_stake(StakeOf::<Test> { owner: 2, amount: 90 }),
+			Ok(None)
+		);
+		assert_eq!(
+			set.try_insert_replace_stake(StakeOf::<Test> { owner: 4, amount: 70 }),
+			Ok(None)
+		);
+		// set is full and stake is not higher than the lowest one
+		assert_eq!(
+			set.try_insert_replace_stake(StakeOf::<Test> { owner: 6, amount: 70 }),
+			Err(true)
+		);
+		assert_eq!(
+			set.try_insert_replace_stake(StakeOf::<Test> { owner: 6, amount: 80 }),
+			Ok(Some(StakeOf::<Test> { owner: 4, amount: 70 }))
+		);
+		assert_eq!(
+			set.into_bounded_vec().into_inner(),
+			vec![
+				StakeOf::<Test> { owner: 1, amount: 100 },
+				StakeOf::<Test> { owner: 3, amount: 90 },
+				StakeOf::<Test> { owner: 2, amount: 90 },
+				StakeOf::<Test> { owner: 6, amount: 80 },
+				StakeOf::<Test> { owner: 5, amount: 70 },
+			]
+		);
+	}
+
+	#[test]
+	fn update_stake() {
+		let mut set: OrderedSet<StakeOf<Test>, Eight> = OrderedSet::from(
+			vec![
+				StakeOf::<Test> { owner: 1, amount: 100 },
+				StakeOf::<Test> { owner: 3, amount: 90 },
+				StakeOf::<Test> { owner: 5, amount: 80 },
+				StakeOf::<Test> { owner: 7, amount: 70 },
+				StakeOf::<Test> { owner: 8, amount: 70 },
+				StakeOf::<Test> { owner: 9, amount: 60 },
+			]
+			.try_into()
+			.unwrap(),
+		);
+		// wrong old amount
+		assert_eq!(set.update_stake(&7, 60, 100), None);
+		// increased stake is placed behind equal stakes
+		assert_eq!(set.update_stake(&8, 70, 90), Some((4, 2)));
+		// decreased stake is placed in front of equal stakes
+		assert_eq!(set.update_stake(&1, 100, 70), Some((0, 3)));
+		assert_eq!(set.update_stake(&9, 60, 60), Some((5, 5)));
+		assert_eq!(set.update_stake_at(6, 10), None);
+		assert_eq!(
+			set.into_bounded_vec().into_inner(),
+			vec![
+				StakeOf::<Test> { owner: 3, amount: 90 },
+				StakeOf::<Test> { owner: 8, amount: 90 },
+				StakeOf::<Test> { owner: 5, amount: 80 },
+				StakeOf::<Test> { owner: 1, amount: 70 },
+				StakeOf::<Test> { owner: 7, amount: 70 },
+				StakeOf::<Test> { owner: 9, amount: 60 },
+			]
+		);
+	}
+
+	#[test]
+	fn remove_stake() {
+		let mut set: OrderedSet<StakeOf<Test>, Eight> = OrderedSet::from(
+			vec![
+				StakeOf::<Test> { owner: 1, amount: 100 },
+				StakeOf::<Test> { owner: 7, amount: 70 },
+				StakeOf::<Test> { owner: 8, amount: 70 },
+			]
+			.try_into()
+			.unwrap(),
+		);
+		assert_eq!(set.remove_stake(&8, 100), None);
+		assert_eq!(set.remove_stake(&8, 70), Some(StakeOf::<Test> { owner: 8, amount: 70 }));
+		assert_eq!(set.position_of(&7), Some(1));
+		assert_eq!(set.position_of(&8), None);
+	}
+
 	#[test]
 	fn upsert_set() {
 		let mut set: OrderedSet<StakeOf<Test>, Eight> = OrderedSet::from(
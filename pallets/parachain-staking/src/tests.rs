@@ -21,23 +21,39 @@
 use std::{convert::TryInto, iter};
 
 use frame_support::{
-	assert_noop, assert_ok, storage::bounded_btree_map::BoundedBTreeMap, traits::EstimateNextSessionRotation,
+	assert_noop, assert_ok,
+	storage::bounded_btree_map::BoundedBTreeMap,
+	traits::{
+		DisabledValidators, EstimateNextSessionRotation, GetStorageVersion, LockableCurrency, OnRuntimeUpgrade,
+		StorageVersion, WithdrawReasons,
+	},
 	BoundedVec,
 };
 use pallet_balances::{BalanceLock, Error as BalancesError, Reasons};
 use pallet_session::{SessionManager, ShouldEndSession};
-use sp_runtime::{traits::Zero, Perbill, Permill, Perquintill, SaturatedConversion};
+use parachain_staking_runtime_api::{
+	CandidateInfo, CollatorRoundStats, DelegationInfo, RewardEstimate, RoundInfo as ApiRoundInfo, RoundStats,
+	StakerInfo, UnstakingInfo,
+};
+use sp_runtime::{
+	traits::{One, Zero},
+	Perbill, Percent, Permill, Perquintill, SaturatedConversion,
+};
 
 use crate::{
+	inflation::{InflationInfo, InflationRates},
 	mock::{
-		almost_equal, events, last_event, roll_to, AccountId, Balance, Balances, BlockNumber, Event as MetaEvent,
-		ExtBuilder, Origin, Session, StakePallet, System, Test, BLOCKS_PER_ROUND, DECIMALS, TREASURY_ACC,
+		almost_equal, events, last_event, roll_to, set_session_keys, AccountId, Balance, Balances, BlockNumber,
+		DelegatorSlash, Event as MetaEvent, ExtBuilder, OfflineThreshold, Origin, RewardInvulnerables, RewardRemainder,
+		RoundStatsRetention, Session, StakePallet, System, Test, UnverifiedIdentities, BLOCKS_PER_ROUND, DECIMALS,
+		TREASURY_ACC,
 	},
 	set::OrderedSet,
 	types::{
-		BalanceOf, Candidate, CandidateStatus, DelegationCounter, Delegator, RoundInfo, Stake, StakeOf, TotalStake,
+		BalanceOf, Candidate, CandidateStatus, CollatorStats, DelegationCounter, Delegator, LoyaltyInfo,
+		PausedOperations, RemainderDestination, RewardSplit, RoundInfo, Stake, StakeOf, TotalStake,
 	},
-	CandidatePool, Config, Error, Event, STAKING_ID,
+	try_state, CandidatePool, Config, DelegatorState, Error, Event, TopCandidates, TotalCollatorStake, STAKING_ID,
 };
 
 #[test]
@@ -57,8 +73,7 @@ fn should_select_collators_genesis_session() {
 			(11, 20),
 		])
 		.with_collators(vec![(1, 20), (2, 20)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(
 				StakePallet::new_session(0)
 					.expect("first session must return new collators")
@@ -90,8 +105,7 @@ fn genesis() {
 		])
 		.with_collators(vec![(1, 500), (2, 200)])
 		.with_delegators(vec![(3, 1, 100), (4, 1, 100), (5, 2, 100), (6, 2, 100)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert!(System::events().is_empty());
 
 			// Collators
@@ -207,8 +221,7 @@ fn genesis() {
 		])
 		.with_collators(vec![(1, 20), (2, 20), (3, 20), (4, 20), (5, 10)])
 		.with_delegators(vec![(6, 1, 10), (7, 1, 10), (8, 2, 10), (9, 2, 10), (10, 1, 10)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert!(System::events().is_empty());
 			assert_eq!(CandidatePool::<Test>::count(), 5);
 
@@ -275,8 +288,7 @@ fn join_collator_candidates() {
 		])
 		.with_collators(vec![(1, 500), (2, 200)])
 		.with_delegators(vec![(3, 1, 100), (4, 1, 100), (5, 2, 100), (6, 2, 100)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(CandidatePool::<Test>::count(), 2);
 			assert_eq!(
 				StakePallet::total_collator_stake(),
@@ -293,14 +305,17 @@ fn join_collator_candidates() {
 				StakePallet::join_delegators(Origin::signed(1), 1, 11u128,),
 				Error::<Test>::CandidateExists
 			);
+			set_session_keys(3);
 			assert_noop!(
 				StakePallet::join_candidates(Origin::signed(3), 11u128,),
 				Error::<Test>::DelegatorExists
 			);
+			set_session_keys(7);
 			assert_noop!(
 				StakePallet::join_candidates(Origin::signed(7), 9u128,),
 				Error::<Test>::ValStakeBelowMin
 			);
+			set_session_keys(8);
 			assert_noop!(
 				StakePallet::join_candidates(Origin::signed(8), 10u128,),
 				BalancesError::<Test>::InsufficientBalance
@@ -317,6 +332,7 @@ fn join_collator_candidates() {
 			);
 
 			// MaxCollatorCandidateStake
+			set_session_keys(10);
 			assert_noop!(
 				StakePallet::join_candidates(Origin::signed(10), 161_000_000 * DECIMALS),
 				Error::<Test>::ValStakeAboveMax
@@ -350,8 +366,7 @@ fn collator_exit_executes_after_delay() {
 		])
 		.with_collators(vec![(1, 500), (2, 200), (7, 100)])
 		.with_delegators(vec![(3, 1, 100), (4, 1, 100), (5, 2, 100), (6, 2, 100)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(CandidatePool::<Test>::count(), 3);
 			assert_eq!(
 				StakePallet::total_collator_stake(),
@@ -429,8 +444,7 @@ fn collator_selection_chooses_top_candidates() {
 			(9, 33),
 		])
 		.with_collators(vec![(1, 100), (2, 90), (3, 80), (4, 70), (5, 60), (6, 50)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(StakePallet::selected_candidates().into_inner(), vec![1, 2]);
 			assert_eq!(
 				StakePallet::total_collator_stake(),
@@ -508,8 +522,7 @@ fn exit_queue_with_events() {
 		])
 		.with_collators(vec![(1, 100), (2, 90), (3, 80), (4, 70), (5, 60), (6, 50)])
 		.with_inflation(100, 15, 40, 10, BLOCKS_PER_ROUND)
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(CandidatePool::<Test>::count(), 6);
 			assert_eq!(StakePallet::selected_candidates().into_inner(), vec![1, 2]);
 			assert_ok!(StakePallet::set_max_selected_candidates(Origin::root(), 5));
@@ -614,8 +627,7 @@ fn execute_leave_candidates_with_delay() {
 		])
 		.with_delegators(vec![(11, 1, 110), (12, 1, 120), (13, 2, 130), (14, 2, 140)])
 		.with_inflation(100, 15, 40, 10, BLOCKS_PER_ROUND)
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(CandidatePool::<Test>::count(), 10);
 			assert_eq!(
 				StakePallet::total_collator_stake(),
@@ -931,8 +943,7 @@ fn multiple_delegations() {
 		.with_collators(vec![(1, 20), (2, 20), (3, 20), (4, 20), (5, 10)])
 		.with_delegators(vec![(6, 1, 10), (7, 1, 10), (8, 2, 10), (9, 2, 10), (10, 1, 10)])
 		.set_blocks_per_round(5)
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_ok!(StakePallet::set_max_selected_candidates(Origin::root(), 5));
 			roll_to(8, vec![]);
 			// chooses top MaxSelectedCandidates (5), in order
@@ -1092,8 +1103,7 @@ fn should_update_total_stake() {
 		.with_collators(vec![(1, 20), (2, 20), (3, 20), (4, 20), (5, 10)])
 		.with_delegators(vec![(7, 1, 10), (8, 2, 10), (9, 2, 10)])
 		.set_blocks_per_round(5)
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			let mut old_stake = StakePallet::total_collator_stake();
 			assert_eq!(
 				old_stake,
@@ -1238,8 +1248,7 @@ fn collators_bond() {
 		.with_collators(vec![(1, 20), (2, 20), (3, 20), (4, 20), (5, 10)])
 		.with_delegators(vec![(6, 1, 10), (7, 1, 10), (8, 2, 10), (9, 2, 10), (10, 1, 10)])
 		.set_blocks_per_round(5)
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			roll_to(4, vec![]);
 			assert_noop!(
 				StakePallet::candidate_stake_more(Origin::signed(6), 50),
@@ -1327,8 +1336,7 @@ fn author_notarization() {
                          (4, 1, 10),
                          (5, 2, 30),
         ])
-        .build()
-        .execute_with(|| {
+        .build_and_execute_with_sanity_tests(|| {
             let collator_one_state = StakePallet::candidate_pool(&1).expect("`1` have not joined the collators");
             let collator_two_state = StakePallet::candidate_pool(&2).expect("`2` have not joined the collators");
 
@@ -1348,6 +1356,39 @@ fn author_notarization() {
             <crate::RewardPerBlock<Test>>::put(20);
             roll_to(2, vec![None, Some(1)]);
 
+            // the rounded shares of the stakers exceed the block reward
+            assert!(events().contains(&Event::BlockRewarded(
+                1,
+                RewardSplit {
+                    collator: 7,
+                    delegators: 14,
+                    loyalty_bonus: 0,
+                    skipped: 0,
+                    dust: 0,
+                    excess: 1,
+                }
+            )));
+
+            // rewards are accrued but not paid out
+            assert_eq!(StakePallet::pending_rewards(&1), 7);
+            assert_eq!(StakePallet::pending_rewards(&2), 0);
+            assert_eq!(StakePallet::pending_rewards(&3), 7);
+            assert_eq!(StakePallet::pending_rewards(&4), 7);
+            assert_eq!(StakePallet::pending_rewards(&5), 0);
+            assert_eq!(Balances::usable_balance(&1), collator_one);
+
+            assert_ok!(StakePallet::claim_rewards(Origin::signed(1)));
+            assert_noop!(
+                StakePallet::claim_rewards(Origin::signed(2)),
+                Error::<Test>::RewardsNotFound
+            );
+            assert_ok!(StakePallet::claim_rewards(Origin::signed(3)));
+            assert_ok!(StakePallet::claim_rewards(Origin::signed(4)));
+            assert_noop!(
+                StakePallet::claim_rewards(Origin::signed(5)),
+                Error::<Test>::RewardsNotFound
+            );
+
             assert_eq!(Balances::usable_balance(&1), collator_one + 7);
             assert_eq!(Balances::usable_balance(&2), collator_two);
             assert_eq!(Balances::usable_balance(&3), delegator_three + 7);
@@ -1363,6 +1404,12 @@ fn author_notarization() {
             <crate::RewardPerBlock<Test>>::put(40);
             roll_to(3, vec![None, None, Some(2)]);
 
+            assert_eq!(StakePallet::pending_rewards(&1), 0);
+            assert_eq!(StakePallet::pending_rewards(&3), 0);
+            assert_eq!(StakePallet::pending_rewards(&4), 0);
+            assert_ok!(StakePallet::claim_rewards(Origin::signed(2)));
+            assert_ok!(StakePallet::claim_rewards(Origin::signed(5)));
+
             assert_eq!(Balances::usable_balance(&1), collator_one);
             assert_eq!(Balances::usable_balance(&2), collator_two + 16);
             assert_eq!(Balances::usable_balance(&3), delegator_three);
@@ -1392,8 +1439,7 @@ fn delegators_bond() {
 		.with_collators(vec![(1, 20), (2, 20), (3, 20), (4, 20), (5, 10)])
 		.with_delegators(vec![(6, 1, 10), (7, 1, 10), (8, 2, 10), (9, 2, 10)])
 		.set_blocks_per_round(5)
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			roll_to(4, vec![]);
 			assert_noop!(
 				StakePallet::join_delegators(Origin::signed(6), 2, 50),
@@ -1479,8 +1525,7 @@ fn revoke_delegation_or_leave_delegators() {
 		.with_collators(vec![(1, 20), (2, 20), (3, 20), (4, 20), (5, 10)])
 		.with_delegators(vec![(6, 1, 10), (7, 1, 10), (8, 2, 10), (9, 2, 10), (10, 1, 10)])
 		.set_blocks_per_round(5)
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			roll_to(4, vec![]);
 			assert_noop!(
 				StakePallet::revoke_delegation(Origin::signed(1), 2),
@@ -1528,8 +1573,7 @@ fn round_transitions() {
 		.with_collators(vec![(1, 20)])
 		.with_delegators(vec![(2, 1, 10), (3, 1, 10)])
 		.with_inflation(col_max, col_rewards, d_max, d_rewards, 5)
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			roll_to(5, vec![]);
 			let init = vec![Event::NewRound(5, 1)];
 			assert_eq!(events(), init);
@@ -1557,8 +1601,7 @@ fn round_transitions() {
 		.with_collators(vec![(1, 20)])
 		.with_delegators(vec![(2, 1, 10), (3, 1, 10)])
 		.with_inflation(col_max, col_rewards, d_max, d_rewards, 5)
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			// Default round every 5 blocks, but MinBlocksPerRound is 3 and we set it to min
 			// 3 blocks
 			roll_to(6, vec![]);
@@ -1589,8 +1632,7 @@ fn round_transitions() {
 		.with_collators(vec![(1, 20)])
 		.with_delegators(vec![(2, 1, 10), (3, 1, 10)])
 		.with_inflation(col_max, col_rewards, d_max, d_rewards, 5)
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			roll_to(7, vec![]);
 			// chooses top MaxSelectedCandidates (5), in order
 			let init = vec![Event::NewRound(5, 1)];
@@ -1626,172 +1668,2630 @@ fn coinbase_rewards_few_blocks_detailed_check() {
 			(5, 2, 16_000_000 * DECIMALS),
 		])
 		.with_inflation(10, 15, 40, 15, 5)
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			let total_issuance = <Test as Config>::Currency::total_issuance();
 			assert_eq!(total_issuance, 160_000_000 * DECIMALS);
 
-            let reward_per_block = 500 * DECIMALS / DAY as u128;
-            <crate::RewardPerBlock<Test>>::put(reward_per_block);
-
-			// compute rewards
-			let c_staking_rate = Perquintill::from_rational(16_000_000 * DECIMALS, total_issuance);
-            let d_staking_rate = Perquintill::from_rational(64_000_000 * DECIMALS, total_issuance);
+			let reward_per_block = 500 * DECIMALS / DAY as u128;
+			<crate::RewardPerBlock<Test>>::put(reward_per_block);
+
+			// compute rewards for a single block
+			let share = |stake: Balance, total: Balance| Perquintill::from_rational(stake, total) * reward_per_block;
+			let reward_1 = share(8_000_000 * DECIMALS, 56_000_000 * DECIMALS);
+			let reward_2 = share(8_000_000 * DECIMALS, 24_000_000 * DECIMALS);
+			let reward_3 = share(32_000_000 * DECIMALS, 56_000_000 * DECIMALS);
+			let reward_4 = share(16_000_000 * DECIMALS, 56_000_000 * DECIMALS);
+			let reward_5 = share(16_000_000 * DECIMALS, 24_000_000 * DECIMALS);
+			// the block reward is split among the stakers of the author
+			assert!(almost_equal(
+				reward_1 + reward_3 + reward_4,
+				reward_per_block,
+				Perbill::from_parts(1)
+			));
+			assert!(almost_equal(reward_2 + reward_5, reward_per_block, Perbill::from_parts(1)));
 
 			// set 1 to be author for blocks 1-3, then 2 for blocks 4-5
 			let authors: Vec<Option<AccountId>> =
 				vec![None, Some(1u64), Some(1u64), Some(1u64), Some(2u64), Some(2u64)];
-			// let d_rewards: Balance = 3 * 2469135802453333 / 2;
 			let user_1 = Balances::usable_balance(&1);
 			let user_2 = Balances::usable_balance(&2);
 			let user_3 = Balances::usable_balance(&3);
 			let user_4 = Balances::usable_balance(&4);
 			let user_5 = Balances::usable_balance(&5);
 
-            let reward_1 = 9915126512000;
-            let reward_2 = 23135295200000;
-            let reward_3 = 39660506048000;
-            let reward_4 = 19830253024000;
-            let reward_5 = 46270590400000;
+			// 1 is block author for 1st block
+			roll_to(2, authors.clone());
+			assert_eq!(StakePallet::pending_rewards(&1), reward_1);
+			assert_eq!(StakePallet::pending_rewards(&2), 0);
+			assert_eq!(StakePallet::pending_rewards(&3), reward_3);
+			assert_eq!(StakePallet::pending_rewards(&4), reward_4);
+			assert_eq!(StakePallet::pending_rewards(&5), 0);
+
+			// 1 is block author for 2nd block
+			roll_to(3, authors.clone());
+			assert!(almost_equal(StakePallet::pending_rewards(&1), reward_1 * 2, Perbill::from_parts(1)));
+			assert!(almost_equal(StakePallet::pending_rewards(&3), reward_3 * 2, Perbill::from_parts(1)));
+			assert!(almost_equal(StakePallet::pending_rewards(&4), reward_4 * 2, Perbill::from_parts(1)));
+
+			// 1 is block author for 3rd block
+			roll_to(4, authors.clone());
+			assert!(almost_equal(StakePallet::pending_rewards(&1), reward_1 * 3, Perbill::from_parts(1)));
+			assert_eq!(StakePallet::pending_rewards(&2), 0);
+			assert!(almost_equal(StakePallet::pending_rewards(&3), reward_3 * 3, Perbill::from_parts(1)));
+			assert!(almost_equal(StakePallet::pending_rewards(&4), reward_4 * 3, Perbill::from_parts(1)));
+			assert_eq!(StakePallet::pending_rewards(&5), 0);
+
+			// 2 is block author for 4th block
+			roll_to(5, authors.clone());
+			assert_eq!(StakePallet::pending_rewards(&2), reward_2);
+			assert_eq!(StakePallet::pending_rewards(&5), reward_5);
+			assert_ok!(StakePallet::revoke_delegation(Origin::signed(5), 2));
+			// the rewards of the revoked delegation have been settled
+			assert_eq!(StakePallet::rewards(&5), reward_5);
+
+			// 2 is block author for 5th block and the only staker left
+			roll_to(6, authors);
+			assert_eq!(StakePallet::pending_rewards(&2), reward_2 + reward_per_block);
+			// should not receive rewards due to revoked delegation
+			assert_eq!(StakePallet::pending_rewards(&5), reward_5);
+
+			// nothing has been paid out so far
+			assert_eq!(Balances::usable_balance(&1), user_1);
+			assert_eq!(Balances::usable_balance(&2), user_2);
+			assert_eq!(Balances::usable_balance(&3), user_3);
+			assert_eq!(Balances::usable_balance(&4), user_4);
+
+			let pending: Vec<Balance> = (1u64..=5).map(|acc| StakePallet::pending_rewards(&acc)).collect();
+			for acc in 1u64..=5 {
+				assert_ok!(StakePallet::claim_rewards(Origin::signed(acc)));
+				assert!(StakePallet::rewards(&acc).is_zero());
+				assert!(StakePallet::pending_rewards(&acc).is_zero());
+			}
+			assert_eq!(Balances::usable_balance(&1), user_1 + pending[0]);
+			assert_eq!(Balances::usable_balance(&2), user_2 + pending[1]);
+			assert_eq!(Balances::usable_balance(&3), user_3 + pending[2]);
+			assert_eq!(Balances::usable_balance(&4), user_4 + pending[3]);
+			// the revoked delegation is still locked
+			assert_eq!(Balances::free_balance(&5), 20_000_000 * DECIMALS + pending[4]);
+			assert_eq!(
+				<Test as Config>::Currency::total_issuance(),
+				total_issuance + pending.iter().sum::<Balance>()
+			);
+		});
+}
+
+#[test]
+fn delegator_should_not_receive_rewards_after_revoking() {
+	// test edge case of 1 delegator
+	ExtBuilder::default()
+		.with_balances(vec![(1, 10_000_000 * DECIMALS), (2, 10_000_000 * DECIMALS)])
+		.with_collators(vec![(1, 10_000_000 * DECIMALS)])
+		.with_delegators(vec![(2, 1, 10_000_000 * DECIMALS)])
+		.with_inflation(10, 15, 40, 15, 5)
+		.build_and_execute_with_sanity_tests(|| {
+            <crate::RewardPerBlock<Test>>::put(3_000_000 * DECIMALS);
+
+			assert_ok!(StakePallet::revoke_delegation(Origin::signed(2), 1));
+			let authors: Vec<Option<AccountId>> = (1u64..100u64).map(|_| Some(1u64)).collect();
+			assert_eq!(Balances::usable_balance(&1), Balance::zero());
+			assert_eq!(Balances::usable_balance(&2), Balance::zero());
+			roll_to(100, authors);
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(1)));
+			assert!(Balances::usable_balance(&1) > Balance::zero());
+			assert_noop!(
+				StakePallet::claim_rewards(Origin::signed(2)),
+				Error::<Test>::RewardsNotFound
+			);
+			assert_ok!(StakePallet::unlock_unstaked(Origin::signed(2), 2));
+			assert_eq!(Balances::usable_balance(&2), 10_000_000 * DECIMALS);
+		});
+
+	ExtBuilder::default()
+		.with_balances(vec![
+			(1, 10_000_000 * DECIMALS),
+			(2, 10_000_000 * DECIMALS),
+			(3, 10_000_000 * DECIMALS),
+		])
+		.with_collators(vec![(1, 10_000_000 * DECIMALS)])
+		.with_delegators(vec![(2, 1, 10_000_000 * DECIMALS), (3, 1, 10_000_000 * DECIMALS)])
+		.with_inflation(10, 15, 40, 15, 5)
+		.build_and_execute_with_sanity_tests(|| {
+            <crate::RewardPerBlock<Test>>::put(3_000_000 * DECIMALS);
+
+			assert_ok!(StakePallet::revoke_delegation(Origin::signed(3), 1));
+			let authors: Vec<Option<AccountId>> = (1u64..100u64).map(|_| Some(1u64)).collect();
+			assert_eq!(Balances::usable_balance(&1), Balance::zero());
+			assert_eq!(Balances::usable_balance(&2), Balance::zero());
+			assert_eq!(Balances::usable_balance(&3), Balance::zero());
+			roll_to(100, authors);
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(1)));
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(2)));
+			assert!(Balances::usable_balance(&1) > Balance::zero());
+			assert!(Balances::usable_balance(&2) > Balance::zero());
+			assert_noop!(
+				StakePallet::claim_rewards(Origin::signed(3)),
+				Error::<Test>::RewardsNotFound
+			);
+			assert_ok!(StakePallet::unlock_unstaked(Origin::signed(3), 3));
+			assert_eq!(Balances::usable_balance(&3), 10_000_000 * DECIMALS);
+		});
+}
+
+// Could only occur if we increase MinDelegatorStakeOf::<Test>via runtime
+// upgrade and don't migrate delegators which fall below minimum
+#[test]
+fn should_not_reward_delegators_below_min_stake() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 10 * DECIMALS), (2, 10 * DECIMALS), (3, 10 * DECIMALS), (4, 5)])
+		.with_collators(vec![(1, 10 * DECIMALS), (2, 10 * DECIMALS)])
+		.with_delegators(vec![(3, 2, 10 * DECIMALS)])
+		.with_inflation(10, 15, 40, 15, 5)
+		.build()
+		.execute_with(|| {
+			// impossible but lets assume it happened
+			let mut state = StakePallet::candidate_pool(&1).expect("CollatorState cannot be missing");
+			let delegator_stake_below_min = <Test as Config>::MinDelegatorStake::get() - 1;
+			state.stake += delegator_stake_below_min;
+			state.total += delegator_stake_below_min;
+			let impossible_bond = StakeOf::<Test> {
+				owner: 4u64,
+				amount: delegator_stake_below_min,
+			};
+			assert_eq!(state.delegators.try_insert(impossible_bond), Ok(true));
+			<crate::CandidatePool<Test>>::insert(&1u64, state);
+
+			let authors: Vec<Option<AccountId>> = vec![Some(1u64), Some(1u64), Some(1u64), Some(1u64)];
+			assert_eq!(Balances::usable_balance(&1), Balance::zero());
+			assert_eq!(Balances::usable_balance(&2), Balance::zero());
+			assert_eq!(Balances::usable_balance(&3), Balance::zero());
+			assert_eq!(Balances::usable_balance(&4), 5);
+
+			// should only reward 1
+			roll_to(4, authors);
+			assert!(StakePallet::pending_rewards(&4).is_zero());
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(1)));
+			assert_noop!(
+				StakePallet::claim_rewards(Origin::signed(4)),
+				Error::<Test>::RewardsNotFound
+			);
+			assert!(Balances::usable_balance(&1) > Balance::zero());
+			assert_eq!(Balances::usable_balance(&4), 5);
+			assert_eq!(Balances::usable_balance(&2), Balance::zero());
+			assert_eq!(Balances::usable_balance(&3), Balance::zero());
+		});
+}
+
+#[test]
+fn rewards_are_settled_on_stake_changes() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100)])
+		.with_collators(vec![(1, 10), (2, 10)])
+		.with_delegators(vec![(3, 1, 10)])
+		.build_and_execute_with_sanity_tests(|| {
+			<crate::RewardPerBlock<Test>>::put(20);
+
+			// 1 and 3 share the block reward equally
+			roll_to(2, vec![None, Some(1)]);
+			assert_eq!(StakePallet::collator_block_rewards(&1), 20);
+			assert_eq!(StakePallet::pending_rewards(&1), 10);
+			assert_eq!(StakePallet::pending_rewards(&3), 10);
+			assert!(StakePallet::rewards(&1).is_zero());
+
+			// new delegation settles the rewards of all stakers of the collator
+			assert_ok!(StakePallet::join_delegators(Origin::signed(4), 1, 20));
+			assert_eq!(StakePallet::rewards(&1), 10);
+			assert_eq!(StakePallet::rewards(&3), 10);
+			assert!(StakePallet::pending_rewards(&4).is_zero());
+			assert_eq!(StakePallet::settled_block_rewards(&4, &1), 20);
+
+			// 4 is only rewarded from the next round on
+			roll_to(3, vec![None, None, Some(1)]);
+			assert_eq!(StakePallet::pending_rewards(&1), 20);
+			assert_eq!(StakePallet::pending_rewards(&3), 20);
+			assert!(StakePallet::pending_rewards(&4).is_zero());
+			roll_to(6, vec![None, None, None, None, None, Some(1)]);
+			assert_eq!(StakePallet::round().current, 1);
+			assert_eq!(StakePallet::rewards(&1), 20);
+			assert_eq!(StakePallet::rewards(&3), 20);
+			assert_eq!(StakePallet::pending_rewards(&1), 25);
+			assert_eq!(StakePallet::pending_rewards(&3), 25);
+			assert_eq!(StakePallet::pending_rewards(&4), 10);
+
+			// increasing a stake settles the rewards with the previous stake
+			assert_ok!(StakePallet::candidate_stake_more(Origin::signed(1), 20));
+			assert_eq!(StakePallet::rewards(&1), 25);
+			assert_eq!(StakePallet::rewards(&4), 10);
+			roll_to(7, vec![None, None, None, None, None, None, Some(1)]);
+			assert_eq!(StakePallet::pending_rewards(&1), 25 + 5);
+			assert_eq!(StakePallet::pending_rewards(&3), 25 + 5);
+			assert_eq!(StakePallet::pending_rewards(&4), 10 + 10);
+			let mut authors = vec![None; 10];
+			authors.push(Some(1));
+			roll_to(11, authors);
+			assert_eq!(StakePallet::round().current, 2);
+			assert_eq!(StakePallet::pending_rewards(&1), 30 + 10);
+			assert_eq!(StakePallet::pending_rewards(&3), 30 + 3);
+			assert_eq!(StakePallet::pending_rewards(&4), 20 + 7);
+
+			// leaving delegators keep their rewards
+			assert_ok!(StakePallet::leave_delegators(Origin::signed(3)));
+			assert_eq!(StakePallet::rewards(&3), 33);
+			assert_eq!(StakePallet::settled_block_rewards(&3, &1), 0);
+			let mut authors = vec![None; 11];
+			authors.push(Some(1));
+			roll_to(12, authors);
+			assert_eq!(StakePallet::pending_rewards(&3), 33);
+			// the removed stake is not rewarded anymore
+			assert_eq!(StakePallet::pending_rewards(&1), 40 + 12);
+			assert_eq!(StakePallet::pending_rewards(&4), 27 + 8);
+
+			// the accrued rewards can be claimed by anyone on behalf of the staker
+			let balance = Balances::free_balance(&3);
+			assert_ok!(StakePallet::claim_rewards_for(Origin::signed(5), 3));
+			assert_eq!(Balances::free_balance(&3), balance + 33);
+			assert!(StakePallet::rewards(&3).is_zero());
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::Rewarded(3, 33)));
+			assert_noop!(
+				StakePallet::claim_rewards_for(Origin::signed(5), 3),
+				Error::<Test>::RewardsNotFound
+			);
+		});
+}
+
+#[test]
+fn rewards_are_based_on_stake_snapshot() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100)])
+		.with_collators(vec![(1, 10), (2, 10), (5, 10)])
+		.with_delegators(vec![(3, 1, 10), (4, 1, 20)])
+		.build_and_execute_with_sanity_tests(|| {
+			<crate::RewardPerBlock<Test>>::put(60);
+			assert!(StakePallet::stake_snapshot(1).is_none());
+
+			// the snapshot is taken at the first block of the collator in a round
+			roll_to(2, vec![None, Some(1)]);
+			let snapshot = StakePallet::stake_snapshot(1).unwrap();
+			assert_eq!(snapshot.round, 0);
+			assert_eq!(snapshot.stake, 10);
+			assert_eq!(snapshot.total, 40);
+			assert_eq!(StakePallet::pending_rewards(&1), 15);
+			assert_eq!(StakePallet::pending_rewards(&3), 15);
+			assert_eq!(StakePallet::pending_rewards(&4), 30);
+
+			// stake added during a round is not rewarded before the next round
+			assert_ok!(StakePallet::delegator_stake_more(Origin::signed(3), 1, 30));
+			assert_eq!(StakePallet::stake_snapshot(1), Some(snapshot));
+			roll_to(3, vec![None, None, Some(1)]);
+			assert_eq!(StakePallet::pending_rewards(&1), 30);
+			assert_eq!(StakePallet::pending_rewards(&3), 30);
+			assert_eq!(StakePallet::pending_rewards(&4), 60);
+
+			// removed stake is not rewarded anymore
+			assert_ok!(StakePallet::delegator_stake_less(Origin::signed(4), 1, 10));
+			roll_to(4, vec![None, None, None, Some(1)]);
+			assert_eq!(StakePallet::pending_rewards(&1), 50);
+			assert_eq!(StakePallet::pending_rewards(&3), 50);
+			assert_eq!(StakePallet::pending_rewards(&4), 80);
+
+			// the changes are rewarded from the first block of the next round on
+			roll_to(6, vec![None, None, None, None, None, Some(1)]);
+			let snapshot = StakePallet::stake_snapshot(1).unwrap();
+			assert_eq!(snapshot.round, 1);
+			assert_eq!(snapshot.total, 60);
+			assert_eq!(StakePallet::rewards(&3), 50);
+			assert_eq!(StakePallet::pending_rewards(&1), 60);
+			assert_eq!(StakePallet::pending_rewards(&3), 90);
+			assert_eq!(StakePallet::pending_rewards(&4), 90);
+
+			// the snapshot is removed with the candidate
+			assert_ok!(StakePallet::force_remove_candidate(Origin::root(), 1));
+			assert!(StakePallet::stake_snapshot(1).is_none());
+		});
+}
+
+#[test]
+fn kicked_delegator_keeps_rewards() {
+	ExtBuilder::default()
+		.with_balances(vec![
+			(1, 100),
+			(2, 100),
+			(3, 100),
+			(4, 100),
+			(5, 100),
+			(6, 100),
+			(7, 100),
+		])
+		.with_collators(vec![(1, 10), (2, 10)])
+		.with_delegators(vec![(3, 1, 10), (4, 1, 10), (5, 1, 10), (6, 1, 9)])
+		.build_and_execute_with_sanity_tests(|| {
+			<crate::RewardPerBlock<Test>>::put(49);
+			roll_to(2, vec![None, Some(1)]);
+			for acc in 1u64..=5 {
+				assert_eq!(StakePallet::pending_rewards(&acc), 10);
+			}
+			assert_eq!(StakePallet::pending_rewards(&6), 9);
+
+			// 7 replaces 6 which has the least stake
+			assert_ok!(StakePallet::join_delegators(Origin::signed(7), 1, 11));
+			assert!(StakePallet::delegator_state(&6).is_none());
+			assert_eq!(StakePallet::rewards(&6), 9);
+			assert_eq!(StakePallet::settled_block_rewards(&6, &1), 0);
+
+			// the kicked delegator does not earn any more rewards
+			<crate::RewardPerBlock<Test>>::put(51);
+			roll_to(3, vec![None, None, Some(1)]);
+			assert_eq!(StakePallet::pending_rewards(&6), 9);
+			assert!(StakePallet::pending_rewards(&7).is_zero());
+
+			// 7 is rewarded from the next round on
+			roll_to(6, vec![None, None, None, None, None, Some(1)]);
+			assert_eq!(StakePallet::pending_rewards(&6), 9);
+			assert_eq!(StakePallet::pending_rewards(&7), 11);
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(6)));
+			assert_eq!(Balances::free_balance(&6), 109);
+		});
+}
+
+#[test]
+fn removed_collator_keeps_rewards() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100)])
+		.with_collators(vec![(1, 10), (2, 10), (3, 10)])
+		.with_delegators(vec![(4, 1, 30)])
+		.build_and_execute_with_sanity_tests(|| {
+			<crate::RewardPerBlock<Test>>::put(40);
+			roll_to(2, vec![None, Some(1)]);
+			assert_eq!(StakePallet::pending_rewards(&1), 10);
+			assert_eq!(StakePallet::pending_rewards(&4), 30);
+
+			assert_ok!(StakePallet::force_remove_candidate(Origin::root(), 1));
+			assert_eq!(StakePallet::rewards(&1), 10);
+			assert_eq!(StakePallet::rewards(&4), 30);
+			assert!(StakePallet::collator_block_rewards(&1).is_zero());
+			assert!(StakePallet::settled_block_rewards(&1, &1).is_zero());
+			assert!(StakePallet::settled_block_rewards(&4, &1).is_zero());
+
+			// blocks of removed collators are rejected
+			assert!(StakePallet::is_disabled_author(&1));
+			roll_to(3, vec![None, None, Some(2)]);
+			assert!(StakePallet::collator_block_rewards(&1).is_zero());
+			assert_eq!(StakePallet::pending_rewards(&1), 10);
+
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(1)));
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(4)));
+			assert_eq!(Balances::free_balance(&1), 110);
+			assert_eq!(Balances::free_balance(&4), 130);
+		});
+}
+
+#[test]
+fn removed_collator_cannot_author() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100)])
+		.with_collators(vec![(1, 10), (2, 10), (3, 10)])
+		.build_and_execute_with_sanity_tests(|| {
+			roll_to(2, vec![None, Some(1)]);
+			assert_eq!(StakePallet::blocks_authored(1), 1);
+			assert!(!<StakePallet as DisabledValidators>::is_disabled(0));
+			assert_ok!(StakePallet::force_remove_candidate(Origin::root(), 1));
+
+			// 1 is still part of the validator set but AuRa rejects their blocks
+			assert_eq!(Session::validators(), vec![1, 2]);
+			assert!(<StakePallet as DisabledValidators>::is_disabled(0));
+			assert!(!<StakePallet as DisabledValidators>::is_disabled(1));
+			assert!(!<StakePallet as DisabledValidators>::is_disabled(2));
+
+			// a block of 1 is neither counted nor rewarded
+			roll_to(3, vec![None, None, Some(1)]);
+			assert_eq!(StakePallet::blocks_authored(1), 1);
+			assert_eq!(StakePallet::collator_round_stats(0, 1).blocks_authored, 1);
+			assert!(StakePallet::collator_block_rewards(1).is_zero());
+
+			// 1 has left the validator set
+			roll_to(10, vec![]);
+			assert_eq!(Session::validators(), vec![2, 3]);
+			assert!(!<StakePallet as DisabledValidators>::is_disabled(0));
+			assert!(!<StakePallet as DisabledValidators>::is_disabled(1));
+		});
+}
+
+#[test]
+fn removed_collator_is_disabled_until_leaving_validator_set() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100)])
+		.with_collators(vec![(1, 10), (2, 10), (3, 10)])
+		.build_and_execute_with_sanity_tests(|| {
+			assert_ok!(StakePallet::force_remove_candidate(Origin::root(), 1));
+			assert_eq!(StakePallet::disabled_collators(1), Some(2));
+			assert!(StakePallet::is_disabled_author(&1));
+			assert!(!StakePallet::is_disabled_author(&2));
+
+			// 1 is part of the queued validator set of session 1
+			roll_to(5, vec![None, Some(2), Some(2), Some(2), Some(2)]);
+			assert_eq!(Session::validators(), vec![1, 2]);
+			assert!(StakePallet::is_disabled_author(&1));
+
+			// 1 has left the validator set
+			roll_to(10, vec![]);
+			assert_eq!(Session::validators(), vec![2, 3]);
+			assert!(!StakePallet::is_disabled_author(&1));
+			assert!(StakePallet::disabled_collators(1).is_none());
+		});
+}
+
+#[test]
+fn last_enabled_collator_cannot_be_removed() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100)])
+		.with_collators(vec![(1, 10), (2, 10), (3, 10), (4, 10)])
+		.build_and_execute_with_sanity_tests(|| {
+			assert_eq!(Session::validators(), vec![1, 2]);
+			assert_ok!(StakePallet::force_remove_candidate(Origin::root(), 1));
+			assert!(StakePallet::is_disabled_author(&1));
+
+			// 2 is the only author of the current session whose blocks are accepted
+			assert_noop!(
+				StakePallet::force_remove_candidate(Origin::root(), 2),
+				Error::<Test>::TooFewEnabledCollators
+			);
+
+			// invulnerable collators are never disabled
+			assert_ok!(StakePallet::add_invulnerable(Origin::root(), 2));
+			assert_ok!(StakePallet::force_remove_candidate(Origin::root(), 2));
+			assert!(!StakePallet::is_disabled_author(&2));
+			assert!(StakePallet::disabled_collators(2).is_none());
+		});
+}
+
+#[test]
+fn leaving_collator_is_not_disabled() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100)])
+		.with_collators(vec![(1, 10), (2, 10), (3, 10)])
+		.build_and_execute_with_sanity_tests(|| {
+			assert_ok!(StakePallet::init_leave_candidates(Origin::signed(1)));
+			roll_to(2 * BLOCKS_PER_ROUND, vec![]);
+			assert_ok!(StakePallet::execute_leave_candidates(Origin::signed(1), 1));
+			assert!(StakePallet::candidate_pool(1).is_none());
+			assert!(StakePallet::disabled_collators(1).is_none());
+			assert!(!StakePallet::is_disabled_author(&1));
+		});
+}
+
+#[test]
+fn commission_is_deducted_from_delegator_rewards() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100)])
+		.with_collators(vec![(1, 10), (2, 10), (3, 10)])
+		.with_delegators(vec![(4, 1, 30), (5, 1, 60)])
+		.build_and_execute_with_sanity_tests(|| {
+			// the commission is bounded by the maximum which is set at genesis
+			assert_eq!(StakePallet::max_commission(), Perbill::from_percent(10));
+			assert_noop!(
+				StakePallet::set_commission(Origin::signed(1), Perbill::from_percent(20)),
+				Error::<Test>::CommissionAboveMax
+			);
+			assert_noop!(
+				StakePallet::set_max_commission(Origin::signed(1), Perbill::from_percent(20)),
+				sp_runtime::DispatchError::BadOrigin
+			);
+			assert_ok!(StakePallet::set_max_commission(
+				Origin::root(),
+				Perbill::from_percent(20)
+			));
+			assert_eq!(
+				last_event(),
+				MetaEvent::StakePallet(Event::MaxCommissionSet(
+					Perbill::from_percent(10),
+					Perbill::from_percent(20)
+				))
+			);
+			assert_noop!(
+				StakePallet::set_commission(Origin::signed(4), Perbill::from_percent(10)),
+				Error::<Test>::CandidateNotFound
+			);
+
+			// increasing the commission is delayed
+			<crate::RewardPerBlock<Test>>::put(100);
+			assert_ok!(StakePallet::set_commission(
+				Origin::signed(1),
+				Perbill::from_percent(10)
+			));
+			assert_eq!(
+				last_event(),
+				MetaEvent::StakePallet(Event::CommissionChangeRequested(
+					1,
+					Perbill::zero(),
+					Perbill::from_percent(10),
+					2
+				))
+			);
+			assert!(StakePallet::commission(&1).is_zero());
+			assert_noop!(
+				StakePallet::execute_commission_change(Origin::signed(4), 1),
+				Error::<Test>::CannotChangeCommissionYet
+			);
+			assert_noop!(
+				StakePallet::execute_commission_change(Origin::signed(4), 2),
+				Error::<Test>::NoPendingCommissionChange
+			);
+
+			// without a commission, rewards are shared pro rata
+			roll_to(2, vec![None, Some(1)]);
+			assert_eq!(StakePallet::pending_rewards(&1), 10);
+			assert_eq!(StakePallet::pending_rewards(&4), 30);
+			assert_eq!(StakePallet::pending_rewards(&5), 60);
+
+			roll_to(11, vec![]);
+			assert_eq!(StakePallet::round().current, 2);
+			assert_ok!(StakePallet::execute_commission_change(Origin::signed(4), 1));
+			assert_eq!(
+				last_event(),
+				MetaEvent::StakePallet(Event::CommissionSet(1, Perbill::zero(), Perbill::from_percent(10)))
+			);
+			assert_eq!(StakePallet::commission(&1), Perbill::from_percent(10));
+			assert!(StakePallet::pending_commission(&1).is_none());
+			// rewards earned before were settled with the old commission
+			assert_eq!(StakePallet::rewards(&1), 10);
+			assert_eq!(StakePallet::rewards(&4), 30);
+			assert_eq!(StakePallet::rewards(&5), 60);
+
+			// the commission is credited to the collator
+			roll_to(12, vec![Some(1); 12]);
+			assert_eq!(StakePallet::pending_rewards(&1), 10 + 10 + 3 + 6);
+			assert_eq!(StakePallet::pending_rewards(&4), 30 + 27);
+			assert_eq!(StakePallet::pending_rewards(&5), 60 + 54);
+
+			// decreasing the commission is applied immediately
+			assert_ok!(StakePallet::set_commission(Origin::signed(1), Perbill::from_percent(5)));
+			assert_eq!(
+				last_event(),
+				MetaEvent::StakePallet(Event::CommissionSet(
+					1,
+					Perbill::from_percent(10),
+					Perbill::from_percent(5)
+				))
+			);
+			assert_eq!(StakePallet::commission(&1), Perbill::from_percent(5));
+			assert_eq!(StakePallet::rewards(&1), 29);
+			assert_eq!(StakePallet::rewards(&4), 57);
+			assert_eq!(StakePallet::rewards(&5), 114);
+
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(1)));
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(4)));
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(5)));
+			assert_eq!(Balances::free_balance(&1), 129);
+			assert_eq!(Balances::free_balance(&4), 157);
+			assert_eq!(Balances::free_balance(&5), 214);
+		});
+}
+
+#[test]
+fn commission_change_is_capped_and_cleared() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100)])
+		.with_collators(vec![(1, 10), (2, 10), (3, 10)])
+		.with_delegators(vec![(4, 1, 30)])
+		.build_and_execute_with_sanity_tests(|| {
+			assert_ok!(StakePallet::set_max_commission(
+				Origin::root(),
+				Perbill::from_percent(20)
+			));
+			assert_ok!(StakePallet::set_commission(
+				Origin::signed(1),
+				Perbill::from_percent(15)
+			));
+
+			// lowering the maximum caps pending requests
+			assert_ok!(StakePallet::set_max_commission(
+				Origin::root(),
+				Perbill::from_percent(8)
+			));
+			roll_to(11, vec![]);
+			assert_ok!(StakePallet::execute_commission_change(Origin::signed(2), 1));
+			assert_eq!(StakePallet::commission(&1), Perbill::from_percent(8));
+
+			// a new request replaces the pending one
+			assert_ok!(StakePallet::set_max_commission(
+				Origin::root(),
+				Perbill::from_percent(20)
+			));
+			assert_ok!(StakePallet::set_commission(
+				Origin::signed(1),
+				Perbill::from_percent(20)
+			));
+			assert_ok!(StakePallet::set_commission(
+				Origin::signed(1),
+				Perbill::from_percent(12)
+			));
+			assert_eq!(
+				StakePallet::pending_commission(&1).map(|c| c.commission),
+				Some(Perbill::from_percent(12))
+			);
+
+			// removing the candidate clears the commission
+			assert_ok!(StakePallet::force_remove_candidate(Origin::root(), 1));
+			assert!(StakePallet::commission(&1).is_zero());
+			assert!(StakePallet::pending_commission(&1).is_none());
+		});
+}
+
+#[test]
+fn offline_collator_is_slashed_and_removed() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000), (2, 1000), (3, 1000), (4, 1000)])
+		.with_collators(vec![(1, 150), (2, 100), (3, 50)])
+		.with_delegators(vec![(4, 2, 100)])
+		.build_and_execute_with_sanity_tests(|| {
+			OfflineThreshold::set(Perbill::from_percent(50));
+			DelegatorSlash::set(Perbill::from_percent(10));
+			assert_eq!(StakePallet::session_collators(0).into_inner(), vec![2, 1]);
+
+			// collator 2 does not author any blocks in the first session
+			roll_to(5, vec![None, Some(1), Some(1), Some(1), Some(1)]);
+			assert!(StakePallet::session_collators(0).is_empty());
+			assert!(StakePallet::blocks_authored(&1).is_zero());
+			assert!(events().contains(&Event::OffenceReported(2, 0, 2, 1)));
+			assert!(events().contains(&Event::Slashed(2, 10)));
+			assert!(events().contains(&Event::Slashed(4, 10)));
+			assert_eq!(StakePallet::offences(&1), 0);
+			assert_eq!(StakePallet::offences(&2), 1);
+
+			let state = StakePallet::candidate_pool(&2).unwrap();
+			assert_eq!(state.stake, 90);
+			assert_eq!(state.total, 180);
+			assert_eq!(StakePallet::delegator_state(&4).unwrap().total, 90);
+			assert_eq!(
+				StakePallet::total_collator_stake(),
+				TotalStake {
+					collators: 240,
+					delegators: 90,
+				}
+			);
+			assert_eq!(Balances::free_balance(&2), 990);
+			assert_eq!(Balances::free_balance(&4), 990);
+			assert_eq!(Balances::free_balance(&TREASURY_ACC), 20);
+			assert_eq!(
+				Balances::locks(2),
+				vec![BalanceLock {
+					id: STAKING_ID,
+					amount: 90,
+					reasons: Reasons::All,
+				}]
+			);
+
+			// the second offence in a row removes the candidate
+			roll_to(10, vec![Some(1); 10]);
+			assert!(events().contains(&Event::OffenceReported(2, 0, 2, 2)));
+			assert!(events().contains(&Event::Slashed(2, 9)));
+			assert!(events().contains(&Event::Slashed(4, 9)));
+			assert!(events().contains(&Event::CollatorRemoved(2, 162)));
+			assert!(StakePallet::candidate_pool(&2).is_none());
+			assert!(StakePallet::delegator_state(&4).is_none());
+			assert_eq!(StakePallet::offences(&2), 0);
+			assert_eq!(StakePallet::session_collators(3).into_inner(), vec![1, 3]);
+			assert_eq!(Balances::free_balance(&TREASURY_ACC), 38);
+
+			// the remaining stakes can be unlocked as usual
+			assert_eq!(StakePallet::unstaking(2).get(&12), Some(&81));
+			assert_eq!(StakePallet::unstaking(4).get(&12), Some(&81));
+			roll_to(12, vec![]);
+			assert_ok!(StakePallet::unlock_unstaked(Origin::signed(2), 2));
+			assert_ok!(StakePallet::unlock_unstaked(Origin::signed(4), 4));
+			assert!(Balances::locks(2).is_empty());
+			assert!(Balances::locks(4).is_empty());
+			assert_eq!(Balances::free_balance(&2), 981);
+			assert_eq!(Balances::free_balance(&4), 981);
+		});
+}
+
+#[test]
+fn offences_are_reset_and_required_collators_kept() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000), (2, 1000)])
+		.with_collators(vec![(1, 200), (2, 100)])
+		.build_and_execute_with_sanity_tests(|| {
+			// offence reports are disabled without a threshold
+			roll_to(5, vec![Some(1); 5]);
+			assert_eq!(StakePallet::offences(&2), 0);
+			assert_eq!(StakePallet::candidate_pool(&2).unwrap().stake, 100);
+
+			OfflineThreshold::set(Perbill::from_percent(50));
+			roll_to(10, vec![Some(1); 10]);
+			assert_eq!(StakePallet::offences(&2), 1);
+			assert_eq!(StakePallet::candidate_pool(&2).unwrap().stake, 90);
+
+			// authoring enough blocks resets the offences
+			let authors: Vec<Option<AccountId>> =
+				(0..15).map(|i| if i % 2 == 0 { Some(1) } else { Some(2) }).collect();
+			roll_to(15, authors);
+			assert_eq!(StakePallet::offences(&2), 0);
+			assert_eq!(StakePallet::candidate_pool(&2).unwrap().stake, 90);
+
+			// candidates are not removed if too few would be left
+			roll_to(25, vec![Some(1); 25]);
+			assert_eq!(StakePallet::offences(&2), 2);
+			assert_eq!(StakePallet::candidate_pool(&2).unwrap().stake, 73);
+			assert_eq!(StakePallet::selected_candidates().into_inner(), vec![1, 2]);
+		});
+}
+
+#[test]
+fn slashed_stakers_below_minimum_are_removed() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000), (2, 1000), (3, 1000), (4, 1000), (5, 1000)])
+		.with_collators(vec![(1, 100), (2, 10), (3, 15)])
+		.with_delegators(vec![(4, 2, 6), (5, 2, 10)])
+		.build_and_execute_with_sanity_tests(|| {
+			OfflineThreshold::set(Perbill::from_percent(50));
+			DelegatorSlash::set(Perbill::from_percent(50));
+			assert_eq!(StakePallet::top_candidates().len(), 3);
+
+			// collator 2 does not author any blocks in the first session
+			roll_to(5, vec![None, Some(1), Some(1), Some(1), Some(1)]);
+			assert_eq!(StakePallet::offences(&2), 0);
+			assert!(events().contains(&Event::OffenceReported(2, 0, 2, 1)));
+			assert!(events().contains(&Event::Slashed(2, 1)));
+			assert!(events().contains(&Event::Slashed(4, 3)));
+			assert!(events().contains(&Event::Slashed(5, 5)));
+
+			// the delegation of 4 is below the minimum and kicked first
+			assert!(events().contains(&Event::DelegatorLeftCollator(4, 2, 3, 14)));
+			assert!(events().contains(&Event::DelegatorLeft(4, 3)));
+			assert!(StakePallet::delegator_state(&4).is_none());
+			assert_eq!(StakePallet::unstaking(4).get(&7), Some(&3));
+
+			// the stake of the candidate is below the minimum
+			assert!(events().contains(&Event::CollatorRemoved(2, 14)));
+			assert!(StakePallet::candidate_pool(&2).is_none());
+			assert!(StakePallet::delegator_state(&5).is_none());
+			assert_eq!(StakePallet::unstaking(2).get(&7), Some(&9));
+			assert_eq!(StakePallet::unstaking(5).get(&7), Some(&5));
+			assert_eq!(Balances::free_balance(&TREASURY_ACC), 9);
+			assert_eq!(
+				StakePallet::total_collator_stake(),
+				TotalStake {
+					collators: 115,
+					delegators: 0,
+				}
+			);
+		});
+}
+
+#[test]
+fn kept_session_clears_authored_blocks() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000), (2, 1000)])
+		.with_collators(vec![(1, 200), (2, 100)])
+		.build_and_execute_with_sanity_tests(|| {
+			// without session keys no collators can be selected for the next sessions
+			assert_ok!(Session::purge_keys(Origin::signed(1)));
+			assert_ok!(Session::purge_keys(Origin::signed(2)));
+			assert!(StakePallet::next_session_collators().is_empty());
+
+			roll_to(10, vec![Some(1); 10]);
+			assert!(StakePallet::session_collators(1).is_empty());
+			assert_eq!(StakePallet::blocks_authored(&1), 0);
+			// the collators of the kept sessions are evaluated at their end
+			assert!(!StakePallet::session_collators(2).is_empty());
+			assert_eq!(StakePallet::session_collators(3), StakePallet::session_collators(2));
+
+			roll_to(15, vec![Some(1); 15]);
+			assert!(StakePallet::session_collators(2).is_empty());
+			assert_eq!(StakePallet::blocks_authored(&1), 0);
+		});
+}
+
+#[test]
+fn redelegate() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100), (6, 100)])
+		.with_collators(vec![(1, 20), (2, 20), (3, 20)])
+		.with_delegators(vec![(4, 1, 30), (5, 2, 10)])
+		.build_and_execute_with_sanity_tests(|| {
+			assert_noop!(
+				StakePallet::redelegate(Origin::signed(4), 1, 3, 0),
+				Error::<Test>::ValStakeZero
+			);
+			assert_noop!(
+				StakePallet::redelegate(Origin::signed(4), 1, 1, 10),
+				Error::<Test>::CannotRedelegateToSameCollator
+			);
+			assert_noop!(
+				StakePallet::redelegate(Origin::signed(6), 1, 3, 10),
+				Error::<Test>::DelegatorNotFound
+			);
+			assert_noop!(
+				StakePallet::redelegate(Origin::signed(4), 2, 3, 10),
+				Error::<Test>::DelegationNotFound
+			);
+			assert_noop!(
+				StakePallet::redelegate(Origin::signed(4), 1, 3, 31),
+				Error::<Test>::Underflow
+			);
+			assert_noop!(
+				StakePallet::redelegate(Origin::signed(4), 1, 3, 29),
+				Error::<Test>::DelegationBelowMin
+			);
+			assert_noop!(
+				StakePallet::redelegate(Origin::signed(4), 1, 9, 10),
+				Error::<Test>::CandidateNotFound
+			);
+
+			<crate::RewardPerBlock<Test>>::put(50);
+			roll_to(2, vec![None, Some(1)]);
+			assert_eq!(StakePallet::pending_rewards(&4), 30);
+
+			// move parts of the stake to a new collator
+			assert_ok!(StakePallet::redelegate(Origin::signed(4), 1, 3, 12));
+			assert_eq!(
+				last_event(),
+				MetaEvent::StakePallet(Event::Redelegated(4, 1, 3, 12, 38, 32))
+			);
+			// rewards earned with the previous stake have been settled
+			assert_eq!(StakePallet::rewards(&4), 30);
+			let delegator = StakePallet::delegator_state(&4).unwrap();
+			assert_eq!(delegator.total, 30);
+			assert_eq!(
+				delegator.delegations.into_bounded_vec().into_inner(),
+				vec![
+					Stake {
+						owner: 1,
+						amount: 18
+					},
+					Stake {
+						owner: 3,
+						amount: 12
+					}
+				]
+			);
+			assert_eq!(StakePallet::candidate_pool(&1).unwrap().total, 38);
+			assert_eq!(StakePallet::candidate_pool(&3).unwrap().total, 32);
+			assert_eq!(StakePallet::selected_candidates().into_inner(), vec![1, 3]);
+			assert_eq!(
+				StakePallet::total_collator_stake(),
+				TotalStake {
+					collators: 40,
+					delegators: 30,
+				}
+			);
+			assert_eq!(StakePallet::last_delegation(&4).counter, 1);
+			// nothing is unstaked
+			assert!(StakePallet::unstaking(&4).is_empty());
+			assert_eq!(
+				Balances::locks(4),
+				vec![BalanceLock {
+					id: STAKING_ID,
+					amount: 30,
+					reasons: Reasons::All,
+				}]
+			);
+
+			// move stake to an existing delegation
+			assert_ok!(StakePallet::redelegate(Origin::signed(4), 3, 1, 5));
+			assert_eq!(StakePallet::candidate_pool(&1).unwrap().total, 43);
+			assert_eq!(StakePallet::candidate_pool(&3).unwrap().total, 27);
+			assert_eq!(StakePallet::last_delegation(&4).counter, 1);
+
+			// move the entire delegation
+			assert_ok!(StakePallet::redelegate(Origin::signed(4), 3, 2, 7));
+			assert_eq!(
+				last_event(),
+				MetaEvent::StakePallet(Event::Redelegated(4, 3, 2, 7, 20, 37))
+			);
+			let delegator = StakePallet::delegator_state(&4).unwrap();
+			assert_eq!(delegator.total, 30);
+			assert_eq!(delegator.delegations.len(), 2);
+			assert!(StakePallet::candidate_pool(&3).unwrap().delegators.is_empty());
+			assert!(StakePallet::settled_block_rewards(&4, &3).is_zero());
+			assert_eq!(StakePallet::last_delegation(&4).counter, 2);
+			assert!(StakePallet::unstaking(&4).is_empty());
+		});
+}
+
+#[test]
+fn redelegate_kicks_lowest_delegator() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100), (6, 100), (7, 100)])
+		.with_collators(vec![(1, 20), (2, 20)])
+		.with_delegators(vec![(3, 1, 10), (4, 2, 5), (5, 2, 6), (6, 2, 7), (7, 2, 8)])
+		.build_and_execute_with_sanity_tests(|| {
+			assert_noop!(
+				StakePallet::redelegate(Origin::signed(3), 1, 2, 4),
+				Error::<Test>::TooManyDelegators
+			);
+
+			assert_ok!(StakePallet::redelegate(Origin::signed(3), 1, 2, 10));
+			assert!(events().contains(&Event::DelegationReplaced(3, 10, 4, 5, 2, 51)));
+			assert_eq!(
+				last_event(),
+				MetaEvent::StakePallet(Event::Redelegated(3, 1, 2, 10, 20, 51))
+			);
+			assert!(StakePallet::candidate_pool(&1).unwrap().delegators.is_empty());
+			assert_eq!(StakePallet::delegator_state(&3).unwrap().total, 10);
+
+			// the kicked delegator has to unstake
+			assert!(StakePallet::delegator_state(&4).is_none());
+			assert_eq!(StakePallet::unstaking(&4).get(&3), Some(&5));
+		});
+}
+
+#[test]
+fn rebond() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100)])
+		.with_collators(vec![(1, 50), (2, 20)])
+		.with_delegators(vec![(3, 1, 50)])
+		.build_and_execute_with_sanity_tests(|| {
+			let lock = BalanceLock {
+				id: STAKING_ID,
+				amount: 50,
+				reasons: Reasons::All,
+			};
+			assert_noop!(
+				StakePallet::rebond(Origin::signed(1), 1, 10),
+				Error::<Test>::UnstakingIsEmpty
+			);
+			assert_ok!(StakePallet::candidate_stake_less(Origin::signed(1), 10));
+			roll_to(2, vec![]);
+			assert_ok!(StakePallet::candidate_stake_less(Origin::signed(1), 5));
+			assert_eq!(StakePallet::candidate_pool(&1).unwrap().stake, 35);
+
+			assert_noop!(
+				StakePallet::rebond(Origin::signed(1), 1, 0),
+				Error::<Test>::ValStakeZero
+			);
+			assert_noop!(
+				StakePallet::rebond(Origin::signed(1), 1, 16),
+				Error::<Test>::InsufficientUnstaking
+			);
+			assert_noop!(
+				StakePallet::rebond(Origin::signed(1), 4, 10),
+				Error::<Test>::CandidateNotFound
+			);
+
+			// the most recent unstaking request is consumed first
+			assert_ok!(StakePallet::rebond(Origin::signed(1), 1, 7));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::Rebonded(1, 1, 7, 92)));
+			let mut unstaking: BoundedBTreeMap<BlockNumber, BalanceOf<Test>, <Test as Config>::MaxUnstakeRequests> =
+				BoundedBTreeMap::new();
+			assert_ok!(unstaking.try_insert(3, 8));
+			assert_eq!(StakePallet::unstaking(1), unstaking);
+			assert_eq!(StakePallet::candidate_pool(&1).unwrap().stake, 42);
+			assert_eq!(
+				StakePallet::total_collator_stake(),
+				TotalStake {
+					collators: 62,
+					delegators: 50,
+				}
+			);
+			assert_eq!(Balances::locks(1), vec![lock.clone()]);
+
+			// delegators can restake for their existing delegations
+			assert_ok!(StakePallet::delegator_stake_less(Origin::signed(3), 1, 10));
+			assert_noop!(
+				StakePallet::rebond(Origin::signed(3), 2, 10),
+				Error::<Test>::DelegationNotFound
+			);
+			assert_ok!(StakePallet::rebond(Origin::signed(3), 1, 10));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::Rebonded(3, 1, 10, 92)));
+			assert!(StakePallet::unstaking(3).is_empty());
+			assert_eq!(StakePallet::delegator_state(&3).unwrap().total, 50);
+			assert_eq!(StakePallet::candidate_pool(&1).unwrap().total, 92);
+			assert_eq!(Balances::locks(3), vec![lock]);
+		});
+}
+
+#[test]
+fn auto_compound() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100)])
+		.with_collators(vec![(1, 50), (2, 50)])
+		.with_delegators(vec![(3, 1, 50)])
+		.build_and_execute_with_sanity_tests(|| {
+			assert_noop!(
+				StakePallet::set_auto_compound(Origin::signed(3), 5, Percent::from_percent(50)),
+				Error::<Test>::CandidateNotFound
+			);
+			assert_noop!(
+				StakePallet::set_auto_compound(Origin::signed(4), 1, Percent::from_percent(50)),
+				Error::<Test>::DelegatorNotFound
+			);
+			assert_noop!(
+				StakePallet::set_auto_compound(Origin::signed(3), 2, Percent::from_percent(50)),
+				Error::<Test>::StakeNotFoundForCollator
+			);
+			assert_ok!(StakePallet::set_auto_compound(
+				Origin::signed(3),
+				1,
+				Percent::from_percent(50)
+			));
+			assert_eq!(
+				last_event(),
+				MetaEvent::StakePallet(Event::AutoCompoundSet(3, 1, Percent::from_percent(50)))
+			);
+			assert_ok!(StakePallet::set_auto_compound(Origin::signed(1), 1, Percent::one()));
+			assert_ok!(StakePallet::set_max_candidate_stake(Origin::root(), 70));
+
+			<crate::RewardPerBlock<Test>>::put(100);
+			roll_to(2, vec![None, Some(1)]);
+			assert_eq!(StakePallet::pending_rewards(&1), 50);
+			assert_eq!(StakePallet::pending_rewards(&3), 50);
+
+			// half of the delegator's rewards are restaked
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(3)));
+			assert_eq!(
+				events().into_iter().rev().take(2).collect::<Vec<_>>(),
+				vec![Event::Rewarded(3, 25), Event::RewardsCompounded(3, 1, 25)]
+			);
+			assert_eq!(StakePallet::delegator_state(&3).unwrap().total, 75);
+			assert_eq!(StakePallet::candidate_pool(&1).unwrap().total, 125);
+			assert_eq!(Balances::free_balance(&3), 150);
+			assert_eq!(
+				Balances::locks(3),
+				vec![BalanceLock {
+					id: STAKING_ID,
+					amount: 75,
+					reasons: Reasons::All,
+				}]
+			);
+			assert!(StakePallet::compound_rewards(&3, &1).is_zero());
+
+			// the self-stake cannot exceed the maximum, the excess is paid out
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(1)));
+			assert_eq!(
+				events().into_iter().rev().take(2).collect::<Vec<_>>(),
+				vec![Event::Rewarded(1, 30), Event::RewardsCompounded(1, 1, 20)]
+			);
+			assert_eq!(StakePallet::candidate_pool(&1).unwrap().stake, 70);
+			assert_eq!(Balances::free_balance(&1), 150);
+			assert_eq!(
+				Balances::locks(1),
+				vec![BalanceLock {
+					id: STAKING_ID,
+					amount: 70,
+					reasons: Reasons::All,
+				}]
+			);
+			assert_eq!(
+				StakePallet::total_collator_stake(),
+				TotalStake {
+					collators: 120,
+					delegators: 75,
+				}
+			);
+			assert_noop!(
+				StakePallet::claim_rewards(Origin::signed(1)),
+				Error::<Test>::RewardsNotFound
+			);
+
+			// rewards set aside for restaking are paid out when the delegation is removed
+			assert_ok!(StakePallet::set_auto_compound(Origin::signed(3), 1, Percent::one()));
+			roll_to(3, vec![None, None, Some(1)]);
+			let pending = StakePallet::pending_rewards(&3);
+			assert!(!pending.is_zero());
+			assert_ok!(StakePallet::revoke_delegation(Origin::signed(3), 1));
+			assert!(StakePallet::auto_compound(&3, &1).is_zero());
+			assert!(StakePallet::compound_rewards(&3, &1).is_zero());
+			assert_eq!(StakePallet::rewards(&3), pending);
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(3)));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::Rewarded(3, pending)));
+
+			// a share of zero disables restaking
+			assert_ok!(StakePallet::set_auto_compound(Origin::signed(1), 1, Percent::zero()));
+			assert!(!crate::AutoCompound::<Test>::contains_key(&1, &1));
+		});
+}
+
+#[test]
+fn rewards_are_compounded_at_round_end() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100)])
+		.with_collators(vec![(1, 50), (2, 50)])
+		.with_delegators(vec![(3, 1, 50)])
+		.build_and_execute_with_sanity_tests(|| {
+			assert_ok!(StakePallet::set_auto_compound(Origin::signed(3), 1, Percent::one()));
+			assert_ok!(StakePallet::set_auto_compound(
+				Origin::signed(1),
+				1,
+				Percent::from_percent(50)
+			));
+
+			<crate::RewardPerBlock<Test>>::put(100);
+			roll_to(5, vec![None, Some(1)]);
+
+			// the rewards are restaked at the end of the round without claiming them
+			assert!(events().contains(&Event::RewardsCompounded(1, 1, 25)));
+			assert!(events().contains(&Event::RewardsCompounded(3, 1, 50)));
+			assert!(StakePallet::compound_rewards(&1, &1).is_zero());
+			assert!(StakePallet::compound_rewards(&3, &1).is_zero());
+			assert_eq!(StakePallet::delegator_state(&3).unwrap().total, 100);
+			let state = StakePallet::candidate_pool(&1).unwrap();
+			assert_eq!(state.stake, 75);
+			assert_eq!(state.total, 175);
+			assert_eq!(StakePallet::rewards(&1), 25);
+			assert!(StakePallet::rewards(&3).is_zero());
+			assert_eq!(Balances::free_balance(&3), 150);
+			assert_eq!(
+				StakePallet::total_collator_stake(),
+				TotalStake {
+					collators: 125,
+					delegators: 100,
+				}
+			);
+
+			// restaking is deferred while payouts are paused
+			assert_ok!(StakePallet::set_paused_operations(
+				Origin::root(),
+				PausedOperations {
+					payouts: true,
+					..Default::default()
+				}
+			));
+			let mut authors = vec![None; 15];
+			authors[6] = Some(1);
+			roll_to(10, authors.clone());
+			assert_eq!(StakePallet::delegator_state(&3).unwrap().total, 100);
+			let pending = StakePallet::pending_rewards(&3);
+			assert!(!pending.is_zero());
+
+			assert_ok!(StakePallet::set_paused_operations(
+				Origin::root(),
+				PausedOperations::default()
+			));
+			roll_to(15, authors);
+			assert_eq!(StakePallet::delegator_state(&3).unwrap().total, 100 + pending);
+			assert!(StakePallet::pending_rewards(&3).is_zero());
+		});
+}
+
+#[test]
+fn set_payee() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 10), (5, 10), (6, 100), (7, 100)])
+		.with_collators(vec![(1, 50), (2, 50), (6, 50)])
+		.with_delegators(vec![(3, 1, 50), (7, 2, 10)])
+		.build_and_execute_with_sanity_tests(|| {
+			assert_noop!(
+				StakePallet::set_payee(Origin::signed(4), 5),
+				Error::<Test>::StakerNotFound
+			);
+			assert_ok!(StakePallet::set_payee(Origin::signed(1), 4));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::PayeeSet(1, 4)));
+			assert_eq!(StakePallet::payee(&1), Some(4));
+			assert_ok!(StakePallet::set_payee(Origin::signed(3), 5));
+			assert_eq!(StakePallet::payee(&3), Some(5));
+
+			<crate::RewardPerBlock<Test>>::put(100);
+			roll_to(2, vec![None, Some(1)]);
+
+			// rewards are paid out to the payee
+			assert_ok!(StakePallet::claim_rewards_for(Origin::signed(2), 1));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::Rewarded(1, 50)));
+			assert_eq!(Balances::free_balance(&1), 100);
+			assert_eq!(Balances::free_balance(&4), 60);
+
+			// setting the staker as payee restores the default
+			assert_ok!(StakePallet::set_payee(Origin::signed(3), 3));
+			assert_eq!(StakePallet::payee(&3), None);
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(3)));
+			assert_eq!(Balances::free_balance(&3), 150);
+
+			// the payee is kept until the rewards of a former staker are paid out
+			assert_ok!(StakePallet::set_payee(Origin::signed(3), 5));
+			roll_to(3, vec![None, Some(1), Some(1)]);
+			assert_ok!(StakePallet::revoke_delegation(Origin::signed(3), 1));
+			assert!(!StakePallet::is_delegator(&3));
+			assert_eq!(StakePallet::rewards(&3), 50);
+			assert_eq!(StakePallet::payee(&3), Some(5));
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(3)));
+			assert_eq!(Balances::free_balance(&3), 150);
+			assert_eq!(Balances::free_balance(&5), 60);
+			assert_eq!(StakePallet::payee(&3), None);
+
+			// the payee is removed immediately if there are no rewards left
+			assert_ok!(StakePallet::set_payee(Origin::signed(7), 5));
+			assert_ok!(StakePallet::leave_delegators(Origin::signed(7)));
+			assert_eq!(StakePallet::payee(&7), None);
+			assert_ok!(StakePallet::set_payee(Origin::signed(6), 5));
+			assert_ok!(StakePallet::init_leave_candidates(Origin::signed(6)));
+			roll_to(15, vec![]);
+			assert_ok!(StakePallet::execute_leave_candidates(Origin::signed(6), 6));
+			assert_eq!(StakePallet::payee(&6), None);
+			assert_eq!(StakePallet::payee(&1), Some(4));
+		});
+}
+
+#[test]
+fn runtime_api() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100)])
+		.with_collators(vec![(1, 50), (2, 50)])
+		.with_delegators(vec![(3, 1, 50)])
+		.build_and_execute_with_sanity_tests(|| {
+			<crate::Commission<Test>>::insert(2, Perbill::from_percent(20));
+			assert_eq!(
+				StakePallet::selected_candidates_info(),
+				vec![
+					CandidateInfo {
+						id: 1,
+						stake: 50,
+						total: 100,
+						commission: Perbill::zero(),
+					},
+					CandidateInfo {
+						id: 2,
+						stake: 50,
+						total: 50,
+						commission: Perbill::from_percent(20),
+					}
+				]
+			);
+
+			assert_ok!(StakePallet::delegator_stake_less(Origin::signed(3), 1, 10));
+			assert_eq!(
+				StakePallet::staker_info(&3),
+				StakerInfo {
+					self_stake: None,
+					delegations: vec![DelegationInfo { collator: 1, amount: 40 }],
+					unstaking: vec![UnstakingInfo {
+						unlock_block: 1 + <Test as Config>::StakeDuration::get(),
+						amount: 10,
+					}],
+					pending_rewards: 0,
+				}
+			);
+			assert_eq!(StakePallet::staker_info(&1).self_stake, Some(50));
+
+			// the stake's share of the block reward is split between the selected collators
+			<crate::RewardPerBlock<Test>>::put(100);
+			let blocks_per_year = <Test as Config>::BLOCKS_PER_YEAR as Balance;
+			assert_eq!(
+				StakePallet::estimate_rewards(&1, 90),
+				Some(RewardEstimate {
+					per_block: 25,
+					per_year: 25 * blocks_per_year,
+				})
+			);
+			// the commission is deducted
+			assert_eq!(
+				StakePallet::estimate_rewards(&2, 50),
+				Some(RewardEstimate {
+					per_block: 20,
+					per_year: 20 * blocks_per_year,
+				})
+			);
+			// stakes below the minimum are not rewarded
+			assert_eq!(
+				StakePallet::estimate_rewards(&2, 1),
+				Some(RewardEstimate {
+					per_block: 0,
+					per_year: 0,
+				})
+			);
+			assert_eq!(StakePallet::estimate_rewards(&3, 50), None);
+
+			roll_to(BLOCKS_PER_ROUND + 1, vec![]);
+			assert_eq!(
+				StakePallet::round_info(),
+				ApiRoundInfo {
+					current: 1,
+					first: BLOCKS_PER_ROUND,
+					length: BLOCKS_PER_ROUND,
+				}
+			);
+		});
+}
+
+#[test]
+fn inflation_curve() {
+	let inflation = InflationInfo::new(
+		Perquintill::from_percent(50),
+		Perquintill::from_percent(2),
+		Perquintill::from_percent(10),
+		Perquintill::from_percent(20),
+	);
+	assert!(inflation.is_valid());
+	assert!(!InflationInfo::new(
+		Perquintill::zero(),
+		Perquintill::zero(),
+		Perquintill::zero(),
+		Perquintill::zero()
+	)
+	.is_valid());
+	assert!(!InflationInfo::new(
+		Perquintill::one(),
+		Perquintill::zero(),
+		Perquintill::zero(),
+		Perquintill::zero()
+	)
+	.is_valid());
+	assert!(!InflationInfo::new(
+		Perquintill::from_percent(50),
+		Perquintill::from_percent(10),
+		Perquintill::from_percent(2),
+		Perquintill::from_percent(20)
+	)
+	.is_valid());
+
+	// nothing is staked
+	assert_eq!(
+		inflation.rates(Perquintill::zero(), Perquintill::zero()),
+		InflationRates {
+			collator_staking_rate: Perquintill::zero(),
+			delegator_staking_rate: Perquintill::zero(),
+			inflation: Perquintill::from_percent(2),
+			collator_reward_rate: Perquintill::zero(),
+			delegator_reward_rate: Perquintill::zero(),
+		}
+	);
+	// below the ideal staking rate
+	assert_eq!(
+		inflation.rates(Perquintill::from_percent(10), Perquintill::from_percent(15)),
+		InflationRates {
+			collator_staking_rate: Perquintill::from_percent(10),
+			delegator_staking_rate: Perquintill::from_percent(15),
+			inflation: Perquintill::from_percent(6),
+			collator_reward_rate: Perquintill::from_percent(12),
+			delegator_reward_rate: Perquintill::from_percent(32),
+		}
+	);
+	// at the ideal staking rate
+	let rates = inflation.rates(Perquintill::from_percent(25), Perquintill::from_percent(25));
+	assert_eq!(
+		rates,
+		InflationRates {
+			collator_staking_rate: Perquintill::from_percent(25),
+			delegator_staking_rate: Perquintill::from_percent(25),
+			inflation: Perquintill::from_percent(10),
+			collator_reward_rate: Perquintill::from_percent(8),
+			delegator_reward_rate: Perquintill::from_percent(32),
+		}
+	);
+	assert_eq!(rates.stake_weights(), (Perquintill::from_percent(25), Perquintill::one()));
+	// above the ideal staking rate
+	assert_eq!(
+		inflation.rates(Perquintill::from_percent(25), Perquintill::from_percent(50)),
+		InflationRates {
+			collator_staking_rate: Perquintill::from_percent(25),
+			delegator_staking_rate: Perquintill::from_percent(50),
+			inflation: Perquintill::from_percent(6),
+			collator_reward_rate: Perquintill::from_perthousand(48),
+			delegator_reward_rate: Perquintill::from_perthousand(96),
+		}
+	);
+	// the collators' share of the stake matches their share of the inflation
+	let rates = inflation.rates(Perquintill::from_percent(20), Perquintill::from_percent(80));
+	assert_eq!(
+		rates,
+		InflationRates {
+			collator_staking_rate: Perquintill::from_percent(20),
+			delegator_staking_rate: Perquintill::from_percent(80),
+			inflation: Perquintill::from_percent(2),
+			collator_reward_rate: Perquintill::from_percent(2),
+			delegator_reward_rate: Perquintill::from_percent(2),
+		}
+	);
+	assert_eq!(rates.stake_weights(), (Perquintill::one(), Perquintill::one()));
+	assert_eq!(InflationRates::default().stake_weights(), (Perquintill::one(), Perquintill::one()));
+}
+
+#[test]
+fn set_inflation() {
+	ExtBuilder::default()
+		.with_balances(vec![
+			(1, 1000 * DECIMALS),
+			(2, 1000 * DECIMALS),
+			(3, 1000 * DECIMALS),
+			(4, 1000 * DECIMALS),
+		])
+		.with_collators(vec![(1, 500 * DECIMALS), (2, 500 * DECIMALS)])
+		.with_delegators(vec![(3, 1, 1000 * DECIMALS)])
+		.build_and_execute_with_sanity_tests(|| {
+			let inflation = InflationInfo::new(
+				Perquintill::from_percent(50),
+				Perquintill::from_percent(2),
+				Perquintill::from_percent(10),
+				Perquintill::from_percent(20),
+			);
+			let issuance = Balances::total_issuance();
+			assert_eq!(issuance, 4000 * DECIMALS);
+			let blocks_per_year = <Test as Config>::BLOCKS_PER_YEAR as Balance;
+
+			assert_noop!(
+				StakePallet::set_inflation(Origin::signed(1), Some(inflation)),
+				sp_runtime::DispatchError::BadOrigin
+			);
+			assert_noop!(
+				StakePallet::set_inflation(
+					Origin::root(),
+					Some(InflationInfo::new(
+						Perquintill::from_percent(50),
+						Perquintill::from_percent(10),
+						Perquintill::from_percent(2),
+						Perquintill::from_percent(20),
+					))
+				),
+				Error::<Test>::InvalidSchedule
+			);
+
+			// the rewards are derived from the curve immediately
+			let old_reward_per_block = StakePallet::reward_per_block();
+			assert_ok!(StakePallet::set_inflation(Origin::root(), Some(inflation)));
+			let reward_per_block = Perquintill::from_percent(10) * issuance / blocks_per_year;
+			assert_eq!(
+				events(),
+				vec![
+					Event::InflationConfigSet(Some(inflation)),
+					Event::RoundInflationSet(
+						Perquintill::from_percent(25),
+						Perquintill::from_percent(8),
+						Perquintill::from_percent(25),
+						Perquintill::from_percent(32),
+					),
+					Event::RewardPerBlockUpdated(old_reward_per_block, reward_per_block),
+				]
+			);
+			assert_eq!(StakePallet::inflation_config(), Some(inflation));
+			assert_eq!(
+				StakePallet::current_inflation(),
+				inflation.rates(Perquintill::from_percent(25), Perquintill::from_percent(25))
+			);
+			assert_eq!(StakePallet::reward_per_block(), reward_per_block);
+
+			// the rewards are recomputed at the start of the next round
+			assert_ok!(StakePallet::join_delegators(Origin::signed(4), 2, 1000 * DECIMALS));
+			roll_to(BLOCKS_PER_ROUND + 1, vec![]);
+			let old_reward_per_block = reward_per_block;
+			let reward_per_block = Perquintill::from_percent(6) * issuance / blocks_per_year;
+			assert!(events().contains(&Event::RoundInflationSet(
+				Perquintill::from_percent(25),
+				Perquintill::from_perthousand(48),
+				Perquintill::from_percent(50),
+				Perquintill::from_perthousand(96),
+			)));
+			assert!(events().contains(&Event::RewardPerBlockUpdated(old_reward_per_block, reward_per_block)));
+			assert_eq!(StakePallet::reward_per_block(), reward_per_block);
+
+			// without a curve, the rewards stay fixed
+			assert_ok!(StakePallet::set_inflation(Origin::root(), None));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::InflationConfigSet(None)));
+			assert_eq!(StakePallet::inflation_config(), None);
+			assert_eq!(StakePallet::current_inflation(), InflationRates::default());
+			assert_ok!(StakePallet::delegator_stake_less(Origin::signed(4), 2, 500 * DECIMALS));
+			roll_to(2 * BLOCKS_PER_ROUND + 1, vec![]);
+			assert_eq!(StakePallet::reward_per_block(), reward_per_block);
+		});
+}
+
+#[test]
+fn rewards_are_settled_with_snapshot_weights() {
+	ExtBuilder::default()
+		.with_balances(vec![
+			(1, 1000 * DECIMALS),
+			(2, 1000 * DECIMALS),
+			(3, 1000 * DECIMALS),
+			(4, 1000 * DECIMALS),
+		])
+		.with_collators(vec![(1, 500 * DECIMALS), (2, 500 * DECIMALS)])
+		.with_delegators(vec![(3, 1, 1000 * DECIMALS)])
+		.build_and_execute_with_sanity_tests(|| {
+			// the delegators earn a lower reward rate than the collators
+			let inflation = InflationInfo::new(
+				Perquintill::from_percent(50),
+				Perquintill::from_percent(2),
+				Perquintill::from_percent(10),
+				Perquintill::from_percent(80),
+			);
+			<crate::RewardPerBlock<Test>>::put(300);
+			roll_to(2, vec![None, Some(1)]);
+			assert_eq!(StakePallet::collator_block_rewards(1), 300);
+			let issuance = Balances::total_issuance();
+
+			// the delegator settles with equal weights
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(3)));
+			assert_eq!(Balances::total_issuance(), issuance + 200);
+
+			// changing the inflation settles the collator with the weights of the snapshot
+			assert_ok!(StakePallet::set_inflation(Origin::root(), Some(inflation)));
+			let weights = StakePallet::stake_snapshot(1).unwrap().weights;
+			assert_eq!(weights.collator, Perquintill::one());
+			assert!(weights.delegator < Perquintill::from_percent(30));
+			assert_eq!(StakePallet::rewards(&1), 100);
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(1)));
+			assert_eq!(Balances::total_issuance(), issuance + 300);
+
+			// the weights of the snapshot apply until the next snapshot
+			<crate::RewardPerBlock<Test>>::put(300);
+			roll_to(3, vec![None, Some(1), Some(1)]);
+			assert_eq!(StakePallet::collator_block_rewards(1), 600);
+			let issuance = Balances::total_issuance();
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(3)));
+			<crate::CurrentInflation<Test>>::put(InflationRates::default());
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(1)));
+			assert!(Balances::total_issuance() <= issuance + 300);
+			assert!(Balances::total_issuance() + 2 >= issuance + 300);
+		});
+}
+
+#[test]
+fn rewards_are_weighted_by_reward_rates() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100)])
+		.with_collators(vec![(1, 20), (2, 20)])
+		.with_delegators(vec![(3, 1, 20)])
+		.build_and_execute_with_sanity_tests(|| {
+			<crate::RewardPerBlock<Test>>::put(50);
+
+			// without a curve, the stakes are weighted equally
+			roll_to(2, vec![None, Some(1)]);
+			assert_eq!(StakePallet::pending_rewards(&1), 25);
+			assert_eq!(StakePallet::pending_rewards(&3), 25);
+
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(1)));
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(3)));
+
+			// the delegators' reward rate is four times the collators' one
+			let inflation = InflationInfo::new(
+				Perquintill::from_percent(50),
+				Perquintill::from_percent(2),
+				Perquintill::from_percent(10),
+				Perquintill::from_percent(20),
+			);
+			<crate::CurrentInflation<Test>>::put(
+				inflation.rates(Perquintill::from_percent(25), Perquintill::from_percent(25)),
+			);
+			roll_to(3, vec![None, None, Some(1)]);
+			assert_eq!(StakePallet::pending_rewards(&1), 10);
+			assert_eq!(StakePallet::pending_rewards(&3), 40);
+		});
+}
+
+#[test]
+fn delegation_pool() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (10, 100), (11, 100)])
+		.with_collators(vec![(1, 50), (2, 50)])
+		.build_and_execute_with_sanity_tests(|| {
+			let pool = StakePallet::pool_account(0);
+			assert_noop!(
+				StakePallet::create_pool(Origin::signed(10), 1, 4),
+				Error::<Test>::NomStakeBelowMin
+			);
+			assert_ok!(StakePallet::create_pool(Origin::signed(10), 1, 20));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::PoolCreated(0, 10, 1, 20)));
+			assert_eq!(StakePallet::next_pool_id(), 1);
+			assert_eq!(Balances::free_balance(&10), 80);
+			assert_eq!(StakePallet::delegator_state(&pool).unwrap().total, 20);
+			assert_eq!(StakePallet::pools(0).unwrap().members, 1);
+
+			// members can join with any amount
+			assert_noop!(
+				StakePallet::join_pool(Origin::signed(11), 1, 30),
+				Error::<Test>::PoolNotFound
+			);
+			assert_noop!(
+				StakePallet::join_pool(Origin::signed(11), 0, 0),
+				Error::<Test>::ValStakeZero
+			);
+			assert_ok!(StakePallet::join_pool(Origin::signed(11), 0, 1));
+			assert_ok!(StakePallet::join_pool(Origin::signed(11), 0, 29));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::PoolJoined(0, 11, 29, 50)));
+			assert_eq!(StakePallet::pool_members(0, &11).unwrap().points, 30);
+			assert_eq!(StakePallet::pools(0).unwrap().members, 2);
+			assert_eq!(StakePallet::delegator_state(&pool).unwrap().total, 50);
+			assert_eq!(StakePallet::candidate_pool(&1).unwrap().total, 100);
+
+			// the rewards of the pool are shared in proportion to the stake
+			<crate::RewardPerBlock<Test>>::put(100);
+			roll_to(6, vec![None, None, None, None, None, Some(1)]);
+			assert_eq!(StakePallet::pending_pool_rewards(0, &10), 20);
+			assert_eq!(StakePallet::pending_pool_rewards(0, &11), 30);
+			assert_ok!(StakePallet::claim_pool_rewards(Origin::signed(11), 0));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::PoolRewarded(0, 11, 30)));
+			assert_eq!(Balances::free_balance(&11), 100);
+			assert_noop!(
+				StakePallet::claim_pool_rewards(Origin::signed(11), 0),
+				Error::<Test>::RewardsNotFound
+			);
+
+			// the operator has to keep the minimum stake while there are other members
+			assert_noop!(
+				StakePallet::unbond_from_pool(Origin::signed(10), 0, 20),
+				Error::<Test>::PoolOperatorStakeBelowMin
+			);
+			assert_noop!(
+				StakePallet::unbond_from_pool(Origin::signed(10), 0, 16),
+				Error::<Test>::PoolOperatorStakeBelowMin
+			);
+			assert_noop!(
+				StakePallet::unbond_from_pool(Origin::signed(11), 0, 31),
+				Error::<Test>::Underflow
+			);
+
+			// unbonded funds can be withdrawn after the stake duration
+			assert_ok!(StakePallet::unbond_from_pool(Origin::signed(11), 0, 30));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::PoolUnbonded(0, 11, 30, 20)));
+			assert_eq!(StakePallet::delegator_state(&pool).unwrap().total, 20);
+			assert_noop!(
+				StakePallet::withdraw_from_pool(Origin::signed(11), 0),
+				Error::<Test>::PoolUnbondingNotFound
+			);
+			roll_to(8, vec![]);
+			assert_ok!(StakePallet::withdraw_from_pool(Origin::signed(11), 0));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::PoolWithdrawn(0, 11, 30)));
+			assert_eq!(Balances::free_balance(&11), 130);
+			assert!(StakePallet::pool_members(0, &11).is_none());
+			assert_eq!(StakePallet::pools(0).unwrap().members, 1);
+
+			// the pool is removed once the last member has withdrawn their funds
+			assert_ok!(StakePallet::unbond_from_pool(Origin::signed(10), 0, 20));
+			assert_eq!(Balances::free_balance(&10), 100);
+			assert!(StakePallet::delegator_state(&pool).is_none());
+			roll_to(10, vec![]);
+			assert_ok!(StakePallet::withdraw_from_pool(Origin::signed(10), 0));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::PoolDissolved(0)));
+			assert_eq!(Balances::free_balance(&10), 120);
+			assert_eq!(Balances::free_balance(&pool), 0);
+			assert!(StakePallet::pools(0).is_none());
+			assert!(StakePallet::pool_members(0, &10).is_none());
+		});
+}
+
+#[test]
+fn redelegate_pool() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (10, 100), (11, 100)])
+		.with_collators(vec![(1, 50), (2, 50)])
+		.build_and_execute_with_sanity_tests(|| {
+			let pool = StakePallet::pool_account(0);
+			assert_ok!(StakePallet::create_pool(Origin::signed(10), 1, 20));
+			assert_noop!(
+				StakePallet::redelegate_pool(Origin::signed(11), 0, 1, 2, 8),
+				Error::<Test>::NotPoolOperator
+			);
+			assert_ok!(StakePallet::redelegate_pool(Origin::signed(10), 0, 1, 2, 8));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::Redelegated(pool, 1, 2, 8, 62, 58)));
+
+			// new stake is added to the largest delegation
+			assert_ok!(StakePallet::join_pool(Origin::signed(11), 0, 10));
+			assert_eq!(StakePallet::candidate_pool(&1).unwrap().total, 72);
+			assert_eq!(StakePallet::candidate_pool(&2).unwrap().total, 58);
+
+			// unbonding starts with the largest delegation
+			assert_ok!(StakePallet::unbond_from_pool(Origin::signed(10), 0, 15));
+			assert_eq!(StakePallet::candidate_pool(&1).unwrap().total, 57);
+			assert_eq!(StakePallet::candidate_pool(&2).unwrap().total, 58);
+
+			// delegations are revoked if they are covered completely
+			assert_ok!(StakePallet::unbond_from_pool(Origin::signed(11), 0, 10));
+			assert_eq!(StakePallet::candidate_pool(&1).unwrap().total, 55);
+			assert_eq!(StakePallet::candidate_pool(&2).unwrap().total, 50);
+			let delegations = StakePallet::delegator_state(&pool).unwrap();
+			assert_eq!(delegations.total, 5);
+			assert_eq!(delegations.delegations.len(), 1);
+			assert_eq!(StakePallet::pools(0).unwrap().stake, 5);
+		});
+}
+
+#[test]
+fn delegation_pool_unbonding_is_batched() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (10, 100), (11, 100)])
+		.with_collators(vec![(1, 50), (2, 50)])
+		.build_and_execute_with_sanity_tests(|| {
+			let pool = StakePallet::pool_account(0);
+			assert_ok!(StakePallet::create_pool(Origin::signed(10), 1, 20));
+			assert_ok!(StakePallet::join_pool(Origin::signed(11), 0, 30));
+
+			// unbonding requests are rounded up to multiples of the stake duration
+			assert_ok!(StakePallet::unbond_from_pool(Origin::signed(11), 0, 1));
+			roll_to(2, vec![]);
+			assert_ok!(StakePallet::unbond_from_pool(Origin::signed(11), 0, 1));
+			let mut unstaking = BoundedBTreeMap::new();
+			assert_ok!(unstaking.try_insert(4, 2));
+			assert_eq!(StakePallet::unstaking(&pool), unstaking);
+			assert_eq!(StakePallet::pool_members(0, &11).unwrap().unbonding, unstaking);
+
+			roll_to(3, vec![]);
+			assert_ok!(StakePallet::unbond_from_pool(Origin::signed(11), 0, 1));
+			assert_ok!(unstaking.try_insert(6, 1));
+			assert_eq!(StakePallet::unstaking(&pool), unstaking);
+
+			// expired requests are freed before unbonding
+			roll_to(4, vec![]);
+			assert_ok!(StakePallet::unbond_from_pool(Origin::signed(11), 0, 1));
+			unstaking.remove(&4);
+			assert_ok!(unstaking.try_insert(6, 2));
+			assert_eq!(StakePallet::unstaking(&pool), unstaking);
+			assert_ok!(StakePallet::withdraw_from_pool(Origin::signed(11), 0));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::PoolWithdrawn(0, 11, 2)));
+			assert_eq!(StakePallet::pool_members(0, &11).unwrap().unbonding, unstaking);
+		});
+}
+
+#[test]
+fn delegation_pool_slashes_and_removed_collator() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000), (2, 1000), (3, 1000), (10, 100), (11, 100)])
+		.with_collators(vec![(1, 100), (2, 20), (3, 15)])
+		.build_and_execute_with_sanity_tests(|| {
+			OfflineThreshold::set(Perbill::from_percent(50));
+			DelegatorSlash::set(Perbill::from_percent(50));
+			let pool = StakePallet::pool_account(0);
+			assert_ok!(StakePallet::create_pool(Origin::signed(10), 2, 10));
+			assert_ok!(StakePallet::join_pool(Origin::signed(11), 0, 30));
+
+			// collator 2 does not author any blocks in the first session
+			roll_to(5, vec![None, Some(1), Some(1), Some(1), Some(1)]);
+			assert!(events().contains(&Event::Slashed(pool, 20)));
+			assert!(StakePallet::candidate_pool(&2).is_some());
+
+			// the members share the slash
+			let state = StakePallet::pools(0).unwrap();
+			assert_eq!(state.stake, 20);
+			assert_eq!(state.points, 40);
+			assert_eq!(state.stake_of(StakePallet::pool_members(0, &10).unwrap().points), 5);
+			assert_eq!(state.stake_of(StakePallet::pool_members(0, &11).unwrap().points), 15);
+			assert_noop!(
+				StakePallet::unbond_from_pool(Origin::signed(11), 0, 16),
+				Error::<Test>::Underflow
+			);
+
+			// new members receive points according to the slashed stake
+			assert_ok!(StakePallet::join_pool(Origin::signed(11), 0, 5));
+			assert_eq!(StakePallet::pool_members(0, &11).unwrap().points, 40);
+			assert_eq!(StakePallet::pools(0).unwrap().stake, 25);
+
+			// the pool does not delegate anymore once the collator has been removed
+			assert_ok!(StakePallet::force_remove_candidate(Origin::root(), 2));
+			assert!(StakePallet::delegator_state(&pool).is_none());
+			assert_noop!(
+				StakePallet::join_pool(Origin::signed(11), 0, 5),
+				Error::<Test>::PoolNotDelegating
+			);
+
+			// the operator delegates the undelegated stake again
+			assert_noop!(
+				StakePallet::redelegate_pool(Origin::signed(10), 0, 2, 1, 26),
+				Error::<Test>::PoolStakeUnavailable
+			);
+			assert_ok!(StakePallet::redelegate_pool(Origin::signed(10), 0, 2, 1, 20));
+			assert_eq!(StakePallet::delegator_state(&pool).unwrap().total, 20);
+			assert_ok!(StakePallet::redelegate_pool(Origin::signed(10), 0, 2, 1, 5));
+			assert_eq!(StakePallet::delegator_state(&pool).unwrap().total, 25);
+			assert!(StakePallet::unstaking(&pool).is_empty());
+			assert_ok!(StakePallet::join_pool(Origin::signed(11), 0, 5));
+			assert_eq!(StakePallet::candidate_pool(&1).unwrap().total, 130);
+		});
+}
+
+#[test]
+fn delegation_pool_is_dissolved_once_unlocked() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (10, 100)])
+		.with_collators(vec![(1, 50), (2, 50)])
+		.build_and_execute_with_sanity_tests(|| {
+			let pool = StakePallet::pool_account(0);
+			assert_ok!(StakePallet::create_pool(Origin::signed(10), 1, 20));
+			assert_ok!(StakePallet::unbond_from_pool(Origin::signed(10), 0, 20));
+
+			// some funds of the pool are still locked, e.g. because of a later forced removal
+			assert_ok!(Balances::transfer(Origin::signed(10), pool, 10));
+			let mut unstaking = StakePallet::unstaking(&pool);
+			assert_ok!(unstaking.try_insert(8, 10));
+			<crate::Unstaking<Test>>::insert(&pool, unstaking);
+			Balances::set_lock(STAKING_ID, &pool, 30, WithdrawReasons::all());
+
+			// the last member withdraws their funds but stays in the pool
+			roll_to(4, vec![]);
+			assert_ok!(StakePallet::withdraw_from_pool(Origin::signed(10), 0));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::PoolWithdrawn(0, 10, 20)));
+			assert_eq!(Balances::free_balance(&10), 90);
+			assert!(StakePallet::pool_members(0, &10).is_some());
+			assert_noop!(
+				StakePallet::withdraw_from_pool(Origin::signed(10), 0),
+				Error::<Test>::PoolUnbondingNotFound
+			);
+
+			// the pool is dissolved once nothing is locked anymore
+			roll_to(8, vec![]);
+			assert_ok!(StakePallet::withdraw_from_pool(Origin::signed(10), 0));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::PoolDissolved(0)));
+			assert_eq!(Balances::free_balance(&10), 100);
+			assert_eq!(Balances::free_balance(&pool), 0);
+			assert!(StakePallet::pools(0).is_none());
+		});
+}
+
+#[test]
+fn delegation_pool_defers_rewards_while_payouts_paused() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (10, 100), (11, 100)])
+		.with_collators(vec![(1, 50), (2, 50)])
+		.build_and_execute_with_sanity_tests(|| {
+			let pool = StakePallet::pool_account(0);
+			assert_ok!(StakePallet::create_pool(Origin::signed(10), 1, 20));
+			assert_ok!(StakePallet::join_pool(Origin::signed(11), 0, 30));
+			<crate::RewardPerBlock<Test>>::put(100);
+			roll_to(6, vec![None, None, None, None, None, Some(1)]);
+			let paused = PausedOperations {
+				payouts: true,
+				..Default::default()
+			};
+			assert_ok!(StakePallet::set_paused_operations(Origin::root(), paused));
+
+			// unbonding neither mints the pool's rewards nor pays out the member
+			let pool_balance = Balances::free_balance(&pool);
+			assert_ok!(StakePallet::unbond_from_pool(Origin::signed(11), 0, 30));
+			assert!(!events().iter().any(|e| matches!(e, Event::Rewarded(..) | Event::PoolRewarded(..))));
+			assert_eq!(Balances::free_balance(&pool), pool_balance);
+			assert_eq!(Balances::free_balance(&11), 70);
+			assert_eq!(StakePallet::pools(0).unwrap().pending_rewards, 50);
+			assert_eq!(StakePallet::pool_members(0, &11).unwrap().pending_rewards, 30);
+			assert_eq!(StakePallet::pending_pool_rewards(0, &11), 30);
+
+			// the member stays in the pool until the deferred rewards are paid out
+			roll_to(8, vec![]);
+			assert_ok!(StakePallet::withdraw_from_pool(Origin::signed(11), 0));
+			assert_eq!(Balances::free_balance(&11), 100);
+			assert!(StakePallet::pool_members(0, &11).is_some());
+			assert_eq!(StakePallet::pools(0).unwrap().members, 2);
+			assert_noop!(
+				StakePallet::claim_pool_rewards(Origin::signed(11), 0),
+				Error::<Test>::OperationPaused
+			);
+
+			// once payouts are resumed, the rewards are minted and paid out
+			assert_ok!(StakePallet::set_paused_operations(
+				Origin::root(),
+				PausedOperations::default()
+			));
+			assert_ok!(StakePallet::claim_pool_rewards(Origin::signed(11), 0));
+			assert!(events().contains(&Event::Rewarded(pool, 50)));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::PoolRewarded(0, 11, 30)));
+			assert_eq!(Balances::free_balance(&11), 130);
+			assert!(StakePallet::pool_members(0, &11).is_none());
+			assert_eq!(StakePallet::pools(0).unwrap().members, 1);
+			assert!(StakePallet::pools(0).unwrap().pending_rewards.is_zero());
+			assert_eq!(StakePallet::pending_pool_rewards(0, &10), 20);
+		});
+}
+
+#[test]
+fn delegator_waitlist() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100), (6, 100), (7, 100), (8, 100)])
+		.with_collators(vec![(1, 50)])
+		.with_delegators(vec![(2, 1, 10), (3, 1, 10), (4, 1, 10), (5, 1, 10)])
+		.with_waitlist_size(2)
+		.build_and_execute_with_sanity_tests(|| {
+			let staking_lock = |amount| {
+				vec![BalanceLock {
+					id: STAKING_ID,
+					amount,
+					reasons: Reasons::All,
+				}]
+			};
+
+			// 6 does not stake more than the lowest delegator and has to wait
+			assert_ok!(StakePallet::join_delegators(Origin::signed(6), 1, 10));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::DelegatorWaitlisted(6, 1, 10)));
+			assert!(StakePallet::delegator_state(6).is_none());
+			assert_eq!(StakePallet::waitlist(1).into_inner(), vec![6]);
+			assert_eq!(StakePallet::waitlisted(6, 1), Some(10));
+			assert_eq!(Balances::locks(6), staking_lock(10));
+			assert_eq!(StakePallet::candidate_pool(1).unwrap().total, 90);
+			assert_noop!(
+				StakePallet::join_delegators(Origin::signed(6), 1, 10),
+				Error::<Test>::AlreadyWaitlisted
+			);
+
+			// 7 replaces 5 which waits instead of unstaking
+			assert_ok!(StakePallet::join_delegators(Origin::signed(7), 1, 20));
+			assert!(events().contains(&Event::DelegatorWaitlisted(5, 1, 10)));
+			assert!(StakePallet::delegator_state(5).is_none());
+			assert!(StakePallet::unstaking(5).is_empty());
+			assert_eq!(Balances::locks(5), staking_lock(10));
+			assert_eq!(StakePallet::waitlist(1).into_inner(), vec![6, 5]);
+			assert_eq!(StakePallet::candidate_pool(1).unwrap().total, 100);
+
+			// the waitlist is full
+			assert_noop!(
+				StakePallet::join_delegators(Origin::signed(8), 1, 5),
+				Error::<Test>::TooManyDelegators
+			);
+
+			// 6 takes the slot of 2
+			assert_ok!(StakePallet::revoke_delegation(Origin::signed(2), 1));
+			assert!(events().contains(&Event::DelegatorPromoted(6, 1, 10, 100)));
+			assert_eq!(StakePallet::delegator_state(6).unwrap().total, 10);
+			assert_eq!(StakePallet::waitlisted(6, 1), None);
+			assert_eq!(Balances::locks(6), staking_lock(10));
+			assert_eq!(StakePallet::waitlist(1).into_inner(), vec![5]);
+
+			// 5 replaces 3 which reduced their stake below the waiting amount
+			assert_ok!(StakePallet::delegator_stake_less(Origin::signed(3), 1, 5));
+			assert!(events().contains(&Event::DelegationReplaced(5, 10, 3, 5, 1, 100)));
+			assert!(events().contains(&Event::DelegatorPromoted(5, 1, 10, 100)));
+			assert!(events().contains(&Event::DelegatorWaitlisted(3, 1, 5)));
+			assert!(StakePallet::delegator_state(3).is_none());
+			assert_eq!(StakePallet::delegator_state(5).unwrap().total, 10);
+			assert_eq!(StakePallet::waitlist(1).into_inner(), vec![3]);
+			assert_eq!(StakePallet::candidate_pool(1).unwrap().total, 100);
+			assert_eq!(Balances::locks(3), staking_lock(10));
+
+			// leaving the waitlist unlocks the funds immediately
+			assert_ok!(StakePallet::leave_waitlist(Origin::signed(3), 1));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::WaitlistLeft(3, 1, 5)));
+			assert!(StakePallet::waitlist(1).is_empty());
+			assert_eq!(Balances::locks(3), staking_lock(5));
+			assert_noop!(
+				StakePallet::leave_waitlist(Origin::signed(3), 1),
+				Error::<Test>::NotWaitlisted
+			);
+		});
+}
+
+#[test]
+fn bond_for() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 1), (4, 1), (5, 100)])
+		.with_collators(vec![(1, 50)])
+		.build_and_execute_with_sanity_tests(|| {
+			let staking_lock = |amount| {
+				vec![BalanceLock {
+					id: STAKING_ID,
+					amount,
+					reasons: Reasons::All,
+				}]
+			};
+			set_session_keys(3);
+			assert_noop!(
+				StakePallet::bond_candidate_for(Origin::signed(2), 2, 20),
+				Error::<Test>::CannotFundSelf
+			);
+			assert_noop!(
+				StakePallet::bond_candidate_for(Origin::signed(2), StakePallet::pool_account(0), 20),
+				Error::<Test>::CannotFundPool
+			);
+
+			// 3 has to accept 2 as their funder first
+			assert_noop!(
+				StakePallet::bond_candidate_for(Origin::signed(2), 3, 20),
+				Error::<Test>::FunderNotAccepted
+			);
+			assert_noop!(
+				StakePallet::accept_funder(Origin::signed(3), Some(3)),
+				Error::<Test>::CannotFundSelf
+			);
+			assert_ok!(StakePallet::accept_funder(Origin::signed(3), Some(2)));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::FunderAccepted(3, Some(2))));
+			assert_eq!(StakePallet::accepted_funder(3), Some(2));
+			assert_noop!(
+				StakePallet::bond_candidate_for(Origin::signed(5), 3, 20),
+				Error::<Test>::FunderNotAccepted
+			);
+			assert_noop!(
+				StakePallet::bond_candidate_for(Origin::signed(2), 3, 101),
+				BalancesError::<Test>::InsufficientBalance
+			);
+
+			// 2 funds the candidacy of 3 with funds which stay locked on its own account
+			assert_ok!(StakePallet::bond_candidate_for(Origin::signed(2), 3, 20));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::StakeFunded(2, 3, 20)));
+			assert_eq!(StakePallet::candidate_pool(3).unwrap().stake, 20);
+			assert_eq!(StakePallet::funder(3), Some((2, 20)));
+			assert_eq!(StakePallet::funded_stake(2), 20);
+			assert_eq!(Balances::free_balance(2), 100);
+			assert_eq!(Balances::locks(2), staking_lock(20));
+			assert_eq!(Balances::free_balance(3), 1);
+			assert_eq!(Balances::locks(3), vec![]);
+
+			// another funder cannot be accepted while the stake is funded by 2
+			assert_ok!(StakePallet::accept_funder(Origin::signed(3), Some(5)));
+			assert_noop!(
+				StakePallet::bond_candidate_for(Origin::signed(5), 3, 10),
+				Error::<Test>::FunderMismatch
+			);
+			assert_ok!(StakePallet::accept_funder(Origin::signed(3), Some(2)));
+			assert_noop!(
+				StakePallet::bond_candidate_for(Origin::signed(2), 3, 81),
+				BalancesError::<Test>::InsufficientBalance
+			);
+			assert_ok!(StakePallet::bond_candidate_for(Origin::signed(2), 3, 10));
+			assert_eq!(StakePallet::candidate_pool(3).unwrap().stake, 30);
+			assert_eq!(StakePallet::funder(3), Some((2, 30)));
+			assert_eq!(Balances::locks(2), staking_lock(30));
+
+			// unlocked funds are released to the funder
+			assert_ok!(StakePallet::candidate_stake_less(Origin::signed(3), 15));
+			assert_noop!(
+				StakePallet::bond_candidate_for(Origin::signed(2), 3, 5),
+				Error::<Test>::FundingWhileUnstaking
+			);
+			roll_to(3, vec![]);
+			assert_ok!(StakePallet::unlock_unstaked(Origin::signed(3), 3));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::FundsReturned(3, 2, 15)));
+			assert_eq!(StakePallet::funder(3), Some((2, 15)));
+			assert_eq!(StakePallet::funded_stake(2), 15);
+			assert_eq!(Balances::locks(2), staking_lock(15));
+			assert_eq!(Balances::locks(3), vec![]);
+
+			// 5 funds the delegations of 4
+			assert_ok!(StakePallet::accept_funder(Origin::signed(4), Some(5)));
+			assert_ok!(StakePallet::bond_delegator_for(Origin::signed(5), 4, 1, 10));
+			assert_eq!(StakePallet::delegator_state(4).unwrap().total, 10);
+			assert_ok!(StakePallet::bond_delegator_for(Origin::signed(5), 4, 1, 5));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::StakeFunded(5, 4, 5)));
+			assert_eq!(StakePallet::delegator_state(4).unwrap().total, 15);
+			assert_eq!(StakePallet::funder(4), Some((5, 15)));
+			assert_eq!(Balances::free_balance(5), 100);
+			assert_eq!(Balances::locks(5), staking_lock(15));
+
+			// the funding is removed once the principal has been released
+			assert_ok!(StakePallet::revoke_delegation(Origin::signed(4), 1));
+			roll_to(5, vec![]);
+			assert_ok!(StakePallet::unlock_unstaked(Origin::signed(4), 4));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::FundsReturned(4, 5, 15)));
+			assert_eq!(StakePallet::funder(4), None);
+			assert_eq!(StakePallet::funded_stake(5), 0);
+			assert_eq!(Balances::locks(5), vec![]);
+			assert_eq!(Balances::free_balance(4), 1);
+			assert_eq!(Balances::locks(4), vec![]);
+
+			// the acceptance can be revoked
+			assert_ok!(StakePallet::accept_funder(Origin::signed(4), None));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::FunderAccepted(4, None)));
+			assert_eq!(StakePallet::accepted_funder(4), None);
+			assert_noop!(
+				StakePallet::bond_delegator_for(Origin::signed(5), 4, 1, 10),
+				Error::<Test>::FunderNotAccepted
+			);
+		});
+}
+
+#[test]
+fn funded_stake_is_slashed_from_funder() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000), (2, 1000), (3, 1000), (4, 1), (5, 100)])
+		.with_collators(vec![(1, 100), (2, 20), (3, 15)])
+		.build_and_execute_with_sanity_tests(|| {
+			OfflineThreshold::set(Perbill::from_percent(50));
+			DelegatorSlash::set(Perbill::from_percent(50));
+			assert_ok!(StakePallet::accept_funder(Origin::signed(4), Some(5)));
+			assert_ok!(StakePallet::bond_delegator_for(Origin::signed(5), 4, 2, 10));
+
+			// collator 2 does not author any blocks in the first session
+			roll_to(5, vec![None, Some(1), Some(1), Some(1), Some(1)]);
+			assert!(events().contains(&Event::Slashed(4, 5)));
+			assert_eq!(StakePallet::delegator_state(4).unwrap().total, 5);
+			assert_eq!(StakePallet::funder(4), Some((5, 5)));
+			assert_eq!(StakePallet::funded_stake(5), 5);
+			assert_eq!(Balances::free_balance(4), 1);
+			assert_eq!(Balances::free_balance(5), 95);
+			assert_eq!(
+				Balances::locks(5),
+				vec![BalanceLock {
+					id: STAKING_ID,
+					amount: 5,
+					reasons: Reasons::All,
+				}]
+			);
+		});
+}
+
+#[test]
+fn loyalty_bonus() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000), (2, 1000), (3, 1000), (4, 1000), (5, 1000)])
+		.with_collators(vec![(1, 200), (5, 200)])
+		.with_delegators(vec![(2, 1, 100), (3, 1, 100)])
+		.build_and_execute_with_sanity_tests(|| {
+			let loyalty = LoyaltyInfo::new(Perbill::from_percent(40), 4);
+			assert_noop!(
+				StakePallet::set_loyalty_bonus(Origin::signed(1), Some(loyalty)),
+				sp_runtime::DispatchError::BadOrigin
+			);
+			assert_noop!(
+				StakePallet::set_loyalty_bonus(Origin::root(), Some(LoyaltyInfo::new(Perbill::from_percent(40), 0))),
+				Error::<Test>::InvalidLoyaltyConfig
+			);
+			assert_ok!(StakePallet::set_loyalty_bonus(Origin::root(), Some(loyalty)));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::LoyaltyConfigSet(Some(loyalty))));
+			assert_eq!(StakePallet::delegation_age(2, 1), 0);
+
+			// new delegations do not earn a bonus
+			<crate::RewardPerBlock<Test>>::put(400);
+			StakePallet::note_author(1);
+			assert_eq!(StakePallet::collator_block_rewards(1), 400);
+			assert_eq!(StakePallet::pending_rewards(&2), 100);
+
+			// the bonus increases the share of the delegations in the rewards of the collator
+			roll_to(2 * BLOCKS_PER_ROUND, vec![]);
+			assert_eq!(StakePallet::round().current, 2);
+			<crate::RewardPerBlock<Test>>::put(220);
+			StakePallet::note_author(1);
+			assert_eq!(StakePallet::collator_block_rewards(1), 620);
+			assert_eq!(StakePallet::pending_rewards(&1), 200 + 100);
+			assert_eq!(StakePallet::pending_rewards(&2), 100 + 60);
+			assert_eq!(StakePallet::pending_rewards(&3), 100 + 60);
+
+			// reducing a delegation reduces its age proportionally
+			assert_ok!(StakePallet::delegator_stake_less(Origin::signed(3), 1, 50));
+			assert_eq!(StakePallet::rewards(&3), 160);
+			assert!(events().contains(&Event::BlockRewardSplit(
+				1,
+				RewardSplit {
+					collator: 100,
+					delegators: 120,
+					loyalty_bonus: 20,
+					skipped: 0,
+					dust: 0,
+					excess: 0,
+				}
+			)));
+			assert_eq!(StakePallet::delegation_age(3, 1), 1);
+
+			// increasing a delegation averages its age over the added stake
+			assert_ok!(StakePallet::delegator_stake_more(Origin::signed(2), 1, 100));
+			assert_eq!(StakePallet::rewards(&2), 160);
+			assert_eq!(StakePallet::delegation_age(2, 1), 1);
+
+			// the age of new delegations starts at the current round
+			assert_ok!(StakePallet::join_delegators(Origin::signed(4), 1, 50));
+			assert_eq!(StakePallet::delegation_age(4, 1), 2);
+			assert_eq!(StakePallet::candidate_pool(1).unwrap().total, 500);
+
+			// 2 and 3 have a stake age of 3 rounds and 4 of 2 rounds
+			roll_to(4 * BLOCKS_PER_ROUND, vec![]);
+			<crate::RewardPerBlock<Test>>::put(468);
+			StakePallet::note_author(1);
+			assert_eq!(StakePallet::collator_block_rewards(1), 1088);
+			assert_eq!(StakePallet::pending_rewards(&2), 160 + 208);
+			assert_eq!(StakePallet::pending_rewards(&3), 160 + 52);
+			assert_eq!(StakePallet::pending_rewards(&4), 48);
+
+			// the bonus is capped once the delegation has matured
+			roll_to(8 * BLOCKS_PER_ROUND, vec![]);
+			<crate::RewardPerBlock<Test>>::put(496);
+			StakePallet::note_author(1);
+			assert_eq!(StakePallet::collator_block_rewards(1), 1584);
+			assert_eq!(StakePallet::pending_rewards(&2), 368 + 224);
+			assert_eq!(StakePallet::pending_rewards(&3), 212 + 56);
+			assert_eq!(StakePallet::pending_rewards(&4), 48 + 56);
+
+			// the rewards earned so far are settled with the previous bonus
+			assert_eq!(StakePallet::rewards(&2), 368);
+			assert_ok!(StakePallet::set_loyalty_bonus(Origin::root(), None));
+			assert_eq!(StakePallet::rewards(&2), 368 + 224);
+			<crate::RewardPerBlock<Test>>::put(500);
+			StakePallet::note_author(1);
+			assert_eq!(StakePallet::pending_rewards(&2), 592 + 200);
+
+			// revoking a delegation clears its age
+			assert_ok!(StakePallet::revoke_delegation(Origin::signed(4), 1));
+			assert!(!<crate::DelegationAge<Test>>::contains_key(4, 1));
+		});
+}
+
+#[test]
+fn loyalty_stake_age_of_rebonded_and_restaked_funds() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000), (2, 1000), (3, 1000), (5, 1000)])
+		.with_collators(vec![(1, 200), (5, 200)])
+		.with_delegators(vec![(2, 1, 100), (3, 1, 100)])
+		.build_and_execute_with_sanity_tests(|| {
+			roll_to(4 * BLOCKS_PER_ROUND, vec![]);
+			assert_eq!(StakePallet::round().current, 4);
+
+			// rebonding averages the age over the rebonded stake
+			assert_ok!(StakePallet::delegator_stake_less(Origin::signed(2), 1, 50));
+			assert_eq!(StakePallet::delegation_age(2, 1), 2);
+			assert_ok!(StakePallet::rebond(Origin::signed(2), 1, 50));
+			assert_eq!(StakePallet::delegation_age(2, 1), 3);
+
+			// restaking rewards averages the age over the restaked rewards
+			assert_ok!(StakePallet::set_auto_compound(
+				Origin::signed(3),
+				1,
+				Percent::from_percent(100)
+			));
+			<crate::RewardPerBlock<Test>>::put(400);
+			StakePallet::note_author(1);
+			assert_eq!(StakePallet::pending_rewards(&3), 100);
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(3)));
+			assert_eq!(StakePallet::delegator_state(3).unwrap().total, 200);
+			assert_eq!(StakePallet::delegation_age(3, 1), 2);
+		});
+}
+
+#[test]
+fn migrate_stake_age() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000), (2, 1000), (3, 1000), (5, 1000)])
+		.with_collators(vec![(1, 100), (5, 100)])
+		.with_delegators(vec![(2, 1, 100), (3, 1, 100)])
+		.build_and_execute_with_sanity_tests(|| {
+			roll_to(3 * BLOCKS_PER_ROUND, vec![]);
+			let _ = <crate::DelegationAge<Test>>::clear(u32::MAX, None);
+			StorageVersion::new(7).put::<StakePallet>();
+
+			crate::migrations::v8::MigrateToV8::<Test>::on_runtime_upgrade();
+			assert_eq!(StakePallet::on_chain_storage_version(), StorageVersion::new(8));
+			assert_eq!(StakePallet::delegation_age(2, 1), 3);
+			assert_eq!(StakePallet::delegation_age(3, 1), 3);
+			assert_eq!(StakePallet::oldest_round_stats(), 3);
+
+			// the migration is only applied once
+			roll_to(4 * BLOCKS_PER_ROUND, vec![]);
+			crate::migrations::v8::MigrateToV8::<Test>::on_runtime_upgrade();
+			assert_eq!(StakePallet::delegation_age(2, 1), 3);
+		});
+}
+
+#[test]
+fn invulnerables() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 1000), (2, 1000), (3, 1000), (4, 1000), (5, 1000)])
+		.with_collators(vec![(1, 200), (2, 100), (3, 50)])
+		.with_delegators(vec![(5, 3, 50)])
+		.with_invulnerables(vec![4])
+		.build_and_execute_with_sanity_tests(|| {
+			// invulnerables are part of the validator set without being staked
+			assert_eq!(StakePallet::invulnerables().into_inner(), vec![4]);
+			assert_eq!(StakePallet::selected_candidates().into_inner(), vec![1, 2]);
+			assert_eq!(StakePallet::next_session_collators().into_inner(), vec![4, 1]);
+			assert_eq!(Session::validators(), vec![4, 1]);
+
+			assert_noop!(
+				StakePallet::add_invulnerable(Origin::signed(1), 3),
+				sp_runtime::DispatchError::BadOrigin
+			);
+			assert_noop!(
+				StakePallet::add_invulnerable(Origin::root(), 4),
+				Error::<Test>::AlreadyInvulnerable
+			);
+			assert_ok!(StakePallet::add_invulnerable(Origin::root(), 3));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::InvulnerableAdded(3)));
+
+			// invulnerables count against the number of selected candidates
+			assert_noop!(
+				StakePallet::add_invulnerable(Origin::root(), 2),
+				Error::<Test>::TooManyInvulnerables
+			);
+			assert_eq!(StakePallet::next_session_collators().into_inner(), vec![4, 3]);
+			roll_to(10, vec![]);
+			assert_eq!(Session::validators(), vec![4, 3]);
+
+			// invulnerables are not rewarded unless configured
+			assert_ok!(StakePallet::set_rewards_per_block(Origin::root(), 10));
+			assert_eq!(StakePallet::estimate_rewards(&3, 10).unwrap().per_block, 0);
+			assert_eq!(StakePallet::estimate_rewards(&1, 10).unwrap().per_block, 0);
+			StakePallet::note_author(3);
+			assert_eq!(StakePallet::collator_block_rewards(3), 0);
+			RewardInvulnerables::set(true);
+			StakePallet::note_author(3);
+			assert_eq!(StakePallet::collator_block_rewards(3), 10);
+
+			assert_noop!(
+				StakePallet::remove_invulnerable(Origin::signed(1), 3),
+				sp_runtime::DispatchError::BadOrigin
+			);
+			assert_ok!(StakePallet::remove_invulnerable(Origin::root(), 3));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::InvulnerableRemoved(3)));
+			assert_noop!(
+				StakePallet::remove_invulnerable(Origin::root(), 3),
+				Error::<Test>::NotInvulnerable
+			);
+			assert_eq!(StakePallet::next_session_collators().into_inner(), vec![4, 1]);
+		});
+}
 
-			assert_eq!(Balances::usable_balance(&1), user_1);
-			assert_eq!(Balances::usable_balance(&2), user_2);
-			assert_eq!(Balances::usable_balance(&3), user_3);
-			assert_eq!(Balances::usable_balance(&4), user_4);
-			assert_eq!(Balances::usable_balance(&5), user_5);
+#[test]
+fn join_candidates_requires_session_keys_and_identity() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100)])
+		.with_collators(vec![(1, 100), (2, 50)])
+		.build_and_execute_with_sanity_tests(|| {
+			assert_noop!(
+				StakePallet::join_candidates(Origin::signed(3), 100),
+				Error::<Test>::SessionKeysNotSet
+			);
 
-			// 1 is block author for 1st block
-			roll_to(2, authors.clone());
-			assert_eq!(Balances::usable_balance(&1), user_1 + reward_1);
-			assert_eq!(Balances::usable_balance(&2), user_2);
-			assert_eq!(Balances::usable_balance(&3), user_3 + reward_3);
-			assert_eq!(Balances::usable_balance(&4), user_4 + reward_4);
-			assert_eq!(Balances::usable_balance(&5), user_5);
+			set_session_keys(3);
+			UnverifiedIdentities::set(vec![3]);
+			assert_noop!(
+				StakePallet::join_candidates(Origin::signed(3), 100),
+				Error::<Test>::IdentityNotVerified
+			);
+			UnverifiedIdentities::set(vec![]);
+			assert_ok!(StakePallet::join_candidates(Origin::signed(3), 100));
+			assert_eq!(StakePallet::next_session_collators().into_inner(), vec![1, 3]);
 
-			// 1 is block author for 2nd block
-			roll_to(3, authors.clone());
-			assert_eq!(Balances::usable_balance(&1), user_1 + reward_1 * 2);
-			assert_eq!(Balances::usable_balance(&2), user_2);
-			assert_eq!(Balances::usable_balance(&3), user_3 + reward_3 * 2);
-			assert_eq!(Balances::usable_balance(&4), user_4 + reward_4 * 2);
-			assert_eq!(Balances::usable_balance(&5), user_5);
+			// candidates which purged their keys are skipped in the selection
+			assert_ok!(Session::purge_keys(Origin::signed(3)));
+			assert_eq!(StakePallet::selected_candidates().into_inner(), vec![1, 3]);
+			assert_eq!(StakePallet::next_session_collators().into_inner(), vec![1, 2]);
+			roll_to(5, vec![]);
+			assert!(events().contains(&Event::CandidateWithoutSessionKeys(3, 2)));
+			roll_to(10, vec![]);
+			assert_eq!(Session::validators(), vec![1, 2]);
+		});
+}
 
-			// 1 is block author for 3rd block
-			roll_to(4, authors.clone());
-			assert_eq!(Balances::usable_balance(&1), user_1 + reward_1 * 3);
-			assert_eq!(Balances::usable_balance(&2), user_2 );
-			assert_eq!(Balances::usable_balance(&3), user_3 + reward_3 * 3);
-			assert_eq!(Balances::usable_balance(&4), user_4 + reward_4 * 3);
-			assert_eq!(Balances::usable_balance(&5), user_5);
+#[test]
+fn reward_remainder() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100)])
+		.with_collators(vec![(1, 60), (2, 60)])
+		.with_delegators(vec![(3, 1, 36), (4, 2, 10)])
+		.build_and_execute_with_sanity_tests(|| {
+			// the delegation of 4 to 1 is below MinRewardedDelegation and rewarded
+			// from the next round on
+			assert_ok!(StakePallet::delegate_another_candidate(Origin::signed(4), 1, 4));
+			roll_to(6, vec![]);
+			<crate::RewardPerBlock<Test>>::put(100);
+			let mut authors = vec![None; 6];
+			authors.resize(13, Some(1));
+			let split = |collator, delegators, skipped, dust| RewardSplit {
+				collator,
+				delegators,
+				loyalty_bonus: 0,
+				skipped,
+				dust,
+				excess: 0,
+			};
 
-			// 2 is block author for 4th block
-			roll_to(5, authors.clone());
-			assert_eq!(Balances::usable_balance(&1), user_1 + reward_1 * 3);
-			assert_eq!(Balances::usable_balance(&2), user_2 + reward_2);
-			assert_eq!(Balances::usable_balance(&3), user_3 + reward_3 * 3);
-			assert_eq!(Balances::usable_balance(&4), user_4 + reward_4 * 3);
-			assert_eq!(Balances::usable_balance(&5), user_5 + reward_5);
-			assert_ok!(StakePallet::revoke_delegation(Origin::signed(5), 2));
+			// the rewards are split once the stakers are settled and the remainder is
+			// issued to the beneficiary
+			let issuance = Balances::total_issuance();
+			roll_to(7, authors.clone());
+			assert!(events().contains(&Event::BlockRewarded(1, split(60, 36, 4, 0))));
+			assert!(!events().iter().any(|e| matches!(e, Event::BlockRewardSplit(..))));
+			assert_ok!(StakePallet::set_commission(Origin::signed(1), Perbill::zero()));
+			assert!(events().contains(&Event::BlockRewardSplit(1, split(60, 36, 4, 0))));
+			assert_eq!(StakePallet::pending_rewards(&1), 60);
+			assert_eq!(StakePallet::pending_rewards(&3), 36);
+			assert_eq!(StakePallet::pending_rewards(&4), 0);
+			assert_eq!(Balances::total_issuance(), issuance);
+
+			// the remainder is credited to the author
+			RewardRemainder::set(RemainderDestination::Collator);
+			roll_to(8, authors.clone());
+			assert_ok!(StakePallet::set_commission(Origin::signed(1), Perbill::zero()));
+			assert_eq!(
+				events().iter().filter(|e| **e == Event::BlockRewardSplit(1, split(60, 36, 4, 0))).count(),
+				2
+			);
+			assert_eq!(StakePallet::rewards(&1), 124);
+			assert_eq!(StakePallet::pending_rewards(&3), 72);
 
-            // since it is removed from delegation
-            let new_reward_5 = 0;
-            // since collator is only left
-            let new_reward_2 = 69405885616000; // not exact as reward_per_block. Some precision lost
+			// the skipped share is redistributed and the dust which has not been settled
+			// for the stakers is credited to the author
+			RewardRemainder::set(RemainderDestination::Stakers);
+			roll_to(13, authors);
+			assert_eq!(
+				events().iter().filter(|e| **e == Event::BlockRewarded(1, split(62, 37, 0, 1))).count(),
+				3
+			);
+			assert!(events().contains(&Event::BlockRewardSplit(1, split(125, 75, 0, 0))));
+			assert_eq!(StakePallet::unsplit_block_rewards(1).round, 2);
+			assert_ok!(StakePallet::set_commission(Origin::signed(1), Perbill::zero()));
+			assert!(events().contains(&Event::BlockRewardSplit(1, split(187, 112, 0, 1))));
+			assert_eq!(StakePallet::rewards(&1), 124 + 125 + 188);
+			assert_eq!(StakePallet::pending_rewards(&3), 72 + 75 + 112);
+			assert_eq!(StakePallet::pending_rewards(&4), 0);
 
-			// 2 is block author for 5th block
-			roll_to(6, authors);
-			assert_eq!(Balances::usable_balance(&1), user_1 + reward_1 * 3);
-			assert_eq!(Balances::usable_balance(&2), user_2 + reward_2 + new_reward_2);
-			assert_eq!(Balances::usable_balance(&3), user_3 + reward_3 * 3);
-			assert_eq!(Balances::usable_balance(&4), user_4 + reward_4 * 3);
-			// should not receive rewards due to revoked delegation
-			assert_eq!(Balances::usable_balance(&5), user_5 + reward_5 + new_reward_5);
+			// the stakers and the remainder receive exactly the earned block rewards
+			assert_eq!(
+				StakePallet::pending_rewards(&1) + StakePallet::pending_rewards(&3) + 4,
+				7 * 100
+			);
+			assert_eq!(
+				StakePallet::collator_round_stats(1, 1),
+				CollatorStats {
+					blocks_authored: 4,
+					collator_rewards: 60 + 60 + 4 + 62 + 62,
+					delegator_rewards: 36 + 36 + 37 + 37,
+				}
+			);
+			assert_eq!(
+				StakePallet::collator_round_stats(2, 1),
+				CollatorStats {
+					blocks_authored: 3,
+					collator_rewards: 3 * 62 + 1,
+					delegator_rewards: 3 * 37,
+				}
+			);
 		});
 }
 
 #[test]
-fn delegator_should_not_receive_rewards_after_revoking() {
-	// test edge case of 1 delegator
+fn round_stats() {
 	ExtBuilder::default()
-		.with_balances(vec![(1, 10_000_000 * DECIMALS), (2, 10_000_000 * DECIMALS)])
-		.with_collators(vec![(1, 10_000_000 * DECIMALS)])
-		.with_delegators(vec![(2, 1, 10_000_000 * DECIMALS)])
-		.with_inflation(10, 15, 40, 15, 5)
-		.build()
-		.execute_with(|| {
-            <crate::RewardPerBlock<Test>>::put(3_000_000 * DECIMALS);
+		.with_balances(vec![(1, 1000), (2, 1000), (3, 1000)])
+		.with_collators(vec![(1, 100), (2, 100)])
+		.with_delegators(vec![(3, 1, 100)])
+		.build_and_execute_with_sanity_tests(|| {
+			<crate::RewardPerBlock<Test>>::put(40);
+			assert_eq!(
+				StakePallet::round_total_stake(0),
+				Some(TotalStake {
+					collators: 200,
+					delegators: 100,
+				})
+			);
 
-			assert_ok!(StakePallet::revoke_delegation(Origin::signed(2), 1));
-			let authors: Vec<Option<AccountId>> = (1u64..100u64).map(|_| Some(1u64)).collect();
-			assert_eq!(Balances::usable_balance(&1), Balance::zero());
-			assert_eq!(Balances::usable_balance(&2), Balance::zero());
-			roll_to(100, authors);
-			assert!(Balances::usable_balance(&1) > Balance::zero());
-			assert_ok!(StakePallet::unlock_unstaked(Origin::signed(2), 2));
-			assert_eq!(Balances::usable_balance(&2), 10_000_000 * DECIMALS);
-		});
+			roll_to(4, vec![None, Some(1), Some(2), Some(1)]);
+			assert_eq!(
+				StakePallet::collator_round_stats(0, 1),
+				CollatorStats {
+					blocks_authored: 2,
+					collator_rewards: 40,
+					delegator_rewards: 40,
+				}
+			);
+			let mut stats = StakePallet::round_stats(0).unwrap();
+			stats.collators.sort_by_key(|c| c.collator);
+			assert_eq!(
+				stats,
+				RoundStats {
+					round: 0,
+					collator_stake: 200,
+					delegator_stake: 100,
+					collators: vec![
+						CollatorRoundStats {
+							collator: 1,
+							blocks_authored: 2,
+							collator_rewards: 40,
+							delegator_rewards: 40,
+						},
+						CollatorRoundStats {
+							collator: 2,
+							blocks_authored: 1,
+							collator_rewards: 40,
+							delegator_rewards: 0,
+						},
+					],
+				}
+			);
 
-	ExtBuilder::default()
-		.with_balances(vec![
-			(1, 10_000_000 * DECIMALS),
-			(2, 10_000_000 * DECIMALS),
-			(3, 10_000_000 * DECIMALS),
-		])
-		.with_collators(vec![(1, 10_000_000 * DECIMALS)])
-		.with_delegators(vec![(2, 1, 10_000_000 * DECIMALS), (3, 1, 10_000_000 * DECIMALS)])
-		.with_inflation(10, 15, 40, 15, 5)
-		.build()
-		.execute_with(|| {
-            <crate::RewardPerBlock<Test>>::put(3_000_000 * DECIMALS);
+			// the total stake is recorded at the start of each round
+			assert_ok!(StakePallet::delegator_stake_less(Origin::signed(3), 1, 50));
+			roll_to(6, vec![None, None, None, None, None, Some(2)]);
+			assert_eq!(StakePallet::round().current, 1);
+			assert_eq!(
+				StakePallet::round_total_stake(1),
+				Some(TotalStake {
+					collators: 200,
+					delegators: 50,
+				})
+			);
+			assert_eq!(StakePallet::collator_round_stats(1, 2).blocks_authored, 1);
+			assert!(StakePallet::round_stats(2).is_none());
 
-			assert_ok!(StakePallet::revoke_delegation(Origin::signed(3), 1));
-			let authors: Vec<Option<AccountId>> = (1u64..100u64).map(|_| Some(1u64)).collect();
-			assert_eq!(Balances::usable_balance(&1), Balance::zero());
-			assert_eq!(Balances::usable_balance(&2), Balance::zero());
-			assert_eq!(Balances::usable_balance(&3), Balance::zero());
-			roll_to(100, authors);
-			assert!(Balances::usable_balance(&1) > Balance::zero());
-			assert!(Balances::usable_balance(&2) > Balance::zero());
-			assert_ok!(StakePallet::unlock_unstaked(Origin::signed(3), 3));
-			assert_eq!(Balances::usable_balance(&3), 10_000_000 * DECIMALS);
+			// the statistics are pruned after the retention window
+			roll_to(15, vec![]);
+			assert_eq!(StakePallet::round().current, 3);
+			assert!(StakePallet::round_stats(0).is_none());
+			assert_eq!(StakePallet::collator_round_stats(0, 1), CollatorStats::default());
+			assert!(StakePallet::round_stats(1).is_some());
+			assert_eq!(StakePallet::oldest_round_stats(), 1);
+
+			// all rounds which left a lowered retention window are pruned
+			RoundStatsRetention::set(0);
+			roll_to(20, vec![]);
+			assert_eq!(StakePallet::round().current, 4);
+			assert_eq!(StakePallet::oldest_round_stats(), 4);
+			for round in 1..4 {
+				assert!(StakePallet::round_stats(round).is_none());
+				assert!(StakePallet::round_total_stake(round).is_none());
+			}
+			assert_eq!(StakePallet::collator_round_stats(1, 2), CollatorStats::default());
+			assert!(StakePallet::round_stats(4).is_some());
 		});
 }
 
-// Could only occur if we increase MinDelegatorStakeOf::<Test>via runtime
-// upgrade and don't migrate delegators which fall below minimum
 #[test]
-fn should_not_reward_delegators_below_min_stake() {
+fn paused_operations() {
 	ExtBuilder::default()
-		.with_balances(vec![(1, 10 * DECIMALS), (2, 10 * DECIMALS), (3, 10 * DECIMALS), (4, 5)])
-		.with_collators(vec![(1, 10 * DECIMALS), (2, 10 * DECIMALS)])
-		.with_delegators(vec![(3, 2, 10 * DECIMALS)])
-		.with_inflation(10, 15, 40, 15, 5)
-		.build()
-		.execute_with(|| {
-			// impossible but lets assume it happened
-			let mut state = StakePallet::candidate_pool(&1).expect("CollatorState cannot be missing");
-			let delegator_stake_below_min = <Test as Config>::MinDelegatorStake::get() - 1;
-			state.stake += delegator_stake_below_min;
-			state.total += delegator_stake_below_min;
-			let impossible_bond = StakeOf::<Test> {
-				owner: 4u64,
-				amount: delegator_stake_below_min,
+		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100)])
+		.with_collators(vec![(1, 10), (2, 10)])
+		.with_delegators(vec![(3, 1, 10)])
+		.build_and_execute_with_sanity_tests(|| {
+			assert_eq!(StakePallet::paused(), PausedOperations::default());
+			assert_noop!(
+				StakePallet::set_paused_operations(Origin::signed(1), PausedOperations::all()),
+				sp_runtime::DispatchError::BadOrigin
+			);
+
+			// the kinds of operations can be paused separately
+			let paused = PausedOperations {
+				joins: true,
+				..Default::default()
 			};
-			assert_eq!(state.delegators.try_insert(impossible_bond), Ok(true));
-			<crate::CandidatePool<Test>>::insert(&1u64, state);
+			assert_ok!(StakePallet::set_paused_operations(Origin::root(), paused));
+			assert_eq!(
+				last_event(),
+				MetaEvent::StakePallet(Event::PausedOperationsSet(PausedOperations::default(), paused))
+			);
+			assert_noop!(
+				StakePallet::join_delegators(Origin::signed(4), 1, 10),
+				Error::<Test>::OperationPaused
+			);
+			assert_noop!(
+				StakePallet::create_pool(Origin::signed(4), 1, 10),
+				Error::<Test>::OperationPaused
+			);
+			assert_ok!(StakePallet::delegator_stake_more(Origin::signed(3), 1, 10));
 
-			let authors: Vec<Option<AccountId>> = vec![Some(1u64), Some(1u64), Some(1u64), Some(1u64)];
-			assert_eq!(Balances::usable_balance(&1), Balance::zero());
-			assert_eq!(Balances::usable_balance(&2), Balance::zero());
-			assert_eq!(Balances::usable_balance(&3), Balance::zero());
-			assert_eq!(Balances::usable_balance(&4), 5);
+			// the emergency mode pauses all operations
+			assert_ok!(StakePallet::set_paused_operations(
+				Origin::root(),
+				PausedOperations::all()
+			));
+			assert!(StakePallet::paused().is_emergency());
+			assert_noop!(
+				StakePallet::candidate_stake_more(Origin::signed(1), 10),
+				Error::<Test>::OperationPaused
+			);
+			assert_noop!(
+				StakePallet::delegator_stake_less(Origin::signed(3), 1, 10),
+				Error::<Test>::OperationPaused
+			);
+			assert_noop!(
+				StakePallet::leave_delegators(Origin::signed(3)),
+				Error::<Test>::OperationPaused
+			);
+			assert_noop!(
+				StakePallet::unlock_unstaked(Origin::signed(3), 3),
+				Error::<Test>::OperationPaused
+			);
+			assert_noop!(
+				StakePallet::set_commission(Origin::signed(1), Perbill::from_percent(10)),
+				Error::<Test>::OperationPaused
+			);
+			assert_noop!(
+				StakePallet::execute_commission_change(Origin::signed(4), 1),
+				Error::<Test>::OperationPaused
+			);
+			assert_noop!(
+				StakePallet::set_auto_compound(Origin::signed(3), 1, Percent::from_percent(50)),
+				Error::<Test>::OperationPaused
+			);
+			assert_noop!(
+				StakePallet::set_payee(Origin::signed(3), 4),
+				Error::<Test>::OperationPaused
+			);
 
-			// should only reward 1
-			roll_to(4, authors);
-			assert!(Balances::usable_balance(&1) > Balance::zero());
-			assert_eq!(Balances::usable_balance(&4), 5);
-			assert_eq!(Balances::usable_balance(&2), Balance::zero());
-			assert_eq!(Balances::usable_balance(&3), Balance::zero());
+			// rewards are accrued but not paid out
+			<crate::RewardPerBlock<Test>>::put(20);
+			roll_to(2, vec![None, Some(1)]);
+			assert_eq!(StakePallet::pending_rewards(&3), 10);
+			assert_noop!(
+				StakePallet::claim_rewards(Origin::signed(3)),
+				Error::<Test>::OperationPaused
+			);
+			assert_noop!(
+				StakePallet::claim_rewards_for(Origin::signed(4), 3),
+				Error::<Test>::OperationPaused
+			);
+
+			// the accrued rewards can be claimed once payouts are resumed
+			let paused = PausedOperations {
+				payouts: false,
+				..PausedOperations::all()
+			};
+			assert_ok!(StakePallet::set_paused_operations(Origin::root(), paused));
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(3)));
+			assert_eq!(last_event(), MetaEvent::StakePallet(Event::Rewarded(3, 10)));
+
+			assert_ok!(StakePallet::set_paused_operations(
+				Origin::root(),
+				PausedOperations::default()
+			));
+			assert_ok!(StakePallet::delegator_stake_less(Origin::signed(3), 1, 10));
 		});
 }
 
@@ -1856,13 +4356,13 @@ fn reach_max_top_candidates() {
 			(9, 10),
 			(10, 10),
 		])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(
 				StakePallet::top_candidates().len().saturated_into::<u32>(),
 				<Test as Config>::MaxTopCandidates::get()
 			);
 			// should not be possible to join candidate pool, even with more stake
+			set_session_keys(11);
 			assert_ok!(StakePallet::join_candidates(Origin::signed(11), 11));
 			assert_eq!(
 				StakePallet::top_candidates()
@@ -1872,6 +4372,7 @@ fn reach_max_top_candidates() {
 				vec![2, 11, 1, 3, 4, 5, 6, 7, 8, 9]
 			);
 			// last come, last one in the list
+			set_session_keys(12);
 			assert_ok!(StakePallet::join_candidates(Origin::signed(12), 11));
 			assert_eq!(
 				StakePallet::top_candidates()
@@ -1926,8 +4427,7 @@ fn should_estimate_current_session_progress() {
 			(9, 10),
 			(10, 10),
 		])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(
 				StakePallet::estimate_current_session_progress(10).0.unwrap(),
 				Permill::from_percent(10)
@@ -1980,8 +4480,7 @@ fn should_estimate_next_session_rotation() {
 			(9, 10),
 			(10, 10),
 		])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(StakePallet::estimate_next_session_rotation(10).0.unwrap(), 100);
 			assert_eq!(StakePallet::estimate_next_session_rotation(20).0.unwrap(), 100);
 			assert_eq!(StakePallet::estimate_next_session_rotation(30).0.unwrap(), 100);
@@ -2019,8 +4518,7 @@ fn should_end_session_when_appropriate() {
 			(9, 10),
 			(10, 10),
 		])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert!(!StakePallet::should_end_session(10));
 			assert!(!StakePallet::should_end_session(20));
 			assert!(!StakePallet::should_end_session(30));
@@ -2034,8 +4532,7 @@ fn set_max_selected_candidates_safe_guards() {
 	ExtBuilder::default()
 		.with_balances(vec![(1, 10)])
 		.with_collators(vec![(1, 10)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_noop!(
 				StakePallet::set_max_selected_candidates(Origin::root(), <Test as Config>::MinCollators::get() - 1),
 				Error::<Test>::CannotSetBelowMin
@@ -2076,8 +4573,7 @@ fn set_max_selected_candidates_total_stake() {
 			(17, 7, 27),
 			(18, 8, 28),
 		])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(
 				StakePallet::total_collator_stake(),
 				TotalStake {
@@ -2135,8 +4631,7 @@ fn unlock_unstaked() {
 		.with_balances(vec![(1, 10), (2, 100)])
 		.with_collators(vec![(1, 10)])
 		.with_delegators(vec![(2, 1, 100)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_ok!(StakePallet::revoke_delegation(Origin::signed(2), 1));
 			let mut unstaking: BoundedBTreeMap<BlockNumber, BalanceOf<Test>, <Test as Config>::MaxUnstakeRequests> =
 				BoundedBTreeMap::new();
@@ -2193,8 +4688,7 @@ fn unlock_unstaked() {
 		.with_balances(vec![(1, 10), (2, 100)])
 		.with_collators(vec![(1, 10)])
 		.with_delegators(vec![(2, 1, 10)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_ok!(StakePallet::revoke_delegation(Origin::signed(2), 1));
 			let mut unstaking: BoundedBTreeMap<BlockNumber, BalanceOf<Test>, <Test as Config>::MaxUnstakeRequests> =
 				BoundedBTreeMap::new();
@@ -2253,8 +4747,7 @@ fn unlock_unstaked() {
 		.with_balances(vec![(1, 10), (2, 100)])
 		.with_collators(vec![(1, 10)])
 		.with_delegators(vec![(2, 1, 100)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_ok!(StakePallet::revoke_delegation(Origin::signed(2), 1));
 			let mut unstaking: BoundedBTreeMap<BlockNumber, BalanceOf<Test>, <Test as Config>::MaxUnstakeRequests> =
 				BoundedBTreeMap::new();
@@ -2315,8 +4808,7 @@ fn unlock_unstaked() {
 		.with_balances(vec![(1, 200), (2, 200)])
 		.with_collators(vec![(1, 200)])
 		.with_delegators(vec![(2, 1, 200)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			// should be able to decrease more often than MaxUnstakeRequests because it's
 			// the same block and thus unstaking is increased at block 3 instead of having
 			// multiple entries for the same block
@@ -2444,8 +4936,7 @@ fn kick_candidate_with_full_unstaking() {
 	ExtBuilder::default()
 		.with_balances(vec![(1, 200), (2, 200), (3, 300)])
 		.with_collators(vec![(1, 200), (2, 200), (3, 200)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			let max_unstake_reqs: usize = <Test as Config>::MaxUnstakeRequests::get()
 				.saturating_sub(1)
 				.saturated_into();
@@ -2482,8 +4973,7 @@ fn kick_delegator_with_full_unstaking() {
 		.with_balances(vec![(1, 200), (2, 200), (3, 200), (4, 200), (5, 420), (6, 200)])
 		.with_collators(vec![(1, 200)])
 		.with_delegators(vec![(2, 1, 200), (3, 1, 200), (4, 1, 200), (5, 1, 200)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			let max_unstake_reqs: usize = <Test as Config>::MaxUnstakeRequests::get()
 				.saturating_sub(1)
 				.saturated_into();
@@ -2523,8 +5013,7 @@ fn candidate_leaves() {
 		.with_balances(balances)
 		.with_collators(vec![(1, 100), (2, 100)])
 		.with_delegators(vec![(12, 1, 100), (13, 1, 10)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(
 				StakePallet::top_candidates()
 					.into_iter()
@@ -2542,6 +5031,7 @@ fn candidate_leaves() {
 			);
 			// add five more collator to max fill TopCandidates
 			for candidate in 3u64..11u64 {
+				set_session_keys(candidate);
 				assert_ok!(StakePallet::join_candidates(Origin::signed(candidate), 100));
 			}
 			assert_eq!(
@@ -2604,6 +5094,7 @@ fn candidate_leaves() {
 			);
 			// add 11 as candidate to reach max size for TopCandidates and then try leave
 			// again as 1 which should not be possible
+			set_session_keys(11);
 			assert_ok!(StakePallet::join_candidates(Origin::signed(11), 100));
 			assert_eq!(
 				StakePallet::top_candidates()
@@ -2680,8 +5171,7 @@ fn adjust_reward_rates() {
 		.with_collators(vec![(1, 10_000_000 * DECIMALS)])
 		.with_delegators(vec![(2, 1, 40_000_000 * DECIMALS)])
 		.with_inflation(10, 10, 40, 8, 5)
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			let num_of_years = 3 * <Test as Config>::BLOCKS_PER_YEAR;
 			// 1 authors every block
 			let authors: Vec<Option<AccountId>> = (0u64..=num_of_years).map(|_| Some(1u64)).collect();
@@ -2691,6 +5181,8 @@ fn adjust_reward_rates() {
 
 			// reward once in first year
 			roll_to(2, authors.clone());
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(1)));
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(2)));
             assert_eq!(Balances::free_balance(&1), 10_000_000 * DECIMALS + (20 * DECIMALS));
             assert_eq!(Balances::free_balance(&2), 90_000_000 * DECIMALS + (80 * DECIMALS));
 			let c_rewards_0 = Balances::free_balance(&1).saturating_sub(10_000_000 * DECIMALS);
@@ -2703,6 +5195,8 @@ fn adjust_reward_rates() {
 			roll_to(<Test as Config>::BLOCKS_PER_YEAR + 1, vec![]);
 			// reward once in 2nd year
 			roll_to(<Test as Config>::BLOCKS_PER_YEAR + 2, authors.clone());
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(1)));
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(2)));
 			let c_rewards_1 = Balances::free_balance(&1)
 				.saturating_sub(10_000_000 * DECIMALS)
 				.saturating_sub(c_rewards_0);
@@ -2722,6 +5216,8 @@ fn adjust_reward_rates() {
 			roll_to(2 * <Test as Config>::BLOCKS_PER_YEAR + 1, vec![]);
 			// reward once in 3rd year
 			roll_to(2 * <Test as Config>::BLOCKS_PER_YEAR + 2, authors);
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(1)));
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(2)));
 			let c_rewards_2 = Balances::free_balance(&1)
 				.saturating_sub(10_000_000 * DECIMALS)
 				.saturating_sub(c_rewards_0)
@@ -2741,8 +5237,7 @@ fn increase_max_candidate_stake() {
 	ExtBuilder::default()
 		.with_balances(vec![(1, 200_000_000 * DECIMALS)])
 		.with_collators(vec![(1, max_stake)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(StakePallet::max_candidate_stake(), max_stake);
 			assert_noop!(
 				StakePallet::candidate_stake_more(Origin::signed(1), 1),
@@ -2769,8 +5264,7 @@ fn decrease_max_candidate_stake() {
 		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100)])
 		.with_collators(vec![(1, 100), (2, 90), (3, 40)])
 		.with_delegators(vec![(4, 2, 10), (5, 3, 20)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(StakePallet::selected_candidates().into_inner(), vec![1, 2]);
 			assert_eq!(
 				StakePallet::top_candidates(),
@@ -2832,8 +5326,7 @@ fn exceed_delegations_per_round() {
 		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100), (6, 100)])
 		.with_collators(vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100)])
 		.with_delegators(vec![(6, 1, 10)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_ok!(StakePallet::delegate_another_candidate(Origin::signed(6), 2, 10));
 			assert_ok!(StakePallet::delegate_another_candidate(Origin::signed(6), 3, 10));
 			assert_ok!(StakePallet::delegate_another_candidate(Origin::signed(6), 4, 10));
@@ -2892,8 +5385,7 @@ fn force_remove_candidate() {
 		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100), (6, 100)])
 		.with_collators(vec![(1, 100), (2, 100), (3, 100)])
 		.with_delegators(vec![(4, 1, 50), (5, 1, 50)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(CandidatePool::<Test>::count(), 3);
 			assert_ok!(StakePallet::delegate_another_candidate(Origin::signed(4), 2, 50));
 			assert_eq!(StakePallet::selected_candidates().into_inner(), vec![1, 2]);
@@ -2973,8 +5465,7 @@ fn prioritize_collators() {
 			(7, 200),
 		])
 		.with_collators(vec![(2, 100), (3, 100)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(
 				StakePallet::top_candidates(),
 				OrderedSet::from_sorted_set(
@@ -2987,6 +5478,7 @@ fn prioritize_collators() {
 				)
 			);
 			assert_eq!(StakePallet::selected_candidates().into_inner(), vec![2, 3]);
+			set_session_keys(1);
 			assert_ok!(StakePallet::join_candidates(Origin::signed(1), 100));
 			assert_eq!(
 				StakePallet::top_candidates(),
@@ -3007,6 +5499,7 @@ fn prioritize_collators() {
 			assert_eq!(StakePallet::selected_candidates().into_inner(), vec![1, 3]);
 
 			// add 6
+			set_session_keys(6);
 			assert_ok!(StakePallet::join_candidates(Origin::signed(6), 100));
 			assert_eq!(StakePallet::selected_candidates().into_inner(), vec![1, 6]);
 			assert_eq!(
@@ -3023,6 +5516,7 @@ fn prioritize_collators() {
 			);
 
 			// add 4
+			set_session_keys(4);
 			assert_ok!(StakePallet::join_candidates(Origin::signed(4), 100));
 			assert_eq!(StakePallet::selected_candidates().into_inner(), vec![1, 6]);
 			assert_eq!(
@@ -3039,6 +5533,7 @@ fn prioritize_collators() {
 			);
 
 			// add 5
+			set_session_keys(5);
 			assert_ok!(StakePallet::join_candidates(Origin::signed(5), 100));
 			assert_eq!(StakePallet::selected_candidates().into_inner(), vec![1, 6]);
 			assert_eq!(
@@ -3158,8 +5653,7 @@ fn prioritize_delegators() {
 		])
 		.with_collators(vec![(1, 100), (2, 100), (3, 100)])
 		.with_delegators(vec![(5, 1, 100), (4, 2, 100), (7, 2, 100), (6, 2, 100)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(StakePallet::selected_candidates().into_inner(), vec![2, 1]);
 			assert_eq!(
 				StakePallet::candidate_pool(2).unwrap().delegators,
@@ -3276,8 +5770,7 @@ fn authorities_per_round() {
 			(11, 100 * stake),
 		])
 		.with_collators(vec![(1, stake), (2, stake), (3, stake), (4, stake)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
             let reward = 50 * DECIMALS;
             <crate::RewardPerBlock<Test>>::put(reward);
 
@@ -3289,20 +5782,24 @@ fn authorities_per_round() {
 
 			// roll to last block of round 0
 			roll_to(4, authors.clone());
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(1)));
 			assert_eq!(Balances::free_balance(1), stake + reward);
 			// increase max selected candidates which will become effective in round 2
 			assert_ok!(StakePallet::set_max_selected_candidates(Origin::root(), 10));
 
 			// roll to last block of round 1
 			roll_to(9, authors.clone());
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(1)));
 			assert_eq!(Balances::free_balance(1), stake + reward * 2);
 
 			// roll to last block of round 2
 			roll_to(14, authors.clone());
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(1)));
 			assert_eq!(Balances::free_balance(1), stake + reward * 3);
 
 			// roll to last block of round 3
 			roll_to(19, authors);
+			assert_ok!(StakePallet::claim_rewards(Origin::signed(1)));
 			assert_eq!(Balances::free_balance(1), stake + reward * 4);
 		});
 }
@@ -3312,8 +5809,7 @@ fn force_new_round() {
 	ExtBuilder::default()
 		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100), (6, 100)])
 		.with_collators(vec![(1, 100), (2, 100), (3, 100), (4, 100)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			let mut round = RoundInfo {
 				current: 0,
 				first: 0,
@@ -3391,8 +5887,7 @@ fn replace_lowest_delegator() {
 		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100), (6, 100)])
 		.with_collators(vec![(1, 100)])
 		.with_delegators(vec![(2, 1, 51), (3, 1, 51), (4, 1, 51), (5, 1, 50)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(
 				StakePallet::candidate_pool(1).unwrap().delegators.len() as u32,
 				<Test as Config>::MaxDelegatorsPerCollator::get()
@@ -3434,8 +5929,7 @@ fn network_reward_multiple_blocks() {
 	ExtBuilder::default()
 		.with_balances(collators.clone())
 		.with_collators(collators)
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(max_stake, StakePallet::max_candidate_stake());
 			let total_collator_stake = max_stake.saturating_mul(<Test as Config>::MinCollators::get().into());
 			assert_eq!(total_collator_stake, StakePallet::total_collator_stake().collators);
@@ -3500,8 +5994,7 @@ fn update_total_stake_collators_stay() {
 		.with_balances(vec![(1, 200), (2, 200), (3, 200), (4, 200)])
 		.with_collators(vec![(1, 100), (2, 50)])
 		.with_delegators(vec![(3, 1, 100), (4, 2, 50)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(
 				StakePallet::total_collator_stake(),
 				TotalStake {
@@ -3560,8 +6053,7 @@ fn update_total_stake_displace_collators() {
 		])
 		.with_collators(vec![(1, 10), (2, 20), (3, 30), (4, 40)])
 		.with_delegators(vec![(5, 1, 50), (6, 2, 50), (7, 3, 55), (8, 4, 55)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(
 				StakePallet::total_collator_stake(),
 				TotalStake {
@@ -3592,6 +6084,7 @@ fn update_total_stake_displace_collators() {
 			);
 
 			// 1 is pushed out by new candidate
+			set_session_keys(1337);
 			assert_ok!(StakePallet::join_candidates(Origin::signed(1337), 100));
 			assert_eq!(
 				StakePallet::total_collator_stake(),
@@ -3609,8 +6102,7 @@ fn update_total_stake_new_collators() {
 		.with_balances(vec![(1, 100), (2, 100), (3, 100), (4, 100)])
 		.with_collators(vec![(1, 100)])
 		.with_delegators(vec![(4, 1, 100)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(
 				StakePallet::total_collator_stake(),
 				TotalStake {
@@ -3618,6 +6110,7 @@ fn update_total_stake_new_collators() {
 					delegators: 100
 				}
 			);
+			set_session_keys(2);
 			assert_ok!(StakePallet::join_candidates(Origin::signed(2), 100));
 			assert_eq!(
 				StakePallet::total_collator_stake(),
@@ -3661,8 +6154,7 @@ fn update_total_stake_no_collator_changes() {
 		])
 		.with_collators(vec![(1, 10), (2, 20), (3, 30), (4, 40)])
 		.with_delegators(vec![(5, 1, 50), (6, 2, 50), (7, 3, 55), (8, 4, 55)])
-		.build()
-		.execute_with(|| {
+		.build_and_execute_with_sanity_tests(|| {
 			assert_eq!(
 				StakePallet::total_collator_stake(),
 				TotalStake {
@@ -3704,3 +6196,66 @@ fn update_total_stake_no_collator_changes() {
 			);
 		});
 }
+
+#[test]
+fn try_state_detects_corrupted_storage() {
+	ExtBuilder::default()
+		.with_balances(vec![(1, 100), (2, 100), (3, 100)])
+		.with_collators(vec![(1, 20), (2, 10)])
+		.with_delegators(vec![(3, 1, 10)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(try_state::do_try_state::<Test>());
+
+			// candidate total does not match the delegations
+			let mut state = StakePallet::candidate_pool(&1).unwrap();
+			state.total += 1;
+			CandidatePool::<Test>::insert(&1, state.clone());
+			assert_eq!(
+				try_state::do_try_state::<Test>(),
+				Err("Candidate total does not equal the sum of their stake and delegations")
+			);
+
+			// delegator delegates more than the candidate lists
+			state.total -= 1;
+			CandidatePool::<Test>::insert(&1, state);
+			let mut delegator = StakePallet::delegator_state(&3).unwrap();
+			delegator.total += 1;
+			assert!(delegator.delegations.update_stake(&1, 10, 11).is_some());
+			DelegatorState::<Test>::insert(&3, delegator.clone());
+			assert_eq!(
+				try_state::do_try_state::<Test>(),
+				Err("Delegation of a candidate is not part of the delegator state")
+			);
+
+			// ranked with an outdated stake
+			delegator.total -= 1;
+			assert!(delegator.delegations.update_stake(&1, 11, 10).is_some());
+			DelegatorState::<Test>::insert(&3, delegator);
+			let mut top_candidates = StakePallet::top_candidates();
+			assert!(top_candidates.update_stake(&2, 10, 15).is_some());
+			TopCandidates::<Test>::put(top_candidates.clone());
+			assert_eq!(
+				try_state::do_try_state::<Test>(),
+				Err("TopCandidates contain an outdated stake")
+			);
+
+			// total collator stake does not match the selected candidates
+			assert!(top_candidates.update_stake(&2, 15, 10).is_some());
+			TopCandidates::<Test>::put(top_candidates);
+			TotalCollatorStake::<Test>::mutate(|total| total.collators += 1);
+			assert_eq!(
+				try_state::do_try_state::<Test>(),
+				Err("TotalCollatorStake does not equal the stake of the selected candidates")
+			);
+
+			// lock does not match the stake
+			TotalCollatorStake::<Test>::mutate(|total| total.collators -= 1);
+			assert_ok!(try_state::do_try_state::<Test>());
+			<Balances as LockableCurrency<AccountId>>::set_lock(STAKING_ID, &3, 5, WithdrawReasons::all());
+			assert_eq!(
+				try_state::do_try_state::<Test>(),
+				Err("Staking lock does not equal the staked, unstaking and waitlisted funds")
+			);
+		});
+}
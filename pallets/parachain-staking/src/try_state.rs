@@ -0,0 +1,295 @@
+// KILT Blockchain – https://botlabs.org
+// Copyright (C) 2019-2022 BOTLabs GmbH
+
+// The KILT Blockchain is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The KILT Blockchain is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// If you feel like getting in touch with us, you can do so at info@botlabs.org
+
+//! Invariants of the parachain staking pallet.
+//!
+//! The checks are run after each unit test and by the `try_state` hook when
+//! executing blocks with `try-runtime`. They iterate over all staking related
+//! storage and are thus only meant to be used off-chain.
+
+use frame_support::{ensure, traits::Get};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	SaturatedConversion,
+};
+use sp_std::collections::{btree_map::BTreeMap, btree_set::BTreeSet};
+
+use crate::{
+	types::{BalanceOf, TotalStake},
+	CandidatePool, Config, DelegatorState, FundedStake, Funders, MaxSelectedCandidates, Pallet, TopCandidates,
+	TotalCollatorStake, Unstaking, Waitlist, Waitlisted, STAKING_ID,
+};
+
+/// Check all invariants of the staking storage.
+///
+/// Returns a description of the first violated invariant. The offending
+/// accounts are logged.
+pub(crate) fn do_try_state<T: Config>() -> Result<(), &'static str> {
+	validate_candidates::<T>()?;
+	validate_delegators::<T>()?;
+	validate_top_candidates::<T>()?;
+	validate_total_stake::<T>()?;
+	validate_waitlist::<T>()?;
+	validate_funding::<T>()?;
+	validate_locks::<T>()
+}
+
+/// Every collator candidate's total has to be the sum of their own stake and
+/// their delegations, each of which must be mirrored by the delegator.
+fn validate_candidates<T: Config>() -> Result<(), &'static str> {
+	for (id, candidate) in CandidatePool::<T>::iter() {
+		let delegated = candidate
+			.delegators
+			.iter()
+			.fold(BalanceOf::<T>::zero(), |acc, stake| acc.saturating_add(stake.amount));
+		if candidate.total != candidate.stake.saturating_add(delegated) {
+			log::error!(
+				"candidate {:?} has a total of {:?} but stakes {:?} and is delegated {:?}",
+				id,
+				candidate.total,
+				candidate.stake,
+				delegated
+			);
+			return Err("Candidate total does not equal the sum of their stake and delegations");
+		}
+		ensure!(
+			candidate
+				.delegators
+				.iter()
+				.zip(candidate.delegators.iter().skip(1))
+				.all(|(prev, next)| prev.amount >= next.amount),
+			"Delegators of a candidate are not sorted by their stake"
+		);
+		ensure!(
+			DelegatorState::<T>::get(&id).is_none(),
+			"A collator candidate must not be a delegator"
+		);
+
+		for stake in candidate.delegators.iter() {
+			let delegation = DelegatorState::<T>::get(&stake.owner)
+				.and_then(|delegator| delegator.delegations.iter().find(|d| d.owner == id).map(|d| d.amount));
+			if delegation != Some(stake.amount) {
+				log::error!(
+					"candidate {:?} is delegated {:?} by {:?} who delegates {:?}",
+					id,
+					stake.amount,
+					stake.owner,
+					delegation
+				);
+				return Err("Delegation of a candidate is not part of the delegator state");
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Every delegation of a delegator has to appear with the same amount in the
+/// matching collator candidate's delegators.
+fn validate_delegators<T: Config>() -> Result<(), &'static str> {
+	for (id, delegator) in DelegatorState::<T>::iter() {
+		let delegated = delegator
+			.delegations
+			.iter()
+			.fold(BalanceOf::<T>::zero(), |acc, stake| acc.saturating_add(stake.amount));
+		if delegator.total != delegated {
+			log::error!(
+				"delegator {:?} has a total of {:?} but delegates {:?}",
+				id,
+				delegator.total,
+				delegated
+			);
+			return Err("Delegator total does not equal the sum of their delegations");
+		}
+
+		for stake in delegator.delegations.iter() {
+			let delegation = CandidatePool::<T>::get(&stake.owner)
+				.and_then(|candidate| candidate.delegators.iter().find(|d| d.owner == id).map(|d| d.amount));
+			if delegation != Some(stake.amount) {
+				log::error!(
+					"delegator {:?} delegates {:?} to {:?} which lists {:?}",
+					id,
+					stake.amount,
+					stake.owner,
+					delegation
+				);
+				return Err("Delegation of a delegator is not part of the candidate's delegators");
+			}
+		}
+	}
+	Ok(())
+}
+
+/// The top candidates have to be sorted by their total stake and may only
+/// contain active collator candidates at their current total stake.
+fn validate_top_candidates<T: Config>() -> Result<(), &'static str> {
+	let top_candidates = TopCandidates::<T>::get();
+	ensure!(
+		top_candidates
+			.iter()
+			.zip(top_candidates.iter().skip(1))
+			.all(|(prev, next)| prev.amount >= next.amount),
+		"TopCandidates are not sorted by their stake"
+	);
+
+	let mut seen = BTreeSet::new();
+	for stake in top_candidates.iter() {
+		ensure!(seen.insert(stake.owner.clone()), "TopCandidates contain duplicates");
+		let candidate = if let Some(candidate) = CandidatePool::<T>::get(&stake.owner) {
+			candidate
+		} else {
+			log::error!("top candidate {:?} is not a collator candidate", stake.owner);
+			return Err("TopCandidates contain an account which is not a collator candidate");
+		};
+		if !candidate.is_active() {
+			log::error!("top candidate {:?} is not active", stake.owner);
+			return Err("TopCandidates contain an inactive collator candidate");
+		}
+		if candidate.total != stake.amount {
+			log::error!(
+				"top candidate {:?} is ranked with {:?} but has a total of {:?}",
+				stake.owner,
+				stake.amount,
+				candidate.total
+			);
+			return Err("TopCandidates contain an outdated stake");
+		}
+	}
+	Ok(())
+}
+
+/// The total collator stake has to be the sum over the stakes of the first
+/// `MaxSelectedCandidates` many top candidates.
+fn validate_total_stake<T: Config>() -> Result<(), &'static str> {
+	let max_selected_candidates = MaxSelectedCandidates::<T>::get().saturated_into::<usize>();
+	let expected = TopCandidates::<T>::get()
+		.iter()
+		.take(max_selected_candidates)
+		.filter_map(|stake| CandidatePool::<T>::get(&stake.owner))
+		.fold(TotalStake::<BalanceOf<T>>::default(), |acc, candidate| TotalStake {
+			collators: acc.collators.saturating_add(candidate.stake),
+			delegators: acc
+				.delegators
+				.saturating_add(candidate.total.saturating_sub(candidate.stake)),
+		});
+	let total = TotalCollatorStake::<T>::get();
+	if total != expected {
+		log::error!(
+			"TotalCollatorStake is {:?} but the selected candidates stake {:?}",
+			total,
+			expected
+		);
+		return Err("TotalCollatorStake does not equal the stake of the selected candidates");
+	}
+	Ok(())
+}
+
+/// Each waiting delegator has to be registered with their funds and must not
+/// delegate to the candidate yet.
+fn validate_waitlist<T: Config>() -> Result<(), &'static str> {
+	for (collator, waitlist) in Waitlist::<T>::iter() {
+		ensure!(
+			waitlist.len() <= T::MaxWaitlistSize::get().saturated_into::<usize>(),
+			"Waitlist exceeds MaxWaitlistSize"
+		);
+		for who in waitlist.iter() {
+			if !Waitlisted::<T>::contains_key(who, &collator) {
+				log::error!("{:?} waits for {:?} without waitlisted funds", who, collator);
+				return Err("Waitlist contains a delegator without waitlisted funds");
+			}
+		}
+	}
+	for (who, collator, _) in Waitlisted::<T>::iter() {
+		if !Waitlist::<T>::get(&collator).contains(&who) {
+			log::error!("{:?} has waitlisted funds for {:?} without waiting", who, collator);
+			return Err("Waitlisted funds of a delegator which is not on the waitlist");
+		}
+		let is_delegating = CandidatePool::<T>::get(&collator)
+			.map(|candidate| candidate.delegators.iter().any(|stake| stake.owner == who))
+			.unwrap_or(false);
+		ensure!(!is_delegating, "A waitlisted delegator already delegates to the candidate");
+	}
+	Ok(())
+}
+
+/// The funded stake of every funder has to equal the sum of the principal
+/// they have funded for other stakers.
+fn validate_funding<T: Config>() -> Result<(), &'static str> {
+	let mut funded = BTreeMap::<T::AccountId, BalanceOf<T>>::new();
+	for (_, (funder, amount)) in Funders::<T>::iter() {
+		let total = funded.entry(funder).or_insert_with(Zero::zero);
+		*total = total.saturating_add(amount);
+	}
+	for funder in FundedStake::<T>::iter_keys() {
+		funded.entry(funder).or_insert_with(Zero::zero);
+	}
+	for (funder, total) in funded {
+		if FundedStake::<T>::get(&funder) != total {
+			log::error!(
+				"{:?} has funded {:?} but their funded stake is {:?}",
+				funder,
+				total,
+				FundedStake::<T>::get(&funder)
+			);
+			return Err("FundedStake does not equal the funded principal");
+		}
+	}
+	Ok(())
+}
+
+/// The staking lock of every account has to equal their staked funds plus
+/// the funds which are unstaking or waiting on a waitlist. The principal
+/// which has been funded by another account is locked on the funder's account
+/// instead.
+fn validate_locks<T: Config>() -> Result<(), &'static str> {
+	let stakers = CandidatePool::<T>::iter_keys()
+		.chain(DelegatorState::<T>::iter_keys())
+		.chain(Unstaking::<T>::iter_keys())
+		.chain(Waitlisted::<T>::iter_keys().map(|(who, _)| who))
+		.chain(
+			pallet_balances::Locks::<T>::iter()
+				.filter(|(_, locks)| locks.iter().any(|lock| lock.id == STAKING_ID))
+				.map(|(who, _)| who),
+		)
+		.collect::<BTreeSet<_>>();
+
+	for who in stakers {
+		let staked = CandidatePool::<T>::get(&who)
+			.map(|candidate| candidate.stake)
+			.or_else(|| DelegatorState::<T>::get(&who).map(|delegator| delegator.total))
+			.unwrap_or_else(Zero::zero);
+		let unstaking = Unstaking::<T>::get(&who)
+			.values()
+			.fold(BalanceOf::<T>::zero(), |acc, amount| acc.saturating_add(*amount));
+		let expected = staked
+			.saturating_add(unstaking)
+			.saturating_add(Pallet::<T>::waitlisted_total(&who));
+		let locked = Pallet::<T>::staking_lock(&who);
+		if locked != expected {
+			log::error!(
+				"{:?} has {:?} locked but stakes {:?}, unstakes {:?} and waits with {:?}",
+				who,
+				locked,
+				staked,
+				unstaking,
+				expected.saturating_sub(staked).saturating_sub(unstaking)
+			);
+			return Err("Staking lock does not equal the staked, unstaking and waitlisted funds");
+		}
+	}
+	Ok(())
+}
@@ -16,12 +16,15 @@
 
 // If you feel like getting in touch with us, you can do so at info@botlabs.org
 
-use frame_support::traits::{Currency, Get};
+use frame_support::{
+	storage::bounded_btree_map::BoundedBTreeMap,
+	traits::{Currency, Get},
+};
 use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 use scale_info::TypeInfo;
 use sp_runtime::{
-	traits::{AtLeast32BitUnsigned, Saturating, Zero},
-	RuntimeDebug,
+	traits::{AtLeast32BitUnsigned, SaturatedConversion, Saturating, Zero},
+	FixedPointNumber, FixedU128, Perbill, Perquintill, RuntimeDebug,
 };
 use sp_staking::SessionIndex;
 use sp_std::{
@@ -182,27 +185,27 @@ where
 		}
 	}
 
-	pub fn inc_delegator(&mut self, delegator: A, more: B) {
-		if let Ok(i) = self.delegators.linear_search(&Stake::<A, B> {
-			owner: delegator,
-			amount: B::zero(),
-		}) {
-			self.delegators
-				.mutate(|vec| vec[i].amount = vec[i].amount.saturating_add(more));
+	/// Increase the stake of the delegator which amounts to `delegated`
+	/// before the increase.
+	pub fn inc_delegator(&mut self, delegator: A, delegated: B, more: B) {
+		if self
+			.delegators
+			.update_stake(&delegator, delegated, delegated.saturating_add(more))
+			.is_some()
+		{
 			self.total = self.total.saturating_add(more);
-			self.delegators.sort_greatest_to_lowest()
 		}
 	}
 
-	pub fn dec_delegator(&mut self, delegator: A, less: B) {
-		if let Ok(i) = self.delegators.linear_search(&Stake::<A, B> {
-			owner: delegator,
-			amount: B::zero(),
-		}) {
-			self.delegators
-				.mutate(|vec| vec[i].amount = vec[i].amount.saturating_sub(less));
+	/// Decrease the stake of the delegator which amounts to `delegated`
+	/// before the decrease.
+	pub fn dec_delegator(&mut self, delegator: A, delegated: B, less: B) {
+		if self
+			.delegators
+			.update_stake(&delegator, delegated, delegated.saturating_sub(less))
+			.is_some()
+		{
 			self.total = self.total.saturating_sub(less);
-			self.delegators.sort_greatest_to_lowest()
 		}
 	}
 
@@ -211,6 +214,99 @@ where
 	}
 }
 
+/// The weights of the stakes in the rewards of a collator candidate which
+/// apply to a stake snapshot.
+#[derive(Clone, Copy, Encode, Decode, Eq, MaxEncodedLen, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct RewardWeights {
+	/// The weight of the stake of the candidate.
+	pub collator: Perquintill,
+	/// The weight of the stake of the delegators.
+	pub delegator: Perquintill,
+	/// The bonus delegations earn for their stake age.
+	pub loyalty: Option<LoyaltyInfo>,
+}
+
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+#[scale_info(skip_type_params(MaxDelegatorsPerCandidate))]
+#[codec(mel_bound(AccountId: MaxEncodedLen, Balance: MaxEncodedLen))]
+/// The stake of a collator candidate and their delegators at the start of a
+/// round.
+pub struct StakeSnapshot<AccountId, Balance, MaxDelegatorsPerCandidate>
+where
+	AccountId: Eq + Ord + Debug,
+	Balance: Eq + Ord + Debug,
+	MaxDelegatorsPerCandidate: Get<u32> + Debug + PartialEq,
+{
+	/// The round in which the snapshot has been taken.
+	pub round: SessionIndex,
+
+	/// The stake of the candidate.
+	pub stake: Balance,
+
+	/// The delegators that backed the candidate.
+	pub delegators: OrderedSet<Stake<AccountId, Balance>, MaxDelegatorsPerCandidate>,
+
+	/// The sum of the stake of the candidate and their delegators.
+	pub total: Balance,
+
+	/// The weights of the stakes in the rewards earned with the snapshot.
+	pub weights: RewardWeights,
+}
+
+impl<A, B, S> StakeSnapshot<A, B, S>
+where
+	A: Ord + Clone + Debug,
+	B: AtLeast32BitUnsigned + Ord + Copy + Saturating + Debug + Zero,
+	S: Get<u32> + Debug + PartialEq,
+{
+	pub fn new(candidate: &Candidate<A, B, S>, round: SessionIndex, weights: RewardWeights) -> Self {
+		StakeSnapshot {
+			round,
+			stake: candidate.stake,
+			delegators: candidate.delegators.clone(),
+			total: candidate.total,
+			weights,
+		}
+	}
+
+	/// Return the stake of the given delegator in the snapshot.
+	pub fn delegation(&self, delegator: &A) -> Option<B> {
+		self.delegators
+			.iter()
+			.find(|stake| stake.owner == *delegator)
+			.map(|stake| stake.amount)
+	}
+
+	/// Return the part of the current stake of the candidate which has been
+	/// bonded since the snapshot.
+	///
+	/// Stake which has been added since the snapshot is left out while stake
+	/// which has been removed is not accounted for anymore.
+	pub fn rewarded(&self, candidate: &Candidate<A, B, S>) -> Self {
+		let stake = self.stake.min(candidate.stake);
+		let mut delegators = candidate.delegators.clone();
+		delegators.mutate(|stakes| {
+			for stake in stakes[..].iter_mut() {
+				stake.amount = self
+					.delegation(&stake.owner)
+					.map_or_else(B::zero, |amount| amount.min(stake.amount));
+			}
+			stakes.retain(|stake| !stake.amount.is_zero());
+		});
+		let total = delegators
+			.iter()
+			.fold(stake, |total, stake| total.saturating_add(stake.amount));
+
+		StakeSnapshot {
+			round: self.round,
+			stake,
+			delegators,
+			total,
+			weights: self.weights,
+		}
+	}
+}
+
 #[derive(Encode, Decode, Eq, MaxEncodedLen, PartialEq, RuntimeDebug, TypeInfo)]
 #[scale_info(skip_type_params(MaxCollatorsPerDelegator))]
 #[codec(mel_bound(AccountId: MaxEncodedLen, Balance: MaxEncodedLen))]
@@ -271,15 +367,11 @@ where
 
 	/// Returns None if delegation was not found.
 	pub fn inc_delegation(&mut self, collator: AccountId, more: Balance) -> Option<Balance> {
-		if let Ok(i) = self.delegations.linear_search(&Stake::<AccountId, Balance> {
-			owner: collator,
-			amount: Balance::zero(),
-		}) {
-			self.delegations
-				.mutate(|vec| vec[i].amount = vec[i].amount.saturating_add(more));
+		if let Some(i) = self.delegations.position_of(&collator) {
+			let amount = self.delegations[i].amount.saturating_add(more);
+			self.delegations.update_stake_at(i, amount);
 			self.total = self.total.saturating_add(more);
-			self.delegations.sort_greatest_to_lowest();
-			Some(self.delegations[i].amount)
+			Some(amount)
 		} else {
 			None
 		}
@@ -288,16 +380,12 @@ where
 	/// Returns Some(Some(balance)) if successful, None if delegation was not
 	/// found and Some(None) if delegated stake would underflow.
 	pub fn dec_delegation(&mut self, collator: AccountId, less: Balance) -> Option<Option<Balance>> {
-		if let Ok(i) = self.delegations.linear_search(&Stake::<AccountId, Balance> {
-			owner: collator,
-			amount: Balance::zero(),
-		}) {
+		if let Some(i) = self.delegations.position_of(&collator) {
 			if self.delegations[i].amount > less {
-				self.delegations
-					.mutate(|vec| vec[i].amount = vec[i].amount.saturating_sub(less));
+				let amount = self.delegations[i].amount.saturating_sub(less);
+				self.delegations.update_stake_at(i, amount);
 				self.total = self.total.saturating_sub(less);
-				self.delegations.sort_greatest_to_lowest();
-				Some(Some(self.delegations[i].amount))
+				Some(Some(amount))
 			} else {
 				// underflow error; should rm entire delegation
 				Some(None)
@@ -361,6 +449,78 @@ pub struct TotalStake<Balance: Default> {
 	pub delegators: Balance,
 }
 
+/// The block production and the rewards of a collator in a single round.
+#[derive(Default, Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct CollatorStats<Balance> {
+	/// The number of blocks the collator authored.
+	pub blocks_authored: u32,
+	/// The block rewards credited to the collator including the commission.
+	pub collator_rewards: Balance,
+	/// The block rewards credited to the delegators of the collator.
+	pub delegator_rewards: Balance,
+}
+
+/// The destination of the part of a block reward which is not credited to the
+/// stakers of its author.
+#[derive(Clone, Copy, Encode, Decode, Eq, MaxEncodedLen, PartialEq, RuntimeDebug, TypeInfo)]
+pub enum RemainderDestination {
+	/// Credit the remainder to the rewards of the author.
+	Collator,
+	/// Redistribute the share of the unrewarded delegations among the
+	/// rewarded stakers in proportion to their stake. The rounding remainder
+	/// is credited to the rewards of the author.
+	Stakers,
+	/// Issue the remainder to the `RewardRemainderBeneficiary`.
+	Beneficiary,
+}
+
+/// The split of the block rewards of a collator among their stakers.
+#[derive(Default, Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct RewardSplit<Balance> {
+	/// The rewards accrued for the collator including the commission.
+	pub collator: Balance,
+	/// The rewards accrued for the delegators of the collator.
+	pub delegators: Balance,
+	/// The part of the delegators' rewards which stems from their loyalty
+	/// bonus.
+	pub loyalty_bonus: Balance,
+	/// The share of the delegations below `MinRewardedDelegation` which has
+	/// not been redistributed.
+	pub skipped: Balance,
+	/// The rounding remainder.
+	pub dust: Balance,
+	/// The rewards which have been credited to the stakers in excess of the
+	/// block rewards due to rounding. They are issued in addition to the
+	/// block rewards.
+	pub excess: Balance,
+}
+
+impl<Balance: Saturating + Copy> RewardSplit<Balance> {
+	/// The part of the block rewards which has not been credited to the
+	/// stakers.
+	pub fn remainder(&self) -> Balance {
+		self.skipped.saturating_add(self.dust)
+	}
+}
+
+/// The block rewards of a collator which have not been split among their
+/// stakers yet.
+#[derive(Default, Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
+pub struct UnsplitRewards<Balance> {
+	/// The round in which the collator has earned the block rewards.
+	pub round: SessionIndex,
+	/// The block rewards the collator has earned.
+	pub earned: Balance,
+	/// The rewards which have been accrued for the stakers of the collator.
+	pub accrued: Balance,
+	/// The rewards which have been settled for the collator including the
+	/// commission.
+	pub collator: Balance,
+	/// The rewards which have been settled for the delegators of the
+	/// collator.
+	pub delegators: Balance,
+}
+
 /// The number of delegations a delegator has done within the last session in
 /// which they delegated.
 #[derive(Default, Clone, Encode, Decode, Eq, MaxEncodedLen, PartialEq, RuntimeDebug, TypeInfo)]
@@ -371,6 +531,164 @@ pub struct DelegationCounter {
 	pub counter: u32,
 }
 
+/// A requested increase of a collator candidate's commission which can only be
+/// applied after a delay to allow delegators to react.
+#[derive(Default, Clone, Encode, Decode, Eq, MaxEncodedLen, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct CommissionChange {
+	/// The requested commission.
+	pub commission: Perbill,
+	/// The first round in which the requested commission can be applied.
+	pub effective_round: SessionIndex,
+}
+
+/// The bonus on the reward share delegations earn for the number of rounds
+/// they have been staked.
+#[derive(Default, Clone, Copy, Encode, Decode, Eq, MaxEncodedLen, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct LoyaltyInfo {
+	/// The highest bonus on the weight of a delegation in the rewards.
+	pub max_bonus: Perbill,
+	/// The stake age in rounds at which the highest bonus is reached.
+	pub maturity: SessionIndex,
+}
+
+impl LoyaltyInfo {
+	/// Create a loyalty bonus which reaches `max_bonus` after `maturity`
+	/// rounds.
+	pub fn new(max_bonus: Perbill, maturity: SessionIndex) -> Self {
+		LoyaltyInfo { max_bonus, maturity }
+	}
+
+	/// Check whether the highest bonus can be reached.
+	pub fn is_valid(&self) -> bool {
+		!self.maturity.is_zero()
+	}
+
+	/// Return the bonus for a stake of the given age which grows linearly
+	/// up to `max_bonus` at `maturity`.
+	pub fn bonus(&self, age: SessionIndex) -> Perbill {
+		if self.maturity.is_zero() {
+			return Perbill::zero();
+		}
+		Perbill::from_rational(age.min(self.maturity), self.maturity) * self.max_bonus
+	}
+}
+
+/// The kinds of staking operations which can be paused by governance.
+///
+/// Pausing all of them puts the pallet into emergency mode in which no staked
+/// funds can be moved and no rewards are paid out.
+#[derive(Default, Clone, Copy, Encode, Decode, Eq, MaxEncodedLen, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct PausedOperations {
+	/// Joining the collator candidates, delegating and joining pools.
+	pub joins: bool,
+	/// Increasing, decreasing, moving and withdrawing stake, including
+	/// leaving the candidates or delegators, and changing commissions.
+	pub stake_changes: bool,
+	/// Unlocking unstaked funds.
+	pub unlocks: bool,
+	/// Paying out rewards and changing their payee or restaked share.
+	/// Rewards are still accrued.
+	pub payouts: bool,
+}
+
+impl PausedOperations {
+	/// Pause all staking operations.
+	pub fn all() -> Self {
+		PausedOperations {
+			joins: true,
+			stake_changes: true,
+			unlocks: true,
+			payouts: true,
+		}
+	}
+
+	/// Check whether all staking operations are paused.
+	pub fn is_emergency(&self) -> bool {
+		*self == Self::all()
+	}
+}
+
+/// A pool which delegates the pooled funds of its members.
+///
+/// The pool's account is a regular delegator. Each member holds points which
+/// represent their share of the pool's stake, such that slashes of the pool's
+/// delegations reduce the stake of all members proportionally. The rewards of
+/// its delegations are shared between the members in proportion to their
+/// points.
+#[derive(Clone, Encode, Decode, Eq, MaxEncodedLen, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct DelegationPool<AccountId, Balance> {
+	/// The account which created the pool and manages its delegations.
+	pub operator: AccountId,
+	/// The stake of all members, reduced by the slashes of the pool's
+	/// delegations.
+	pub stake: Balance,
+	/// The sum of the points of all members.
+	pub points: Balance,
+	/// The number of members, including the ones which are only waiting for
+	/// their unbonded funds.
+	pub members: u32,
+	/// The rewards the pool has received per point since its creation.
+	pub reward_counter: FixedU128,
+	/// The rewards which have been added to the reward counter but have not
+	/// been minted into the pool's account yet because payouts are paused.
+	pub pending_rewards: Balance,
+}
+
+impl<AccountId, Balance> DelegationPool<AccountId, Balance>
+where
+	Balance: AtLeast32BitUnsigned + Copy,
+{
+	/// Return the share of the pool's stake which belongs to the given
+	/// points.
+	pub fn stake_of(&self, points: Balance) -> Balance {
+		if points >= self.points {
+			self.stake
+		} else {
+			Self::convert(points, self.stake, self.points)
+		}
+	}
+
+	/// Return the points which correspond to the given share of the pool's
+	/// stake.
+	///
+	/// As long as the pool does not have any points or stake, each unit of
+	/// stake is worth one point.
+	pub fn points_of(&self, stake: Balance) -> Balance {
+		if self.points.is_zero() || self.stake.is_zero() {
+			stake
+		} else if stake == self.stake {
+			self.points
+		} else {
+			Self::convert(stake, self.points, self.stake)
+		}
+	}
+
+	/// Return `amount * numerator / denominator`, rounded down.
+	fn convert(amount: Balance, numerator: Balance, denominator: Balance) -> Balance {
+		FixedU128::saturating_from_rational(numerator.saturated_into::<u128>(), denominator.saturated_into::<u128>())
+			.saturating_mul_int(amount.saturated_into::<u128>())
+			.saturated_into()
+	}
+}
+
+/// The stake of a member of a delegation pool.
+#[derive(Clone, Encode, Decode, Eq, MaxEncodedLen, PartialEq, RuntimeDebug, TypeInfo)]
+#[scale_info(skip_type_params(MaxUnstakeRequests))]
+#[codec(mel_bound(BlockNumber: MaxEncodedLen, Balance: MaxEncodedLen))]
+pub struct PoolMember<BlockNumber: Ord, Balance, MaxUnstakeRequests: Get<u32>> {
+	/// The member's points, i.e. their share of the pool's stake.
+	pub points: Balance,
+	/// The pool's reward counter when the member's rewards were paid out the
+	/// last time.
+	pub reward_counter: FixedU128,
+	/// The funds which have been unbonded and can be withdrawn from the given
+	/// block on.
+	pub unbonding: BoundedBTreeMap<BlockNumber, Balance, MaxUnstakeRequests>,
+	/// The rewards which are due to the member but have not been paid out
+	/// because payouts were paused.
+	pub pending_rewards: Balance,
+}
+
 /// Internal type which is only used when a delegator is replaced by another
 /// one to delay the storage entry removal until failure cannot happen anymore.
 pub(crate) struct ReplacedDelegator<T: Config> {
@@ -381,5 +699,10 @@ pub(crate) struct ReplacedDelegator<T: Config> {
 pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
 pub type BalanceOf<T> = <<T as Config>::Currency as Currency<AccountIdOf<T>>>::Balance;
 pub type CandidateOf<T, S> = Candidate<AccountIdOf<T>, BalanceOf<T>, S>;
+pub type StakeSnapshotOf<T, S> = StakeSnapshot<AccountIdOf<T>, BalanceOf<T>, S>;
 pub type StakeOf<T> = Stake<AccountIdOf<T>, BalanceOf<T>>;
+pub type PoolId = u32;
+pub type PoolOf<T> = DelegationPool<AccountIdOf<T>, BalanceOf<T>>;
+pub type PoolMemberOf<T> =
+	PoolMember<<T as frame_system::Config>::BlockNumber, BalanceOf<T>, <T as Config>::MaxUnstakeRequests>;
 pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<AccountIdOf<T>>>::NegativeImbalance;
@@ -77,6 +77,9 @@ pub mod treasury {
 
 pub mod staking {
 	use super::*;
+	use frame_support::PalletId;
+	use parachain_staking::RemainderDestination;
+	use sp_runtime::Perbill;
     pub(super) const REWARD_PER_DAY: Balance = 500 * currency::DOLLARS;
 
 	parameter_types! {
@@ -88,6 +91,8 @@ pub mod staking {
 		pub const StakeDuration: BlockNumber = 7 * time::DAYS;
 		/// Collator exit requests are delayed by 4 hours (2 rounds/sessions)
 		pub const ExitQueueDelay: u32 = 2;
+		/// Commission increases are delayed by 1 day (12 rounds/sessions)
+		pub const CommissionChangeDelay: u32 = 12;
 		/// Minimum 5 collators selected per round, default at genesis and minimum forever after
 		pub const MinCollators: u32 = 14;
 		/// At least 4 candidates which cannot leave the network if there are no other candidates.
@@ -100,10 +105,14 @@ pub mod staking {
 		/// Maximum 1 collator per delegator at launch, will be increased later
 		#[derive(Debug, Eq, PartialEq)]
 		pub const MaxCollatorsPerDelegator: u32 = 1;
+		/// Up to 10 delegators can wait for a free delegation slot of a collator
+		pub const MaxWaitlistSize: u32 = 10;
 		/// Minimum stake required to be reserved to be a collator is 10
 		pub const MinCollatorStake: Balance = 10 * currency::DOLLARS;
 		/// Minimum stake required to be reserved to be a delegator is 10
 		pub const MinDelegatorStake: Balance = 10 * currency::DOLLARS;
+		/// Delegations below the minimum delegator stake are not rewarded
+		pub const MinRewardedDelegation: Balance = 10 * currency::DOLLARS;
 		/// Maximum number of collator candidates
 		#[derive(Debug, Eq, PartialEq)]
 		pub const MaxCollatorCandidates: u32 = aura::MAX_AUTHORITIES;
@@ -113,9 +122,26 @@ pub mod staking {
 		pub const NetworkRewardStart: BlockNumber = 1; // this is not really being used
 		/// The rate in percent for the network rewards
 		pub const NetworkRewardRate: Balance = 0 as Balance;
+		/// Block rewards which are not credited to the stakers are sent to the treasury
+		pub const RewardRemainder: RemainderDestination = RemainderDestination::Beneficiary;
+		/// Collators have to author at least half of their expected blocks per session
+		pub const OfflineThreshold: Perbill = Perbill::from_percent(50);
+		/// 1% of the collator's stake is slashed for each offence
+		pub const CollatorSlash: Perbill = Perbill::from_percent(1);
+		/// Delegators are not slashed for offences of their collator
+		pub const DelegatorSlash: Perbill = Perbill::zero();
+		/// Collators are removed after 3 offences in consecutive sessions
+		pub const MaxOffences: u32 = 3;
+		/// The accounts of delegation pools are derived from this id
+		pub const PoolPalletId: PalletId = PalletId(*b"dhx/pool");
+		/// Invulnerable collators are not rewarded for the blocks they author
+		pub const RewardInvulnerables: bool = false;
+		/// The statistics of the last 120 rounds (10 days) are kept
+		pub const RoundStatsRetention: u32 = 120;
 	}
 
     pub const MAX_CANDIDATE_STAKE: Balance = 10_000 * currency::DOLLARS;
+    pub const MAX_COMMISSION: Perbill = Perbill::from_percent(20);
     pub const REWARD_PER_BLOCK: Balance = REWARD_PER_DAY / time::DAYS as Balance;
 }
 
@@ -13,6 +13,7 @@ mod tests;
 mod benchmarking;
 
 mod functions;
+pub mod migrations;
 pub mod types;
 pub mod weights;
 
@@ -30,7 +31,10 @@ pub mod pallet {
             DispatchResult,
             *,
         },
-        traits::Currency,
+        traits::{
+            Currency,
+            StorageVersion,
+        },
     };
     use frame_system::pallet_prelude::{
         OriginFor,
@@ -63,8 +67,12 @@ pub mod pallet {
     };
     use weights::WeightInfo;
 
+    /// The current storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
     /// Configuration trait for reward campaign pallet
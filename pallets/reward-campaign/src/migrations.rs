@@ -0,0 +1,83 @@
+//! Storage migrations of the reward campaign pallet.
+//!
+//! Each storage version has its own module with a migration implementing
+//! `OnRuntimeUpgrade`. A migration is applied once when the on-chain storage
+//! version is below the version the migration upgrades to, and checks with
+//! `try-runtime` that all stored campaigns can still be decoded. The runtime
+//! applies [Migrations] when it is upgraded.
+
+use crate::{
+    CampaignStatus,
+    Config,
+    Contribution,
+    Pallet,
+    RewardInfo,
+};
+use frame_support::{
+    traits::{
+        Get,
+        GetStorageVersion,
+        OnRuntimeUpgrade,
+        StorageVersion,
+    },
+    weights::Weight,
+};
+use sp_std::marker::PhantomData;
+
+/// All migrations of the pallet in the order in which they have to be applied.
+pub type Migrations<T> = (v1::MigrateToV1<T>,);
+
+/// Ensure that every stored campaign, reward info and contribution can be
+/// decoded with the current types.
+#[cfg(feature = "try-runtime")]
+pub fn ensure_decodable<T: Config>() -> Result<(), &'static str> {
+    frame_support::ensure!(
+        CampaignStatus::<T>::iter_keys().count() == CampaignStatus::<T>::iter_values().count(),
+        "CampaignStatus contains undecodable values"
+    );
+    frame_support::ensure!(
+        RewardInfo::<T>::iter_keys().count() == RewardInfo::<T>::iter_values().count(),
+        "RewardInfo contains undecodable values"
+    );
+    frame_support::ensure!(
+        Contribution::<T>::iter_keys().count() == Contribution::<T>::iter_values().count(),
+        "Contribution contains undecodable values"
+    );
+    Ok(())
+}
+
+/// Start versioning the storage. The storage layout is unchanged.
+pub mod v1 {
+    use super::*;
+
+    /// Migrate the unversioned storage to version 1.
+    pub struct MigrateToV1<T>(PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::on_chain_storage_version() < 1 {
+                StorageVersion::new(1).put::<Pallet<T>>();
+                log::info!("Reward campaign storage has been migrated to storage version 1");
+                T::DbWeight::get().reads_writes(1, 1)
+            } else {
+                log::info!("Reward campaign storage has already been migrated to storage version 1");
+                T::DbWeight::get().reads(1)
+            }
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<(), &'static str> {
+            ensure_decodable::<T>()
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade() -> Result<(), &'static str> {
+            ensure_decodable::<T>()?;
+            frame_support::ensure!(
+                Pallet::<T>::on_chain_storage_version() >= 1,
+                "On-chain storage version has not been migrated to version 1"
+            );
+            Ok(())
+        }
+    }
+}
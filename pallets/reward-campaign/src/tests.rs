@@ -1,5 +1,6 @@
 use crate::{
     functions,
+    migrations,
     mock::*,
     types::{
         self,
@@ -11,6 +12,11 @@ use frame_support::{
     assert_err,
     assert_noop,
     assert_ok,
+    traits::{
+        GetStorageVersion,
+        OnRuntimeUpgrade,
+        StorageVersion,
+    },
 };
 
 type RewardError = crate::Error<Test>;
@@ -788,3 +794,17 @@ fn construct_reward_unit() {
         assert_err!(functions::construct_reward_unit::<Test>(amount, ratio, 10, 20), RewardError::RewardTooSmall);
     }
 }
+
+#[test]
+fn migrate_to_v1() {
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<Reward>();
+
+        migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+        assert_eq!(Reward::on_chain_storage_version(), StorageVersion::new(1));
+
+        // the migration is only applied once
+        migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+        assert_eq!(Reward::on_chain_storage_version(), Reward::current_storage_version());
+    });
+}
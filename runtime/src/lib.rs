@@ -143,9 +143,22 @@ pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signatu
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
+/// Storage migrations applied on runtime upgrades, in order. Each pallet
+/// migration only runs if the on-chain storage version is outdated.
+pub type Migrations = (
+    parachain_staking::migrations::Migrations<Runtime>,
+    pallet_reward_campaign::migrations::Migrations<Runtime>,
+    EnsureAccountsWontDie,
+);
 /// Executive: handles dispatch to the various modules.
-pub type Executive =
-    frame_executive::Executive<Runtime, Block, frame_system::ChainContext<Runtime>, Runtime, AllPalletsWithSystem>;
+pub type Executive = frame_executive::Executive<
+    Runtime,
+    Block,
+    frame_system::ChainContext<Runtime>,
+    Runtime,
+    AllPalletsWithSystem,
+    Migrations,
+>;
 
 /// Handles converting a weight scalar to a fee value, based on the scale and granularity of the
 /// node's balance type.
@@ -175,20 +188,26 @@ impl WeightToFeePolynomial for WeightToFee {
 }
 
 // https://docs.substrate.io/v3/tools/try-runtime/#helper-functions
+/// Ensures that the migrations of a runtime upgrade do not kill any account.
 pub struct EnsureAccountsWontDie;
 impl OnRuntimeUpgrade for EnsureAccountsWontDie {
     #[cfg(feature = "try-runtime")]
-    fn pre_upgrade() {
-        let account_count = frame_system::Accounts::<Runtime>::iter().count();
+    fn pre_upgrade() -> Result<(), &'static str> {
+        use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+        let account_count = frame_system::Account::<Runtime>::iter_keys().count() as u32;
         Self::set_temp_storage(account_count, "account_count");
+        Ok(())
     }
 
     #[cfg(feature = "try-runtime")]
-    fn post_upgrade() {
-        // ensure that this migration doesn't kill any account.
-        let post_migration = frame_system::Accounts::<Runtime>::iter().count();
-        let pre_migration = Self::get_temp_storage::<u32>("account_count");
-        ensure!(post_migration == pre_migration, "error ...");
+    fn post_upgrade() -> Result<(), &'static str> {
+        use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+        // ensure that the migrations didn't kill any account.
+        let post_migration = frame_system::Account::<Runtime>::iter_keys().count() as u32;
+        let pre_migration = Self::get_temp_storage::<u32>("account_count")
+            .ok_or("Account count has not been stored before the upgrade")?;
+        frame_support::ensure!(post_migration >= pre_migration, "Accounts have been killed by the runtime upgrade");
+        Ok(())
     }
 }
 
@@ -655,7 +674,7 @@ impl pallet_tips::Config for Runtime {
 
 impl pallet_aura::Config for Runtime {
     type AuthorityId = AuraId;
-    type DisabledValidators = ();
+    type DisabledValidators = ParachainStaking;
     type MaxAuthorities = ConstU32<MAX_AUTHORITIES_AS_CONST>;
 }
 
@@ -772,6 +791,13 @@ pub enum ProxyType {
     Any,
     NonTransfer,
     Governance,
+    /// Only staking calls, also when batched, except for redirecting the rewards and
+    /// staking funds for another account.
+    Staking,
+    /// Only the calls a collator's operator needs to run the collator, i.e. setting
+    /// the session keys and managing the candidacy and self-stake, without access
+    /// to the collator's free funds.
+    CollatorOperator,
 }
 impl Default for ProxyType {
     fn default() -> Self {
@@ -794,9 +820,35 @@ impl InstanceFilter<Call> for ProxyType {
                 Call::TechnicalCommittee(..) |
                 Call::Elections(..) | Call::Treasury(..)
             ),
+            // the batched calls are filtered again when they are dispatched, and calls which
+            // redirect the rewards or lock funds for or from another account are excluded
+            ProxyType::Staking => match c {
+                Call::ParachainStaking(
+                    parachain_staking::Call::set_payee { .. } |
+                    parachain_staking::Call::bond_candidate_for { .. } |
+                    parachain_staking::Call::bond_delegator_for { .. } |
+                    parachain_staking::Call::accept_funder { .. },
+                ) => false,
+                Call::ParachainStaking(..) |
+                Call::Utility(pallet_utility::Call::batch { .. }) |
+                Call::Utility(pallet_utility::Call::batch_all { .. }) |
+                Call::Utility(pallet_utility::Call::force_batch { .. }) => true,
+                _ => false,
+            },
+            ProxyType::CollatorOperator => matches!(
+                c,
+                Call::Session(pallet_session::Call::set_keys { .. }) |
+                Call::ParachainStaking(parachain_staking::Call::candidate_stake_more { .. }) |
+                Call::ParachainStaking(parachain_staking::Call::candidate_stake_less { .. }) |
+                Call::ParachainStaking(parachain_staking::Call::init_leave_candidates { .. }) |
+                Call::ParachainStaking(parachain_staking::Call::execute_leave_candidates { .. }) |
+                Call::ParachainStaking(parachain_staking::Call::cancel_leave_candidates { .. })
+            ),
         }
     }
     fn is_superset(&self, o: &Self) -> bool {
+        // `Staking` and `CollatorOperator` are not comparable since only the latter
+        // can set session keys
         match (self, o) {
             (x, y) if x == y => true,
             (ProxyType::Any, _) => true,
@@ -1072,6 +1124,26 @@ impl pallet_inflation::Config for Runtime {
 	type WeightInfo = pallet_inflation::default_weights::SubstrateWeight<Runtime>;
 }
 
+/// Staking parameters can be changed by Root or a two-thirds supermajority of
+/// the Council.
+type EnsureRootOrTwoThirdsCouncil = EitherOfDiverse<
+    EnsureRoot<AccountId>,
+    pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>,
+>;
+
+/// The accounts with a `Reasonable` or `KnownGood` identity judgement which are
+/// allowed to join the set of collator candidates.
+pub struct CollatorIdentity;
+impl Contains<AccountId> for CollatorIdentity {
+	fn contains(who: &AccountId) -> bool {
+		Identity::identity(who).map_or(false, |registration| {
+			registration.judgements.iter().any(|(_, judgement)| {
+				matches!(judgement, pallet_identity::Judgement::Reasonable | pallet_identity::Judgement::KnownGood)
+			})
+		})
+	}
+}
+
 impl parachain_staking::Config for Runtime {
 	type Event = Event;
 	type Currency = Balances;
@@ -1081,21 +1153,40 @@ impl parachain_staking::Config for Runtime {
 	type DefaultBlocksPerRound = staking_constants::DefaultBlocksPerRound;
 	type StakeDuration = staking_constants::StakeDuration;
 	type ExitQueueDelay = staking_constants::ExitQueueDelay;
+	type CommissionChangeDelay = staking_constants::CommissionChangeDelay;
 	type MinCollators = staking_constants::MinCollators;
 	type MinRequiredCollators = staking_constants::MinRequiredCollators;
 	type MaxDelegationsPerRound = staking_constants::MaxDelegationsPerRound;
 	type MaxDelegatorsPerCollator = staking_constants::MaxDelegatorsPerCollator;
 	type MaxCollatorsPerDelegator = staking_constants::MaxCollatorsPerDelegator;
+	type MaxWaitlistSize = staking_constants::MaxWaitlistSize;
 	type MinCollatorStake = staking_constants::MinCollatorStake;
 	type MinCollatorCandidateStake = staking_constants::MinCollatorStake;
 	type MaxTopCandidates = staking_constants::MaxCollatorCandidates;
 	type MinDelegation = staking_constants::MinDelegatorStake;
 	type MinDelegatorStake = staking_constants::MinDelegatorStake;
+	type MinRewardedDelegation = staking_constants::MinRewardedDelegation;
 	type MaxUnstakeRequests = staking_constants::MaxUnstakeRequests;
 	type NetworkRewardRate = staking_constants::NetworkRewardRate;
 	type NetworkRewardStart = staking_constants::NetworkRewardStart;
 
     type NetworkRewardBeneficiary = Treasury;
+	type RewardRemainder = staking_constants::RewardRemainder;
+	type RewardRemainderBeneficiary = Treasury;
+	type OfflineThreshold = staking_constants::OfflineThreshold;
+	type CollatorSlash = staking_constants::CollatorSlash;
+	type DelegatorSlash = staking_constants::DelegatorSlash;
+	type MaxOffences = staking_constants::MaxOffences;
+	type Slash = Treasury;
+	type AdminOrigin = EnsureRootOrTwoThirdsCouncil;
+	type PoolPalletId = staking_constants::PoolPalletId;
+	type RewardInvulnerables = staking_constants::RewardInvulnerables;
+	type RoundStatsRetention = staking_constants::RoundStatsRetention;
+	#[cfg(not(feature = "runtime-benchmarks"))]
+	type CandidateIdentity = CollatorIdentity;
+	// the benchmarks cannot provide identity judgements
+	#[cfg(feature = "runtime-benchmarks")]
+	type CandidateIdentity = Everything;
 	const BLOCKS_PER_YEAR: BlockNumber = 365 * DAYS;
 	type WeightInfo = parachain_staking::default_weights::SubstrateWeight<Runtime>;
 }
@@ -1305,6 +1396,33 @@ impl_runtime_apis! {
         }
     }
 
+    impl parachain_staking_runtime_api::ParachainStakingApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+        fn selected_candidates() -> Vec<parachain_staking_runtime_api::CandidateInfo<AccountId, Balance>> {
+            ParachainStaking::selected_candidates_info()
+        }
+
+        fn staker_info(
+            account: AccountId,
+        ) -> parachain_staking_runtime_api::StakerInfo<AccountId, Balance, BlockNumber> {
+            ParachainStaking::staker_info(&account)
+        }
+
+        fn estimate_rewards(
+            collator: AccountId,
+            stake: Balance,
+        ) -> Option<parachain_staking_runtime_api::RewardEstimate<Balance>> {
+            ParachainStaking::estimate_rewards(&collator, stake)
+        }
+
+        fn round_info() -> parachain_staking_runtime_api::RoundInfo<BlockNumber> {
+            ParachainStaking::round_info()
+        }
+
+        fn round_stats(round: u32) -> Option<parachain_staking_runtime_api::RoundStats<AccountId, Balance>> {
+            ParachainStaking::round_stats(round)
+        }
+    }
+
     impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
 		fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
 			ParachainSystem::collect_collation_info(header)
@@ -1406,3 +1524,113 @@ cumulus_pallet_parachain_system::register_validate_block! {
     BlockExecutor = cumulus_pallet_aura_ext::BlockExecutor::<Runtime, Executive>,
     CheckInherents = CheckInherents,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_runtime::traits::TrailingZeroInput;
+
+    fn set_keys() -> Call {
+        let keys = SessionKeys::decode(&mut TrailingZeroInput::zeroes()).expect("keys decode from zeroes");
+        Call::Session(pallet_session::Call::set_keys { keys, proof: vec![] })
+    }
+
+    fn transfer() -> Call {
+        Call::Balances(pallet_balances::Call::transfer {
+            dest: MultiAddress::Id(AccountId::new([1u8; 32])),
+            value: 1,
+        })
+    }
+
+    #[test]
+    fn staking_proxy_filter() {
+        let claim = Call::ParachainStaking(parachain_staking::Call::claim_rewards {});
+        assert!(ProxyType::Staking.filter(&claim));
+        assert!(ProxyType::Staking.filter(&Call::Utility(pallet_utility::Call::batch { calls: vec![claim.clone()] })));
+        assert!(ProxyType::Staking.filter(&Call::Utility(pallet_utility::Call::batch_all { calls: vec![claim] })));
+        assert!(!ProxyType::Staking.filter(&transfer()));
+        assert!(!ProxyType::Staking.filter(&set_keys()));
+
+        // the proxy can neither redirect the rewards nor stake the funds for or from another account
+        let other = MultiAddress::Id(AccountId::new([1u8; 32]));
+        let set_payee = Call::ParachainStaking(parachain_staking::Call::set_payee { payee: other.clone() });
+        assert!(!ProxyType::Staking.filter(&set_payee));
+        assert!(!ProxyType::Staking.filter(&Call::ParachainStaking(
+            parachain_staking::Call::bond_candidate_for {
+                beneficiary: other.clone(),
+                amount: 1,
+            }
+        )));
+        assert!(!ProxyType::Staking.filter(&Call::ParachainStaking(
+            parachain_staking::Call::bond_delegator_for {
+                beneficiary: other.clone(),
+                collator: other.clone(),
+                amount: 1,
+            }
+        )));
+        assert!(!ProxyType::Staking.filter(&Call::ParachainStaking(
+            parachain_staking::Call::accept_funder { funder: Some(other) }
+        )));
+        assert!(!ProxyType::Staking.filter(&Call::Utility(pallet_utility::Call::as_derivative {
+            index: 0,
+            call: Box::new(transfer()),
+        })));
+    }
+
+    #[test]
+    fn collator_operator_proxy_filter() {
+        assert!(ProxyType::CollatorOperator.filter(&set_keys()));
+        assert!(ProxyType::CollatorOperator
+            .filter(&Call::ParachainStaking(parachain_staking::Call::candidate_stake_more { more: 1 })));
+        assert!(ProxyType::CollatorOperator
+            .filter(&Call::ParachainStaking(parachain_staking::Call::candidate_stake_less { less: 1 })));
+        assert!(ProxyType::CollatorOperator.filter(&Call::ParachainStaking(
+            parachain_staking::Call::init_leave_candidates {}
+        )));
+        assert!(ProxyType::CollatorOperator.filter(&Call::ParachainStaking(
+            parachain_staking::Call::cancel_leave_candidates {}
+        )));
+        assert!(!ProxyType::CollatorOperator.filter(&Call::ParachainStaking(
+            parachain_staking::Call::claim_rewards {}
+        )));
+        assert!(!ProxyType::CollatorOperator.filter(&Call::Session(pallet_session::Call::purge_keys {})));
+        assert!(!ProxyType::CollatorOperator.filter(&transfer()));
+    }
+
+    #[test]
+    fn staking_admin_origin() {
+        use frame_support::traits::EnsureOrigin;
+        type AdminOrigin = <Runtime as parachain_staking::Config>::AdminOrigin;
+        type CouncilOrigin = pallet_collective::RawOrigin<AccountId, CouncilCollective>;
+        let council = |yes, total| Origin::from(CouncilOrigin::Members(yes, total));
+
+        assert!(AdminOrigin::try_origin(Origin::root()).is_ok());
+        // a two-thirds supermajority of the council is sufficient
+        assert!(AdminOrigin::try_origin(council(2, 3)).is_ok());
+        assert!(AdminOrigin::try_origin(council(3, 3)).is_ok());
+        assert!(AdminOrigin::try_origin(council(1, 2)).is_err());
+        assert!(AdminOrigin::try_origin(council(3, 5)).is_err());
+        assert!(AdminOrigin::try_origin(Origin::from(CouncilOrigin::Member(AccountId::new([1u8; 32])))).is_err());
+        assert!(AdminOrigin::try_origin(Origin::signed(AccountId::new([1u8; 32]))).is_err());
+    }
+
+    #[test]
+    fn proxy_type_supersets() {
+        let all = [
+            ProxyType::Any,
+            ProxyType::NonTransfer,
+            ProxyType::Governance,
+            ProxyType::Staking,
+            ProxyType::CollatorOperator,
+        ];
+        for proxy in all {
+            assert!(proxy.is_superset(&proxy));
+            assert!(ProxyType::Any.is_superset(&proxy));
+            assert!(ProxyType::NonTransfer.is_superset(&proxy) || proxy == ProxyType::Any);
+        }
+        assert!(!ProxyType::Staking.is_superset(&ProxyType::CollatorOperator));
+        assert!(!ProxyType::CollatorOperator.is_superset(&ProxyType::Staking));
+        assert!(!ProxyType::Staking.is_superset(&ProxyType::NonTransfer));
+        assert!(!ProxyType::CollatorOperator.is_superset(&ProxyType::Governance));
+    }
+}